@@ -3,13 +3,14 @@
 //! This module contains the decomposed handle_action method and category-specific
 //! handlers for processing user actions in the TUI application.
 
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::config::themes_dir;
 use crate::daemon::KillSignal;
 
 use super::types::{Action, AppView, MAX_REFRESH_MS, MIN_REFRESH_MS, REFRESH_STEP_MS};
 use super::App;
+use super::BugReportStatus;
 
 impl App {
     /// Main action handler - dispatches to category-specific handlers.
@@ -20,9 +21,9 @@ impl App {
             Quit => return false,
             None => {}
             // View toggles
-            ToggleHelp | ToggleAbout | ToggleSettings | ToggleHistory | ToggleBatteryDetails => {
-                self.handle_view_action(action)
-            }
+            ToggleHelp | ToggleAbout | ToggleSettings | ToggleHistory | ToggleBatteryDetails
+            | ToggleChargerDetails | ToggleDebugPanel | ToggleFleet | ToggleHeatmap
+            | ToggleBugReport => self.handle_view_action(action),
             // Navigation
             SelectNext | SelectPrevious | ExitSelectionMode | PageUp | PageDown | Home | End => {
                 self.handle_navigation_action(action)
@@ -51,18 +52,29 @@ impl App {
             | ImporterFilterBackspace
             | ImporterClearFilter => self.handle_importer_action(action),
             // History
-            HistoryPrevPeriod | HistoryNextPeriod => self.handle_history_action(action),
-            // Settings
-            SettingsToggleValue | SettingsIncrement | SettingsDecrement => {
-                self.handle_settings_action(action)
+            HistoryPrevPeriod | HistoryNextPeriod | HistoryZoomIn | HistoryZoomOut
+            | HistoryPanBack | HistoryPanForward | HistoryFollowLive => {
+                self.handle_history_action(action)
             }
+            // Settings
+            SettingsToggleValue | SettingsIncrement | SettingsDecrement | SettingsSave
+            | SettingsKeepUnsaved => self.handle_settings_action(action),
             // Refresh rate
             IncreaseRefreshRate | DecreaseRefreshRate => self.handle_refresh_action(action),
+            CopyToClipboard => self.copy_to_clipboard(),
+            ConfirmBugReport => self.start_bug_report(),
+            ResumeSession => self.answer_resume(true),
+            DiscardSession => self.answer_resume(false),
+            // Battery planner
+            ToggleLastUntil | LastUntilChar(_) | LastUntilBackspace => {
+                self.handle_last_until_action(action)
+            }
         }
         true
     }
 
-    /// Handles view toggle actions (Help, About, Settings, History, BatteryDetails).
+    /// Handles view toggle actions (Help, About, Settings, History, BatteryDetails,
+    /// ChargerDetails, DebugPanel, Fleet, Heatmap, BugReport).
     fn handle_view_action(&mut self, action: Action) {
         match action {
             Action::ToggleHelp => {
@@ -79,6 +91,14 @@ impl App {
             }
             Action::ToggleSettings => {
                 self.view = match self.view {
+                    AppView::Settings if self.settings_save_prompt => {
+                        self.settings_save_prompt = false;
+                        AppView::Settings
+                    }
+                    AppView::Settings if self.settings_unsaved => {
+                        self.settings_save_prompt = true;
+                        AppView::Settings
+                    }
                     AppView::Settings => AppView::Main,
                     _ => {
                         self.refresh_daemon_status();
@@ -102,10 +122,88 @@ impl App {
                     _ => AppView::BatteryDetails,
                 };
             }
+            Action::ToggleChargerDetails => {
+                // Opened from battery details, so closing returns there.
+                self.view = match self.view {
+                    AppView::ChargerDetails => AppView::BatteryDetails,
+                    _ => AppView::ChargerDetails,
+                };
+            }
+            Action::ToggleDebugPanel => {
+                self.view = match self.view {
+                    AppView::DebugPanel => AppView::Main,
+                    _ => AppView::DebugPanel,
+                };
+            }
+            Action::ToggleFleet => {
+                self.view = match self.view {
+                    AppView::Fleet => AppView::Main,
+                    _ => AppView::Fleet,
+                };
+            }
+            Action::ToggleHeatmap => {
+                self.view = match self.view {
+                    AppView::Heatmap => AppView::Main,
+                    _ => {
+                        self.load_heatmap();
+                        AppView::Heatmap
+                    }
+                };
+            }
+            Action::ToggleBugReport => {
+                self.view = match self.view {
+                    AppView::BugReport => AppView::Main,
+                    _ => {
+                        if matches!(self.bug_report, BugReportStatus::Done(_)) {
+                            self.bug_report = BugReportStatus::Idle;
+                        }
+                        AppView::BugReport
+                    }
+                };
+            }
             _ => {}
         }
     }
 
+    /// Handles the "will it last until" prompt.
+    fn handle_last_until_action(&mut self, action: Action) {
+        match action {
+            Action::ToggleLastUntil => {
+                self.view = match self.view {
+                    AppView::LastUntil => AppView::Main,
+                    _ => {
+                        self.last_until_input.clear();
+                        self.load_drain_profile();
+                        AppView::LastUntil
+                    }
+                };
+            }
+            Action::LastUntilChar(c) if self.last_until_input.len() < 5 => {
+                self.last_until_input.push(c);
+            }
+            Action::LastUntilBackspace => {
+                self.last_until_input.pop();
+            }
+            _ => {}
+        }
+    }
+
+    /// Copies text for the current view and confirms it in the status bar.
+    fn copy_to_clipboard(&mut self) {
+        let Some((text, description)) = crate::ui::copy_text::for_current_view(self) else {
+            self.show_toast("Nothing to copy");
+            return;
+        };
+
+        match crate::clipboard::copy(&text) {
+            Ok(()) => self.show_toast(format!("Copied {}", description)),
+            Err(e) => {
+                warn!(error = %e, "Clipboard copy failed");
+                self.show_toast("Clipboard copy failed");
+            }
+        }
+    }
+
     /// Handles navigation actions (selection movement, scrolling).
     fn handle_navigation_action(&mut self, action: Action) {
         match action {
@@ -350,17 +448,13 @@ impl App {
             Action::ImporterToggleSearch => {
                 self.importer_search_focused = !self.importer_search_focused;
             }
-            Action::ImporterFilterChar(c) => {
-                if self.importer_search_focused {
-                    self.importer_filter.push(c);
-                    self.importer_index = 0;
-                }
+            Action::ImporterFilterChar(c) if self.importer_search_focused => {
+                self.importer_filter.push(c);
+                self.importer_index = 0;
             }
-            Action::ImporterFilterBackspace => {
-                if self.importer_search_focused {
-                    self.importer_filter.pop();
-                    self.importer_index = 0;
-                }
+            Action::ImporterFilterBackspace if self.importer_search_focused => {
+                self.importer_filter.pop();
+                self.importer_index = 0;
             }
             Action::ImporterClearFilter => {
                 self.importer_filter.clear();
@@ -371,8 +465,10 @@ impl App {
         }
     }
 
-    /// Handles history view actions (period navigation).
+    /// Handles history view actions (period navigation, timeline zoom and pan).
     fn handle_history_action(&mut self, action: Action) {
+        let now = chrono::Utc::now().timestamp();
+        let oldest = self.history_oldest_sample;
         match action {
             Action::HistoryNextPeriod => {
                 self.history_period = self.history_period.next();
@@ -382,11 +478,33 @@ impl App {
                 self.history_period = self.history_period.prev();
                 self.load_history_data();
             }
+            Action::HistoryZoomIn | Action::HistoryZoomOut => {
+                if action == Action::HistoryZoomIn {
+                    self.history_range.zoom_in(now, oldest);
+                } else {
+                    self.history_range.zoom_out(now, oldest);
+                }
+                self.config.user_config.history.timeline_zoom = self.history_range.zoom;
+                let _ = self.config.user_config.save();
+                self.load_timeline();
+            }
+            Action::HistoryPanBack => {
+                self.history_range.pan_back(now, oldest);
+                self.load_timeline();
+            }
+            Action::HistoryPanForward => {
+                self.history_range.pan_forward(now, oldest);
+                self.load_timeline();
+            }
+            Action::HistoryFollowLive => {
+                self.history_range.follow_live();
+                self.load_timeline();
+            }
             _ => {}
         }
     }
 
-    /// Handles settings view actions (toggle, increment, decrement).
+    /// Handles settings view actions (toggle, increment, decrement, save).
     fn handle_settings_action(&mut self, action: Action) {
         match action {
             Action::SettingsToggleValue => {
@@ -425,6 +543,28 @@ impl App {
                     }
                 }
             }
+            Action::SettingsSave => {
+                match self.config.user_config.save() {
+                    Ok(()) => {
+                        self.settings_unsaved = false;
+                        self.show_toast("Settings saved");
+                    }
+                    Err(e) => {
+                        warn!(error = %e, "Failed to save settings");
+                        self.show_toast("Failed to save settings");
+                    }
+                }
+                if self.settings_save_prompt {
+                    self.settings_save_prompt = false;
+                    self.view = AppView::Main;
+                }
+            }
+            Action::SettingsKeepUnsaved => {
+                self.settings_save_prompt = false;
+                self.settings_unsaved = false;
+                self.show_toast("Settings kept for this session");
+                self.view = AppView::Main;
+            }
             _ => {}
         }
     }
@@ -0,0 +1,69 @@
+//! Bug-report bundle methods for App.
+//!
+//! Collecting a bundle takes several seconds, so it runs on a worker thread
+//! and the modal polls for the result on each tick.
+
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+use color_eyre::eyre::Result;
+use tracing::{info, warn};
+
+use crate::bugreport::{self, BundleOptions};
+
+use super::types::AppView;
+use super::App;
+
+/// Progress of the bug report started from the TUI.
+#[derive(Default)]
+pub enum BugReportStatus {
+    /// Nothing started; the modal asks for confirmation.
+    #[default]
+    Idle,
+    Collecting(Receiver<Result<PathBuf>>),
+    Done(Result<PathBuf, String>),
+}
+
+impl App {
+    /// Starts collecting a bundle with identifiers redacted, unless one is
+    /// already being collected.
+    pub(crate) fn start_bug_report(&mut self) {
+        if matches!(self.bug_report, BugReportStatus::Collecting(_)) {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(bugreport::create(&BundleOptions::default()));
+        });
+        self.bug_report = BugReportStatus::Collecting(rx);
+    }
+
+    /// Picks up a finished bundle. Returns `true` when the status changed.
+    ///
+    /// If the modal was closed while collecting, the outcome is also shown
+    /// as a toast.
+    pub(crate) fn poll_bug_report(&mut self) -> bool {
+        let BugReportStatus::Collecting(rx) = &self.bug_report else {
+            return false;
+        };
+        let outcome = match rx.try_recv() {
+            Ok(result) => result.map_err(|e| e.to_string()),
+            Err(TryRecvError::Empty) => return false,
+            Err(TryRecvError::Disconnected) => Err("bug report worker stopped".to_string()),
+        };
+
+        match &outcome {
+            Ok(path) => info!(path = %path.display(), "Bug report written"),
+            Err(e) => warn!(error = %e, "Bug report failed"),
+        }
+        if self.view != AppView::BugReport {
+            match &outcome {
+                Ok(path) => self.show_toast(format!("Bug report: {}", path.display())),
+                Err(e) => self.show_toast(format!("Bug report failed: {}", e)),
+            }
+        }
+        self.bug_report = BugReportStatus::Done(outcome);
+        true
+    }
+}
@@ -0,0 +1,106 @@
+//! Config file reload methods for App.
+//!
+//! [`ConfigWatcher`](crate::config_watch::ConfigWatcher) decides which keys
+//! changed; this applies them to the running app.
+
+use std::time::{Duration, Instant};
+
+use tracing::{debug, info, warn};
+
+use crate::config::UserConfig;
+use crate::config_watch::{Reload, HOT_KEYS};
+
+use super::{history_metric, App};
+
+impl App {
+    /// Applies config file edits once the file has settled. Returns `true`
+    /// when anything on screen may have changed.
+    pub(crate) fn poll_config_reload(&mut self, now: Instant) -> bool {
+        let Some(reload) = self.config_watcher.poll(now, &self.config.user_config) else {
+            return false;
+        };
+
+        let (config, changed) = match reload {
+            Reload::Applied { config, changed } => (config, changed),
+            Reload::Rejected(e) => {
+                warn!(error = %e, "Config file rejected, keeping the running config");
+                self.show_toast(format!("Config not reloaded: {}", e));
+                return true;
+            }
+        };
+        if changed.is_empty() {
+            return false;
+        }
+
+        info!(?changed, pending_restart = ?self.config_watcher.pending_restart(), "Config file reloaded");
+        for key in &changed {
+            self.apply_config_key(&config, key);
+        }
+        if changed.iter().any(|key| HOT_KEYS.contains(&key.as_str())) {
+            self.show_toast("Config reloaded");
+        }
+        true
+    }
+
+    /// Takes `key` from `config`. Keys outside [`HOT_KEYS`] are only stored,
+    /// so saving the config keeps them for the next start.
+    fn apply_config_key(&mut self, config: &UserConfig, key: &str) {
+        let running = &mut self.config.user_config;
+        match key {
+            "appearance" => running.appearance = config.appearance,
+            "theme" => {
+                if !self.config.apply_theme(&config.theme) {
+                    self.show_toast(format!("Unknown theme `{}`", config.theme));
+                }
+            }
+            "refresh_ms" => {
+                running.refresh_ms = config.refresh_ms;
+                self.refresh_ms = config.refresh_ms;
+                self.sync_daemon_broadcast_interval();
+            }
+            "power_smoothing_samples" => {
+                self.power
+                    .set_smoothing_samples(config.power_smoothing_samples);
+                running.power_smoothing_samples = self.power.smoothing_samples();
+            }
+            "power_sample_ms" => {
+                running.power_sample_ms = config.power_sample_ms;
+                self.power
+                    .set_sample_interval(Duration::from_millis(config.power_sample_ms));
+            }
+            "forecast_window_secs" => running.forecast_window_secs = config.forecast_window_secs,
+            "energy_threshold" => running.energy_threshold = config.energy_threshold,
+            "advice" => {
+                running.advice = config.advice;
+                self.battery.set_advice_config(config.advice);
+            }
+            "show_graph" => running.show_graph = config.show_graph,
+            "graph_metric" => {
+                running.graph_metric = config.graph_metric;
+                self.history.current_metric = history_metric(config.graph_metric);
+            }
+            "process_count" => running.process_count = config.process_count,
+            "merge_mode" => {
+                running.merge_mode = config.merge_mode;
+                self.merge_mode = config.merge_mode;
+            }
+            "process_network" => {
+                running.process_network = config.process_network;
+                self.set_process_network_sampling(config.process_network);
+            }
+            "hide_system_processes" => {
+                running.hide_system_processes = config.hide_system_processes;
+                self.selected_process_index = 0;
+                self.process_scroll_offset = 0;
+            }
+            "sparklines" => running.sparklines = config.sparklines,
+            "units" => running.units = config.units,
+            "excluded_processes" => running.excluded_processes = config.excluded_processes.clone(),
+            "redact_processes" => running.redact_processes = config.redact_processes.clone(),
+            "fleet_peers" => running.fleet_peers = config.fleet_peers.clone(),
+            "history" => running.history = config.history.clone(),
+            "log_level" => running.log_level = config.log_level,
+            _ => debug!(key, "Config key has no reload handling"),
+        }
+    }
+}
@@ -160,6 +160,7 @@ impl App {
                     "Received daemon snapshot from channel"
                 );
                 self.apply_snapshot(&snapshot);
+                crate::crash::remember_snapshot(&snapshot);
                 self.last_snapshot = Some(snapshot);
                 self.last_daemon_update = Some(std::time::Instant::now());
                 self.reconnect_attempts = 0;
@@ -3,20 +3,41 @@
 //! This module contains methods for loading and managing history data,
 //! including daily/hourly stats, cycle summaries, and charge sessions.
 
+use std::time::{Duration, Instant};
+
 use crate::daemon::DaemonClient;
+use crate::data::battery_wear::WearLog;
+use crate::data::heatmap::PowerHeatmap;
+use crate::data::{HourlyStat, WakeLog};
 
-use super::types::HistoryPeriod;
+use super::types::{AppView, HistoryPeriod};
 use super::App;
 
+/// How much hourly history feeds the "will it last until" planner.
+const DRAIN_PROFILE_DAYS: i64 = 7;
+
+/// How often a live-following history timeline fetches new samples.
+const LIVE_TIMELINE_REFRESH: Duration = Duration::from_secs(5);
+
 impl App {
     /// Loads history data from the daemon for the current period.
     ///
     /// This method fetches daily stats, top processes, cycle summary,
-    /// daily cycles, charge sessions, and hourly stats (for Today period).
+    /// daily cycles, the battery wear log, charge sessions, and hourly stats
+    /// and energy per app (for Today period).
     /// If the daemon is not connected, it clears all history data.
+    ///
+    /// Wake events come from the local power management log rather than the
+    /// daemon and are only loaded for the Today period.
     pub(crate) fn load_history_data(&mut self) {
         self.history_loading = true;
 
+        self.history_wakes = if self.history_period == HistoryPeriod::Today {
+            WakeLog::load()
+        } else {
+            WakeLog::default()
+        };
+
         if let Ok(mut client) = DaemonClient::connect() {
             self.daemon_connected = true;
 
@@ -39,6 +60,15 @@ impl App {
                 self.daily_cycles = cycles.into_iter().map(Into::into).collect();
             }
 
+            self.fetch_timeline(&mut client);
+
+            // The whole wear log, whatever the period: the fade projection
+            // needs the long view and there is one entry per day.
+            if let Ok(entries) = client.get_battery_health("1970-01-01", &to_date) {
+                let snapshots: Vec<_> = entries.into_iter().map(Into::into).collect();
+                self.battery_wear = WearLog::from_snapshots(&snapshots);
+            }
+
             let now = chrono::Utc::now();
             let session_window_days = self.history_period.days() as i64;
             let session_from = (now - chrono::Duration::days(session_window_days)).timestamp();
@@ -57,20 +87,100 @@ impl App {
                 if let Ok(hourly) = client.get_hourly_stats(start_of_day, end_ts) {
                     self.history_hourly_stats = hourly.into_iter().map(Into::into).collect();
                 }
+                if let Ok(apps) = client.get_top_apps(start_of_day, end_ts, true, 10) {
+                    self.history_top_apps = apps.into_iter().map(Into::into).collect();
+                }
+            } else {
+                self.history_top_apps.clear();
             }
         } else {
             self.daemon_connected = false;
             self.history_daily_stats.clear();
             self.history_hourly_stats.clear();
             self.history_top_processes.clear();
+            self.history_top_apps.clear();
             self.cycle_summary = None;
             self.recent_charge_sessions.clear();
             self.daily_cycles.clear();
+            self.battery_wear = WearLog::default();
+            self.history_samples.clear();
         }
 
         self.history_loading = false;
     }
 
+    /// Fetches the samples inside the history timeline's visible range.
+    pub(crate) fn load_timeline(&mut self) {
+        match DaemonClient::connect() {
+            Ok(mut client) => self.fetch_timeline(&mut client),
+            Err(_) => {
+                self.daemon_connected = false;
+                self.history_samples.clear();
+            }
+        }
+    }
+
+    fn fetch_timeline(&mut self, client: &mut DaemonClient) {
+        let (from, to) = self.history_range.window(chrono::Utc::now().timestamp());
+        if let Ok((samples, oldest)) = client.get_samples(from, to) {
+            self.history_samples = samples.into_iter().map(Into::into).collect();
+            self.history_oldest_sample = oldest;
+        }
+        self.history_samples_loaded_at = Some(Instant::now());
+    }
+
+    /// Refetches the timeline while the history view is open and following
+    /// live. Returns whether it was refetched.
+    pub(crate) fn refresh_live_timeline(&mut self) -> bool {
+        let due = self
+            .history_samples_loaded_at
+            .is_none_or(|at| at.elapsed() >= LIVE_TIMELINE_REFRESH);
+        if self.view != AppView::History
+            || !self.daemon_connected
+            || !self.history_range.is_following_live()
+            || !due
+        {
+            return false;
+        }
+        self.load_timeline();
+        true
+    }
+
+    /// Loads the last week of hourly stats as the battery's drain profile.
+    ///
+    /// Without the daemon the planner falls back to the current drain alone.
+    pub(crate) fn load_drain_profile(&mut self) {
+        let Ok(mut client) = DaemonClient::connect() else {
+            return;
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        let from = now - DRAIN_PROFILE_DAYS * 24 * 3600;
+        if let Ok(hourly) = client.get_hourly_stats(from, now) {
+            let stats: Vec<HourlyStat> = hourly.into_iter().map(Into::into).collect();
+            self.battery.set_hourly_profile(&stats);
+        }
+    }
+
+    /// Loads hourly stats for the last `heatmap_weeks` weeks into the power
+    /// heatmap, bucketed in the local time zone.
+    pub(crate) fn load_heatmap(&mut self) {
+        let Ok(mut client) = DaemonClient::connect() else {
+            self.daemon_connected = false;
+            self.heatmap = PowerHeatmap::default();
+            return;
+        };
+        self.daemon_connected = true;
+
+        let weeks = self.config.user_config.history.heatmap_weeks.max(1) as i64;
+        let now = chrono::Utc::now().timestamp();
+        let from = now - weeks * 7 * 24 * 3600;
+        if let Ok(hourly) = client.get_hourly_stats(from, now) {
+            let stats: Vec<HourlyStat> = hourly.into_iter().map(Into::into).collect();
+            self.heatmap = PowerHeatmap::from_hourly_stats(&stats, &chrono::Local);
+        }
+    }
+
     /// Calculates the date range for the current history period.
     ///
     /// Returns a tuple of (from_date, to_date) as ISO date strings (YYYY-MM-DD).
@@ -3,13 +3,18 @@
 //! This module contains the main App struct and related types for the TUI application.
 
 mod actions;
+mod bug_report;
+mod config_reload;
 mod daemon;
 mod history;
 mod process;
+mod session;
 mod theme;
 mod tui;
 pub mod types;
 
+pub use bug_report::BugReportStatus;
+pub use session::PendingResume;
 pub use tui::run_tui;
 
 use std::time::Duration;
@@ -17,17 +22,25 @@ use std::time::Duration;
 use color_eyre::eyre::Result;
 use tracing::{debug, info};
 
-use crate::config::{GraphMetric, RuntimeConfig, UserConfig};
+use crate::config::{config_path, GraphMetric, RuntimeConfig, UserConfig};
+use crate::config_watch::ConfigWatcher;
 use crate::daemon::CycleSummary;
 use crate::daemon::{DaemonClient, DaemonStatus, DataSnapshot, KillSignal};
+use crate::data::battery_wear::WearLog;
+use crate::data::fleet::Fleet;
+use crate::data::heatmap::PowerHeatmap;
+use crate::data::session_state::SessionState;
+use crate::data::timeline::TimeRange;
 use crate::data::{
-    BatteryData, ChargeSession, DailyCycle, DailyStat, DailyTopProcess, ForecastData, HistoryData,
-    HistoryMetric, HourlyStat, PowerData, ProcessData, ProcessInfo, SystemInfo, SystemStatsData,
+    all_data, AllData, AppEnergy, BatteryData, ChargeSession, DailyCycle, DailyStat,
+    DailyTopProcess, ForecastData, HistoryData, HistoryMetric, HourlyStat, MemoryData, PowerData,
+    ProcessData, ProcessInfo, RefreshCoordinator, RefreshStage, RefreshTimings, Sample, SystemInfo,
+    SystemStatsData, WakeLog,
 };
 use jolt_theme::cache::ThemeGroup;
 use jolt_theme::NamedTheme;
 
-pub use types::{Action, AppView, HistoryPeriod, SortColumn};
+pub use types::{Action, AppView, HistoryPeriod, SortColumn, MAX_REFRESH_MS, MIN_REFRESH_MS};
 
 /// How many ticks between forecast refreshes.
 const FORECAST_REFRESH_TICKS: u32 = 10;
@@ -35,6 +48,16 @@ const FORECAST_REFRESH_TICKS: u32 = 10;
 /// Interval for checking system theme changes.
 const THEME_CHECK_INTERVAL: Duration = Duration::from_secs(2);
 
+/// How often peers listed in `fleet_peers` are polled.
+const FLEET_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long a status bar toast stays visible.
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+/// Longest a local refresh holds up the UI for slow subsystems; they show
+/// their previous values until a later tick catches up.
+const LOCAL_REFRESH_DEADLINE: Duration = Duration::from_millis(250);
+
 /// Main application state for the TUI.
 ///
 /// This struct holds all the runtime state needed to render the TUI and
@@ -48,8 +71,10 @@ pub struct App {
     pub power: PowerData,
     pub processes: ProcessData,
     pub system_stats: SystemStatsData,
+    pub memory: MemoryData,
     pub history: HistoryData,
     pub forecast: ForecastData,
+    pub refresh_timings: RefreshTimings,
     pub selected_process_index: usize,
     pub process_scroll_offset: usize,
     pub expanded_groups: std::collections::HashSet<u32>,
@@ -77,22 +102,65 @@ pub struct App {
     pub history_period: HistoryPeriod,
     pub history_daily_stats: Vec<DailyStat>,
     pub history_hourly_stats: Vec<HourlyStat>,
+    pub history_wakes: WakeLog,
+    /// Visible window of the history view timeline.
+    pub history_range: TimeRange,
+    /// Stored samples inside `history_range`.
+    pub history_samples: Vec<Sample>,
+    pub history_oldest_sample: Option<i64>,
+    history_samples_loaded_at: Option<std::time::Instant>,
     pub history_top_processes: Vec<DailyTopProcess>,
+    /// Energy per app so far today, for the Today period.
+    pub history_top_apps: Vec<AppEnergy>,
     pub history_loading: bool,
+    pub heatmap: PowerHeatmap,
     pub cycle_summary: Option<CycleSummary>,
     pub recent_charge_sessions: Vec<ChargeSession>,
     pub daily_cycles: Vec<DailyCycle>,
+    pub battery_wear: WearLog,
     pub daemon_status: Option<DaemonStatus>,
     pub daemon_connected: bool,
     pub settings_selected_item: usize,
+    /// Settings were changed from the settings panel since the config file
+    /// was last written.
+    pub settings_unsaved: bool,
+    /// Closing the settings panel is waiting on whether to write the changes
+    /// to the config file.
+    pub settings_save_prompt: bool,
+    /// Peers for the fleet view; `None` when no `fleet_peers` are configured.
+    pub fleet: Option<Fleet>,
+    pub bug_report: BugReportStatus,
+    /// Counters for this session, including resumed earlier runs.
+    pub session: SessionState,
+    /// Session saved at the last clean quit, until resumed or discarded.
+    pub pending_resume: Option<PendingResume>,
+    pub(crate) last_session_tick: Option<std::time::Instant>,
+    /// Target time typed into the "will it last until" prompt, as `HH:MM`.
+    pub last_until_input: String,
     pub(crate) daemon_subscription: Option<DaemonClient>,
     pub(crate) last_snapshot: Option<DataSnapshot>,
     pub using_daemon_data: bool,
+    /// Short-lived confirmation shown in the status bar.
+    pub(crate) toast: Option<(String, std::time::Instant)>,
     pub(crate) last_daemon_update: Option<std::time::Instant>,
     pub(crate) reconnect_attempts: u32,
     pub(crate) last_reconnect_attempt: Option<std::time::Instant>,
     last_theme_check: std::time::Instant,
     pub(crate) snapshot_rx: Option<std::sync::mpsc::Receiver<DataSnapshot>>,
+    /// Refreshes local data while not using the daemon; started on the
+    /// first local tick.
+    pub(crate) coordinator: Option<RefreshCoordinator>,
+    pub(crate) config_watcher: ConfigWatcher,
+}
+
+fn history_metric(metric: GraphMetric) -> HistoryMetric {
+    match metric {
+        GraphMetric::Power => HistoryMetric::Power,
+        GraphMetric::Battery => HistoryMetric::Battery,
+        GraphMetric::Split => HistoryMetric::Split,
+        GraphMetric::Merged => HistoryMetric::Merged,
+        GraphMetric::Domains => HistoryMetric::Domains,
+    }
 }
 
 impl App {
@@ -105,31 +173,47 @@ impl App {
 
         let refresh_ms = user_config.refresh_ms;
         let merge_mode = user_config.merge_mode;
-        let graph_metric = match user_config.graph_metric {
-            GraphMetric::Power => HistoryMetric::Power,
-            GraphMetric::Battery => HistoryMetric::Battery,
-            GraphMetric::Split => HistoryMetric::Split,
-            GraphMetric::Merged => HistoryMetric::Merged,
-        };
+        let graph_metric = history_metric(user_config.graph_metric);
         let excluded = user_config
             .effective_excluded_processes()
             .into_iter()
             .map(|s| s.to_string())
             .collect();
+        let fleet = (!user_config.fleet_peers.is_empty())
+            .then(|| Fleet::spawn(user_config.fleet_peers.clone(), FLEET_POLL_INTERVAL));
+        let timeline_zoom = user_config.history.timeline_zoom;
+        let pending_resume = session::take_pending_resume(user_config.session_resume_mins);
+        let view = if pending_resume.is_some() {
+            AppView::ResumeSession
+        } else {
+            AppView::Main
+        };
+        let config_watcher = ConfigWatcher::watch(config_path(), &user_config);
         let config = RuntimeConfig::new(user_config);
 
+        let AllData {
+            battery,
+            power,
+            mut processes,
+            mut history,
+        } = all_data()?;
+        processes.set_exclusions(excluded);
+        history.current_metric = graph_metric;
+
         debug!("Data sources initialized");
 
         let mut app = Self {
             config,
-            view: AppView::Main,
+            view,
             system_info: SystemInfo::new(),
-            battery: BatteryData::new()?,
-            power: PowerData::new()?,
-            processes: ProcessData::with_exclusions(excluded)?,
+            battery,
+            power,
+            processes,
             system_stats: SystemStatsData::new()?,
-            history: HistoryData::with_metric(graph_metric),
+            memory: MemoryData::new()?,
+            history,
             forecast: ForecastData::new(),
+            refresh_timings: RefreshTimings::new(),
             selected_process_index: 0,
             process_scroll_offset: 0,
             expanded_groups: std::collections::HashSet::new(),
@@ -157,24 +241,49 @@ impl App {
             history_period: HistoryPeriod::default(),
             history_daily_stats: Vec::new(),
             history_hourly_stats: Vec::new(),
+            history_wakes: WakeLog::default(),
+            history_range: TimeRange::new(timeline_zoom),
+            history_samples: Vec::new(),
+            history_oldest_sample: None,
+            history_samples_loaded_at: None,
             history_top_processes: Vec::new(),
+            history_top_apps: Vec::new(),
             history_loading: false,
+            heatmap: PowerHeatmap::default(),
             cycle_summary: None,
             recent_charge_sessions: Vec::new(),
             daily_cycles: Vec::new(),
+            battery_wear: WearLog::default(),
             daemon_status: None,
             daemon_connected: false,
             settings_selected_item: crate::settings::first_selectable_index(),
+            settings_unsaved: false,
+            settings_save_prompt: false,
+            bug_report: BugReportStatus::default(),
+            session: session::new_session(),
+            pending_resume,
+            last_session_tick: None,
+            last_until_input: String::new(),
+            fleet,
             daemon_subscription: None,
             last_snapshot: None,
             using_daemon_data: false,
+            toast: None,
             last_daemon_update: None,
             reconnect_attempts: 0,
             last_reconnect_attempt: None,
             last_theme_check: std::time::Instant::now(),
             snapshot_rx: None,
+            coordinator: None,
+            config_watcher,
         };
 
+        app.battery.set_advice_config(app.config.user_config.advice);
+        app.power
+            .set_smoothing_samples(app.config.user_config.power_smoothing_samples);
+        app.power.set_sample_interval(Duration::from_millis(
+            app.config.user_config.power_sample_ms,
+        ));
         app.try_connect_daemon();
 
         Ok(app)
@@ -184,6 +293,7 @@ impl App {
     ///
     /// This method:
     /// - Checks for system theme changes
+    /// - Applies edits to the config file
     /// - Updates data from the daemon or local sources
     /// - Records history data points
     /// - Refreshes the forecast periodically
@@ -219,6 +329,8 @@ impl App {
         if !self.using_daemon_data {
             self.system_stats.refresh()?;
         }
+        // The daemon does not sample memory, so it is always read locally
+        self.memory.refresh()?;
 
         if data_updated {
             self.tick_count = self.tick_count.wrapping_add(1);
@@ -226,14 +338,37 @@ impl App {
             self.history.record(
                 self.battery.charge_percent(),
                 self.power.total_power_watts(),
+                self.power.cpu_power_watts(),
+                self.power.gpu_power_watts(),
                 self.battery.temperature_c(),
             );
+            self.history.set_latest_soc_power(self.power.soc_power());
+            self.history.set_latest_memory(self.memory.sample());
+            self.history.set_latest_gpu_top_state(
+                self.power
+                    .gpu_residency()
+                    .map(|r| r.top_state_fraction() * 100.0),
+            );
+
+            self.record_session();
 
             if !self.using_daemon_data && self.tick_count.is_multiple_of(FORECAST_REFRESH_TICKS) {
                 self.refresh_forecast();
             }
         }
 
+        let bug_report_changed = self.poll_bug_report();
+        let config_reloaded = self.poll_config_reload(std::time::Instant::now());
+        let timeline_changed = self.refresh_live_timeline();
+
+        let toast_expired = self
+            .toast
+            .as_ref()
+            .is_some_and(|(_, shown_at)| shown_at.elapsed() >= TOAST_DURATION);
+        if toast_expired {
+            self.toast = None;
+        }
+
         debug!(
             data_updated,
             theme_changed,
@@ -242,31 +377,98 @@ impl App {
             "App::tick result"
         );
 
-        Ok(data_updated || theme_changed)
+        Ok(data_updated
+            || theme_changed
+            || toast_expired
+            || bug_report_changed
+            || timeline_changed
+            || config_reloaded)
+    }
+
+    /// Shows a message in the status bar for a few seconds.
+    pub fn show_toast(&mut self, message: impl Into<String>) {
+        self.toast = Some((message.into(), std::time::Instant::now()));
+    }
+
+    /// The toast message, if one is still visible.
+    pub fn active_toast(&self) -> Option<&str> {
+        self.toast
+            .as_ref()
+            .filter(|(_, shown_at)| shown_at.elapsed() < TOAST_DURATION)
+            .map(|(message, _)| message.as_str())
+    }
+
+    /// Config keys edited in the file that take effect on the next start.
+    pub fn config_pending_restart(&self) -> &[String] {
+        self.config_watcher.pending_restart()
     }
 
     /// Updates data from local sources (battery, power, processes).
     ///
     /// This is used when not connected to the daemon, or as a fallback
-    /// when daemon data is unavailable.
+    /// when daemon data is unavailable. The refresh goes through a
+    /// [`RefreshCoordinator`], so the values shown together were read at
+    /// the same time.
     fn tick_from_local(&mut self) -> Result<()> {
         let start = std::time::Instant::now();
 
-        self.battery.refresh()?;
-        let battery_time = start.elapsed();
+        if self.coordinator.is_none() {
+            let excluded = self
+                .config
+                .user_config
+                .effective_excluded_processes()
+                .into_iter()
+                .map(|s| s.to_string())
+                .collect();
+            let coordinator =
+                RefreshCoordinator::with_exclusions(LOCAL_REFRESH_DEADLINE, excluded)?;
+            coordinator.set_process_network_sampling(self.config.user_config.process_network);
+            self.coordinator = Some(coordinator);
+        }
+        let Some(coordinator) = self.coordinator.as_mut() else {
+            return Ok(());
+        };
+        let snapshot = coordinator.refresh(&mut self.power);
+
+        for (subsystem, age) in snapshot.stale_subsystems() {
+            debug!(
+                subsystem,
+                age_ms = age.as_millis() as u64,
+                "Subsystem missed the refresh deadline"
+            );
+        }
+
+        self.battery.update_from_snapshot(&snapshot.battery.value);
+        if !snapshot.battery.stale {
+            self.refresh_timings
+                .record(RefreshStage::Battery, snapshot.battery.refresh_time);
+        }
 
-        self.power.refresh()?;
-        let power_time = start.elapsed() - battery_time;
+        if self.power.reset_on_mode_change() {
+            debug!(
+                mode = self.power.power_mode_label(),
+                "Power mode changed, restarting power averages"
+            );
+        }
+        // Sampling runs on the power worker; time its refreshes, not the
+        // drain.
+        for &time in self.power.last_refresh_times() {
+            self.refresh_timings.record(RefreshStage::Power, time);
+        }
 
         if !self.selection_mode {
-            self.processes.refresh()?;
+            self.processes
+                .update_from_snapshots(snapshot.processes.value);
+            if !snapshot.processes.stale {
+                self.refresh_timings
+                    .record(RefreshStage::Processes, snapshot.processes.refresh_time);
+            }
         }
-        let process_time = start.elapsed() - battery_time - power_time;
 
         debug!(
-            battery_ms = battery_time.as_millis() as u64,
-            power_ms = power_time.as_millis() as u64,
-            process_ms = process_time.as_millis() as u64,
+            battery_ms = snapshot.battery.refresh_time.as_millis() as u64,
+            power_ms = snapshot.power.refresh_time.as_millis() as u64,
+            process_ms = snapshot.processes.refresh_time.as_millis() as u64,
             total_ms = start.elapsed().as_millis() as u64,
             battery_percent = self.battery.charge_percent(),
             battery_state = self.battery.state_label(),
@@ -344,9 +546,12 @@ impl App {
 
     /// Performs cleanup before the application exits.
     ///
-    /// This optionally shuts down the daemon if background recording is disabled,
-    /// and unsubscribes from daemon updates.
+    /// This saves the session for the next launch, optionally shuts down the
+    /// daemon if background recording is disabled, and unsubscribes from
+    /// daemon updates.
     pub fn cleanup(&mut self) {
+        self.save_session();
+
         // Drop the snapshot receiver to signal background thread to exit
         self.snapshot_rx = None;
 
@@ -6,7 +6,7 @@
 use std::collections::HashMap;
 
 use crate::daemon::{DaemonClient, KillSignal};
-use crate::data::ProcessInfo;
+use crate::data::{normalize_cpu, NormalizedCpu, ProcessInfo};
 
 use super::types::SortColumn;
 use super::App;
@@ -36,6 +36,24 @@ fn get_base_process_name(name: &str) -> String {
 }
 
 impl App {
+    /// Turns per-process network sampling on or off for local refreshes.
+    /// The daemon samples by its own config.
+    pub fn set_process_network_sampling(&mut self, enabled: bool) {
+        if let Some(coordinator) = &self.coordinator {
+            coordinator.set_process_network_sampling(enabled);
+        }
+    }
+
+    /// `cpu_usage` scaled to the configured normalization, using the core
+    /// count of this machine (or the daemon's, when connected).
+    pub fn normalized_cpu(&self, cpu_usage: f32) -> NormalizedCpu {
+        normalize_cpu(
+            cpu_usage,
+            self.config.user_config.units.cpu,
+            self.system_info.core_count(),
+        )
+    }
+
     /// Returns the count of currently visible processes.
     pub fn visible_process_count(&self) -> usize {
         self.get_visible_processes().len()
@@ -52,6 +70,11 @@ impl App {
         } else {
             self.processes.processes.clone()
         };
+        let processes = if self.config.user_config.hide_system_processes {
+            processes.into_iter().filter(|p| p.is_killable).collect()
+        } else {
+            processes
+        };
 
         let sorted = if self.merge_mode {
             self.merge_processes(processes)
@@ -99,6 +122,18 @@ impl App {
                     cmp.reverse()
                 }
             }),
+            SortColumn::Network => sorted.sort_by(|a, b| {
+                let cmp = a
+                    .network_bytes_per_sec
+                    .unwrap_or(0.0)
+                    .partial_cmp(&b.network_bytes_per_sec.unwrap_or(0.0))
+                    .unwrap_or(std::cmp::Ordering::Equal);
+                if asc {
+                    cmp
+                } else {
+                    cmp.reverse()
+                }
+            }),
             SortColumn::Energy => sorted.sort_by(|a, b| {
                 let cmp = a
                     .energy_impact
@@ -150,6 +185,12 @@ impl App {
                 existing.disk_write_bytes += process.disk_write_bytes;
                 existing.total_cpu_time_secs += process.total_cpu_time_secs;
                 existing.run_time_secs = existing.run_time_secs.max(process.run_time_secs);
+                if let Some(rate) = process.network_bytes_per_sec {
+                    *existing.network_bytes_per_sec.get_or_insert(0.0) += rate;
+                }
+                if let Some(count) = process.power_assertion_count {
+                    *existing.power_assertion_count.get_or_insert(0) += count;
+                }
                 if let Some(ref mut children) = existing.children {
                     children.push(process);
                     existing.name = format!("{} ({})", base_name, children.len());
@@ -171,6 +212,13 @@ impl App {
                     status: process.status,
                     run_time_secs: process.run_time_secs,
                     total_cpu_time_secs: process.total_cpu_time_secs,
+                    network_bytes_per_sec: process.network_bytes_per_sec,
+                    sandbox_container: process.sandbox_container.clone(),
+                    executable_path: process.executable_path.clone(),
+                    start_time: process.start_time,
+                    team_id: process.team_id.clone(),
+                    binary_arch: process.binary_arch,
+                    power_assertion_count: process.power_assertion_count,
                 };
                 merged.insert(base_name, group);
             }
@@ -0,0 +1,99 @@
+//! Session counters and their handoff across restarts.
+//!
+//! Counters are updated every tick, written on a clean quit and offered for
+//! resuming at the next launch; see [`crate::data::session_state`].
+
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+
+use crate::data::session_state::{
+    current_boot_time, session_state_path, ResumeOffer, SessionState,
+};
+
+use super::types::AppView;
+use super::App;
+
+/// Processes whose estimated power is added to the per-app totals each tick.
+const SESSION_APP_COUNT: usize = 20;
+
+/// A saved session waiting for the user to resume or discard it.
+#[derive(Debug, Clone)]
+pub struct PendingResume {
+    pub offer: ResumeOffer,
+    pub saved: SessionState,
+}
+
+/// The saved session, if any, and whether to offer it given the resume
+/// window in minutes. Reading it removes the file.
+pub(super) fn take_pending_resume(window_mins: u64) -> Option<PendingResume> {
+    let saved = SessionState::take(&session_state_path())?;
+    let now = chrono::Utc::now().timestamp();
+    let window = Duration::from_secs(window_mins * 60);
+    let offer = saved.resume_offer(now, current_boot_time(), window)?;
+    info!(
+        ?offer,
+        saved_at = saved.saved_at,
+        "Offering to resume session"
+    );
+    Some(PendingResume { offer, saved })
+}
+
+/// A session starting now.
+pub(super) fn new_session() -> SessionState {
+    SessionState::new(chrono::Utc::now().timestamp(), current_boot_time())
+}
+
+impl App {
+    /// Adds the time since the previous call to the session counters.
+    pub(crate) fn record_session(&mut self) {
+        let now = Instant::now();
+        let Some(last) = self.last_session_tick.replace(now) else {
+            return;
+        };
+        let apps = self
+            .processes
+            .top_energy_consumers(SESSION_APP_COUNT, &self.power);
+        self.session.record(
+            now.duration_since(last),
+            self.power.total_power_watts(),
+            !self.battery.external_connected(),
+            apps.iter()
+                .filter_map(|(process, watts)| Some((process.name.as_str(), (*watts)?))),
+        );
+    }
+
+    /// Answers the resume prompt: merges the saved counters into this
+    /// session, or drops them.
+    pub(crate) fn answer_resume(&mut self, resume: bool) {
+        if let Some(pending) = self.pending_resume.take() {
+            if resume {
+                self.session.merge_previous(pending.saved, pending.offer);
+                self.show_toast(match pending.offer {
+                    ResumeOffer::Resume => "Session resumed",
+                    ResumeOffer::ResumeAfterReboot => "Session resumed after reboot",
+                });
+            }
+        }
+        if self.view == AppView::ResumeSession {
+            self.view = AppView::Main;
+        }
+    }
+
+    /// Writes the session for the next launch. A prompt still unanswered
+    /// keeps the saved session as it was, so the next launch can offer it
+    /// again while its window lasts.
+    pub(super) fn save_session(&mut self) {
+        let path = session_state_path();
+        let result = match self.pending_resume.take() {
+            Some(mut pending) => {
+                let saved_at = pending.saved.saved_at;
+                pending.saved.save(&path, saved_at)
+            }
+            None => self.session.save(&path, chrono::Utc::now().timestamp()),
+        };
+        if let Err(e) = result {
+            warn!(error = %e, path = %path.display(), "Failed to save session");
+        }
+    }
+}
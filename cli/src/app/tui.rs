@@ -8,6 +8,7 @@ use std::time::Duration;
 
 use color_eyre::eyre::Result;
 use crossterm::{
+    cursor::Show,
     event::{self, Event, KeyEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -16,6 +17,8 @@ use ratatui::prelude::*;
 use tracing::{debug, trace};
 
 use crate::config::UserConfig;
+use crate::crash;
+use crate::data::RefreshStage;
 use crate::input;
 use crate::ui;
 
@@ -43,11 +46,19 @@ fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Re
     Ok(())
 }
 
+/// Best-effort [`restore_terminal`] for the panic hook, which has no
+/// `Terminal` to hand and must not fail.
+fn restore_terminal_after_panic() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, Show);
+}
+
 /// Entry point for running the TUI application.
 ///
 /// Sets up the terminal, runs the main event loop, and restores the terminal
 /// when finished. This is the main entry point called from the CLI handler.
 pub fn run_tui(user_config: UserConfig) -> Result<()> {
+    crash::install_panic_hook(restore_terminal_after_panic);
     let mut terminal = setup_terminal()?;
     let result = run_tui_loop(&mut terminal, user_config);
     restore_terminal(&mut terminal)?;
@@ -109,7 +120,10 @@ fn run_tui_loop(
         needs_redraw = needs_redraw || data_changed;
 
         if needs_redraw {
+            let render_start = std::time::Instant::now();
             terminal.draw(|frame| ui::render(frame, &mut app))?;
+            app.refresh_timings
+                .record(RefreshStage::Render, render_start.elapsed());
             needs_redraw = false;
         }
 
@@ -52,10 +52,29 @@ pub enum Action {
     ToggleHistory,
     HistoryPrevPeriod,
     HistoryNextPeriod,
+    HistoryZoomIn,
+    HistoryZoomOut,
+    HistoryPanBack,
+    HistoryPanForward,
+    HistoryFollowLive,
     SettingsToggleValue,
     SettingsIncrement,
     SettingsDecrement,
+    SettingsSave,
+    SettingsKeepUnsaved,
     ToggleBatteryDetails,
+    ToggleChargerDetails,
+    ToggleDebugPanel,
+    CopyToClipboard,
+    ToggleLastUntil,
+    LastUntilChar(char),
+    LastUntilBackspace,
+    ToggleFleet,
+    ToggleHeatmap,
+    ToggleBugReport,
+    ConfirmBugReport,
+    ResumeSession,
+    DiscardSession,
     None,
 }
 
@@ -118,6 +137,7 @@ pub enum SortColumn {
     Name,
     Cpu,
     Memory,
+    Network,
     #[default]
     Energy,
 }
@@ -129,7 +149,8 @@ impl SortColumn {
             SortColumn::Pid => SortColumn::Name,
             SortColumn::Name => SortColumn::Cpu,
             SortColumn::Cpu => SortColumn::Memory,
-            SortColumn::Memory => SortColumn::Energy,
+            SortColumn::Memory => SortColumn::Network,
+            SortColumn::Network => SortColumn::Energy,
             SortColumn::Energy => SortColumn::Pid,
         }
     }
@@ -147,6 +168,14 @@ pub enum AppView {
     History,
     Settings,
     BatteryDetails,
+    ChargerDetails,
+    DebugPanel,
+    LastUntil,
+    Fleet,
+    Heatmap,
+    BugReport,
+    /// Offer to resume the session saved at the last clean quit.
+    ResumeSession,
 }
 
 #[cfg(test)]
@@ -227,7 +256,8 @@ mod tests {
         assert_eq!(SortColumn::Pid.next(), SortColumn::Name);
         assert_eq!(SortColumn::Name.next(), SortColumn::Cpu);
         assert_eq!(SortColumn::Cpu.next(), SortColumn::Memory);
-        assert_eq!(SortColumn::Memory.next(), SortColumn::Energy);
+        assert_eq!(SortColumn::Memory.next(), SortColumn::Network);
+        assert_eq!(SortColumn::Network.next(), SortColumn::Energy);
         assert_eq!(SortColumn::Energy.next(), SortColumn::Pid);
     }
 
@@ -239,7 +269,7 @@ mod tests {
     #[test]
     fn sort_column_full_cycle_returns_to_start() {
         let start = SortColumn::Pid;
-        let result = start.next().next().next().next().next();
+        let result = start.next().next().next().next().next().next();
         assert_eq!(result, start);
     }
 
@@ -248,13 +278,18 @@ mod tests {
         assert_ne!(SortColumn::Pid, SortColumn::Name);
         assert_ne!(SortColumn::Pid, SortColumn::Cpu);
         assert_ne!(SortColumn::Pid, SortColumn::Memory);
+        assert_ne!(SortColumn::Pid, SortColumn::Network);
         assert_ne!(SortColumn::Pid, SortColumn::Energy);
         assert_ne!(SortColumn::Name, SortColumn::Cpu);
         assert_ne!(SortColumn::Name, SortColumn::Memory);
+        assert_ne!(SortColumn::Name, SortColumn::Network);
         assert_ne!(SortColumn::Name, SortColumn::Energy);
         assert_ne!(SortColumn::Cpu, SortColumn::Memory);
+        assert_ne!(SortColumn::Cpu, SortColumn::Network);
         assert_ne!(SortColumn::Cpu, SortColumn::Energy);
+        assert_ne!(SortColumn::Memory, SortColumn::Network);
         assert_ne!(SortColumn::Memory, SortColumn::Energy);
+        assert_ne!(SortColumn::Network, SortColumn::Energy);
     }
 
     // Action tests
@@ -319,6 +354,7 @@ mod tests {
         assert_ne!(AppView::Main, AppView::History);
         assert_ne!(AppView::Main, AppView::Settings);
         assert_ne!(AppView::Main, AppView::BatteryDetails);
+        assert_ne!(AppView::Main, AppView::DebugPanel);
     }
 
     #[test]
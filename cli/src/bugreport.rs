@@ -0,0 +1,500 @@
+//! Redacted bug-report bundles.
+//!
+//! Collects the debug report, recent log lines, the power channel listing,
+//! the battery registry, the active config and a few `jolt pipe` samples
+//! into one `.tar.gz` under the temp directory. Hostnames, usernames, the
+//! home path, the battery serial and fleet peers are redacted unless
+//! identifiers are explicitly included.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{bail, Result};
+use jolt_platform::command::{self, CommandLimits};
+
+use crate::commands::{debug, doctor, pipe};
+use crate::config::{self, UserConfig};
+use crate::data::{BatteryData, PowerData, ProcessRedactor, RefreshCoordinator, SystemInfo};
+
+/// Default window of log lines included, in minutes.
+pub const DEFAULT_LOG_MINUTES: u64 = 10;
+
+/// Minimum time between two bundles, so a repeated key press or a retry
+/// loop cannot fill the temp directory.
+const MIN_BUNDLE_INTERVAL: Duration = Duration::from_secs(60);
+
+const SESSION_SAMPLES: usize = 5;
+const SESSION_INTERVAL: Duration = Duration::from_secs(1);
+
+const STAMP_FILE: &str = "bugreport.last";
+
+#[derive(Debug, Clone, Copy)]
+pub struct BundleOptions {
+    pub log_minutes: u64,
+    pub include_identifiers: bool,
+}
+
+impl Default for BundleOptions {
+    fn default() -> Self {
+        Self {
+            log_minutes: DEFAULT_LOG_MINUTES,
+            include_identifiers: false,
+        }
+    }
+}
+
+/// A file in the bundle, listed to the user before anything is collected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleFile {
+    Doctor,
+    Log,
+    Channels,
+    BatteryRegistry,
+    Config,
+    Session,
+}
+
+impl BundleFile {
+    pub const ALL: [BundleFile; 6] = [
+        BundleFile::Doctor,
+        BundleFile::Log,
+        BundleFile::Channels,
+        BundleFile::BatteryRegistry,
+        BundleFile::Config,
+        BundleFile::Session,
+    ];
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            BundleFile::Doctor => "doctor.txt",
+            BundleFile::Log => "log.txt",
+            BundleFile::Channels => "channels.txt",
+            BundleFile::BatteryRegistry => "battery-registry.txt",
+            BundleFile::Config => "config.toml",
+            BundleFile::Session => "session.jsonl",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            BundleFile::Doctor => "jolt debug and jolt doctor output",
+            BundleFile::Log => "recent log lines",
+            BundleFile::Channels => "power channel readings",
+            BundleFile::BatteryRegistry => "battery registry dump",
+            BundleFile::Config => "active config",
+            BundleFile::Session => "short recorded session",
+        }
+    }
+
+    fn collect(&self, options: &BundleOptions) -> String {
+        let collected = match self {
+            BundleFile::Doctor => doctor_report(),
+            BundleFile::Log => Ok(recent_log(options.log_minutes)),
+            BundleFile::Channels => channel_listing(),
+            BundleFile::BatteryRegistry => Ok(battery_registry()),
+            BundleFile::Config => config_toml(UserConfig::load(), options.include_identifiers),
+            BundleFile::Session => recorded_session(),
+        };
+        collected.unwrap_or_else(|e| format!("unavailable: {e}\n"))
+    }
+}
+
+/// What redaction hides, for the confirmation prompt.
+pub fn redaction_summary(include_identifiers: bool) -> &'static str {
+    if include_identifiers {
+        "Identifiers are included (hostname, username, home path, battery serial, fleet peers)."
+    } else {
+        "Hostname, username, home path, battery serial and fleet peers are redacted."
+    }
+}
+
+/// Collects every [`BundleFile`] and archives them, returning the path of
+/// the `.tar.gz`.
+///
+/// Refuses to run again within a minute of the previous bundle. A piece that
+/// cannot be collected is written as a note instead of failing the bundle.
+pub fn create(options: &BundleOptions) -> Result<PathBuf> {
+    let stamp = config::cache_dir().join(STAMP_FILE);
+    let now = Utc::now().timestamp();
+    let last = fs::read_to_string(&stamp)
+        .ok()
+        .and_then(|s| s.trim().parse::<i64>().ok());
+    if let Some(wait) = rate_limit_remaining(last, now) {
+        bail!(
+            "a bug report was created moments ago; try again in {}s",
+            wait.as_secs().max(1)
+        );
+    }
+    fs::write(&stamp, now.to_string())?;
+
+    let redactor = if options.include_identifiers {
+        Redactor::default()
+    } else {
+        Redactor::for_this_machine()
+    };
+
+    let name = format!("jolt-bugreport-{}", Utc::now().format("%Y%m%d-%H%M%S"));
+    let staging = std::env::temp_dir().join(&name);
+    fs::create_dir_all(&staging)?;
+    for file in BundleFile::ALL {
+        let contents = redactor.apply(&file.collect(options));
+        fs::write(staging.join(file.file_name()), contents)?;
+    }
+
+    let archive = std::env::temp_dir().join(format!("{name}.tar.gz"));
+    let result = archive_dir(&staging, &archive);
+    let _ = fs::remove_dir_all(&staging);
+    result?;
+    Ok(archive)
+}
+
+fn archive_dir(dir: &Path, archive: &Path) -> Result<()> {
+    // `--numeric-owner` keeps the local user and group names out of the
+    // archive headers; GNU tar and bsdtar both accept it.
+    let output = command::run(
+        Command::new("tar")
+            .arg("--numeric-owner")
+            .arg("-czf")
+            .arg(archive)
+            .arg("-C")
+            .arg(dir)
+            .args(BundleFile::ALL.iter().map(|f| f.file_name())),
+        CommandLimits::DEFAULT.with_timeout(Duration::from_secs(30)),
+    )?;
+    if !output.success() {
+        bail!(
+            "tar exited with {}: {}",
+            output.status,
+            output.stderr_lossy().trim()
+        );
+    }
+    Ok(())
+}
+
+/// Time left before another bundle may be created.
+fn rate_limit_remaining(last: Option<i64>, now: i64) -> Option<Duration> {
+    let elapsed = now - last?;
+    let min = MIN_BUNDLE_INTERVAL.as_secs() as i64;
+    (0..min)
+        .contains(&elapsed)
+        .then(|| Duration::from_secs((min - elapsed) as u64))
+}
+
+fn doctor_report() -> Result<String> {
+    let mut out = Vec::new();
+    debug::write_report(&mut out)?;
+    doctor::write_report(&mut out)?;
+    Ok(String::from_utf8_lossy(&out).into_owned())
+}
+
+fn recent_log(minutes: u64) -> String {
+    let Some(path) = latest_log_file() else {
+        return "no log files found\n".to_string();
+    };
+    match fs::read_to_string(&path) {
+        Ok(contents) => {
+            let since = Utc::now() - chrono::Duration::minutes(minutes as i64);
+            recent_log_lines(&contents, since)
+        }
+        Err(e) => format!("unavailable: {e}\n"),
+    }
+}
+
+fn latest_log_file() -> Option<PathBuf> {
+    let mut logs: Vec<PathBuf> = fs::read_dir(config::runtime_dir())
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy())
+                .is_some_and(|n| n.starts_with("jolt.") && n.ends_with(".log"))
+        })
+        .collect();
+    logs.sort();
+    logs.pop()
+}
+
+/// Log lines stamped at or after `since`. Lines without a leading RFC 3339
+/// timestamp (multi-line messages) follow the line before them.
+fn recent_log_lines(contents: &str, since: DateTime<Utc>) -> String {
+    let mut keep = false;
+    let mut out = String::new();
+    for line in contents.lines() {
+        let stamp = line
+            .split_whitespace()
+            .next()
+            .and_then(|token| DateTime::parse_from_rfc3339(token).ok());
+        if let Some(stamp) = stamp {
+            keep = stamp >= since;
+        }
+        if keep {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn channel_listing() -> Result<String> {
+    let mut power = PowerData::new()?;
+    power.set_sample_interval(Duration::from_millis(500));
+    power.wait_for_sample(Duration::from_secs(2))?;
+
+    let channels = power.raw_channels();
+    if channels.is_empty() {
+        return Ok("no per-channel readings on this platform\n".to_string());
+    }
+    Ok(channels
+        .iter()
+        .map(|c| {
+            let note = if c.aggregate {
+                "  (aggregate, not summed)"
+            } else {
+                ""
+            };
+            format!("{:<24} {:>8.3} W{}\n", c.name, c.watts, note)
+        })
+        .collect())
+}
+
+fn battery_registry() -> String {
+    if cfg!(target_os = "macos") {
+        return match command::run(
+            Command::new("ioreg").args(["-rn", "AppleSmartBattery"]),
+            CommandLimits::DEFAULT,
+        ) {
+            Ok(output) => output.stdout_lossy(),
+            Err(e) => format!("unavailable: {e}\n"),
+        };
+    }
+
+    let Ok(entries) = fs::read_dir("/sys/class/power_supply") else {
+        return "no power supplies found\n".to_string();
+    };
+    let mut supplies: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    supplies.sort();
+    let dump: String = supplies
+        .iter()
+        .filter_map(|supply| {
+            let uevent = fs::read_to_string(supply.join("uevent")).ok()?;
+            Some(format!("== {} ==\n{}\n", supply.display(), uevent))
+        })
+        .collect();
+    if dump.is_empty() {
+        return "no power supplies found\n".to_string();
+    }
+    dump
+}
+
+/// The config as TOML, with fleet peers replaced by placeholders unless
+/// identifiers are included. Redaction patterns are always replaced, as they
+/// name the very processes the user wants kept out of shared data.
+fn config_toml(mut config: UserConfig, include_identifiers: bool) -> Result<String> {
+    if !include_identifiers {
+        for (index, peer) in config.fleet_peers.iter_mut().enumerate() {
+            *peer = format!("<peer-{}>", index + 1);
+        }
+    }
+    for (index, pattern) in config.redact_processes.iter_mut().enumerate() {
+        *pattern = format!("<pattern-{}>", index + 1);
+    }
+    Ok(toml::to_string_pretty(&config)?)
+}
+
+fn recorded_session() -> Result<String> {
+    let cores = SystemInfo::new().core_count();
+    let config = UserConfig::load();
+    let cpu_normalization = config.units.cpu;
+    let mut redactor = ProcessRedactor::new(&config.redact_processes);
+    let mut coordinator = RefreshCoordinator::new(SESSION_INTERVAL)?;
+    let mut power = PowerData::new()?;
+    let mut out = String::new();
+    for sample in 0..SESSION_SAMPLES {
+        if sample > 0 {
+            std::thread::sleep(SESSION_INTERVAL);
+        }
+        let snapshot = coordinator.refresh(&mut power);
+        let doc = pipe::snapshot_json(&snapshot, cpu_normalization, cores, &mut redactor);
+        out.push_str(&serde_json::to_string(&doc)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Replaces machine and user identifiers with placeholders.
+///
+/// Matches only whole tokens, so a short username does not clobber words
+/// that merely contain it.
+#[derive(Debug, Default)]
+struct Redactor {
+    replacements: Vec<(String, &'static str)>,
+}
+
+impl Redactor {
+    fn new(identifiers: impl IntoIterator<Item = (String, &'static str)>) -> Self {
+        let mut replacements: Vec<_> = identifiers
+            .into_iter()
+            .filter(|(needle, _)| needle.trim().len() >= 2)
+            .collect();
+        // Longest first, so the home path wins over the username inside it.
+        replacements.sort_by_key(|(needle, _)| std::cmp::Reverse(needle.len()));
+        Self { replacements }
+    }
+
+    fn for_this_machine() -> Self {
+        let mut identifiers = Vec::new();
+        if let Some(home) = dirs::home_dir() {
+            if let Some(user) = home.file_name() {
+                identifiers.push((user.to_string_lossy().into_owned(), "<user>"));
+            }
+            identifiers.push((home.to_string_lossy().into_owned(), "~"));
+        }
+        if let Ok(user) = std::env::var("USER") {
+            identifiers.push((user, "<user>"));
+        }
+        if let Some(host) = sysinfo::System::host_name() {
+            if let Some((short, _)) = host.split_once('.') {
+                identifiers.push((short.to_string(), "<host>"));
+            }
+            identifiers.push((host, "<host>"));
+        }
+        let serial = BatteryData::new()
+            .ok()
+            .and_then(|b| b.serial_number().map(str::to_string));
+        if let Some(serial) = serial {
+            identifiers.push((serial, "<serial>"));
+        }
+        Self::new(identifiers)
+    }
+
+    fn apply(&self, text: &str) -> String {
+        self.replacements
+            .iter()
+            .fold(text.to_string(), |text, (needle, placeholder)| {
+                replace_token(&text, needle, placeholder)
+            })
+    }
+}
+
+fn replace_token(text: &str, needle: &str, placeholder: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find(needle) {
+        let before = rest[..pos].chars().next_back().or(out.chars().next_back());
+        let after = rest[pos + needle.len()..].chars().next();
+        let bounded = !before.is_some_and(is_word) && !after.is_some_and(is_word);
+        out.push_str(&rest[..pos]);
+        out.push_str(if bounded { placeholder } else { needle });
+        rest = &rest[pos + needle.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redactor() -> Redactor {
+        Redactor::new([
+            ("felix".to_string(), "<user>"),
+            ("/Users/felix".to_string(), "~"),
+            ("Felixs-MacBook-Pro".to_string(), "<host>"),
+            ("Felixs-MacBook-Pro.local".to_string(), "<host>"),
+            ("F5D1234ABC".to_string(), "<serial>"),
+            (String::new(), "<empty>"),
+        ])
+    }
+
+    #[test]
+    fn redacts_identifiers_across_bundle_files() {
+        let doctor = "Config: /Users/felix/.config/jolt/config.toml\n";
+        let log = "2026-10-16T10:00:00Z INFO host=Felixs-MacBook-Pro.local user=felix\n";
+        let registry = "\"Serial\" = \"F5D1234ABC\"\nPOWER_SUPPLY_SERIAL_NUMBER=F5D1234ABC\n";
+
+        let redactor = redactor();
+        assert_eq!(
+            redactor.apply(doctor),
+            "Config: ~/.config/jolt/config.toml\n"
+        );
+        assert_eq!(
+            redactor.apply(log),
+            "2026-10-16T10:00:00Z INFO host=<host> user=<user>\n"
+        );
+        assert_eq!(
+            redactor.apply(registry),
+            "\"Serial\" = \"<serial>\"\nPOWER_SUPPLY_SERIAL_NUMBER=<serial>\n"
+        );
+    }
+
+    #[test]
+    fn redaction_keeps_words_containing_an_identifier() {
+        let redactor = redactor();
+        assert_eq!(
+            redactor.apply("felixir felix_x felix."),
+            "felixir felix_x <user>."
+        );
+        assert_eq!(Redactor::default().apply("felix"), "felix");
+    }
+
+    #[test]
+    fn config_strips_fleet_peers_unless_identifiers_included() {
+        let config = UserConfig {
+            fleet_peers: vec!["studio.lan:9898".to_string()],
+            ..UserConfig::default()
+        };
+
+        let redacted = config_toml(config.clone(), false).unwrap();
+        assert!(redacted.contains("<peer-1>"));
+        assert!(!redacted.contains("studio"));
+        assert!(config_toml(config, true)
+            .unwrap()
+            .contains("studio.lan:9898"));
+    }
+
+    #[test]
+    fn config_never_includes_redaction_patterns() {
+        let config = UserConfig {
+            redact_processes: vec!["Signal*".to_string()],
+            ..UserConfig::default()
+        };
+
+        for include_identifiers in [false, true] {
+            let toml = config_toml(config.clone(), include_identifiers).unwrap();
+            assert!(toml.contains("<pattern-1>"));
+            assert!(!toml.contains("Signal"));
+        }
+    }
+
+    #[test]
+    fn log_window_keeps_recent_lines_and_their_continuations() {
+        let log = "2026-10-16T09:40:00Z INFO old\n\
+                   continued old\n\
+                   2026-10-16T09:55:00.5Z WARN recent\n\
+                   continued recent\n\
+                   not a date\n";
+        let since = DateTime::parse_from_rfc3339("2026-10-16T09:50:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            recent_log_lines(log, since),
+            "2026-10-16T09:55:00.5Z WARN recent\ncontinued recent\nnot a date\n"
+        );
+    }
+
+    #[test]
+    fn bundles_are_rate_limited() {
+        assert_eq!(rate_limit_remaining(None, 1_000), None);
+        assert_eq!(
+            rate_limit_remaining(Some(1_000), 1_015),
+            Some(Duration::from_secs(45))
+        );
+        assert_eq!(rate_limit_remaining(Some(1_000), 1_060), None);
+    }
+}
@@ -35,8 +35,64 @@ pub enum Commands {
         compact: bool,
     },
 
+    #[command(about = "Serve snapshots and Prometheus metrics over HTTP")]
+    Serve {
+        #[arg(
+            short,
+            long,
+            default_value = "127.0.0.1:9898",
+            help = "Address to listen on"
+        )]
+        bind: String,
+
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Other jolt serve instances to aggregate (host:port,...)"
+        )]
+        peers: Vec<String>,
+
+        #[arg(
+            long,
+            default_value_t = 5000,
+            help = "Interval between peer polls in ms"
+        )]
+        peer_interval: u64,
+    },
+
     #[command(about = "Print system and battery debug info")]
-    Debug,
+    Debug {
+        #[arg(
+            long,
+            help = "Print the IOReport Energy Model channels as JSON instead (macOS)"
+        )]
+        list_channels: bool,
+    },
+
+    #[command(about = "Time a few refreshes of each data source to diagnose slow sampling")]
+    Doctor,
+
+    #[command(about = "Write this Mac's power channels as a test fixture for jolt (macOS)")]
+    ContributeChannels {
+        #[arg(
+            short,
+            long,
+            help = "File to write (defaults to a name from the model)"
+        )]
+        output: Option<std::path::PathBuf>,
+    },
+
+    #[command(about = "Compare power readings against powermetrics (macOS, needs sudo)")]
+    Validate {
+        #[arg(short, long, default_value_t = 60, help = "Seconds to sample for")]
+        duration: u64,
+    },
+
+    #[command(about = "Validate a config file and report problems with their positions")]
+    Check {
+        #[arg(long, help = "Config file to check (defaults to the active config)")]
+        config: Option<std::path::PathBuf>,
+    },
 
     #[command(about = "Manage configuration")]
     Config {
@@ -68,6 +124,21 @@ pub enum Commands {
         command: Option<HistoryCommands>,
     },
 
+    #[command(
+        about = "Summarize recorded energy use",
+        group = clap::ArgGroup::new("period").required(true).args(["weekly"])
+    )]
+    Report {
+        #[arg(long, help = "Report on the last seven days, ending today")]
+        weekly: bool,
+
+        #[arg(long, help = "Output JSON instead of text")]
+        json: bool,
+
+        #[arg(long, help = "Leave out app energy recorded while power was estimated")]
+        measured_only: bool,
+    },
+
     #[command(about = "View daemon logs")]
     Logs {
         #[arg(short, long, default_value_t = 50, help = "Number of lines to show")]
@@ -76,6 +147,26 @@ pub enum Commands {
         #[arg(short, long, help = "Follow log output")]
         follow: bool,
     },
+
+    #[command(
+        alias = "bug-report",
+        about = "Bundle diagnostics into a redacted archive for bug reports"
+    )]
+    Bugreport {
+        #[arg(
+            short,
+            long,
+            default_value_t = crate::bugreport::DEFAULT_LOG_MINUTES,
+            help = "Minutes of log output to include"
+        )]
+        minutes: u64,
+
+        #[arg(long, help = "Keep hostname, username, serial and peers unredacted")]
+        include_identifiers: bool,
+
+        #[arg(short, long, help = "Skip the confirmation prompt")]
+        yes: bool,
+    },
 }
 
 #[derive(Debug, Parser)]
@@ -94,4 +185,10 @@ pub struct Cli {
         help = "Set log level (error, warn, info, debug, trace)"
     )]
     pub log_level: Option<String>,
+
+    #[arg(
+        long,
+        help = "Print the UI as plain text blocks for screen readers and dumb terminals"
+    )]
+    pub plain: bool,
 }
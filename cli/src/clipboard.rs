@@ -0,0 +1,44 @@
+//! System clipboard access.
+//!
+//! Text is piped into the platform's clipboard tool: `pbcopy` on macOS, and
+//! `wl-copy` or `xclip` on Linux depending on the display server.
+
+use std::process::Command;
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use jolt_platform::command::{self, CommandLimits};
+
+#[cfg(target_os = "macos")]
+const CLIPBOARD_COMMANDS: &[(&str, &[&str])] = &[("pbcopy", &[])];
+
+#[cfg(not(target_os = "macos"))]
+const CLIPBOARD_COMMANDS: &[(&str, &[&str])] =
+    &[("wl-copy", &[]), ("xclip", &["-selection", "clipboard"])];
+
+/// Replaces the clipboard contents with `text`.
+pub fn copy(text: &str) -> Result<()> {
+    let mut last_error = None;
+
+    for (program, args) in CLIPBOARD_COMMANDS {
+        match pipe_to(program, args, text) {
+            Ok(()) => return Ok(()),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| eyre!("No clipboard command available")))
+}
+
+fn pipe_to(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let output = command::run_with_input(
+        Command::new(program).args(args),
+        text.as_bytes(),
+        CommandLimits::DEFAULT,
+    )
+    .wrap_err_with(|| format!("Failed to run {}", program))?;
+
+    if !output.success() {
+        return Err(eyre!("{} exited with {}", program, output.status));
+    }
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::io::{BufRead, Write};
+
+use color_eyre::eyre::Result;
+
+use crate::bugreport::{self, BundleFile, BundleOptions};
+
+pub fn run(minutes: u64, include_identifiers: bool, yes: bool) -> Result<()> {
+    let options = BundleOptions {
+        log_minutes: minutes,
+        include_identifiers,
+    };
+
+    println!("The bug report will contain:");
+    for file in BundleFile::ALL {
+        println!("  {:<22} {}", file.file_name(), file.description());
+    }
+    println!("Log lines cover the last {} minutes.", minutes);
+    println!("{}", bugreport::redaction_summary(include_identifiers));
+
+    if !yes && !confirm("Create bug report? [y/N] ")? {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    println!("Collecting (this takes about ten seconds)...");
+    let archive = bugreport::create(&options)?;
+    println!("Bug report written to {}", archive.display());
+    println!("Review it before attaching it to an issue.");
+
+    Ok(())
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{}", prompt);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
@@ -0,0 +1,370 @@
+//! `jolt check`: validate a config file without starting anything.
+//!
+//! Syntax and type errors come straight from the TOML parser. The file is
+//! then parsed a second time into a spanned document so that semantic
+//! problems (an unknown theme, a retention policy that drops rollups before
+//! the samples they summarise, a malformed peer address) can be reported at
+//! the key that caused them.
+
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{Result, WrapErr};
+use toml::de::{DeTable, DeValue};
+use toml::Spanned;
+
+use crate::app::{MAX_REFRESH_MS, MIN_REFRESH_MS};
+use crate::config::{config_path, themes_dir, UserConfig};
+
+/// Accepted by serde in place of the current key name.
+const KEY_ALIASES: &[(&str, &str)] = &[("history", "enabled")];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Diagnostic {
+    severity: Severity,
+    /// 1-based line and column, when the problem maps to a position.
+    position: Option<(usize, usize)>,
+    message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((line, col)) = self.position {
+            write!(f, "{}:{}: ", line, col)?;
+        }
+        let label = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: {}", label, self.message)
+    }
+}
+
+pub fn run(path: Option<PathBuf>) -> Result<()> {
+    let path = path.unwrap_or_else(config_path);
+    let content =
+        fs::read_to_string(&path).wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+
+    let diagnostics = check_config(&content, &themes_dir());
+    for diagnostic in &diagnostics {
+        println!("{}:{}", path.display(), diagnostic);
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+
+    if diagnostics.is_empty() {
+        println!("{}: OK", path.display());
+    } else {
+        println!();
+        println!("{} error(s), {} warning(s)", errors, warnings);
+    }
+
+    if errors > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn check_config(content: &str, themes_dir: &Path) -> Vec<Diagnostic> {
+    let doc = match DeTable::parse(content) {
+        Ok(doc) => doc.into_inner(),
+        Err(e) => return vec![parse_error(content, &e)],
+    };
+    let config: UserConfig = match toml::from_str(content) {
+        Ok(config) => config,
+        Err(e) => return vec![parse_error(content, &e)],
+    };
+
+    let mut checker = Checker {
+        content,
+        doc: &doc,
+        diagnostics: Vec::new(),
+    };
+
+    if let Ok(known) = toml::Table::try_from(UserConfig::default()) {
+        checker.unknown_keys(&doc, &known, None);
+    }
+    checker.theme(&config, themes_dir);
+    checker.refresh(&config);
+    checker.retention(&config);
+    checker.lists(&config);
+
+    let mut diagnostics = checker.diagnostics;
+    diagnostics.sort_by_key(|d| (d.position, d.severity));
+    diagnostics
+}
+
+struct Checker<'a, 'i> {
+    content: &'a str,
+    doc: &'a DeTable<'i>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker<'_, '_> {
+    fn push(&mut self, severity: Severity, span: Option<Range<usize>>, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            position: span.map(|s| line_col(self.content, s.start)),
+            message,
+        });
+    }
+
+    fn unknown_keys(&mut self, table: &DeTable, known: &toml::Table, section: Option<&str>) {
+        for (key, value) in table {
+            let name = key.get_ref().as_ref();
+            let is_alias = section.is_some_and(|s| KEY_ALIASES.contains(&(s, name)));
+            match known.get(name) {
+                Some(toml::Value::Table(known_child)) => {
+                    if let DeValue::Table(child) = value.get_ref() {
+                        self.unknown_keys(child, known_child, Some(name));
+                    }
+                }
+                Some(_) => {}
+                None if is_alias => {}
+                None => {
+                    let qualified = match section {
+                        Some(section) => format!("{}.{}", section, name),
+                        None => name.to_string(),
+                    };
+                    self.push(
+                        Severity::Warning,
+                        Some(key.span()),
+                        format!("unknown key `{}` is ignored", qualified),
+                    );
+                }
+            }
+        }
+    }
+
+    fn theme(&mut self, config: &UserConfig, themes_dir: &Path) {
+        let span = value_span(self.doc, &["theme"]);
+        if jolt_theme::get_theme_by_id(&config.theme, Some(themes_dir)).is_none() {
+            self.push(
+                Severity::Error,
+                span,
+                format!(
+                    "theme `{}` is neither built in nor in {}",
+                    config.theme,
+                    themes_dir.display()
+                ),
+            );
+            return;
+        }
+
+        let theme_file = themes_dir.join(format!("{}.toml", config.theme));
+        let Ok(theme_content) = fs::read_to_string(&theme_file) else {
+            return;
+        };
+        let result = jolt_theme::validation::validate_theme_content(
+            &theme_content,
+            &theme_file.display().to_string(),
+            &config.theme,
+        );
+        for error in &result.errors {
+            self.push(
+                Severity::Error,
+                span.clone(),
+                format!("{}: {}", theme_file.display(), error),
+            );
+        }
+        for warning in &result.warnings {
+            self.push(
+                Severity::Warning,
+                span.clone(),
+                format!("{}: {}", theme_file.display(), warning),
+            );
+        }
+    }
+
+    fn refresh(&mut self, config: &UserConfig) {
+        if !(MIN_REFRESH_MS..=MAX_REFRESH_MS).contains(&config.refresh_ms) {
+            self.push(
+                Severity::Warning,
+                value_span(self.doc, &["refresh_ms"]),
+                format!(
+                    "refresh_ms = {} is outside the adjustable range {}-{}",
+                    config.refresh_ms, MIN_REFRESH_MS, MAX_REFRESH_MS
+                ),
+            );
+        }
+        if config.history.sample_interval_secs == 0 {
+            self.push(
+                Severity::Error,
+                value_span(self.doc, &["history", "sample_interval_secs"]),
+                "history.sample_interval_secs must be at least 1".to_string(),
+            );
+        }
+    }
+
+    /// Each coarser tier is built from the one before it, so it should not be
+    /// deleted sooner. A retention of 0 keeps data forever.
+    fn retention(&mut self, config: &UserConfig) {
+        let history = &config.history;
+        let tiers = [
+            ("retention_raw_days", history.retention_raw_days),
+            ("retention_hourly_days", history.retention_hourly_days),
+            ("retention_daily_days", history.retention_daily_days),
+        ];
+
+        for pair in tiers.windows(2) {
+            let (finer_key, finer) = pair[0];
+            let (coarser_key, coarser) = pair[1];
+            if coarser != 0 && (finer == 0 || finer > coarser) {
+                self.push(
+                    Severity::Warning,
+                    value_span(self.doc, &["history", coarser_key]),
+                    format!(
+                        "history.{} ({}) expires before history.{} ({})",
+                        coarser_key,
+                        retention_label(coarser),
+                        finer_key,
+                        retention_label(finer)
+                    ),
+                );
+            }
+        }
+    }
+
+    fn lists(&mut self, config: &UserConfig) {
+        for (i, peer) in config.fleet_peers.iter().enumerate() {
+            if !is_host_port(peer) {
+                self.push(
+                    Severity::Error,
+                    element_span(self.doc, "fleet_peers", i),
+                    format!("fleet peer `{}` is not a host:port address", peer),
+                );
+            }
+        }
+        for (i, name) in config.excluded_processes.iter().enumerate() {
+            if name.trim().is_empty() {
+                self.push(
+                    Severity::Warning,
+                    element_span(self.doc, "excluded_processes", i),
+                    "empty entry in excluded_processes".to_string(),
+                );
+            }
+        }
+        for (i, pattern) in config.redact_processes.iter().enumerate() {
+            if pattern.trim().is_empty() {
+                self.push(
+                    Severity::Warning,
+                    element_span(self.doc, "redact_processes", i),
+                    "empty entry in redact_processes".to_string(),
+                );
+            }
+        }
+    }
+}
+
+fn parse_error(content: &str, error: &toml::de::Error) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Error,
+        position: error.span().map(|s| line_col(content, s.start)),
+        message: error.message().to_string(),
+    }
+}
+
+fn line_col(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, offset - line_start + 1)
+}
+
+fn lookup<'a, 'i>(table: &'a DeTable<'i>, path: &[&str]) -> Option<&'a Spanned<DeValue<'i>>> {
+    let (first, rest) = path.split_first()?;
+    let (_, value) = table.iter().find(|(k, _)| k.get_ref().as_ref() == *first)?;
+    match (rest.is_empty(), value.get_ref()) {
+        (true, _) => Some(value),
+        (false, DeValue::Table(child)) => lookup(child, rest),
+        (false, _) => None,
+    }
+}
+
+fn value_span(table: &DeTable, path: &[&str]) -> Option<Range<usize>> {
+    lookup(table, path).map(|v| v.span())
+}
+
+fn element_span(table: &DeTable, key: &str, index: usize) -> Option<Range<usize>> {
+    match lookup(table, &[key])?.get_ref() {
+        DeValue::Array(items) => items.get(index).map(|v| v.span()),
+        _ => None,
+    }
+}
+
+fn is_host_port(address: &str) -> bool {
+    address
+        .rsplit_once(':')
+        .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+}
+
+fn retention_label(days: u32) -> String {
+    if days == 0 {
+        "forever".to_string()
+    } else {
+        format!("{} days", days)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(content: &str) -> Vec<Diagnostic> {
+        check_config(content, Path::new("/nonexistent"))
+    }
+
+    #[test]
+    fn syntax_errors_report_their_position() {
+        let diagnostics = check("refresh_ms = 1000\ntheme = \n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].position.map(|(line, _)| line), Some(2));
+    }
+
+    #[test]
+    fn semantic_problems_point_at_the_offending_key() {
+        let diagnostics = check(
+            "theme = \"default\"\n\
+             refersh_ms = 1000\n\
+             fleet_peers = [\"studio:9898\", \"air\"]\n\
+             \n\
+             [history]\n\
+             retention_raw_days = 30\n\
+             retention_hourly_days = 7\n",
+        );
+
+        let summary: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.severity, d.position.map(|(line, _)| line)))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Severity::Warning, Some(2)),
+                (Severity::Error, Some(3)),
+                (Severity::Warning, Some(7)),
+            ]
+        );
+        assert!(diagnostics[0].message.contains("refersh_ms"));
+        assert!(diagnostics[1].message.contains("`air`"));
+    }
+
+    #[test]
+    fn defaults_are_clean() {
+        let content = toml::to_string_pretty(&UserConfig::default()).unwrap();
+        assert_eq!(check(&content), Vec::new());
+    }
+}
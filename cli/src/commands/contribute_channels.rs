@@ -0,0 +1,50 @@
+//! `jolt contribute-channels`: this Mac's Energy Model channels as a fixture
+//! for `fixtures/channels`, where the test suite checks jolt's domain
+//! mapping against every contributed machine.
+//!
+//! The file holds the model identifier, chip, macOS version and the channel
+//! listing, nothing that identifies the machine or its user.
+
+use std::path::PathBuf;
+
+use color_eyre::eyre::{Result, WrapErr};
+
+use crate::commands::debug::CHANNEL_LISTING_INTERVAL;
+use crate::data::channel_fixture::{self, ChannelFixture};
+use crate::data::system::model_identifier;
+use crate::data::SystemInfo;
+
+pub fn run(output: Option<PathBuf>) -> Result<()> {
+    let channels = channel_fixture::list_channels(CHANNEL_LISTING_INTERVAL)?;
+    let system = SystemInfo::new();
+    let fixture = ChannelFixture::new(
+        model_identifier().unwrap_or_else(|| "unknown".to_string()),
+        system.chip,
+        system.os_version,
+        channels,
+    );
+
+    let path = output.unwrap_or_else(|| PathBuf::from(fixture.file_name()));
+    let json = format!("{}\n", serde_json::to_string_pretty(&fixture)?);
+    std::fs::write(&path, json).wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+
+    println!(
+        "Wrote {} channels to {}",
+        fixture.channels.len(),
+        path.display()
+    );
+    let violations = fixture.violations();
+    if violations.is_empty() {
+        println!("jolt's channel mapping handles every channel on this Mac.");
+    } else {
+        println!("jolt's channel mapping gets these wrong on this Mac:");
+        for violation in &violations {
+            println!("  - {}", violation);
+        }
+    }
+    println!(
+        "\nCheck that `expected_other` only lists channels that are not CPU, GPU or ANE \
+         power, then open a pull request adding the file to fixtures/channels/."
+    );
+    Ok(())
+}
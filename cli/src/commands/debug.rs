@@ -1,78 +1,118 @@
+use std::io::Write;
+use std::process::Command;
 use std::time::Duration;
 
 use color_eyre::eyre::Result;
+use jolt_platform::command::{self, CommandLimits};
 
 use crate::config::{self, config_path, UserConfig};
+use crate::data::channel_fixture::{self, FixtureChannel};
 use crate::data::{BatteryData, PowerData};
 
+/// How long channel listings average power over.
+pub const CHANNEL_LISTING_INTERVAL: Duration = Duration::from_secs(1);
+
 pub fn run() -> Result<()> {
-    println!("jolt debug information");
-    println!("{}", "=".repeat(60));
+    let mut out = std::io::stdout().lock();
+    write_report(&mut out)?;
+
+    writeln!(out, "\n--- Current Config ---")?;
+    let config = UserConfig::load();
+    writeln!(out, "{}", toml::to_string_pretty(&config)?)?;
+
+    Ok(())
+}
 
-    println!("\n--- System Info ---");
-    if let Ok(output) = std::process::Command::new("system_profiler")
-        .args(["SPHardwareDataType", "-json"])
-        .output()
-    {
+/// `jolt debug --list-channels`: every Energy Model channel with its group,
+/// unit and power, as a JSON array.
+pub fn list_channels() -> Result<()> {
+    let channels: Vec<FixtureChannel> = channel_fixture::list_channels(CHANNEL_LISTING_INTERVAL)?
+        .into_iter()
+        .map(FixtureChannel::from)
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&channels)?);
+    Ok(())
+}
+
+/// Writes every debug section except the current config, which callers
+/// that share the report add (or redact) themselves.
+pub fn write_report(out: &mut impl Write) -> Result<()> {
+    writeln!(out, "jolt debug information")?;
+    writeln!(out, "{}", "=".repeat(60))?;
+
+    writeln!(out, "\n--- System Info ---")?;
+    if let Ok(output) = command::run(
+        Command::new("system_profiler").args(["SPHardwareDataType", "-json"]),
+        CommandLimits::DEFAULT.with_timeout(Duration::from_secs(15)),
+    ) {
         if let Ok(json) = serde_json::from_slice::<serde_json::Value>(&output.stdout) {
             if let Some(hw) = json.get("SPHardwareDataType").and_then(|v| v.get(0)) {
-                println!(
+                writeln!(
+                    out,
                     "Chip: {}",
                     hw.get("chip_type")
                         .and_then(|v| v.as_str())
                         .unwrap_or("Unknown")
-                );
-                println!(
+                )?;
+                writeln!(
+                    out,
                     "Model: {}",
                     hw.get("machine_model")
                         .and_then(|v| v.as_str())
                         .unwrap_or("Unknown")
-                );
-                println!(
+                )?;
+                writeln!(
+                    out,
                     "Cores: {}",
                     hw.get("number_processors")
                         .and_then(|v| v.as_str())
                         .unwrap_or("Unknown")
-                );
+                )?;
             }
         }
     }
 
-    println!("\n--- Battery Info ---");
+    writeln!(out, "\n--- Battery Info ---")?;
     let battery = BatteryData::new()?;
-    println!("Charge: {:.1}%", battery.charge_percent());
-    println!("State: {}", battery.state_label());
+    writeln!(out, "Charge: {:.1}%", battery.charge_percent())?;
+    writeln!(out, "State: {}", battery.status_label())?;
+    if let Some(limit) = battery.charge_limit_percent() {
+        writeln!(out, "Charge limit: {}%", limit)?;
+    }
     if let Some(watts) = battery.charging_watts() {
-        println!("Charging at: {:.1}W", watts);
+        writeln!(out, "Charging at: {:.1}W", watts)?;
     }
     if let Some(charger) = battery.charger_watts() {
-        println!("Charger: {}W", charger);
+        writeln!(out, "Charger: {}W", charger)?;
     }
-    println!("Health: {:.1}%", battery.health_percent());
-    println!("Capacity: {:.1}Wh", battery.max_capacity_wh());
+    writeln!(out, "Health: {:.1}%", battery.health_percent())?;
+    writeln!(out, "Capacity: {:.1}Wh", battery.max_capacity_wh())?;
     if let Some(cycles) = battery.cycle_count() {
-        println!("Cycles: {}", cycles);
+        writeln!(out, "Cycles: {}", cycles)?;
     }
     if let Some(time) = battery.time_remaining_formatted() {
-        println!("Time remaining: {}", time);
+        writeln!(out, "Time remaining: {}", time)?;
     }
 
-    println!("\n--- Power Metrics ---");
+    writeln!(out, "\n--- Power Metrics ---")?;
     let mut power = PowerData::new()?;
-    std::thread::sleep(Duration::from_millis(500));
-    power.refresh()?;
-    println!("CPU Power: {:.2}W", power.cpu_power_watts());
-    println!("GPU Power: {:.2}W", power.gpu_power_watts());
-    println!("Total Power: {:.2}W", power.total_power_watts());
-    println!("Power Mode: {}", power.power_mode_label());
-
-    println!("\n--- Config Paths ---");
-    println!("Config: {}", config_path().display());
-    println!("Cache: {}", config::cache_dir().display());
+    power.set_sample_interval(Duration::from_millis(500));
+    power.wait_for_sample(Duration::from_secs(2))?;
+    writeln!(out, "CPU Power: {:.2}W", power.cpu_power_watts())?;
+    writeln!(out, "GPU Power: {:.2}W", power.gpu_power_watts())?;
+    writeln!(out, "Total Power: {:.2}W", power.total_power_watts())?;
+    writeln!(out, "Power Mode: {}", power.power_mode_label())?;
+    if cfg!(target_os = "macos") {
+        match power.energy_model_subgroups() {
+            Some(subgroups) => writeln!(out, "Energy Model subgroups: {}", subgroups.join(", "))?,
+            None => writeln!(out, "Energy Model subgroups: all")?,
+        }
+    }
+    writeln!(out, "Channels per sample: {}", power.raw_channels().len())?;
 
-    println!("\n--- Current Config ---");
-    let config = UserConfig::load();
-    println!("{}", toml::to_string_pretty(&config)?);
+    writeln!(out, "\n--- Config Paths ---")?;
+    writeln!(out, "Config: {}", config_path().display())?;
+    writeln!(out, "Cache: {}", config::cache_dir().display())?;
 
     Ok(())
 }
@@ -0,0 +1,99 @@
+//! `jolt doctor`: time a few refreshes of every data source and report
+//! where sampling spends its time.
+
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::Result;
+use jolt_platform::command;
+
+use crate::data::{BatteryData, PowerData, ProcessData, RefreshStage, RefreshTimings};
+
+/// Number of refresh rounds timed for the sampling health section.
+const SAMPLING_HEALTH_ROUNDS: usize = 5;
+
+pub fn run() -> Result<()> {
+    let mut out = std::io::stdout().lock();
+    writeln!(out, "jolt doctor")?;
+    writeln!(out, "{}", "=".repeat(60))?;
+    write_report(&mut out)
+}
+
+/// Writes the sampling health section: per-stage refresh durations, raw
+/// power over the timed rounds and the external commands they ran.
+pub fn write_report(out: &mut impl Write) -> Result<()> {
+    writeln!(out, "\n--- Sampling Health ---")?;
+    let mut battery = BatteryData::new()?;
+    let mut processes = ProcessData::new()?;
+    // Average the raw readings over the timed rounds
+    let mut power = PowerData::with_average_window(SAMPLING_HEALTH_ROUNDS)?;
+    power.set_stats_include_fallback(true);
+    power.set_sample_interval(Duration::from_millis(500));
+    power.wait_for_sample(Duration::from_secs(2))?;
+    power.reset_peaks();
+
+    let mut timings = RefreshTimings::new();
+    for _ in 0..SAMPLING_HEALTH_ROUNDS {
+        let start = Instant::now();
+        battery.refresh()?;
+        timings.record(RefreshStage::Battery, start.elapsed());
+
+        // Time the worker's provider refreshes, not the hand-over
+        power.wait_for_sample(Duration::from_secs(2))?;
+        for &time in power.last_refresh_times() {
+            timings.record(RefreshStage::Power, time);
+        }
+
+        let start = Instant::now();
+        processes.refresh()?;
+        timings.record(RefreshStage::Processes, start.elapsed());
+    }
+    for stage in [
+        RefreshStage::Battery,
+        RefreshStage::Power,
+        RefreshStage::Processes,
+    ] {
+        if let Some(stats) = timings.stats(stage) {
+            writeln!(
+                out,
+                "{:<10} p50 {:>7.1}ms  p95 {:>7.1}ms  max {:>7.1}ms",
+                stage.label(),
+                stats.p50.as_secs_f64() * 1000.0,
+                stats.p95.as_secs_f64() * 1000.0,
+                stats.max.as_secs_f64() * 1000.0
+            )?;
+        }
+    }
+    let average = power.rolling_average();
+    writeln!(
+        out,
+        "Raw power peak CPU {:.2}W  GPU {:.2}W  total {:.2}W{}",
+        power.peak_cpu_power_watts(),
+        power.peak_gpu_power_watts(),
+        power.peak_total_power_watts(),
+        if power.is_using_fallback() {
+            " (estimated)"
+        } else {
+            ""
+        }
+    )?;
+    writeln!(
+        out,
+        "Raw power mean CPU {:.2}W  GPU {:.2}W  total {:.2}W",
+        average.cpu_watts, average.gpu_watts, average.total_watts
+    )?;
+    for stats in command::command_stats() {
+        writeln!(
+            out,
+            "{:<10} runs {:>3}  mean {:>7.1}ms  max {:>7.1}ms  failed {}  timed out {}",
+            stats.program,
+            stats.runs,
+            stats.mean().as_secs_f64() * 1000.0,
+            stats.max.as_secs_f64() * 1000.0,
+            stats.failures,
+            stats.timeouts
+        )?;
+    }
+
+    Ok(())
+}
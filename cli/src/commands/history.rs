@@ -3,9 +3,9 @@ use std::io::Write;
 use color_eyre::eyre::Result;
 
 use crate::cli::HistoryCommands;
-use crate::data::{self, HistoryStore};
+use crate::data::{self, HistoryStore, ProcessRedactor, WakeLog};
 
-pub fn run(command: Option<HistoryCommands>) -> Result<()> {
+pub fn run(command: Option<HistoryCommands>, mut redactor: ProcessRedactor) -> Result<()> {
     let cmd = command.unwrap_or(HistoryCommands::Summary {
         period: "week".to_string(),
     });
@@ -51,6 +51,8 @@ pub fn run(command: Option<HistoryCommands>) -> Result<()> {
                     eprintln!("Error reading stats: {}", e);
                 }
             }
+
+            print_wakes(&WakeLog::load());
         }
         HistoryCommands::Top { period, limit } => {
             let (from, to) = get_date_range(&period);
@@ -102,9 +104,10 @@ pub fn run(command: Option<HistoryCommands>) -> Result<()> {
             let daily_stats = store
                 .get_daily_stats(&from_date, &to_date)
                 .unwrap_or_default();
-            let top_processes = store
+            let mut top_processes = store
                 .get_top_processes_range(&from_date, &to_date, 20)
                 .unwrap_or_default();
+            redact_top_processes(&mut top_processes, &mut redactor);
 
             let samples = if include_samples {
                 store
@@ -183,7 +186,10 @@ pub fn run(command: Option<HistoryCommands>) -> Result<()> {
                 .unwrap_or(0);
 
             let deleted_samples = store.delete_samples_before(before_ts).unwrap_or(0);
-            let deleted_hourly = store.delete_hourly_stats_before(before_ts).unwrap_or(0);
+            let deleted_hourly = store.delete_hourly_stats_before(before_ts).unwrap_or(0)
+                + store
+                    .delete_hourly_attribution_before(before_ts)
+                    .unwrap_or(0);
             let deleted_daily = store.delete_daily_stats_before(&before_date).unwrap_or(0);
             let deleted_processes = store
                 .delete_daily_processes_before(&before_date)
@@ -206,6 +212,35 @@ pub fn run(command: Option<HistoryCommands>) -> Result<()> {
     Ok(())
 }
 
+fn print_wakes(log: &WakeLog) {
+    let events = log.events();
+    if events.is_empty() {
+        return;
+    }
+
+    println!(
+        "\nWakes (last 24h): {} ({} dark)",
+        events.len(),
+        log.dark_wake_count()
+    );
+    println!("{}", "-".repeat(50));
+
+    for event in events {
+        let time = chrono::DateTime::from_timestamp(event.timestamp, 0)
+            .map(|dt| {
+                dt.with_timezone(&chrono::Local)
+                    .format("%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let kind = if event.dark_wake { "DarkWake" } else { "Wake" };
+        let charge = event
+            .charge_percent
+            .map_or(String::new(), |c| format!(" ({}%)", c));
+        println!("{}  {:<8}  {}{}", time, kind, event.reason, charge);
+    }
+}
+
 pub fn get_date_range(period: &str) -> (String, String) {
     let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
 
@@ -226,6 +261,12 @@ pub fn truncate_str(s: &str, max_len: usize) -> String {
     }
 }
 
+fn redact_top_processes(top: &mut [data::DailyTopProcess], redactor: &mut ProcessRedactor) {
+    for process in top {
+        process.process_name = redactor.name(&process.process_name);
+    }
+}
+
 fn export_to_json(
     from: &str,
     to: &str,
@@ -321,3 +362,38 @@ pub fn escape_csv(s: &str) -> String {
         s.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_list_redacted_processes_by_alias() {
+        let mut top: Vec<data::DailyTopProcess> = ["Discord", "Discord Helper", "Finder"]
+            .into_iter()
+            .map(|name| data::DailyTopProcess {
+                id: None,
+                date: "2024-03-17".to_string(),
+                process_name: name.to_string(),
+                total_impact: 1.0,
+                avg_cpu: 1.0,
+                avg_memory_mb: 1.0,
+                sample_count: 1,
+                avg_power: 1.0,
+                total_energy_wh: 1.0,
+            })
+            .collect();
+        redact_top_processes(
+            &mut top,
+            &mut ProcessRedactor::new(&["discord*".to_string()]),
+        );
+
+        let json = export_to_json("2024-03-11", "2024-03-17", &[], &top, &[]);
+        let csv = export_to_csv("2024-03-11", "2024-03-17", &[], &top, &[]);
+        for export in [json, csv] {
+            assert!(!export.to_lowercase().contains("discord"), "{}", export);
+            assert!(export.contains("app-1") && export.contains("app-2"));
+            assert!(export.contains("Finder"));
+        }
+    }
+}
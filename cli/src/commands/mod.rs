@@ -1,7 +1,15 @@
+pub mod bugreport;
+pub mod check;
 pub mod config;
+pub mod contribute_channels;
 pub mod daemon;
 pub mod debug;
+pub mod doctor;
 pub mod history;
 pub mod logs;
 pub mod pipe;
+pub mod plain;
+pub mod report;
+pub mod serve;
 pub mod theme;
+pub mod validate;
@@ -1,54 +1,34 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use color_eyre::eyre::Result;
 use serde_json::json;
 
-use crate::data::{BatteryData, PowerData, ProcessData};
+use crate::config::CpuNormalization;
+use crate::data::coordinator::CoordinatedSnapshot;
+use crate::data::{normalize_cpu, PowerData, ProcessRedactor, RefreshCoordinator, SystemInfo};
 
-pub fn run(samples: u32, interval: u64, compact: bool) -> Result<()> {
-    let mut battery = BatteryData::new()?;
+/// Lower bound on how long a sample waits for slow subsystems, so very short
+/// intervals don't mark every process scan as stale.
+const MIN_REFRESH_DEADLINE: Duration = Duration::from_millis(250);
+
+pub fn run(
+    samples: u32,
+    interval: u64,
+    compact: bool,
+    cpu_normalization: CpuNormalization,
+    mut redactor: ProcessRedactor,
+) -> Result<()> {
+    let cores = SystemInfo::new().core_count();
+    let interval = Duration::from_millis(interval);
+    let mut coordinator = RefreshCoordinator::new(interval.max(MIN_REFRESH_DEADLINE))?;
     let mut power = PowerData::new()?;
-    let mut processes = ProcessData::new()?;
     let mut counter = 0u32;
 
     loop {
-        battery.refresh()?;
-        power.refresh()?;
-        processes.refresh()?;
-
-        let top_processes: Vec<_> = processes
-            .processes
-            .iter()
-            .take(10)
-            .map(|p| {
-                json!({
-                    "pid": p.pid,
-                    "name": p.name,
-                    "cpu": p.cpu_usage,
-                    "memory_mb": p.memory_mb,
-                    "energy": p.energy_impact,
-                })
-            })
-            .collect();
-
-        let doc = json!({
-            "timestamp": chrono::Utc::now().to_rfc3339(),
-            "battery": {
-                "percent": battery.charge_percent(),
-                "state": battery.state_label(),
-                "health": battery.health_percent(),
-                "capacity_wh": battery.max_capacity_wh(),
-                "time_remaining_min": battery.time_remaining_minutes(),
-                "cycle_count": battery.cycle_count(),
-            },
-            "power": {
-                "cpu_watts": power.cpu_power_watts(),
-                "gpu_watts": power.gpu_power_watts(),
-                "total_watts": power.total_power_watts(),
-                "mode": power.power_mode_label(),
-            },
-            "top_processes": top_processes,
-        });
+        let tick_start = Instant::now();
+        let snapshot = coordinator.refresh(&mut power);
+
+        let doc = snapshot_json(&snapshot, cpu_normalization, cores, &mut redactor);
 
         if compact {
             println!("{}", serde_json::to_string(&doc)?);
@@ -61,8 +41,107 @@ pub fn run(samples: u32, interval: u64, compact: bool) -> Result<()> {
             break;
         }
 
-        std::thread::sleep(Duration::from_millis(interval));
+        std::thread::sleep(interval.saturating_sub(tick_start.elapsed()));
     }
 
     Ok(())
 }
+
+/// One `jolt pipe` sample as JSON, with process names passed through
+/// `redactor`.
+pub fn snapshot_json(
+    snapshot: &CoordinatedSnapshot,
+    cpu_normalization: CpuNormalization,
+    cores: u32,
+    redactor: &mut ProcessRedactor,
+) -> serde_json::Value {
+    let battery = &snapshot.battery.value;
+    let power = &snapshot.power.value;
+
+    let top_processes: Vec<_> = snapshot
+        .processes
+        .value
+        .iter()
+        .take(10)
+        .map(|p| {
+            let cpu = normalize_cpu(p.cpu_usage, cpu_normalization, cores);
+            json!({
+                "pid": p.pid,
+                "name": redactor.process_name(p),
+                "cpu": cpu.percent,
+                "cpu_clamped": cpu.clamped,
+                "memory_mb": p.memory_mb,
+                "energy": p.energy_impact,
+            })
+        })
+        .collect();
+
+    let stale: serde_json::Map<_, _> = snapshot
+        .stale_subsystems()
+        .into_iter()
+        .map(|(name, age)| (name.to_string(), json!(age.as_millis() as u64)))
+        .collect();
+
+    json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "stale_age_ms": stale,
+        "battery": {
+            "percent": battery.charge_percent,
+            "state": battery.state_label,
+            "health": battery.health_percent,
+            "capacity_wh": battery.max_capacity_wh,
+            "time_remaining_min": battery.time_remaining_mins,
+            "cycle_count": battery.cycle_count,
+        },
+        "power": {
+            "cpu_watts": power.cpu_power_watts,
+            "gpu_watts": power.gpu_power_watts,
+            "total_watts": power.total_power_watts,
+            "mode": power.power_mode_label,
+        },
+        "cpu_normalization": cpu_normalization,
+        "top_processes": top_processes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::daemon::{BatterySnapshot, PowerSnapshot};
+    use crate::data::coordinator::Stamped;
+    use crate::data::redaction::tests::process;
+
+    fn stamped<T>(value: T) -> Stamped<T> {
+        Stamped {
+            value,
+            captured_at: Instant::now(),
+            refresh_time: Duration::ZERO,
+            stale: false,
+        }
+    }
+
+    #[test]
+    fn redacted_processes_are_listed_by_alias() {
+        let snapshot = CoordinatedSnapshot {
+            battery: stamped(BatterySnapshot::default()),
+            power: stamped(PowerSnapshot::default()),
+            processes: stamped(vec![
+                process(
+                    "zoom.us",
+                    "/Applications/zoom.us.app/Contents/MacOS/zoom.us",
+                ),
+                process("Safari", "/Applications/Safari.app/Contents/MacOS/Safari"),
+            ]),
+        };
+        let mut redactor = ProcessRedactor::new(&["ZOOM*".to_string()]);
+
+        let doc = snapshot_json(&snapshot, CpuNormalization::default(), 8, &mut redactor);
+
+        let json = doc.to_string();
+        assert!(!json.to_lowercase().contains("zoom"), "{}", json);
+        assert_eq!(doc["top_processes"][0]["name"], "app-1");
+        assert_eq!(doc["top_processes"][1]["name"], "Safari");
+    }
+}
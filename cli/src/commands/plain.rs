@@ -0,0 +1,492 @@
+//! Screen-reader friendly text mode (`jolt --plain`).
+//!
+//! Instead of drawing a screen, every refresh prints one complete block of
+//! lines built from the same coordinated snapshot and formatters the TUI
+//! uses. Nothing is ever redrawn in place: the output contains no cursor
+//! addressing, colors or box drawing, only printable text and newlines, so
+//! it reads well through screen readers and on dumb terminals.
+//!
+//! Keys are replaced by line commands on stdin, e.g. `view power`,
+//! `sort cpu` or `quit`.
+
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::Result;
+
+use crate::app::SortColumn;
+use crate::config::{UnitsConfig, UserConfig};
+use crate::daemon::ProcessSnapshot;
+use crate::data::coordinator::CoordinatedSnapshot;
+use crate::data::{normalize_cpu, PowerData, RefreshCoordinator, SystemInfo};
+use crate::ui::utils::{
+    format_cpu, format_energy, format_memory, format_network_rate, format_percent,
+    format_temperature, format_watts,
+};
+
+/// Processes listed in the overview; the processes view lists
+/// `process_count` of them.
+const OVERVIEW_PROCESSES: usize = 5;
+
+const HELP: &str = "Commands:
+  view overview | battery | power | processes
+  sort energy | cpu | memory | network | name | pid
+  help
+  quit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlainView {
+    #[default]
+    Overview,
+    Battery,
+    Power,
+    Processes,
+}
+
+impl PlainView {
+    fn label(self) -> &'static str {
+        match self {
+            PlainView::Overview => "overview",
+            PlainView::Battery => "battery",
+            PlainView::Power => "power",
+            PlainView::Processes => "processes",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlainCommand {
+    View(PlainView),
+    Sort(SortColumn),
+    Help,
+    Quit,
+}
+
+/// Parses one line typed on stdin; `None` for anything unrecognised.
+pub fn parse_command(line: &str) -> Option<PlainCommand> {
+    let line = line.trim().to_lowercase();
+    let mut words = line.split_whitespace();
+    let command = match (words.next()?, words.next()) {
+        ("quit" | "q" | "exit", None) => PlainCommand::Quit,
+        ("help" | "h" | "?", None) => PlainCommand::Help,
+        ("view", Some(view)) => PlainCommand::View(match view {
+            "overview" | "main" => PlainView::Overview,
+            "battery" => PlainView::Battery,
+            "power" => PlainView::Power,
+            "processes" | "process" => PlainView::Processes,
+            _ => return None,
+        }),
+        ("sort", Some(column)) => PlainCommand::Sort(match column {
+            "energy" => SortColumn::Energy,
+            "cpu" => SortColumn::Cpu,
+            "memory" | "mem" => SortColumn::Memory,
+            "network" | "net" => SortColumn::Network,
+            "name" => SortColumn::Name,
+            "pid" => SortColumn::Pid,
+            _ => return None,
+        }),
+        _ => return None,
+    };
+    words.next().is_none().then_some(command)
+}
+
+/// What the reader chose to look at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PlainState {
+    pub view: PlainView,
+    pub sort: SortColumn,
+}
+
+pub fn run(config: &UserConfig) -> Result<()> {
+    let cores = SystemInfo::new().core_count();
+    let interval = Duration::from_millis(config.refresh_ms);
+    let mut coordinator = RefreshCoordinator::new(interval)?;
+    let mut power = PowerData::new()?;
+    let commands = spawn_stdin_reader();
+    let mut stdin_open = true;
+    let mut state = PlainState::default();
+
+    println!("jolt plain mode. Type help for commands.");
+    loop {
+        let snapshot = coordinator.refresh(&mut power);
+        let block = render(&snapshot, state, &config.units, config.process_count, cores);
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(block.as_bytes())?;
+        stdout.flush()?;
+        drop(stdout);
+
+        let next_refresh = Instant::now() + interval;
+        loop {
+            let timeout = next_refresh.saturating_duration_since(Instant::now());
+            if !stdin_open {
+                thread::sleep(timeout);
+                break;
+            }
+            let line = match commands.recv_timeout(timeout) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => break,
+                // Without stdin, e.g. `jolt --plain < /dev/null`, keep printing.
+                Err(RecvTimeoutError::Disconnected) => {
+                    stdin_open = false;
+                    continue;
+                }
+            };
+            match parse_command(&line) {
+                Some(PlainCommand::Quit) => return Ok(()),
+                Some(PlainCommand::Help) => println!("{}\n", HELP),
+                Some(PlainCommand::View(view)) => {
+                    state.view = view;
+                    break;
+                }
+                Some(PlainCommand::Sort(sort)) => {
+                    state.sort = sort;
+                    break;
+                }
+                None if line.trim().is_empty() => {}
+                None => println!(
+                    "Unknown command: {}. Type help for commands.\n",
+                    plain_text(line.trim())
+                ),
+            }
+        }
+    }
+}
+
+fn spawn_stdin_reader() -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// One full block of text for `state.view`, ending with a blank line.
+pub fn render(
+    snapshot: &CoordinatedSnapshot,
+    state: PlainState,
+    units: &UnitsConfig,
+    process_count: usize,
+    cores: u32,
+) -> String {
+    let mut lines = vec![format!(
+        "jolt {}, {} view",
+        chrono::Local::now().format("%H:%M:%S"),
+        state.view.label()
+    )];
+
+    match state.view {
+        PlainView::Overview => {
+            battery_summary(&mut lines, snapshot, units);
+            power_summary(&mut lines, snapshot);
+            process_lines(
+                &mut lines,
+                &snapshot.processes.value,
+                state.sort,
+                OVERVIEW_PROCESSES,
+                units,
+                cores,
+            );
+        }
+        PlainView::Battery => battery_details(&mut lines, snapshot, units),
+        PlainView::Power => power_summary(&mut lines, snapshot),
+        PlainView::Processes => process_lines(
+            &mut lines,
+            &snapshot.processes.value,
+            state.sort,
+            process_count,
+            units,
+            cores,
+        ),
+    }
+
+    let stale: Vec<String> = snapshot
+        .stale_subsystems()
+        .into_iter()
+        .map(|(name, age)| format!("{} ({}s old)", name, age.as_secs()))
+        .collect();
+    if !stale.is_empty() {
+        lines.push(format!("Not updated this time: {}", stale.join(", ")));
+    }
+
+    let mut block = lines.join("\n");
+    block.push_str("\n\n");
+    block
+}
+
+fn battery_summary(lines: &mut Vec<String>, snapshot: &CoordinatedSnapshot, units: &UnitsConfig) {
+    let battery = &snapshot.battery.value;
+    let mut line = format!(
+        "Battery {}, {}",
+        format_percent(battery.charge_percent),
+        plain_text(&battery.state_label)
+    );
+    if let Some(remaining) = &battery.time_remaining_formatted {
+        line.push_str(&format!(", {} remaining", plain_text(remaining)));
+    }
+    lines.push(line);
+    if let Some(temperature) = battery.temperature_c {
+        lines.push(format!(
+            "Battery temperature {}",
+            format_temperature(temperature, units.temperature)
+        ));
+    }
+}
+
+fn battery_details(lines: &mut Vec<String>, snapshot: &CoordinatedSnapshot, units: &UnitsConfig) {
+    battery_summary(lines, snapshot, units);
+    let battery = &snapshot.battery.value;
+    lines.push(format!(
+        "Health {}, capacity {} of {} design",
+        format_percent(battery.health_percent),
+        format_energy(battery.max_capacity_wh, units.energy),
+        format_energy(battery.design_capacity_wh, units.energy)
+    ));
+    if let Some(cycles) = battery.cycle_count {
+        lines.push(format!("Cycle count {}", cycles));
+    }
+    if let Some(watts) = battery.charging_watts {
+        lines.push(format!("Charging at {}", format_watts(watts)));
+    }
+    if let Some(watts) = battery.discharge_watts {
+        lines.push(format!("Discharging at {}", format_watts(watts)));
+    }
+    if let Some(watts) = battery.charger_watts {
+        lines.push(format!("Charger rated {}W", watts));
+    }
+    if let Some(limit) = battery.charge_limit_percent {
+        lines.push(format!("Charge limit {}%", limit));
+    }
+    lines.push(format!(
+        "Voltage {:.2}V, current {}mA",
+        battery.voltage_mv as f32 / 1000.0,
+        battery.amperage_ma
+    ));
+}
+
+fn power_summary(lines: &mut Vec<String>, snapshot: &CoordinatedSnapshot) {
+    let power = &snapshot.power.value;
+    if !power.is_warmed_up {
+        lines.push("Power readings are still warming up".to_string());
+        return;
+    }
+    lines.push(format!(
+        "Power {} total, CPU {}, GPU {}",
+        format_watts(power.total_power_watts),
+        format_watts(power.cpu_power_watts),
+        format_watts(power.gpu_power_watts)
+    ));
+    lines.push(format!(
+        "Power mode {}",
+        plain_text(&power.power_mode_label)
+    ));
+}
+
+fn process_lines(
+    lines: &mut Vec<String>,
+    processes: &[ProcessSnapshot],
+    sort: SortColumn,
+    count: usize,
+    units: &UnitsConfig,
+    cores: u32,
+) {
+    let mut sorted: Vec<&ProcessSnapshot> = processes.iter().collect();
+    sort_processes(&mut sorted, sort);
+
+    lines.push(format!("Top {} processes by {}", count, sort_label(sort)));
+    for (rank, process) in sorted.iter().take(count).enumerate() {
+        let cpu = normalize_cpu(process.cpu_usage, units.cpu, cores);
+        lines.push(format!(
+            "{}. {}, pid {}, energy {:.1}, CPU {}%, memory {}, network {}",
+            rank + 1,
+            plain_text(&process.name),
+            process.pid,
+            process.energy_impact,
+            format_cpu(cpu),
+            format_memory(process.memory_mb),
+            format_network_rate(process.network_bytes_per_sec)
+        ));
+    }
+}
+
+/// Highest first, except names and PIDs which read naturally ascending.
+fn sort_processes(processes: &mut [&ProcessSnapshot], sort: SortColumn) {
+    let descending = |a: f64, b: f64| b.partial_cmp(&a).unwrap_or(std::cmp::Ordering::Equal);
+    match sort {
+        SortColumn::Pid => processes.sort_by_key(|p| p.pid),
+        SortColumn::Name => processes.sort_by_key(|p| p.name.to_lowercase()),
+        SortColumn::Cpu => {
+            processes.sort_by(|a, b| descending(a.cpu_usage as f64, b.cpu_usage as f64))
+        }
+        SortColumn::Memory => processes.sort_by(|a, b| descending(a.memory_mb, b.memory_mb)),
+        SortColumn::Network => processes.sort_by(|a, b| {
+            descending(
+                a.network_bytes_per_sec.unwrap_or(0.0),
+                b.network_bytes_per_sec.unwrap_or(0.0),
+            )
+        }),
+        SortColumn::Energy => {
+            processes.sort_by(|a, b| descending(a.energy_impact as f64, b.energy_impact as f64))
+        }
+    }
+}
+
+fn sort_label(sort: SortColumn) -> &'static str {
+    match sort {
+        SortColumn::Pid => "PID",
+        SortColumn::Name => "name",
+        SortColumn::Cpu => "CPU",
+        SortColumn::Memory => "memory",
+        SortColumn::Network => "network",
+        SortColumn::Energy => "energy",
+    }
+}
+
+/// Drops control characters from text that came from the system, so a
+/// process name cannot smuggle escape sequences into the output.
+fn plain_text(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::{BatterySnapshot, BinaryArch, PowerSnapshot, ProcessState};
+    use crate::data::coordinator::Stamped;
+
+    fn stamped<T>(value: T) -> Stamped<T> {
+        Stamped {
+            value,
+            captured_at: Instant::now(),
+            refresh_time: Duration::ZERO,
+            stale: false,
+        }
+    }
+
+    fn process(pid: u32, name: &str, cpu_usage: f32, energy_impact: f32) -> ProcessSnapshot {
+        ProcessSnapshot {
+            pid,
+            name: name.to_string(),
+            command: name.to_string(),
+            command_args: String::new(),
+            cpu_usage,
+            memory_mb: 256.0,
+            energy_impact,
+            parent_pid: None,
+            children: None,
+            is_killable: true,
+            disk_read_bytes: 0,
+            disk_write_bytes: 0,
+            status: ProcessState::Running,
+            run_time_secs: 0,
+            total_cpu_time_secs: 0,
+            network_bytes_per_sec: None,
+            sandbox_container_id: None,
+            executable_path: Default::default(),
+            start_time: 0,
+            team_id: None,
+            binary_arch: BinaryArch::Unknown,
+            power_assertion_count: None,
+        }
+    }
+
+    fn snapshot() -> CoordinatedSnapshot {
+        CoordinatedSnapshot {
+            battery: stamped(BatterySnapshot {
+                charge_percent: 82.0,
+                state_label: "On Battery".to_string(),
+                health_percent: 91.0,
+                max_capacity_wh: 52.3,
+                design_capacity_wh: 57.5,
+                cycle_count: Some(412),
+                time_remaining_formatted: Some("3h 12m".to_string()),
+                temperature_c: Some(31.2),
+                ..Default::default()
+            }),
+            power: stamped(PowerSnapshot {
+                cpu_power_watts: 5.1,
+                gpu_power_watts: 1.2,
+                total_power_watts: 8.4,
+                power_mode_label: "Automatic".to_string(),
+                is_warmed_up: true,
+                ..Default::default()
+            }),
+            processes: stamped(vec![
+                process(10, "Safari", 12.0, 20.0),
+                process(11, "evil\u{1b}[2Jname", 80.0, 5.0),
+                process(12, "kernel_task", 3.0, 1.0),
+            ]),
+        }
+    }
+
+    #[test]
+    fn every_view_is_newline_separated_printable_text() {
+        let snapshot = snapshot();
+        for view in [
+            PlainView::Overview,
+            PlainView::Battery,
+            PlainView::Power,
+            PlainView::Processes,
+        ] {
+            let state = PlainState {
+                view,
+                sort: SortColumn::Energy,
+            };
+            let block = render(&snapshot, state, &UnitsConfig::default(), 10, 8);
+            assert!(block.ends_with("\n\n"), "{:?}", view);
+            for c in block.chars() {
+                assert!(c == '\n' || !c.is_control(), "{:?}: {:?}", view, c);
+                assert!(
+                    !('\u{2500}'..='\u{259f}').contains(&c),
+                    "{:?}: {:?}",
+                    view,
+                    c
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn overview_lists_battery_power_and_sorted_processes() {
+        let snapshot = snapshot();
+        let units = UnitsConfig::default();
+        let block = render(&snapshot, PlainState::default(), &units, 10, 8);
+        assert!(block.contains("Battery 82%, On Battery, 3h 12m remaining\n"));
+        assert!(block.contains("Power 8.4W total, CPU 5.1W, GPU 1.2W\n"));
+        assert!(block.contains("1. Safari, pid 10,"));
+        assert!(block.contains("2. evil[2Jname, pid 11,"));
+
+        let by_cpu = PlainState {
+            view: PlainView::Processes,
+            sort: SortColumn::Cpu,
+        };
+        let block = render(&snapshot, by_cpu, &units, 2, 8);
+        assert!(block.contains("Top 2 processes by CPU\n1. evil[2Jname"));
+        assert!(!block.contains("kernel_task"));
+    }
+
+    #[test]
+    fn line_commands_parse() {
+        assert_eq!(
+            parse_command("view power"),
+            Some(PlainCommand::View(PlainView::Power))
+        );
+        assert_eq!(
+            parse_command("  Sort CPU\n"),
+            Some(PlainCommand::Sort(SortColumn::Cpu))
+        );
+        assert_eq!(parse_command("quit"), Some(PlainCommand::Quit));
+        assert_eq!(parse_command("q"), Some(PlainCommand::Quit));
+        assert_eq!(parse_command("help"), Some(PlainCommand::Help));
+        assert_eq!(parse_command("view"), None);
+        assert_eq!(parse_command("view power now"), None);
+        assert_eq!(parse_command("sort colour"), None);
+        assert_eq!(parse_command(""), None);
+    }
+}
@@ -0,0 +1,227 @@
+use color_eyre::eyre::Result;
+
+use crate::commands::history::truncate_str;
+use crate::data::report::{weekly_report, WeeklyReport};
+use crate::data::{HistoryStore, ProcessRedactor};
+
+pub fn run(
+    json: bool,
+    include_estimated: bool,
+    sample_interval_secs: u64,
+    mut redactor: ProcessRedactor,
+) -> Result<()> {
+    let store = match HistoryStore::open() {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Failed to open history database: {}", e);
+            eprintln!("Make sure the daemon has been running to collect data.");
+            std::process::exit(1);
+        }
+    };
+
+    let today = chrono::Local::now().date_naive();
+    let mut report = weekly_report(
+        &store,
+        today,
+        &chrono::Local,
+        sample_interval_secs,
+        include_estimated,
+    )?;
+    report.redact(&mut redactor);
+
+    if json {
+        println!("{}", render_json(&report));
+    } else {
+        print!("{}", render_text(&report));
+        if report.is_empty() {
+            println!("\nMake sure the daemon is running to collect data:");
+            println!("  jolt daemon start");
+        }
+    }
+
+    Ok(())
+}
+
+pub fn render_json(report: &WeeklyReport) -> String {
+    serde_json::to_string_pretty(report).unwrap_or_default()
+}
+
+pub fn render_text(report: &WeeklyReport) -> String {
+    let mut out = String::new();
+    let rule = "=".repeat(60);
+    let line = "-".repeat(60);
+    let watts = |w: Option<f32>| w.map_or("-".to_string(), |w| format!("{:.1}", w));
+
+    out.push_str(&format!(
+        "Weekly Report ({} to {})\n",
+        report.from, report.to
+    ));
+    out.push_str(&format!("{}\n", rule));
+
+    if report.is_empty() {
+        out.push_str("No data for this period.\n");
+        return out;
+    }
+
+    out.push_str(&format!(
+        "Energy on battery: {:.1} Wh\n",
+        report.energy_on_battery_wh
+    ));
+    out.push_str(&format!("Cycles added:      {:.2}\n", report.cycles_added));
+    out.push_str(&format!(
+        "Avg power:         {} W\n",
+        watts(report.avg_power_watts)
+    ));
+    out.push_str(&format!(
+        "Peak power:        {} W\n",
+        watts(report.peak_power_watts)
+    ));
+
+    out.push_str("\nTop Apps by Energy\n");
+    out.push_str(&format!("{}\n", line));
+    if report.top_apps.is_empty() {
+        out.push_str("No process data for this period.\n");
+    }
+    for (i, app) in report.top_apps.iter().enumerate() {
+        out.push_str(&format!(
+            "{:<4} {:<40} {:>9.1} Wh{}\n",
+            i + 1,
+            truncate_str(&app.name, 38),
+            app.energy_wh,
+            if app.estimated_wh > 0.0 { " *" } else { "" }
+        ));
+    }
+    if report.top_apps.iter().any(|app| app.estimated_wh > 0.0) {
+        out.push_str("* partly estimated; --measured-only leaves that out\n");
+    }
+
+    out.push_str("\nTime per Power Mode\n");
+    out.push_str(&format!("{}\n", line));
+    if report.power_modes.is_empty() {
+        out.push_str("No power mode data for this period.\n");
+    }
+    for mode in &report.power_modes {
+        out.push_str(&format!("{:<20} {:>8.1} h\n", mode.mode, mode.hours));
+    }
+
+    out.push_str("\nDaily Breakdown\n");
+    out.push_str(&format!("{}\n", line));
+    out.push_str(&format!(
+        "{:<12} {:>10} {:>12} {:>7} {:>7} {:>7}\n",
+        "Date", "Energy Wh", "Battery Wh", "Cycles", "Avg W", "Peak W"
+    ));
+    for day in &report.days {
+        out.push_str(&format!(
+            "{:<12} {:>10.1} {:>12.1} {:>7.2} {:>7} {:>7}\n",
+            day.date,
+            day.energy_wh,
+            day.energy_on_battery_wh,
+            day.cycles_added,
+            watts(day.avg_power_watts),
+            watts(day.peak_power_watts)
+        ));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use chrono::{FixedOffset, NaiveDate};
+
+    use super::*;
+
+    const FIXTURE_DB: &str = include_str!("../../../fixtures/report/week.sql");
+
+    fn report_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("report")
+    }
+
+    /// Compares `actual` with the golden file `name`, rewriting it instead
+    /// when `JOLT_UPDATE_GOLDEN` is set.
+    fn assert_golden(name: &str, actual: &str) {
+        let path = report_dir().join(name);
+        if std::env::var_os("JOLT_UPDATE_GOLDEN").is_some() {
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            expected == actual,
+            "{} changed; rerun with JOLT_UPDATE_GOLDEN=1 if intended\n{}",
+            name,
+            actual
+        );
+    }
+
+    fn fixture_report() -> WeeklyReport {
+        let store = HistoryStore::open_in_memory(FIXTURE_DB).unwrap();
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        let last_day = NaiveDate::from_ymd_opt(2024, 3, 17).unwrap();
+        weekly_report(&store, last_day, &tz, 60, true).unwrap()
+    }
+
+    #[test]
+    fn weekly_report_text_matches_golden_file() {
+        assert_golden("weekly.txt", &render_text(&fixture_report()));
+    }
+
+    #[test]
+    fn weekly_report_json_matches_golden_file() {
+        assert_golden(
+            "weekly.json",
+            &format!("{}\n", render_json(&fixture_report())),
+        );
+    }
+
+    #[test]
+    fn redacted_apps_appear_only_by_alias() {
+        let mut report = fixture_report();
+        report.redact(&mut ProcessRedactor::new(&[
+            "slack".to_string(),
+            "google *".to_string(),
+        ]));
+
+        for output in [render_text(&report), render_json(&report)] {
+            assert!(!output.contains("Slack"), "{}", output);
+            assert!(!output.contains("Google"), "{}", output);
+            assert!(output.contains("Xcode"));
+        }
+        assert_eq!(report.top_apps[1].name, "app-1");
+        assert_eq!(report.top_apps[1].bundle_id, None);
+        assert_eq!(report.top_apps[2].name, "app-2");
+        assert!(report.top_apps[0].bundle_id.is_some());
+    }
+
+    #[test]
+    fn measured_only_leaves_out_estimated_energy() {
+        let store = HistoryStore::open_in_memory(FIXTURE_DB).unwrap();
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        let last_day = NaiveDate::from_ymd_opt(2024, 3, 17).unwrap();
+        let all = fixture_report();
+        let measured = weekly_report(&store, last_day, &tz, 60, false).unwrap();
+
+        // Thursday's late session counted 1.3 times Xcode's hourly 3.2 Wh.
+        let xcode = |report: &WeeklyReport| report.top_apps[0].energy_wh;
+        assert!((xcode(&all) - xcode(&measured) - 3.2 * 1.3).abs() < 1e-3);
+        assert!(measured.top_apps.iter().all(|app| app.estimated_wh == 0.0));
+        assert!(render_text(&all).contains(" Wh *\n"));
+        assert!(!render_text(&measured).contains('*'));
+    }
+
+    #[test]
+    fn empty_report_says_so() {
+        let store = HistoryStore::open_in_memory("").unwrap();
+        let last_day = NaiveDate::from_ymd_opt(2024, 3, 17).unwrap();
+        let report = weekly_report(&store, last_day, &chrono::Utc, 60, true).unwrap();
+        assert!(report.is_empty());
+        assert_eq!(report.days.len(), 7);
+        assert!(render_text(&report).ends_with("No data for this period.\n"));
+    }
+}
@@ -0,0 +1,527 @@
+//! `jolt serve`: a small HTTP endpoint for dashboards and other jolt instances.
+//!
+//! Routes:
+//! - `/snapshot`: this machine's battery, power and processes as JSON
+//! - `/fleet`: this machine plus the latest snapshot of every peer
+//! - `/metrics`: Prometheus text format, one `host` label per machine
+//!
+//! Local sampling and peer polling run on their own threads; the listener
+//! only reads the latest results, so a slow peer never delays a response.
+//!
+//! Per-process power is exported for the top [`TOP_PROCESS_SERIES`] apps
+//! only, labelled by app rather than PID so series survive restarts. The
+//! rest of the CPU power goes to `jolt_process_other_watts`, and an app that
+//! leaves the top simply stops being exported so its series goes stale.
+//!
+//! Processes matching `redact_processes` are renamed by the sampler, before
+//! the snapshot is stored, so no route ever sees their real names.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use color_eyre::eyre::{Result, WrapErr};
+use serde_json::json;
+use tracing::{info, warn};
+
+use crate::daemon::ProcessSnapshot;
+use crate::data::fleet::{Fleet, HostSnapshot, PeerStatus};
+use crate::data::redaction::outermost_bundle;
+use crate::data::{PowerData, ProcessRedactor, RefreshCoordinator};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Apps per host exported as their own `jolt_process_watts` series.
+const TOP_PROCESS_SERIES: usize = 10;
+
+/// Longest `app` label; longer names are cut to keep series readable.
+const MAX_APP_LABEL_LEN: usize = 64;
+
+type SharedSnapshot = Arc<Mutex<Option<HostSnapshot>>>;
+
+pub fn run(
+    bind: &str,
+    peers: Vec<String>,
+    peer_interval_ms: u64,
+    redactor: ProcessRedactor,
+) -> Result<()> {
+    let local: SharedSnapshot = Arc::new(Mutex::new(None));
+    spawn_local_sampler(Arc::clone(&local), redactor)?;
+
+    let fleet = Fleet::spawn(peers, Duration::from_millis(peer_interval_ms));
+
+    let listener =
+        TcpListener::bind(bind).wrap_err_with(|| format!("Failed to bind to {}", bind))?;
+    info!(address = %bind, "Serving snapshots");
+    println!("Serving on http://{}", bind);
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = handle_connection(stream, &local, &fleet) {
+                    warn!(error = %e, "Failed to handle request");
+                }
+            }
+            Err(e) => warn!(error = %e, "Failed to accept connection"),
+        }
+    }
+
+    Ok(())
+}
+
+fn spawn_local_sampler(local: SharedSnapshot, mut redactor: ProcessRedactor) -> Result<()> {
+    let mut coordinator = RefreshCoordinator::new(SAMPLE_INTERVAL)?;
+    let mut power = PowerData::new()?;
+    let hostname = HostSnapshot::local_hostname();
+
+    thread::spawn(move || loop {
+        let mut snapshot = coordinator.refresh(&mut power);
+        redactor.redact_processes(&mut snapshot.processes.value);
+        let host = HostSnapshot {
+            hostname: hostname.clone(),
+            timestamp: chrono::Utc::now().timestamp(),
+            battery: snapshot.battery.value,
+            power: snapshot.power.value,
+            processes: snapshot.processes.value,
+        };
+        if let Ok(mut local) = local.lock() {
+            *local = Some(host);
+        }
+        thread::sleep(SAMPLE_INTERVAL);
+    });
+
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream, local: &SharedSnapshot, fleet: &Fleet) -> Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let local = local.lock().ok().and_then(|l| l.clone());
+    let (status, content_type, body) = match (request_path(&request_line), local) {
+        (Some(_), None) => (
+            "503 Service Unavailable",
+            "text/plain",
+            "Warming up\n".to_string(),
+        ),
+        (Some("/snapshot"), Some(local)) => {
+            ("200 OK", "application/json", serde_json::to_string(&local)?)
+        }
+        (Some("/fleet"), Some(local)) => (
+            "200 OK",
+            "application/json",
+            fleet_document(&local, fleet).to_string(),
+        ),
+        (Some("/metrics"), Some(local)) => (
+            "200 OK",
+            "text/plain; version=0.0.4",
+            render_metrics(&local, &fresh_peers(fleet)),
+        ),
+        (Some(_), Some(_)) => ("404 Not Found", "text/plain", "Not found\n".to_string()),
+        (None, _) => ("400 Bad Request", "text/plain", "Bad request\n".to_string()),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+/// Path of a `GET` request line, without any query string.
+fn request_path(request_line: &str) -> Option<&str> {
+    let mut parts = request_line.split_whitespace();
+    if parts.next()? != "GET" {
+        return None;
+    }
+    let target = parts.next()?;
+    Some(target.split('?').next().unwrap_or(target))
+}
+
+fn fleet_document(local: &HostSnapshot, fleet: &Fleet) -> serde_json::Value {
+    let peers: Vec<_> = fleet
+        .peers()
+        .into_iter()
+        .map(|peer| {
+            json!({
+                "address": peer.address,
+                "hostname": peer.snapshot.as_ref().map(|s| &s.hostname),
+                "stale": fleet.is_stale(&peer),
+                "age_secs": peer.age().map(|a| a.as_secs()),
+                "error": peer.last_error,
+                "snapshot": peer.snapshot,
+            })
+        })
+        .collect();
+
+    json!({
+        "local": local,
+        "peers": peers,
+    })
+}
+
+/// Peers paired with whether their last snapshot is still fresh.
+fn fresh_peers(fleet: &Fleet) -> Vec<(PeerStatus, bool)> {
+    fleet
+        .peers()
+        .into_iter()
+        .map(|peer| {
+            let fresh = !fleet.is_stale(&peer);
+            (peer, fresh)
+        })
+        .collect()
+}
+
+fn render_metrics(local: &HostSnapshot, peers: &[(PeerStatus, bool)]) -> String {
+    let mut hosts = vec![local];
+    hosts.extend(
+        peers
+            .iter()
+            .filter(|(_, fresh)| *fresh)
+            .filter_map(|(peer, _)| peer.snapshot.as_ref()),
+    );
+
+    let mut out = String::new();
+
+    metric_header(
+        &mut out,
+        "jolt_battery_percent",
+        "Battery charge in percent.",
+    );
+    for host in &hosts {
+        let _ = writeln!(
+            out,
+            "jolt_battery_percent{{host=\"{}\"}} {}",
+            escape_label(&host.hostname),
+            host.battery.charge_percent
+        );
+    }
+
+    metric_header(&mut out, "jolt_power_watts", "Power draw in watts.");
+    for host in &hosts {
+        let host_label = escape_label(&host.hostname);
+        for (component, watts) in [
+            ("total", host.power.total_power_watts),
+            ("cpu", host.power.cpu_power_watts),
+            ("gpu", host.power.gpu_power_watts),
+        ] {
+            let _ = writeln!(
+                out,
+                "jolt_power_watts{{host=\"{}\",component=\"{}\"}} {}",
+                host_label, component, watts
+            );
+        }
+    }
+
+    metric_header(
+        &mut out,
+        "jolt_process_watts",
+        "CPU power attributed to an app, for the top apps by power.",
+    );
+    let attributions: Vec<_> = hosts
+        .iter()
+        .map(|host| top_app_power(&host.processes, host.power.cpu_power_watts))
+        .collect();
+    for (host, (apps, _)) in hosts.iter().zip(&attributions) {
+        let host_label = escape_label(&host.hostname);
+        for (app, watts) in apps {
+            let _ = writeln!(
+                out,
+                "jolt_process_watts{{host=\"{}\",app=\"{}\"}} {}",
+                host_label,
+                escape_label(app),
+                watts
+            );
+        }
+    }
+
+    metric_header(
+        &mut out,
+        "jolt_process_other_watts",
+        "CPU power not attributed to a top app.",
+    );
+    for (host, (_, other)) in hosts.iter().zip(&attributions) {
+        let _ = writeln!(
+            out,
+            "jolt_process_other_watts{{host=\"{}\"}} {}",
+            escape_label(&host.hostname),
+            other
+        );
+    }
+
+    metric_header(
+        &mut out,
+        "jolt_peer_up",
+        "Whether the most recent poll of a peer succeeded.",
+    );
+    for (peer, _) in peers {
+        let _ = writeln!(
+            out,
+            "jolt_peer_up{{host=\"{}\",address=\"{}\"}} {}",
+            escape_label(peer.name()),
+            escape_label(&peer.address),
+            u8::from(peer.last_error.is_none() && peer.snapshot.is_some())
+        );
+    }
+
+    out
+}
+
+/// Splits `cpu_watts` across apps by their share of CPU usage and returns
+/// the [`TOP_PROCESS_SERIES`] largest, biggest first, plus the remainder.
+/// The apps and the remainder always add up to `cpu_watts`.
+fn top_app_power(processes: &[ProcessSnapshot], cpu_watts: f32) -> (Vec<(String, f32)>, f32) {
+    // Grouped children are also listed at the top level, so only the top
+    // level is counted.
+    let total_cpu: f32 = processes.iter().map(|p| p.cpu_usage.max(0.0)).sum();
+    if total_cpu <= 0.0 || cpu_watts <= 0.0 {
+        return (Vec::new(), cpu_watts.max(0.0));
+    }
+
+    let mut by_app: HashMap<String, f32> = HashMap::new();
+    for process in processes {
+        *by_app.entry(app_label(process)).or_default() +=
+            cpu_watts * process.cpu_usage.max(0.0) / total_cpu;
+    }
+
+    let mut apps: Vec<(String, f32)> = by_app.into_iter().filter(|(_, w)| *w > 0.0).collect();
+    apps.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    apps.truncate(TOP_PROCESS_SERIES);
+
+    let attributed: f32 = apps.iter().map(|(_, w)| w).sum();
+    (apps, (cpu_watts - attributed).max(0.0))
+}
+
+/// Stable per-app label: the outermost `.app` bundle a process runs from,
+/// so helpers count towards their app, or the process name otherwise.
+fn app_label(process: &ProcessSnapshot) -> String {
+    let name = outermost_bundle(&process.executable_path).unwrap_or(&process.name);
+    let label: String = name
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_APP_LABEL_LEN)
+        .collect();
+    if label.is_empty() {
+        "unknown".to_string()
+    } else {
+        label
+    }
+}
+
+fn metric_header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::{BatterySnapshot, BinaryArch, PowerSnapshot, ProcessState};
+    use std::path::PathBuf;
+    use std::time::Instant;
+
+    fn process(name: &str, exe: &str, cpu_usage: f32) -> ProcessSnapshot {
+        ProcessSnapshot {
+            pid: 0,
+            name: name.to_string(),
+            command: name.to_string(),
+            command_args: String::new(),
+            cpu_usage,
+            memory_mb: 0.0,
+            energy_impact: 0.0,
+            parent_pid: None,
+            children: None,
+            is_killable: true,
+            disk_read_bytes: 0,
+            disk_write_bytes: 0,
+            status: ProcessState::Running,
+            run_time_secs: 0,
+            total_cpu_time_secs: 0,
+            network_bytes_per_sec: None,
+            sandbox_container_id: None,
+            executable_path: PathBuf::from(exe),
+            start_time: 0,
+            team_id: None,
+            binary_arch: BinaryArch::Unknown,
+            power_assertion_count: None,
+        }
+    }
+
+    /// Value of the first sample line starting with `series`.
+    fn sample(metrics: &str, series: &str) -> Option<f32> {
+        metrics
+            .lines()
+            .find_map(|l| l.strip_prefix(series))
+            .and_then(|v| v.trim().parse().ok())
+    }
+
+    fn host(name: &str, percent: f32, watts: f32) -> HostSnapshot {
+        HostSnapshot {
+            hostname: name.to_string(),
+            timestamp: 0,
+            battery: BatterySnapshot {
+                charge_percent: percent,
+                ..Default::default()
+            },
+            power: PowerSnapshot {
+                total_power_watts: watts,
+                ..Default::default()
+            },
+            processes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn request_path_strips_query() {
+        assert_eq!(
+            request_path("GET /metrics?x=1 HTTP/1.1\r\n"),
+            Some("/metrics")
+        );
+        assert_eq!(request_path("POST /fleet HTTP/1.1\r\n"), None);
+        assert_eq!(request_path(""), None);
+    }
+
+    #[test]
+    fn metrics_label_each_host_and_skip_stale_peers() {
+        let local = host("studio", 80.0, 12.5);
+        let fresh = PeerStatus {
+            address: "air:9898".to_string(),
+            snapshot: Some(host("air", 41.0, 6.0)),
+            last_success: Some(Instant::now()),
+            last_error: None,
+        };
+        let stale = PeerStatus {
+            address: "pro:9898".to_string(),
+            snapshot: Some(host("pro", 10.0, 30.0)),
+            last_success: Some(Instant::now()),
+            last_error: Some("Request failed".to_string()),
+        };
+
+        let metrics = render_metrics(&local, &[(fresh, true), (stale, false)]);
+
+        assert!(metrics.contains("jolt_battery_percent{host=\"studio\"} 80"));
+        assert!(metrics.contains("jolt_battery_percent{host=\"air\"} 41"));
+        assert!(!metrics.contains("jolt_battery_percent{host=\"pro\"}"));
+        assert!(metrics.contains("jolt_power_watts{host=\"studio\",component=\"total\"} 12.5"));
+        assert!(metrics.contains("jolt_peer_up{host=\"air\",address=\"air:9898\"} 1"));
+        assert!(metrics.contains("jolt_peer_up{host=\"pro\",address=\"pro:9898\"} 0"));
+    }
+
+    #[test]
+    fn process_metrics_group_by_bundle_and_sum_to_cpu_power() {
+        let mut local = host("studio", 80.0, 20.0);
+        local.power.cpu_power_watts = 12.0;
+        local.processes = vec![
+            process(
+                "Google Chrome",
+                "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+                30.0,
+            ),
+            process(
+                "Google Chrome Helper (Renderer)",
+                "/Applications/Google Chrome.app/Contents/Frameworks/Helpers/\
+                 Google Chrome Helper (Renderer).app/Contents/MacOS/helper",
+                30.0,
+            ),
+            process("cargo\"\n  build", "", 20.0),
+        ];
+        local
+            .processes
+            .extend((0..TOP_PROCESS_SERIES).map(|i| process(&format!("tool{}", i), "", 2.0)));
+
+        let metrics = render_metrics(&local, &[]);
+
+        assert!(metrics.contains("# TYPE jolt_process_watts gauge"));
+        let chrome = sample(
+            &metrics,
+            "jolt_process_watts{host=\"studio\",app=\"Google Chrome\"}",
+        )
+        .unwrap();
+        assert!((chrome - 7.2).abs() < 1e-4, "chrome {}", chrome);
+        assert!(!metrics.contains("Renderer"));
+        assert!(metrics.contains("app=\"cargo\\\" build\"}"));
+
+        let series: Vec<f32> = metrics
+            .lines()
+            .filter(|l| l.starts_with("jolt_process_watts{host=\"studio\""))
+            .filter_map(|l| l.rsplit(' ').next()?.parse().ok())
+            .collect();
+        assert_eq!(series.len(), TOP_PROCESS_SERIES);
+        let other = sample(&metrics, "jolt_process_other_watts{host=\"studio\"}").unwrap();
+        assert!(other > 0.0);
+        assert!((series.iter().sum::<f32>() + other - 12.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn apps_leaving_the_top_stop_being_exported() {
+        let mut local = host("studio", 80.0, 20.0);
+        local.power.cpu_power_watts = 10.0;
+        local.processes = vec![process("Xcode", "", 50.0)];
+        assert!(render_metrics(&local, &[]).contains("app=\"Xcode\""));
+
+        local.processes = (0..=TOP_PROCESS_SERIES)
+            .map(|i| process(&format!("tool{}", i), "", 10.0))
+            .chain([process("Xcode", "", 1.0)])
+            .collect();
+        assert!(!render_metrics(&local, &[]).contains("Xcode"));
+
+        // Idle hosts export only the catch-all.
+        local.processes.clear();
+        let idle = render_metrics(&local, &[]);
+        assert!(!idle.contains("jolt_process_watts{"));
+        assert_eq!(
+            sample(&idle, "jolt_process_other_watts{host=\"studio\"}"),
+            Some(10.0)
+        );
+    }
+
+    #[test]
+    fn redacted_processes_never_reach_a_route() {
+        let mut local = host("studio", 80.0, 20.0);
+        local.power.cpu_power_watts = 10.0;
+        let mut processes = vec![
+            process("Slack", "/Applications/Slack.app/Contents/MacOS/Slack", 30.0),
+            process(
+                "Slack Helper",
+                "/Applications/Slack.app/Contents/Frameworks/Slack Helper.app/Contents/MacOS/Slack Helper",
+                30.0,
+            ),
+            process("Xcode", "", 40.0),
+        ];
+        ProcessRedactor::new(&["slack".to_string()]).redact_processes(&mut processes);
+        local.processes = processes;
+
+        let metrics = render_metrics(&local, &[]);
+        let app = sample(
+            &metrics,
+            "jolt_process_watts{host=\"studio\",app=\"app-1\"}",
+        )
+        .unwrap();
+        assert!((app - 6.0).abs() < 1e-4, "app-1 {}", app);
+        let snapshot = serde_json::to_string(&local).unwrap();
+        for output in [metrics, snapshot] {
+            assert!(!output.to_lowercase().contains("slack"), "{}", output);
+            assert!(output.contains("Xcode"));
+        }
+    }
+}
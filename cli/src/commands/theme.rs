@@ -79,9 +79,10 @@ pub fn run(command: Option<ThemeCommands>) -> Result<()> {
 
             #[cfg(target_os = "macos")]
             {
-                std::process::Command::new("open")
-                    .arg(&themes_dir)
-                    .status()?;
+                jolt_platform::command::run(
+                    std::process::Command::new("open").arg(&themes_dir),
+                    jolt_platform::command::CommandLimits::DEFAULT,
+                )?;
             }
 
             println!("Themes directory: {}", themes_dir.display());
@@ -0,0 +1,108 @@
+//! `jolt validate`: sample alongside `powermetrics` and report how far apart
+//! the two are per domain.
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use color_eyre::eyre::{bail, eyre, Result, WrapErr};
+
+use crate::data::power::ChannelCategory;
+use crate::data::powermetrics::{compare, parse_samples, DomainPower};
+use crate::data::PowerData;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn run(duration_secs: u64) -> Result<()> {
+    if !cfg!(target_os = "macos") {
+        bail!("powermetrics is only available on macOS");
+    }
+    if duration_secs == 0 {
+        bail!("--duration must be at least 1 second");
+    }
+    if unsafe { libc::geteuid() } != 0 {
+        eprintln!("powermetrics needs root privileges. Run:");
+        eprintln!("  sudo jolt validate --duration {}", duration_secs);
+        std::process::exit(1);
+    }
+
+    let mut power = PowerData::new()?;
+    power.set_sample_interval(SAMPLE_INTERVAL);
+
+    let mut child = Command::new("powermetrics")
+        .args([
+            "--format",
+            "plist",
+            "--samplers",
+            "cpu_power,gpu_power,ane_power",
+            "-i",
+            &SAMPLE_INTERVAL.as_millis().to_string(),
+            "-n",
+            &duration_secs.to_string(),
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .wrap_err("Failed to start powermetrics")?;
+
+    // Drain stdout on another thread so a full pipe never stalls powermetrics
+    // while jolt is sampling.
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| eyre!("powermetrics stdout unavailable"))?;
+    let reader = thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+
+    println!("Sampling for {}s alongside powermetrics...", duration_secs);
+    let mut jolt = Vec::with_capacity(duration_secs as usize);
+    for _ in 0..duration_secs {
+        power.wait_for_sample(SAMPLE_INTERVAL * 2)?;
+        let (cpu_watts, gpu_watts) = power.latest_cpu_gpu_watts();
+        let ane_watts = power.channel_category_watts(ChannelCategory::Ane);
+        jolt.push(DomainPower {
+            cpu_watts,
+            gpu_watts,
+            ane_watts,
+        });
+    }
+
+    let status = child.wait()?;
+    let output = reader
+        .join()
+        .map_err(|_| eyre!("powermetrics reader panicked"))??;
+    if !status.success() {
+        bail!("powermetrics exited with {}", status);
+    }
+
+    let reference = parse_samples(&output)?;
+    let comparison = compare(&jolt, &reference);
+    if comparison.is_empty() {
+        bail!("powermetrics produced no samples");
+    }
+
+    println!();
+    println!(
+        "{:<8}{:>12}{:>18}{:>12}",
+        "Domain", "jolt (W)", "powermetrics (W)", "MAE (W)"
+    );
+    println!("{}", "-".repeat(50));
+    for row in &comparison {
+        println!(
+            "{:<8}{:>12.3}{:>18.3}{:>12.3}",
+            row.domain, row.jolt_mean, row.reference_mean, row.mean_abs_error
+        );
+    }
+    println!();
+    println!(
+        "{} paired samples ({} from jolt, {} from powermetrics)",
+        jolt.len().min(reference.len()),
+        jolt.len(),
+        reference.len()
+    );
+
+    Ok(())
+}
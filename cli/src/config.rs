@@ -1,6 +1,8 @@
 use crate::theme::ThemeColors;
+use jolt_platform::command::{self, CommandLimits};
 use jolt_theme::NamedTheme;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
@@ -30,7 +32,8 @@ impl LogLevel {
         }
     }
 
-    pub fn from_str(s: &str) -> Self {
+    /// Reads a level name in any case; unknown names mean `Info`.
+    pub fn parse(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "off" => LogLevel::Off,
             "error" => LogLevel::Error,
@@ -74,6 +77,66 @@ fn default_theme_name() -> String {
     "default".to_string()
 }
 
+/// Span presets of the history view timeline, shortest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ZoomLevel {
+    #[serde(rename = "1m")]
+    OneMinute,
+    #[default]
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "30m")]
+    ThirtyMinutes,
+    #[serde(rename = "2h")]
+    TwoHours,
+    #[serde(rename = "24h")]
+    Day,
+}
+
+impl ZoomLevel {
+    pub const ALL: [ZoomLevel; 5] = [
+        ZoomLevel::OneMinute,
+        ZoomLevel::FiveMinutes,
+        ZoomLevel::ThirtyMinutes,
+        ZoomLevel::TwoHours,
+        ZoomLevel::Day,
+    ];
+
+    pub fn span_secs(self) -> i64 {
+        match self {
+            ZoomLevel::OneMinute => 60,
+            ZoomLevel::FiveMinutes => 5 * 60,
+            ZoomLevel::ThirtyMinutes => 30 * 60,
+            ZoomLevel::TwoHours => 2 * 3600,
+            ZoomLevel::Day => 24 * 3600,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ZoomLevel::OneMinute => "1m",
+            ZoomLevel::FiveMinutes => "5m",
+            ZoomLevel::ThirtyMinutes => "30m",
+            ZoomLevel::TwoHours => "2h",
+            ZoomLevel::Day => "24h",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|z| *z == self).unwrap_or(0)
+    }
+
+    /// The next shorter span, or `self` at the shortest.
+    pub fn zoom_in(self) -> Self {
+        Self::ALL[self.index().saturating_sub(1)]
+    }
+
+    /// The next longer span, or `self` at the longest.
+    pub fn zoom_out(self) -> Self {
+        Self::ALL[(self.index() + 1).min(Self::ALL.len() - 1)]
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct HistoryConfig {
@@ -85,6 +148,10 @@ pub struct HistoryConfig {
     pub retention_daily_days: u32,
     pub retention_sessions_days: u32,
     pub max_database_mb: u32,
+    /// Weeks of hourly stats averaged into the power heatmap.
+    pub heatmap_weeks: u32,
+    /// Span of the history view timeline; changed with `+`/`-` and saved.
+    pub timeline_zoom: ZoomLevel,
 }
 
 impl Default for HistoryConfig {
@@ -97,6 +164,8 @@ impl Default for HistoryConfig {
             retention_daily_days: 0,
             retention_sessions_days: 90,
             max_database_mb: 500,
+            heatmap_weeks: 4,
+            timeline_zoom: ZoomLevel::default(),
         }
     }
 }
@@ -113,14 +182,39 @@ pub struct UserConfig {
     pub process_count: usize,
     pub energy_threshold: f32,
     pub merge_mode: bool,
+    /// Sample per-process network throughput (macOS `nettop`).
+    pub process_network: bool,
+    /// Leave system processes, the ones jolt refuses to kill, out of the
+    /// process list.
+    pub hide_system_processes: bool,
+    /// Samples averaged into the displayed power readings.
+    pub power_smoothing_samples: usize,
+    /// Milliseconds between power samples on the sampling thread,
+    /// independent of `refresh_ms`.
+    pub power_sample_ms: u64,
     pub forecast_window_secs: u64,
+    /// Minutes after a clean quit during which the next launch offers to
+    /// resume the session's counters; 0 never offers.
+    pub session_resume_mins: u64,
     #[serde(default)]
     pub excluded_processes: Vec<String>,
+    /// Process name or `.app` bundle patterns shown as `app-N` in everything
+    /// jolt exports: serve endpoints, pipe output, history exports, reports
+    /// and bug reports.
+    #[serde(default)]
+    pub redact_processes: Vec<String>,
+    /// Other `jolt serve` instances (`host:port`) listed in the fleet view.
+    #[serde(default)]
+    pub fleet_peers: Vec<String>,
     #[serde(default)]
     pub history: HistoryConfig,
     #[serde(default)]
     pub units: UnitsConfig,
     #[serde(default)]
+    pub sparklines: SparklineConfig,
+    #[serde(default)]
+    pub advice: AdviceConfig,
+    #[serde(default)]
     pub log_level: LogLevel,
 }
 
@@ -135,10 +229,19 @@ impl Default for UserConfig {
             process_count: 50,
             energy_threshold: 0.5,
             merge_mode: true,
+            process_network: false,
+            hide_system_processes: false,
+            power_smoothing_samples: 5,
+            power_sample_ms: 1000,
             forecast_window_secs: 300,
+            session_resume_mins: 60,
             excluded_processes: Vec::new(),
+            redact_processes: Vec::new(),
+            fleet_peers: Vec::new(),
             history: HistoryConfig::default(),
             units: UnitsConfig::default(),
+            sparklines: SparklineConfig::default(),
+            advice: AdviceConfig::default(),
             log_level: LogLevel::Info,
         }
     }
@@ -152,6 +255,7 @@ pub enum GraphMetric {
     Split,
     #[default]
     Merged,
+    Domains,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -247,12 +351,45 @@ impl DataSizeUnit {
     }
 }
 
+/// How process CPU usage is scaled for display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CpuNormalization {
+    /// 100% is one core, so busy processes can exceed 100% (`top`, sysinfo).
+    #[default]
+    PerCore,
+    /// 100% is every core (Activity Monitor's "% CPU" with "Divide CPU
+    /// usage by number of CPUs").
+    TotalMachine,
+}
+
+impl CpuNormalization {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CpuNormalization::PerCore => "Per core",
+            CpuNormalization::TotalMachine => "Total machine",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            CpuNormalization::PerCore => CpuNormalization::TotalMachine,
+            CpuNormalization::TotalMachine => CpuNormalization::PerCore,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        self.next()
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(default)]
 pub struct UnitsConfig {
     pub energy: EnergyUnit,
     pub temperature: TemperatureUnit,
     pub data_size: DataSizeUnit,
+    pub cpu: CpuNormalization,
 }
 
 impl Default for UnitsConfig {
@@ -261,6 +398,61 @@ impl Default for UnitsConfig {
             energy: EnergyUnit::Wh,
             temperature: TemperatureUnit::Celsius,
             data_size: DataSizeUnit::Si,
+            cpu: CpuNormalization::PerCore,
+        }
+    }
+}
+
+/// Inline sparklines next to overview readouts. Each can be turned off on
+/// terminals that draw block glyphs poorly.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SparklineConfig {
+    pub cpu: bool,
+    pub gpu: bool,
+    pub battery: bool,
+    pub temperature: bool,
+}
+
+impl Default for SparklineConfig {
+    fn default() -> Self {
+        Self {
+            cpu: true,
+            gpu: true,
+            battery: true,
+            temperature: true,
+        }
+    }
+}
+
+/// Charging advice shown under the battery gauge. Temperatures are in °C
+/// regardless of the display unit.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdviceConfig {
+    pub enabled: bool,
+    pub hot_charging: bool,
+    pub hot_charging_c: f32,
+    pub fast_charging_watts: f32,
+    pub hot_battery: bool,
+    pub hot_battery_c: f32,
+    pub held_at_limit: bool,
+    /// How far below its threshold the temperature must fall before hot
+    /// advice clears.
+    pub hysteresis_c: f32,
+}
+
+impl Default for AdviceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            hot_charging: true,
+            hot_charging_c: 40.0,
+            fast_charging_watts: 30.0,
+            hot_battery: true,
+            hot_battery_c: 45.0,
+            held_at_limit: true,
+            hysteresis_c: 2.0,
         }
     }
 }
@@ -283,6 +475,16 @@ pub fn data_dir() -> PathBuf {
         .join("jolt")
 }
 
+/// State kept across runs that is not worth backing up, such as crash
+/// reports. `dirs` has no state directory on macOS, so the data directory
+/// is used there.
+pub fn state_dir() -> PathBuf {
+    dirs::state_dir()
+        .or_else(dirs::data_dir)
+        .unwrap_or_else(|| PathBuf::from("~/.local/state"))
+        .join("jolt")
+}
+
 pub fn runtime_dir() -> PathBuf {
     dirs::runtime_dir()
         .or_else(dirs::cache_dir)
@@ -305,7 +507,27 @@ pub fn ensure_dirs() -> std::io::Result<()> {
     Ok(())
 }
 
+/// Why config file contents were rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    /// 1-based number and text of the offending line, when the parser
+    /// points at one.
+    pub line: Option<(usize, String)>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((number, text)) = &self.line {
+            write!(f, "line {} `{}`: ", number, text)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
 impl UserConfig {
+    /// Reads the config file, falling back to defaults when it is missing
+    /// or rejected by [`Self::parse`].
     pub fn load() -> Self {
         let path = config_path();
         if !path.exists() {
@@ -313,17 +535,38 @@ impl UserConfig {
         }
 
         match fs::read_to_string(&path) {
-            Ok(content) => toml::from_str(&content).unwrap_or_default(),
+            Ok(content) => Self::parse(&content).unwrap_or_default(),
             Err(_) => Self::default(),
         }
     }
 
+    /// Parses config file contents. Startup and live reload both go through
+    /// here, so they accept and reject the same files.
+    pub fn parse(content: &str) -> Result<Self, ConfigError> {
+        toml::from_str(content).map_err(|e| ConfigError {
+            line: e.span().map(|span| {
+                let before = &content[..span.start.min(content.len())];
+                let number = before.matches('\n').count() + 1;
+                let text = content.lines().nth(number - 1).unwrap_or_default();
+                (number, text.trim().to_string())
+            }),
+            message: e.message().trim().to_string(),
+        })
+    }
+
+    /// Writes the config file atomically: the new content goes to a
+    /// temporary file next to it that then replaces the old file, so a crash
+    /// mid-write never leaves a truncated config behind.
     pub fn save(&self) -> std::io::Result<()> {
         let _ = ensure_dirs();
         let path = config_path();
         let content = toml::to_string_pretty(self)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
-        fs::write(path, content)
+        let tmp_path = path.with_extension("toml.tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, &path).inspect_err(|_| {
+            let _ = fs::remove_file(&tmp_path);
+        })
     }
 
     pub fn effective_excluded_processes(&self) -> Vec<&str> {
@@ -372,13 +615,24 @@ impl RuntimeConfig {
     }
 
     pub fn set_theme(&mut self, theme_id: &str) {
-        if let Some(theme) = jolt_theme::get_theme_by_id(theme_id, Some(&themes_dir())) {
-            self.current_theme = theme;
-            self.user_config.theme = theme_id.to_string();
+        if self.apply_theme(theme_id) {
             let _ = self.user_config.save();
         }
     }
 
+    /// Switches to `theme_id` without writing the config file. Returns
+    /// `false`, keeping the current theme, when no such theme exists.
+    pub fn apply_theme(&mut self, theme_id: &str) -> bool {
+        match jolt_theme::get_theme_by_id(theme_id, Some(&themes_dir())) {
+            Some(theme) => {
+                self.current_theme = theme;
+                self.user_config.theme = theme_id.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn cycle_appearance(&mut self) {
         self.user_config.appearance = self.user_config.appearance.next();
         let _ = self.user_config.save();
@@ -411,13 +665,10 @@ impl RuntimeConfig {
 }
 
 pub fn detect_system_dark_mode() -> bool {
-    Command::new("defaults")
-        .args(["read", "-g", "AppleInterfaceStyle"])
-        .output()
-        .map(|output| {
-            String::from_utf8_lossy(&output.stdout)
-                .trim()
-                .eq_ignore_ascii_case("dark")
-        })
-        .unwrap_or(false)
+    command::run(
+        Command::new("defaults").args(["read", "-g", "AppleInterfaceStyle"]),
+        CommandLimits::DEFAULT,
+    )
+    .map(|output| output.stdout_lossy().trim().eq_ignore_ascii_case("dark"))
+    .unwrap_or(false)
 }
@@ -0,0 +1,305 @@
+//! Live reload of the config file.
+//!
+//! The TUI watches the config file and re-reads it once it has been quiet
+//! for [`RELOAD_DEBOUNCE`], through the same [`UserConfig::parse`] as
+//! startup. Keys in [`HOT_KEYS`] take effect right away; every other key is
+//! only kept for the next start, and listed until then as waiting for a
+//! restart. A file that fails to parse leaves the running config untouched.
+//!
+//! A reload only takes keys that changed in the file, so settings kept for
+//! the session from the settings panel survive edits to unrelated keys.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use tracing::debug;
+
+use crate::config::{ConfigError, UserConfig};
+
+/// Quiet time after the last change before the file is re-read, so an
+/// editor's write, rename and chmod reload it once.
+pub const RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Top-level keys a running TUI applies without restarting: intervals,
+/// theme, thresholds, process list columns and units.
+pub const HOT_KEYS: &[&str] = &[
+    "appearance",
+    "theme",
+    "refresh_ms",
+    "power_smoothing_samples",
+    "power_sample_ms",
+    "forecast_window_secs",
+    "energy_threshold",
+    "advice",
+    "show_graph",
+    "graph_metric",
+    "process_count",
+    "merge_mode",
+    "process_network",
+    "hide_system_processes",
+    "sparklines",
+    "units",
+];
+
+/// Outcome of re-reading the config file.
+#[derive(Debug)]
+pub enum Reload {
+    /// The file parsed. `changed` lists the top-level keys to take from
+    /// `config`: those edited since the last reload whose value differs
+    /// from the running one. Only [`HOT_KEYS`] among them take effect now.
+    Applied {
+        config: Box<UserConfig>,
+        changed: Vec<String>,
+    },
+    /// The file was rejected; the running config stays as it is.
+    Rejected(ConfigError),
+}
+
+pub struct ConfigWatcher {
+    path: PathBuf,
+    /// The config in effect since startup, for keys that need a restart.
+    startup: toml::Table,
+    /// The file as of the last successful reload.
+    baseline: toml::Table,
+    /// Time of the latest change not yet reloaded.
+    changed_at: Option<Instant>,
+    /// Keys whose file value differs from the one in effect since startup.
+    pending_restart: Vec<String>,
+    events: Option<Receiver<Instant>>,
+    _watcher: Option<RecommendedWatcher>,
+}
+
+impl ConfigWatcher {
+    /// Watches `path`, whose contents `running` was loaded from. Without
+    /// file notifications the watcher stays idle and the TUI runs as before.
+    pub fn watch(path: PathBuf, running: &UserConfig) -> Self {
+        let mut watcher = Self::new(path, running);
+        match notify_changes(&watcher.path) {
+            Ok((notifier, events)) => {
+                watcher._watcher = Some(notifier);
+                watcher.events = Some(events);
+            }
+            Err(e) => debug!(error = %e, "Config file changes are not watched"),
+        }
+        watcher
+    }
+
+    /// A watcher that only learns of changes through [`Self::notice_change`].
+    pub fn new(path: PathBuf, running: &UserConfig) -> Self {
+        let table = to_table(running);
+        Self {
+            path,
+            startup: table.clone(),
+            baseline: table,
+            changed_at: None,
+            pending_restart: Vec::new(),
+            events: None,
+            _watcher: None,
+        }
+    }
+
+    /// Records that the file changed at `at`.
+    pub fn notice_change(&mut self, at: Instant) {
+        self.changed_at = Some(at);
+    }
+
+    /// Keys edited in the file that take effect on the next start.
+    pub fn pending_restart(&self) -> &[String] {
+        &self.pending_restart
+    }
+
+    /// Re-reads the file if it changed and has since been quiet for
+    /// [`RELOAD_DEBOUNCE`]. `running` is the config currently in effect.
+    ///
+    /// A missing file is not reloaded: editors briefly remove it while
+    /// saving, and the write that follows reports a change of its own.
+    pub fn poll(&mut self, now: Instant, running: &UserConfig) -> Option<Reload> {
+        if let Some(latest) = self.events.as_ref().and_then(|rx| rx.try_iter().last()) {
+            self.notice_change(latest);
+        }
+        let changed_at = self.changed_at?;
+        if now.saturating_duration_since(changed_at) < RELOAD_DEBOUNCE {
+            return None;
+        }
+        self.changed_at = None;
+
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return None,
+            Err(e) => {
+                return Some(Reload::Rejected(ConfigError {
+                    line: None,
+                    message: e.to_string(),
+                }))
+            }
+        };
+        let config = match UserConfig::parse(&content) {
+            Ok(config) => config,
+            Err(e) => return Some(Reload::Rejected(e)),
+        };
+
+        let file = to_table(&config);
+        let running = to_table(running);
+        let changed = file
+            .iter()
+            .filter(|(key, value)| {
+                self.baseline.get(key.as_str()) != Some(value)
+                    && running.get(key.as_str()) != Some(value)
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        self.pending_restart = file
+            .iter()
+            .filter(|(key, value)| {
+                !HOT_KEYS.contains(&key.as_str()) && self.startup.get(key.as_str()) != Some(value)
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        self.baseline = file;
+
+        Some(Reload::Applied {
+            config: Box::new(config),
+            changed,
+        })
+    }
+}
+
+fn to_table(config: &UserConfig) -> toml::Table {
+    toml::Table::try_from(config).unwrap_or_default()
+}
+
+/// Watches the file's directory rather than the file itself, since saving
+/// atomically replaces the file and a watch on it would end with the old one.
+fn notify_changes(path: &Path) -> notify::Result<(RecommendedWatcher, Receiver<Instant>)> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let name = path.file_name().map(|n| n.to_os_string());
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        // Reading the file reports access events, which must not reload it.
+        let edited = event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove();
+        if edited && event.paths.iter().any(|p| p.file_name() == name.as_deref()) {
+            let _ = tx.send(Instant::now());
+        }
+    })?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    Ok((watcher, rx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_file(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("jolt-config-watch-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("config.toml")
+    }
+
+    fn applied(reload: Option<Reload>) -> (UserConfig, Vec<String>) {
+        match reload {
+            Some(Reload::Applied { config, changed }) => (*config, changed),
+            other => panic!("expected a reload, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn edits_reload_once_the_file_is_quiet() {
+        let path = config_file("hot");
+        fs::write(&path, "refresh_ms = 2000\ntheme = \"default\"\n").unwrap();
+        let running = UserConfig::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        let mut watcher = ConfigWatcher::new(path.clone(), &running);
+        let start = Instant::now();
+
+        assert!(watcher
+            .poll(start + RELOAD_DEBOUNCE * 2, &running)
+            .is_none());
+
+        fs::write(&path, "refresh_ms = 1000\ntheme = \"default\"\n").unwrap();
+        watcher.notice_change(start);
+        fs::write(&path, "refresh_ms = 1500\ntheme = \"default\"\n").unwrap();
+        watcher.notice_change(start + RELOAD_DEBOUNCE / 2);
+        assert!(watcher.poll(start + RELOAD_DEBOUNCE, &running).is_none());
+
+        let (config, changed) = applied(watcher.poll(start + RELOAD_DEBOUNCE * 2, &running));
+        assert_eq!(config.refresh_ms, 1500);
+        assert_eq!(changed, vec!["refresh_ms"]);
+        assert!(watcher.pending_restart().is_empty());
+        assert!(watcher
+            .poll(start + RELOAD_DEBOUNCE * 3, &running)
+            .is_none());
+
+        // Saving the running config back reloads nothing.
+        fs::write(&path, toml::to_string(&config).unwrap()).unwrap();
+        watcher.notice_change(start + RELOAD_DEBOUNCE * 3);
+        let (_, changed) = applied(watcher.poll(start + RELOAD_DEBOUNCE * 4, &config));
+        assert!(changed.is_empty(), "{:?}", changed);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn parse_errors_keep_the_last_good_config() {
+        let path = config_file("invalid");
+        let running = UserConfig::default();
+        let mut watcher = ConfigWatcher::new(path.clone(), &running);
+        let start = Instant::now();
+
+        fs::write(&path, "theme = \"default\"\nrefresh_ms = \"fast\"\n").unwrap();
+        watcher.notice_change(start);
+        match watcher.poll(start + RELOAD_DEBOUNCE, &running) {
+            Some(Reload::Rejected(e)) => {
+                assert_eq!(e.line, Some((2, "refresh_ms = \"fast\"".to_string())));
+                assert!(e
+                    .to_string()
+                    .starts_with("line 2 `refresh_ms = \"fast\"`: "));
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+
+        // The rejected file never became the baseline.
+        fs::write(&path, "refresh_ms = 1000\nenergy_threshold = 2.0\n").unwrap();
+        watcher.notice_change(start + RELOAD_DEBOUNCE);
+        let (_, changed) = applied(watcher.poll(start + RELOAD_DEBOUNCE * 2, &running));
+        assert_eq!(changed, vec!["energy_threshold", "refresh_ms"]);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn keys_read_at_startup_wait_for_a_restart() {
+        let path = config_file("cold");
+        let mut running = UserConfig::default();
+        let mut watcher = ConfigWatcher::new(path.clone(), &running);
+        let start = Instant::now();
+
+        fs::write(&path, "log_level = \"debug\"\nshow_graph = false\n").unwrap();
+        watcher.notice_change(start);
+        let (config, changed) = applied(watcher.poll(start + RELOAD_DEBOUNCE, &running));
+        assert_eq!(changed, vec!["log_level", "show_graph"]);
+        assert_eq!(watcher.pending_restart(), ["log_level"]);
+
+        // Taking the new value into the running config does not apply it.
+        running.log_level = config.log_level;
+        running.show_graph = config.show_graph;
+        fs::write(&path, "log_level = \"debug\"\nshow_graph = true\n").unwrap();
+        watcher.notice_change(start + RELOAD_DEBOUNCE);
+        let (_, changed) = applied(watcher.poll(start + RELOAD_DEBOUNCE * 2, &running));
+        assert_eq!(changed, vec!["show_graph"]);
+        assert_eq!(watcher.pending_restart(), ["log_level"]);
+
+        fs::write(&path, "log_level = \"info\"\n").unwrap();
+        watcher.notice_change(start + RELOAD_DEBOUNCE * 2);
+        watcher.poll(start + RELOAD_DEBOUNCE * 3, &running);
+        assert!(watcher.pending_restart().is_empty());
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
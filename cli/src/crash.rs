@@ -0,0 +1,193 @@
+//! Last-chance handling for panics in the TUI.
+//!
+//! A panic in raw mode leaves the terminal unusable, with the message hidden
+//! on the alternate screen. The hook restores the terminal first, then
+//! prints the panic with a backtrace and writes a crash file holding the
+//! last daemon snapshot and the most recent debug log lines.
+
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, TryLockError};
+
+use chrono::Utc;
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt::{self, time::UtcTime, MakeWriter};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::config::state_dir;
+use crate::daemon::DataSnapshot;
+
+/// Log lines kept for the crash file.
+const LOG_RING_LINES: usize = 200;
+
+static LAST_SNAPSHOT: Mutex<Option<DataSnapshot>> = Mutex::new(None);
+static LOG_RING: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+type PanicHook = Box<dyn Fn(&PanicHookInfo<'_>) + Send + Sync + 'static>;
+
+/// Directory crash files are written to.
+pub fn crash_dir() -> PathBuf {
+    state_dir().join("crashes")
+}
+
+/// Replaces the process panic hook; `restore` puts the terminal back to
+/// normal and must not panic itself.
+pub fn install_panic_hook(restore: fn()) {
+    std::panic::set_hook(panic_hook(crash_dir(), restore));
+}
+
+/// Keeps `snapshot` for the crash file, replacing the previous one.
+pub fn remember_snapshot(snapshot: &DataSnapshot) {
+    if let Ok(mut last) = LAST_SNAPSHOT.lock() {
+        *last = Some(snapshot.clone());
+    }
+}
+
+/// Tracing layer that keeps the last [`LOG_RING_LINES`] lines logged by jolt
+/// at debug level or above, whatever level the log file is written at.
+pub fn log_ring_layer<S>() -> impl Layer<S>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    fmt::layer()
+        .with_writer(LogRing)
+        .with_timer(UtcTime::rfc_3339())
+        .with_ansi(false)
+        .with_target(true)
+        .with_filter(Targets::new().with_target(env!("CARGO_CRATE_NAME"), Level::DEBUG))
+}
+
+fn panic_hook(dir: PathBuf, restore: impl Fn() + Send + Sync + 'static) -> PanicHook {
+    Box::new(move |info| {
+        restore();
+
+        let backtrace = Backtrace::force_capture();
+        eprintln!("jolt crashed: {}\n\n{}", info, backtrace);
+        match write_crash_file(&dir, &info.to_string(), &backtrace.to_string()) {
+            Ok(path) => eprintln!("\nCrash report written to {}", path.display()),
+            Err(e) => eprintln!("\nFailed to write crash report to {}: {}", dir.display(), e),
+        }
+    })
+}
+
+fn write_crash_file(dir: &Path, panic: &str, backtrace: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let now = Utc::now();
+    let path = dir.join(format!(
+        "crash-{}-{}.txt",
+        now.format("%Y%m%d-%H%M%S%.3f"),
+        std::process::id()
+    ));
+
+    let mut report = format!(
+        "jolt {} crashed at {}\n\n{}\n\n## Backtrace\n\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        now.to_rfc3339(),
+        panic,
+        backtrace
+    );
+
+    report.push_str("\n## Last snapshot\n\n");
+    let snapshot = try_lock(&LAST_SNAPSHOT).and_then(|last| {
+        last.as_ref()
+            .and_then(|s| serde_json::to_string_pretty(s).ok())
+    });
+    match snapshot {
+        Some(json) => report.push_str(&json),
+        None => report.push_str("none (not connected to the daemon)"),
+    }
+
+    report.push_str("\n\n## Recent log\n\n");
+    if let Some(ring) = try_lock(&LOG_RING) {
+        for line in ring.iter() {
+            report.push_str(line);
+            report.push('\n');
+        }
+    }
+
+    fs::write(&path, report)?;
+    Ok(path)
+}
+
+/// Locks without waiting, so a panic raised while this thread holds the
+/// lock cannot deadlock the hook. A poisoned lock is still read.
+fn try_lock<T>(mutex: &Mutex<T>) -> Option<MutexGuard<'_, T>> {
+    match mutex.try_lock() {
+        Ok(guard) => Some(guard),
+        Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
+
+/// Writer that appends each formatted event to [`LOG_RING`].
+struct LogRing;
+
+impl<'a> MakeWriter<'a> for LogRing {
+    type Writer = LogRing;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LogRing
+    }
+}
+
+impl io::Write for LogRing {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let line = String::from_utf8_lossy(buf).trim_end().to_string();
+        if let Ok(mut ring) = LOG_RING.lock() {
+            if ring.len() >= LOG_RING_LINES {
+                ring.pop_front();
+            }
+            ring.push_back(line);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+
+    fn deliberate_panic() {
+        panic!("deliberate test panic");
+    }
+
+    #[test]
+    fn panic_hook_restores_terminal_and_writes_crash_file() {
+        static RESTORED: AtomicBool = AtomicBool::new(false);
+        let dir = std::env::temp_dir().join(format!("jolt-crash-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        LogRing.write_all(b"DEBUG jolt: last words\n").unwrap();
+
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(panic_hook(dir.clone(), || {
+            RESTORED.store(true, Ordering::SeqCst)
+        }));
+        let result = std::panic::catch_unwind(deliberate_panic);
+        std::panic::set_hook(previous);
+
+        assert!(result.is_err());
+        assert!(RESTORED.load(Ordering::SeqCst));
+        // Other tests panicking meanwhile may add their own crash files.
+        let report = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|entry| fs::read_to_string(entry.ok()?.path()).ok())
+            .find(|report| report.contains("deliberate test panic"))
+            .expect("crash file for the deliberate panic");
+        assert!(report.contains("## Backtrace"));
+        assert!(report.contains("DEBUG jolt: last words"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
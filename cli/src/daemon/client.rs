@@ -3,8 +3,9 @@ use std::os::unix::net::UnixStream;
 use std::time::Duration;
 
 use crate::daemon::protocol::{
-    ChargeSession, CycleSummary, DaemonRequest, DaemonResponse, DaemonStatus, DailyCycle,
-    DailyStat, DailyTopProcess, DataSnapshot, HourlyStat, KillProcessResult, KillSignal, Sample,
+    AppEnergy, BatteryHealthEntry, ChargeSession, CycleSummary, DaemonRequest, DaemonResponse,
+    DaemonStatus, DailyCycle, DailyStat, DailyTopProcess, DataSnapshot, HourlyStat,
+    KillProcessResult, KillSignal, Sample,
 };
 use crate::daemon::socket_path;
 
@@ -153,6 +154,26 @@ impl DaemonClient {
         }
     }
 
+    /// Apps by energy used in hours starting between `from` and `to`.
+    pub fn get_top_apps(
+        &mut self,
+        from: i64,
+        to: i64,
+        include_estimated: bool,
+        limit: usize,
+    ) -> Result<Vec<AppEnergy>> {
+        match self.send_request(DaemonRequest::GetTopApps {
+            from,
+            to,
+            include_estimated,
+            limit,
+        })? {
+            DaemonResponse::TopApps(apps) => Ok(apps),
+            DaemonResponse::Error(e) => Err(ClientError::Daemon(e)),
+            _ => Err(ClientError::Protocol("Unexpected response".into())),
+        }
+    }
+
     pub fn shutdown(&mut self) -> Result<()> {
         match self.send_request(DaemonRequest::Shutdown)? {
             DaemonResponse::Ok => Ok(()),
@@ -169,6 +190,15 @@ impl DaemonClient {
         }
     }
 
+    /// Samples between `from` and `to`, and the oldest stored timestamp.
+    pub fn get_samples(&mut self, from: i64, to: i64) -> Result<(Vec<Sample>, Option<i64>)> {
+        match self.send_request(DaemonRequest::GetSamples { from, to })? {
+            DaemonResponse::Samples { samples, oldest } => Ok((samples, oldest)),
+            DaemonResponse::Error(e) => Err(ClientError::Daemon(e)),
+            _ => Err(ClientError::Protocol("Unexpected response".into())),
+        }
+    }
+
     pub fn kill_process(&mut self, pid: u32, signal: KillSignal) -> Result<KillProcessResult> {
         match self.send_request(DaemonRequest::KillProcess { pid, signal })? {
             DaemonResponse::KillResult(result) => Ok(result),
@@ -231,6 +261,17 @@ impl DaemonClient {
         }
     }
 
+    pub fn get_battery_health(&mut self, from: &str, to: &str) -> Result<Vec<BatteryHealthEntry>> {
+        match self.send_request(DaemonRequest::GetBatteryHealth {
+            from: from.to_string(),
+            to: to.to_string(),
+        })? {
+            DaemonResponse::BatteryHealth(entries) => Ok(entries),
+            DaemonResponse::Error(e) => Err(ClientError::Daemon(e)),
+            _ => Err(ClientError::Protocol("Unexpected response".into())),
+        }
+    }
+
     pub fn read_update(&mut self) -> Result<Option<DataSnapshot>> {
         let mut latest_snapshot: Option<DataSnapshot> = None;
         let mut messages_read = 0;
@@ -6,11 +6,12 @@ pub mod service;
 pub use client::DaemonClient;
 #[allow(unused_imports)]
 pub use jolt_protocol::{
-    BatterySnapshot, BatteryState, ChargeSession, ChargingState, CycleSummary, DaemonRequest,
-    DaemonResponse, DaemonStatus, DailyCycle, DailyStat, DailyTopProcess, DataSnapshot,
-    ForecastSnapshot, ForecastSource, HourlyStat, KillProcessResult, KillSignal, PowerMode,
-    PowerSnapshot, ProcessSnapshot, ProcessState, Sample, SessionType, SystemSnapshot,
-    SystemStatsSnapshot, MAX_SUBSCRIBERS, MIN_SUPPORTED_VERSION, PROTOCOL_VERSION,
+    AppEnergy, BatteryHealthEntry, BatterySnapshot, BatteryState, BinaryArch, ChargeSession,
+    ChargingState, CycleSummary, DaemonRequest, DaemonResponse, DaemonStatus, DailyCycle,
+    DailyStat, DailyTopProcess, DataSnapshot, ForecastSnapshot, ForecastSource, HourlyStat,
+    KillProcessResult, KillSignal, NetPowerBalance, PowerMode, PowerSnapshot, ProcessSnapshot,
+    ProcessState, Sample, SessionType, SystemSnapshot, SystemStatsSnapshot, MAX_SUBSCRIBERS,
+    MIN_SUPPORTED_VERSION, PROTOCOL_VERSION,
 };
 pub use server::run_daemon;
 #[allow(unused_imports)]
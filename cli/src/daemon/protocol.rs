@@ -1,9 +1,9 @@
 pub use jolt_protocol::{
-    BatterySnapshot, BatteryState, ChargeSession, ChargingState, CycleSummary, DaemonRequest,
-    DaemonResponse, DaemonStatus, DailyCycle, DailyStat, DailyTopProcess, DataSnapshot,
-    ForecastSnapshot, ForecastSource, HourlyStat, KillProcessResult, KillSignal, PowerMode,
-    PowerSnapshot, ProcessSnapshot, ProcessState, Sample, SessionType, SystemSnapshot,
-    SystemStatsSnapshot, MAX_SUBSCRIBERS, MIN_SUPPORTED_VERSION, PROTOCOL_VERSION,
+    AppEnergy, BatteryHealthEntry, BatterySnapshot, BatteryState, ChargeSession, ChargingState,
+    CycleSummary, DaemonRequest, DaemonResponse, DaemonStatus, DailyCycle, DailyStat,
+    DailyTopProcess, DataSnapshot, ForecastSnapshot, ForecastSource, HourlyStat, KillProcessResult,
+    KillSignal, PowerMode, PowerSnapshot, ProcessSnapshot, ProcessState, Sample, SessionType,
+    SystemSnapshot, SystemStatsSnapshot, MAX_SUBSCRIBERS, MIN_SUPPORTED_VERSION, PROTOCOL_VERSION,
 };
 
 use crate::data;
@@ -51,6 +51,17 @@ impl From<&data::DailyTopProcess> for DailyTopProcess {
     }
 }
 
+impl From<&data::AppEnergy> for AppEnergy {
+    fn from(a: &data::AppEnergy) -> Self {
+        Self {
+            name: a.name.clone(),
+            bundle_id: a.bundle_id.clone(),
+            energy_wh: a.energy_wh,
+            estimated_wh: a.estimated_wh,
+        }
+    }
+}
+
 impl From<&data::Sample> for Sample {
     fn from(s: &data::Sample) -> Self {
         Self {
@@ -96,6 +107,7 @@ impl From<&data::ChargeSession> for ChargeSession {
             avg_power_watts: s.avg_power_watts,
             session_type: s.session_type.into(),
             is_complete: s.is_complete,
+            deficit_secs: s.deficit_secs,
         }
     }
 }
@@ -119,6 +131,20 @@ impl From<&data::DailyCycle> for DailyCycle {
     }
 }
 
+impl From<&data::BatteryHealthSnapshot> for BatteryHealthEntry {
+    fn from(h: &data::BatteryHealthSnapshot) -> Self {
+        Self {
+            date: h.date.clone(),
+            health_percent: h.health_percent,
+            cycle_count: h.cycle_count,
+            max_capacity_wh: h.max_capacity_wh,
+            design_capacity_wh: h.design_capacity_wh,
+            full_charge_capacity_mah: h.full_charge_capacity_mah,
+            design_capacity_mah: h.design_capacity_mah,
+        }
+    }
+}
+
 impl From<&data::SystemInfo> for SystemSnapshot {
     fn from(s: &data::SystemInfo) -> Self {
         Self {
@@ -156,6 +182,103 @@ impl From<&data::ForecastData> for ForecastSnapshot {
     }
 }
 
+impl From<&data::BatteryData> for BatterySnapshot {
+    fn from(b: &data::BatteryData) -> Self {
+        let state = match b.state_label() {
+            "Charging" => BatteryState::Charging,
+            "On Battery" => BatteryState::Discharging,
+            "Full" => BatteryState::Full,
+            "Not Charging" => BatteryState::NotCharging,
+            _ => BatteryState::Unknown,
+        };
+
+        Self {
+            charge_percent: b.charge_percent(),
+            state,
+            state_label: b.state_label().to_string(),
+            health_percent: b.health_percent(),
+            max_capacity_wh: b.max_capacity_wh(),
+            design_capacity_wh: b.design_capacity_wh(),
+            cycle_count: b.cycle_count(),
+            time_remaining_mins: b.time_remaining_minutes(),
+            time_remaining_formatted: b.time_remaining_formatted(),
+            charging_watts: b.charging_watts(),
+            charger_watts: b.charger_watts(),
+            discharge_watts: b.discharge_watts(),
+            voltage_mv: b.voltage_mv(),
+            amperage_ma: b.amperage_ma(),
+            external_connected: b.external_connected(),
+            temperature_c: b.temperature_c(),
+            daily_min_soc: b.daily_min_soc(),
+            daily_max_soc: b.daily_max_soc(),
+            charge_limit_percent: b.charge_limit_percent(),
+            net_power: b.net_power_balance(),
+            interpolated_percent: b.interpolated_charge_percent(),
+        }
+    }
+}
+
+impl From<&data::PowerData> for PowerSnapshot {
+    fn from(p: &data::PowerData) -> Self {
+        let power_mode = match p.power_mode() {
+            data::power::PowerMode::LowPower => PowerMode::LowPower,
+            data::power::PowerMode::Automatic => PowerMode::Automatic,
+            data::power::PowerMode::HighPerformance => PowerMode::HighPerformance,
+            data::power::PowerMode::Unknown => PowerMode::Unknown,
+        };
+
+        Self {
+            cpu_power_watts: p.cpu_power_watts(),
+            gpu_power_watts: p.gpu_power_watts(),
+            total_power_watts: p.total_power_watts(),
+            power_mode,
+            power_mode_label: p.power_mode_label().to_string(),
+            is_warmed_up: p.is_warmed_up(),
+        }
+    }
+}
+
+impl From<&data::ProcessInfo> for ProcessSnapshot {
+    fn from(p: &data::ProcessInfo) -> Self {
+        let status = match p.status {
+            data::ProcessState::Running => ProcessState::Running,
+            data::ProcessState::Sleeping => ProcessState::Sleeping,
+            data::ProcessState::Idle => ProcessState::Idle,
+            data::ProcessState::Stopped => ProcessState::Stopped,
+            data::ProcessState::Zombie => ProcessState::Zombie,
+            data::ProcessState::Unknown => ProcessState::Unknown,
+        };
+
+        Self {
+            pid: p.pid,
+            name: p.name.clone(),
+            command: p.command.clone(),
+            command_args: p.command_args.clone(),
+            cpu_usage: p.cpu_usage,
+            memory_mb: p.memory_mb,
+            energy_impact: p.energy_impact,
+            parent_pid: p.parent_pid,
+            children: p
+                .children
+                .as_ref()
+                .map(|children| children.iter().map(ProcessSnapshot::from).collect()),
+            is_killable: p.is_killable,
+            disk_read_bytes: p.disk_read_bytes,
+            disk_write_bytes: p.disk_write_bytes,
+            status,
+            run_time_secs: p.run_time_secs,
+            total_cpu_time_secs: p.total_cpu_time_secs,
+            network_bytes_per_sec: p.network_bytes_per_sec,
+            sandbox_container_id: p.sandbox_container_id(),
+            executable_path: p.executable_path.clone(),
+            start_time: p.start_time,
+            team_id: p.team_id.clone(),
+            binary_arch: p.binary_arch,
+            power_assertion_count: p.power_assertion_count,
+        }
+    }
+}
+
 impl From<data::ForecastSource> for ForecastSource {
     fn from(s: data::ForecastSource) -> Self {
         match s {
@@ -222,6 +345,17 @@ impl From<DailyTopProcess> for data::DailyTopProcess {
     }
 }
 
+impl From<AppEnergy> for data::AppEnergy {
+    fn from(a: AppEnergy) -> Self {
+        Self {
+            name: a.name,
+            bundle_id: a.bundle_id,
+            energy_wh: a.energy_wh,
+            estimated_wh: a.estimated_wh,
+        }
+    }
+}
+
 impl From<Sample> for data::Sample {
     fn from(s: Sample) -> Self {
         Self {
@@ -269,6 +403,7 @@ impl From<ChargeSession> for data::ChargeSession {
             avg_power_watts: s.avg_power_watts,
             session_type: s.session_type.into(),
             is_complete: s.is_complete,
+            deficit_secs: s.deficit_secs,
         }
     }
 }
@@ -292,3 +427,18 @@ impl From<DailyCycle> for data::DailyCycle {
         }
     }
 }
+
+impl From<BatteryHealthEntry> for data::BatteryHealthSnapshot {
+    fn from(h: BatteryHealthEntry) -> Self {
+        Self {
+            id: None,
+            date: h.date,
+            health_percent: h.health_percent,
+            cycle_count: h.cycle_count,
+            max_capacity_wh: h.max_capacity_wh,
+            design_capacity_wh: h.design_capacity_wh,
+            full_charge_capacity_mah: h.full_charge_capacity_mah,
+            design_capacity_mah: h.design_capacity_mah,
+        }
+    }
+}
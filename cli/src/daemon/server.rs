@@ -4,6 +4,7 @@ use std::sync::mpsc as std_mpsc;
 use std::thread;
 use std::time::{Duration, Instant};
 
+use jolt_platform::command::{self, CommandLimits};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::UnixListener;
 use tokio::sync::mpsc;
@@ -11,10 +12,10 @@ use tracing::{debug, error, info, trace, warn};
 
 use crate::config::{runtime_dir, HistoryConfig, UserConfig};
 use crate::daemon::protocol::{
-    BatterySnapshot, BatteryState, ChargeSession, DaemonRequest, DaemonResponse, DaemonStatus,
+    AppEnergy, BatteryHealthEntry, ChargeSession, DaemonRequest, DaemonResponse, DaemonStatus,
     DailyCycle, DailyStat, DailyTopProcess, DataSnapshot, ForecastSnapshot, HourlyStat,
-    KillProcessResult, PowerMode, PowerSnapshot, ProcessSnapshot, ProcessState, Sample,
-    SystemSnapshot, SystemStatsSnapshot, MAX_SUBSCRIBERS, MIN_SUPPORTED_VERSION, PROTOCOL_VERSION,
+    KillProcessResult, ProcessSnapshot, Sample, SystemSnapshot, SystemStatsSnapshot,
+    MAX_SUBSCRIBERS, MIN_SUPPORTED_VERSION, PROTOCOL_VERSION,
 };
 use crate::daemon::socket_path;
 use crate::data::aggregator::Aggregator;
@@ -79,9 +80,16 @@ impl RefreshWorker {
 
         let config = user_config.history.clone();
         let excluded_clone = excluded.clone();
+        let process_network = user_config.process_network;
 
         let handle = thread::spawn(move || {
-            Self::worker_loop(request_rx, response_tx, config, excluded_clone);
+            Self::worker_loop(
+                request_rx,
+                response_tx,
+                config,
+                excluded_clone,
+                process_network,
+            );
         });
 
         Ok(Self {
@@ -96,6 +104,7 @@ impl RefreshWorker {
         response_tx: std_mpsc::Sender<DataSnapshot>,
         config: HistoryConfig,
         excluded: Vec<String>,
+        process_network: bool,
     ) {
         debug!("Worker thread starting initialization");
         let mut battery = match BatteryData::new() {
@@ -119,6 +128,7 @@ impl RefreshWorker {
                 return;
             }
         };
+        processes.set_network_sampling(process_network);
 
         let system_snapshot: SystemSnapshot = (&SystemInfo::new()).into();
         let mut system_stats = match SystemStatsData::new() {
@@ -303,100 +313,21 @@ fn create_snapshot(
     system_stats: &SystemStatsSnapshot,
     forecast: &ForecastSnapshot,
 ) -> DataSnapshot {
-    let battery_state = match battery.state_label() {
-        "Charging" => BatteryState::Charging,
-        "On Battery" => BatteryState::Discharging,
-        "Full" => BatteryState::Full,
-        "Not Charging" => BatteryState::NotCharging,
-        _ => BatteryState::Unknown,
-    };
-
-    let power_mode = match power.power_mode() {
-        crate::data::power::PowerMode::LowPower => PowerMode::LowPower,
-        crate::data::power::PowerMode::Automatic => PowerMode::Automatic,
-        crate::data::power::PowerMode::HighPerformance => PowerMode::HighPerformance,
-        crate::data::power::PowerMode::Unknown => PowerMode::Unknown,
-    };
-
-    let battery_snapshot = BatterySnapshot {
-        charge_percent: battery.charge_percent(),
-        state: battery_state,
-        state_label: battery.state_label().to_string(),
-        health_percent: battery.health_percent(),
-        max_capacity_wh: battery.max_capacity_wh(),
-        design_capacity_wh: battery.design_capacity_wh(),
-        cycle_count: battery.cycle_count(),
-        time_remaining_mins: battery.time_remaining_minutes(),
-        time_remaining_formatted: battery.time_remaining_formatted(),
-        charging_watts: battery.charging_watts(),
-        charger_watts: battery.charger_watts(),
-        discharge_watts: battery.discharge_watts(),
-        voltage_mv: battery.voltage_mv(),
-        amperage_ma: battery.amperage_ma(),
-        external_connected: battery.external_connected(),
-        temperature_c: battery.temperature_c(),
-        daily_min_soc: battery.daily_min_soc(),
-        daily_max_soc: battery.daily_max_soc(),
-    };
-
-    let power_snapshot = PowerSnapshot {
-        cpu_power_watts: power.cpu_power_watts(),
-        gpu_power_watts: power.gpu_power_watts(),
-        total_power_watts: power.total_power_watts(),
-        power_mode,
-        power_mode_label: power.power_mode_label().to_string(),
-        is_warmed_up: power.is_warmed_up(),
-    };
-
-    let process_snapshots: Vec<ProcessSnapshot> = processes
-        .processes
-        .iter()
-        .map(process_to_snapshot)
-        .collect();
-
     DataSnapshot {
         timestamp: chrono::Utc::now().timestamp(),
-        battery: battery_snapshot,
-        power: power_snapshot,
-        processes: process_snapshots,
+        battery: battery.into(),
+        power: power.into(),
+        processes: processes
+            .processes
+            .iter()
+            .map(ProcessSnapshot::from)
+            .collect(),
         system: system.clone(),
         system_stats: system_stats.clone(),
         forecast: forecast.clone(),
     }
 }
 
-fn process_to_snapshot(p: &crate::data::ProcessInfo) -> ProcessSnapshot {
-    let status = match p.status {
-        crate::data::ProcessState::Running => ProcessState::Running,
-        crate::data::ProcessState::Sleeping => ProcessState::Sleeping,
-        crate::data::ProcessState::Idle => ProcessState::Idle,
-        crate::data::ProcessState::Stopped => ProcessState::Stopped,
-        crate::data::ProcessState::Zombie => ProcessState::Zombie,
-        crate::data::ProcessState::Unknown => ProcessState::Unknown,
-    };
-
-    ProcessSnapshot {
-        pid: p.pid,
-        name: p.name.clone(),
-        command: p.command.clone(),
-        command_args: p.command_args.clone(),
-        cpu_usage: p.cpu_usage,
-        memory_mb: p.memory_mb,
-        energy_impact: p.energy_impact,
-        parent_pid: p.parent_pid,
-        children: p
-            .children
-            .as_ref()
-            .map(|children| children.iter().map(process_to_snapshot).collect()),
-        is_killable: p.is_killable,
-        disk_read_bytes: p.disk_read_bytes,
-        disk_write_bytes: p.disk_write_bytes,
-        status,
-        run_time_secs: p.run_time_secs,
-        total_cpu_time_secs: p.total_cpu_time_secs,
-    }
-}
-
 struct DaemonState {
     worker: RefreshWorker,
     recorder: Recorder,
@@ -547,6 +478,23 @@ impl DaemonState {
                     Err(e) => DaemonResponse::Error(e.to_string()),
                 }
             }
+            DaemonRequest::GetTopApps {
+                from,
+                to,
+                include_estimated,
+                limit,
+            } => match self.recorder.store().top_apps_by_energy(
+                *from,
+                *to,
+                *include_estimated,
+                *limit,
+            ) {
+                Ok(apps) => {
+                    let converted: Vec<AppEnergy> = apps.iter().map(Into::into).collect();
+                    DaemonResponse::TopApps(converted)
+                }
+                Err(e) => DaemonResponse::Error(e.to_string()),
+            },
             DaemonRequest::GetRecentSamples { window_secs } => {
                 let now = chrono::Utc::now().timestamp();
                 let from = now - *window_secs as i64;
@@ -558,15 +506,28 @@ impl DaemonState {
                     Err(e) => DaemonResponse::Error(e.to_string()),
                 }
             }
+            DaemonRequest::GetSamples { from, to } => {
+                let store = self.recorder.store();
+                match store
+                    .get_samples(*from, *to)
+                    .and_then(|samples| Ok((samples, store.oldest_sample_timestamp()?)))
+                {
+                    Ok((samples, oldest)) => DaemonResponse::Samples {
+                        samples: samples.iter().map(Into::into).collect(),
+                        oldest,
+                    },
+                    Err(e) => DaemonResponse::Error(e.to_string()),
+                }
+            }
             DaemonRequest::GetCurrentData => match self.current_snapshot() {
                 Some(snapshot) => DaemonResponse::CurrentData(snapshot.clone()),
                 None => DaemonResponse::Error("No data available yet".to_string()),
             },
             DaemonRequest::KillProcess { pid, signal } => {
-                match std::process::Command::new("kill")
-                    .args([signal.as_arg(), &pid.to_string()])
-                    .output()
-                {
+                match command::run(
+                    std::process::Command::new("kill").args([signal.as_arg(), &pid.to_string()]),
+                    CommandLimits::DEFAULT,
+                ) {
                     Ok(output) => {
                         let success = output.status.success();
                         let error = if success {
@@ -625,6 +586,16 @@ impl DaemonState {
                     Err(e) => DaemonResponse::Error(e.to_string()),
                 }
             }
+            DaemonRequest::GetBatteryHealth { from, to } => {
+                match self.recorder.store().get_battery_health(from, to) {
+                    Ok(entries) => {
+                        let converted: Vec<BatteryHealthEntry> =
+                            entries.iter().map(Into::into).collect();
+                        DaemonResponse::BatteryHealth(converted)
+                    }
+                    Err(e) => DaemonResponse::Error(e.to_string()),
+                }
+            }
         }
     }
 
@@ -5,12 +5,18 @@
 //! - Linux: systemd user service
 
 use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
 
 use color_eyre::eyre::{eyre, Result};
+use jolt_platform::command::{self, CommandError, CommandLimits, CommandOutput};
 
 #[cfg(target_os = "macos")]
 const SERVICE_LABEL: &str = "sh.getjolt.daemon";
 
+/// Starting or stopping the service can take a while, but not this long.
+const SERVICE_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Debug, Clone)]
 pub struct ServiceStatus {
     pub installed: bool,
@@ -116,6 +122,23 @@ pub fn get_service_status() -> ServiceStatus {
     }
 }
 
+/// Runs `launchctl` or `systemctl`, giving up on one that hangs.
+fn run_service_command(command: &mut Command) -> Result<CommandOutput, CommandError> {
+    command::run(
+        command,
+        CommandLimits::DEFAULT.with_timeout(SERVICE_COMMAND_TIMEOUT),
+    )
+}
+
+/// `message`, followed by what the failed command printed to stderr.
+fn service_error(message: &str, output: &CommandOutput) -> color_eyre::Report {
+    let stderr = output.stderr_lossy();
+    match stderr.trim() {
+        "" => eyre!("{}", message),
+        stderr => eyre!("{}: {}", message, stderr),
+    }
+}
+
 // macOS: launchd LaunchAgent
 
 #[cfg(target_os = "macos")]
@@ -200,23 +223,25 @@ fn install_macos_service(force: bool) -> Result<()> {
     std::fs::write(&plist_path, plist_content)?;
 
     let uid = get_uid();
-    let bootstrap_result = std::process::Command::new("launchctl")
-        .args(["bootstrap", &format!("gui/{}", uid)])
-        .arg(&plist_path)
-        .output();
+    let bootstrap_result = run_service_command(
+        Command::new("launchctl")
+            .args(["bootstrap", &format!("gui/{}", uid)])
+            .arg(&plist_path),
+    );
 
     let (loaded, error_msg) = match bootstrap_result {
-        Ok(output) if output.status.success() => (true, None),
+        Ok(output) if output.success() => (true, None),
         _ => {
-            let legacy_result = std::process::Command::new("launchctl")
-                .args(["load", "-w"])
-                .arg(&plist_path)
-                .output();
+            let legacy_result = run_service_command(
+                Command::new("launchctl")
+                    .args(["load", "-w"])
+                    .arg(&plist_path),
+            );
 
             match legacy_result {
-                Ok(output) if output.status.success() => (true, None),
+                Ok(output) if output.success() => (true, None),
                 Ok(output) => {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    let stderr = output.stderr_lossy();
                     (
                         false,
                         Some(format!("Legacy 'launchctl load' failed: {}", stderr.trim())),
@@ -269,23 +294,20 @@ fn unload_macos_service() -> Result<()> {
     let plist_path = macos_plist_path();
     let uid = get_uid();
 
-    let bootout_result = std::process::Command::new("launchctl")
-        .args(["bootout", &format!("gui/{}/{}", uid, SERVICE_LABEL)])
-        .output();
+    let bootout_result = run_service_command(
+        Command::new("launchctl").args(["bootout", &format!("gui/{}/{}", uid, SERVICE_LABEL)]),
+    );
 
     if let Ok(output) = bootout_result {
-        if output.status.success() {
+        if output.success() {
             return Ok(());
         }
     }
 
-    let status = std::process::Command::new("launchctl")
-        .arg("unload")
-        .arg(&plist_path)
-        .status()?;
+    let output = run_service_command(Command::new("launchctl").arg("unload").arg(&plist_path))?;
 
-    if !status.success() {
-        return Err(eyre!("Failed to unload service"));
+    if !output.success() {
+        return Err(service_error("Failed to unload service", &output));
     }
 
     Ok(())
@@ -293,11 +315,9 @@ fn unload_macos_service() -> Result<()> {
 
 #[cfg(target_os = "macos")]
 fn is_macos_service_loaded() -> bool {
-    let output = std::process::Command::new("launchctl")
-        .args(["list", SERVICE_LABEL])
-        .output();
+    let output = run_service_command(Command::new("launchctl").args(["list", SERVICE_LABEL]));
 
-    matches!(output, Ok(o) if o.status.success())
+    matches!(output, Ok(o) if o.success())
 }
 
 #[cfg(target_os = "macos")]
@@ -424,20 +444,21 @@ fn install_linux_service(force: bool) -> Result<()> {
     let service_content = generate_linux_service(&exe_path);
     std::fs::write(&service_path, service_content)?;
 
-    let reload = std::process::Command::new("systemctl")
-        .args(["--user", "daemon-reload"])
-        .status()?;
+    let reload = run_service_command(Command::new("systemctl").args(["--user", "daemon-reload"]))?;
 
     if !reload.success() {
-        return Err(eyre!("Failed to reload systemd daemon"));
+        return Err(service_error("Failed to reload systemd daemon", &reload));
     }
 
-    let enable = std::process::Command::new("systemctl")
-        .args(["--user", "enable", "--now", SYSTEMD_SERVICE_NAME])
-        .status()?;
+    let enable = run_service_command(Command::new("systemctl").args([
+        "--user",
+        "enable",
+        "--now",
+        SYSTEMD_SERVICE_NAME,
+    ]))?;
 
     if !enable.success() {
-        return Err(eyre!("Failed to enable and start service"));
+        return Err(service_error("Failed to enable and start service", &enable));
     }
 
     println!("Daemon installed and started.");
@@ -463,9 +484,7 @@ fn uninstall_linux_service() -> Result<()> {
 
     std::fs::remove_file(&service_path)?;
 
-    let _ = std::process::Command::new("systemctl")
-        .args(["--user", "daemon-reload"])
-        .status();
+    let _ = run_service_command(Command::new("systemctl").args(["--user", "daemon-reload"]));
 
     println!("Daemon uninstalled.");
     println!("The daemon will no longer start automatically on login.");
@@ -475,12 +494,15 @@ fn uninstall_linux_service() -> Result<()> {
 
 #[cfg(target_os = "linux")]
 fn disable_linux_service() -> Result<()> {
-    let status = std::process::Command::new("systemctl")
-        .args(["--user", "disable", "--now", SYSTEMD_SERVICE_NAME])
-        .status()?;
+    let output = run_service_command(Command::new("systemctl").args([
+        "--user",
+        "disable",
+        "--now",
+        SYSTEMD_SERVICE_NAME,
+    ]))?;
 
-    if !status.success() {
-        return Err(eyre!("Failed to disable service"));
+    if !output.success() {
+        return Err(service_error("Failed to disable service", &output));
     }
 
     Ok(())
@@ -488,29 +510,31 @@ fn disable_linux_service() -> Result<()> {
 
 #[cfg(target_os = "linux")]
 fn is_systemd_available() -> bool {
-    std::process::Command::new("systemctl")
-        .args(["--user", "status"])
-        .output()
+    run_service_command(Command::new("systemctl").args(["--user", "status"]))
         .map(|o| o.status.code() != Some(127))
         .unwrap_or(false)
 }
 
 #[cfg(target_os = "linux")]
 fn is_linux_service_enabled() -> bool {
-    let output = std::process::Command::new("systemctl")
-        .args(["--user", "is-enabled", SYSTEMD_SERVICE_NAME])
-        .output();
+    let output = run_service_command(Command::new("systemctl").args([
+        "--user",
+        "is-enabled",
+        SYSTEMD_SERVICE_NAME,
+    ]));
 
-    matches!(output, Ok(o) if o.status.success())
+    matches!(output, Ok(o) if o.success())
 }
 
 #[cfg(target_os = "linux")]
 fn is_linux_service_active() -> bool {
-    let output = std::process::Command::new("systemctl")
-        .args(["--user", "is-active", SYSTEMD_SERVICE_NAME])
-        .output();
+    let output = run_service_command(Command::new("systemctl").args([
+        "--user",
+        "is-active",
+        SYSTEMD_SERVICE_NAME,
+    ]));
 
-    matches!(output, Ok(o) if o.status.success())
+    matches!(output, Ok(o) if o.success())
 }
 
 #[cfg(target_os = "linux")]
@@ -292,6 +292,7 @@ impl<'a> Aggregator<'a> {
             let cutoff = now - Duration::days(self.config.retention_hourly_days as i64);
             let cutoff_ts = cutoff.timestamp();
             result.hourly_deleted = self.store.delete_hourly_stats_before(cutoff_ts)?;
+            result.hourly_deleted += self.store.delete_hourly_attribution_before(cutoff_ts)?;
         }
 
         if self.config.retention_daily_days > 0 {
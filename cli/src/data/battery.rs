@@ -1,11 +1,32 @@
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use color_eyre::eyre::Result;
 use jolt_platform::BatteryProvider;
 
-use crate::daemon::{BatterySnapshot, BatteryState as ProtocolBatteryState};
+use crate::daemon::{BatterySnapshot, BatteryState as ProtocolBatteryState, NetPowerBalance};
 
-pub use jolt_platform::{BatteryTechnology, ChargeState};
+use crate::config::AdviceConfig;
+
+use super::charge_interpolation::ChargeInterpolator;
+use super::charging_advice::{Advice, AdviceInputs, ChargingAdvisor};
+use super::influx;
+use super::last_until::{DrainTracker, LastUntilEstimate};
+use super::power_balance::{
+    net_power_balance, BalanceInputs, DeficitTracker, DEFICIT_WARNING_AFTER,
+};
+use super::HourlyStat;
+
+pub use jolt_platform::{AdapterPdInfo, BatteryChemistry, BatteryTechnology, ChargeState};
+
+/// Limiters stop slightly below or above the configured level, so a battery
+/// within this margin of its limit counts as being held there.
+const CHARGE_LIMIT_TOLERANCE_PERCENT: f32 = 2.0;
+
+/// Health above this is `good`, matching the green health gauge.
+const GOOD_HEALTH_PERCENT: f32 = 79.0;
+
+/// Health above this (and not good) is `fair`; anything lower is `poor`.
+const FAIR_HEALTH_PERCENT: f32 = 49.0;
 
 #[cfg(target_os = "macos")]
 type PlatformBattery = jolt_platform::macos::MacOSBattery;
@@ -16,11 +37,75 @@ type PlatformBattery = jolt_platform::linux::LinuxBattery;
 #[cfg(not(any(target_os = "macos", target_os = "linux")))]
 compile_error!("BatteryData (PlatformBattery) is only supported on macOS and Linux targets.");
 
+/// A battery transition reported to a callback registered with
+/// [`BatteryData::notifications`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BatteryEvent {
+    /// Charge dropped below the low threshold; carries the current percent.
+    LowBattery(f32),
+    /// Charge dropped below the critical threshold; carries the current percent.
+    CriticalBattery(f32),
+    Charging,
+    FullyCharged,
+    Discharging,
+}
+
+type BatteryEventCallback = Box<dyn Fn(BatteryEvent) + Send>;
+
+/// Detects threshold crossings and state changes between consecutive readings.
+struct BatteryNotifier {
+    on_low: f32,
+    on_critical: f32,
+    previous: Option<(f32, ChargeState)>,
+}
+
+impl BatteryNotifier {
+    fn new(on_low: f32, on_critical: f32) -> Self {
+        Self {
+            on_low,
+            on_critical,
+            previous: None,
+        }
+    }
+
+    /// Returns the events caused by moving from the previous reading to this
+    /// one. The first reading only establishes a baseline.
+    fn observe(&mut self, percent: f32, state: ChargeState) -> Vec<BatteryEvent> {
+        let Some((prev_percent, prev_state)) = self.previous.replace((percent, state)) else {
+            return Vec::new();
+        };
+
+        let mut events = Vec::new();
+
+        if state != prev_state {
+            match state {
+                ChargeState::Charging => events.push(BatteryEvent::Charging),
+                ChargeState::Full => events.push(BatteryEvent::FullyCharged),
+                ChargeState::Discharging => events.push(BatteryEvent::Discharging),
+                ChargeState::NotCharging | ChargeState::Unknown => {}
+            }
+        }
+
+        if prev_percent >= self.on_critical && percent < self.on_critical {
+            events.push(BatteryEvent::CriticalBattery(percent));
+        } else if prev_percent >= self.on_low && percent < self.on_low {
+            events.push(BatteryEvent::LowBattery(percent));
+        }
+
+        events
+    }
+}
+
 pub struct BatteryData {
     provider: PlatformBattery,
     time_to_full: Option<Duration>,
     time_to_empty: Option<Duration>,
     cached_snapshot: Option<BatterySnapshot>,
+    notifier: Option<(BatteryNotifier, BatteryEventCallback)>,
+    drain: DrainTracker,
+    advisor: ChargingAdvisor,
+    deficit: DeficitTracker,
+    interpolation: ChargeInterpolator,
 }
 
 impl BatteryData {
@@ -32,9 +117,151 @@ impl BatteryData {
             time_to_empty: info.time_to_empty,
             provider,
             cached_snapshot: None,
+            notifier: None,
+            drain: DrainTracker::default(),
+            advisor: ChargingAdvisor::default(),
+            deficit: DeficitTracker::default(),
+            interpolation: ChargeInterpolator::default(),
         })
     }
 
+    /// Registers `cb` to be called with battery events after each refresh.
+    ///
+    /// `on_low` and `on_critical` are charge percentages; an event fires when
+    /// the charge drops below them, not on every reading while below. A
+    /// reading that crosses both only reports the critical event. Replaces any
+    /// previously registered callback.
+    pub fn notifications(
+        &mut self,
+        on_low: f32,
+        on_critical: f32,
+        cb: impl Fn(BatteryEvent) + Send + 'static,
+    ) {
+        let mut notifier = BatteryNotifier::new(on_low, on_critical);
+        notifier.observe(self.charge_percent(), self.state());
+        self.notifier = Some((notifier, Box::new(cb)));
+    }
+
+    /// Projects whether the charge will last until `target`.
+    ///
+    /// Returns `None` while not discharging, with less than ten minutes of
+    /// discharge history, or if `target` is in the past.
+    pub fn will_last_until(&self, target: SystemTime) -> Option<LastUntilEstimate> {
+        if self.state() != ChargeState::Discharging {
+            return None;
+        }
+        self.drain.estimate(
+            SystemTime::now(),
+            target,
+            self.charge_percent(),
+            self.max_capacity_wh(),
+        )
+    }
+
+    /// Sets the typical power per hour of day used by [`Self::will_last_until`].
+    pub fn set_hourly_profile(&mut self, stats: &[HourlyStat]) {
+        self.drain.set_hourly_profile(stats);
+    }
+
+    /// Replaces the rules behind [`Self::charging_advice`].
+    pub fn set_advice_config(&mut self, config: AdviceConfig) {
+        self.advisor = ChargingAdvisor::new(config);
+    }
+
+    /// The advice to show under the battery gauge, if any rule is active.
+    pub fn charging_advice(&self) -> Option<Advice> {
+        self.advisor.current()
+    }
+
+    fn observe_advice(&mut self) {
+        let inputs = AdviceInputs {
+            temperature_c: self.temperature_c(),
+            charging_watts: self
+                .charging_watts()
+                .filter(|_| self.state() == ChargeState::Charging),
+            held_at_limit: self
+                .charge_limit_percent()
+                .filter(|_| self.is_held_at_limit()),
+        };
+        self.advisor.observe(inputs);
+    }
+
+    fn record_drain(&mut self) {
+        let state = self.state();
+        let watts = self.discharge_watts();
+        let now = SystemTime::now();
+        self.drain.record(now, state, watts);
+        self.deficit.record(now, self.net_power_balance().as_ref());
+    }
+
+    /// Feeds the interpolator the battery's own power reading. System power
+    /// is never used in its place: where it is estimated rather than
+    /// measured, the interpolation would drift on a guess.
+    fn record_charge(&mut self) {
+        let watts = self
+            .charging_watts()
+            .or_else(|| self.discharge_watts().map(|w| -w));
+        self.interpolation.record(
+            SystemTime::now(),
+            self.charge_percent(),
+            watts,
+            self.max_capacity_wh(),
+        );
+    }
+
+    /// Charge for display, moving smoothly between the whole percents the
+    /// battery reports. `None` where there is no measured battery power to
+    /// interpolate with; use [`Self::display_charge_percent`] for the gauge.
+    pub fn interpolated_charge_percent(&self) -> Option<f32> {
+        if let Some(ref snapshot) = self.cached_snapshot {
+            return snapshot.interpolated_percent;
+        }
+        self.interpolation.percent_at(SystemTime::now())
+    }
+
+    /// [`Self::interpolated_charge_percent`], or the reported charge where
+    /// it is unavailable. Display only: thresholds, history and events use
+    /// [`Self::charge_percent`].
+    pub fn display_charge_percent(&self) -> f32 {
+        self.interpolated_charge_percent()
+            .unwrap_or_else(|| self.charge_percent())
+    }
+
+    /// How the adapter keeps up with the load; `None` on battery power.
+    pub fn net_power_balance(&self) -> Option<NetPowerBalance> {
+        if let Some(ref snapshot) = self.cached_snapshot {
+            return snapshot.net_power;
+        }
+        let info = self.provider.info();
+        net_power_balance(BalanceInputs {
+            external_connected: info.external_connected,
+            amperage_ma: info.amperage_ma,
+            voltage_mv: info.voltage_mv,
+            adapter_rated_watts: info.charger_watts,
+            adapter_input_watts: info.adapter_input_watts,
+            remaining_wh: remaining_wh(info.max_capacity_wh, info.charge_percent),
+        })
+    }
+
+    /// The balance once the battery has drained on external power for
+    /// longer than [`DEFICIT_WARNING_AFTER`].
+    pub fn sustained_deficit(&self) -> Option<NetPowerBalance> {
+        if self.deficit.duration(SystemTime::now()) < DEFICIT_WARNING_AFTER {
+            return None;
+        }
+        self.net_power_balance().filter(NetPowerBalance::is_deficit)
+    }
+
+    fn dispatch_events(&mut self) {
+        let percent = self.charge_percent();
+        let state = self.state();
+        if let Some((notifier, cb)) = self.notifier.as_mut() {
+            for event in notifier.observe(percent, state) {
+                cb(event);
+            }
+        }
+    }
+
     pub fn refresh(&mut self) -> Result<()> {
         let prev_state = self.state();
         let prev_external = self.external_connected();
@@ -59,6 +286,11 @@ impl BatteryData {
             );
         }
 
+        self.record_drain();
+        self.record_charge();
+        self.observe_advice();
+        self.dispatch_events();
+
         Ok(())
     }
 
@@ -100,6 +332,25 @@ impl BatteryData {
         self.state().label()
     }
 
+    /// State label for display. Unlike `state_label`, this recognizes a
+    /// battery held at its charge limit instead of reporting "Not Charging".
+    pub fn status_label(&self) -> String {
+        match self.charge_limit_percent() {
+            Some(limit) if self.is_held_at_limit() => format!("Held at limit ({}%)", limit),
+            _ => self.state_label().to_string(),
+        }
+    }
+
+    /// Whether charging has stopped because the battery reached its charge limit.
+    pub fn is_held_at_limit(&self) -> bool {
+        let Some(limit) = self.charge_limit_percent() else {
+            return false;
+        };
+        self.external_connected()
+            && matches!(self.state(), ChargeState::NotCharging | ChargeState::Full)
+            && self.charge_percent() >= limit as f32 - CHARGE_LIMIT_TOLERANCE_PERCENT
+    }
+
     pub fn time_remaining(&self) -> Option<Duration> {
         let info = self.provider.info();
         let system_estimate = match info.state {
@@ -163,6 +414,20 @@ impl BatteryData {
         self.provider.info().health_percent
     }
 
+    /// Energy left in the battery: the current full-charge capacity scaled by
+    /// the charge level. Both platforms already report capacity in watt-hours
+    /// (converted from mAh at the pack's voltage), so no voltage math is
+    /// needed here. `None` when the capacity is not reported.
+    pub fn watt_hours_remaining(&self) -> Option<f32> {
+        remaining_wh(self.max_capacity_wh(), self.charge_percent())
+    }
+
+    /// Factory design capacity in watt-hours, the reference point for
+    /// [`Self::health_percent`]. `None` when the capacity is not reported.
+    pub fn design_watt_hours(&self) -> Option<f32> {
+        Some(self.design_capacity_wh()).filter(|wh| wh.is_finite() && *wh > 0.0)
+    }
+
     pub fn is_charging(&self) -> bool {
         self.state().is_charging()
     }
@@ -223,6 +488,13 @@ impl BatteryData {
         self.provider.info().daily_max_soc
     }
 
+    pub fn charge_limit_percent(&self) -> Option<u8> {
+        if let Some(ref snapshot) = self.cached_snapshot {
+            return snapshot.charge_limit_percent;
+        }
+        self.provider.info().charge_limit_percent
+    }
+
     pub fn discharge_watts(&self) -> Option<f32> {
         if let Some(ref snapshot) = self.cached_snapshot {
             return snapshot.discharge_watts;
@@ -246,6 +518,45 @@ impl BatteryData {
         self.provider.info().technology
     }
 
+    /// Cell chemistry of the battery, `Unknown` when not reported.
+    pub fn chemical_type(&self) -> BatteryChemistry {
+        self.provider.info().chemistry
+    }
+
+    /// Fuel-gauge full charge capacity in mAh, if the platform reports it.
+    pub fn full_charge_capacity_mah(&self) -> Option<u32> {
+        self.provider.info().full_charge_capacity_mah
+    }
+
+    pub fn design_capacity_mah(&self) -> Option<u32> {
+        self.provider.info().design_capacity_mah
+    }
+
+    /// Internal resistance of the battery in milliohms, which rises with age
+    /// independently of capacity fade.
+    ///
+    /// Read from the `Impedance` key of `AppleSmartBattery`. Only some
+    /// battery and firmware versions publish it, so this is `None` on those
+    /// that don't and on Linux.
+    pub fn impedance_mohm(&self) -> Option<u32> {
+        self.provider.info().impedance_mohm
+    }
+
+    /// Whether macOS Optimized Battery Charging is currently engaged, holding
+    /// the charge at 80% until it expects the Mac to be unplugged.
+    ///
+    /// `None` when the `OptimizedBatteryChargingEngaged` key is absent, as on
+    /// Macs without the feature and on Linux.
+    pub fn is_using_optimized_charging(&self) -> Option<bool> {
+        self.provider.info().optimized_charging_engaged
+    }
+
+    /// USB-C PD negotiation with the connected charger; `None` on battery
+    /// power, on Linux, and for chargers that report no details.
+    pub fn adapter_pd_info(&self) -> Option<&AdapterPdInfo> {
+        self.provider.info().adapter_pd.as_ref()
+    }
+
     pub fn energy_wh(&self) -> f32 {
         self.provider.info().energy_wh
     }
@@ -254,6 +565,44 @@ impl BatteryData {
         self.provider.info().energy_rate_watts
     }
 
+    /// The current readings as one InfluxDB line protocol line in the
+    /// `battery` measurement, tagged with `power_source` (`ac` or `battery`)
+    /// and `health_condition` (`good`, `fair` or `poor`). Readings the
+    /// battery does not report are left out.
+    pub fn serialize_to_influx(&self) -> String {
+        let power_source = if self.external_connected() {
+            "ac"
+        } else {
+            "battery"
+        };
+        influx::line(
+            "battery",
+            &[
+                ("power_source", power_source),
+                ("health_condition", health_condition(self.health_percent())),
+            ],
+            &[
+                ("charge_percent", Some(self.charge_percent())),
+                ("max_capacity_wh", Some(self.max_capacity_wh())),
+                ("design_capacity_wh", Some(self.design_capacity_wh())),
+                ("health_percent", Some(self.health_percent())),
+                ("voltage_mv", Some(self.voltage_mv() as f32)),
+                ("amperage_ma", Some(self.amperage_ma() as f32)),
+                ("energy_rate_watts", Some(self.energy_rate_watts())),
+                ("cycle_count", self.cycle_count().map(|c| c as f32)),
+                ("temperature_c", self.temperature_c()),
+                ("charger_watts", self.charger_watts().map(|w| w as f32)),
+                ("charging_watts", self.charging_watts()),
+                ("discharge_watts", self.discharge_watts()),
+                (
+                    "time_remaining_minutes",
+                    self.time_remaining_minutes().map(|m| m as f32),
+                ),
+            ],
+            influx::now_ns(),
+        )
+    }
+
     pub fn update_from_snapshot(&mut self, snapshot: &BatterySnapshot) {
         self.time_to_full = if matches!(snapshot.state, ProtocolBatteryState::Charging) {
             snapshot
@@ -272,5 +621,86 @@ impl BatteryData {
         };
 
         self.cached_snapshot = Some(snapshot.clone());
+        self.record_drain();
+        self.observe_advice();
+        self.dispatch_events();
+    }
+}
+
+fn health_condition(health_percent: f32) -> &'static str {
+    if health_percent > GOOD_HEALTH_PERCENT {
+        "good"
+    } else if health_percent > FAIR_HEALTH_PERCENT {
+        "fair"
+    } else {
+        "poor"
+    }
+}
+
+fn remaining_wh(max_capacity_wh: f32, charge_percent: f32) -> Option<f32> {
+    if !max_capacity_wh.is_finite() || max_capacity_wh <= 0.0 {
+        return None;
+    }
+    Some(max_capacity_wh * charge_percent.clamp(0.0, 100.0) / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_energy_scales_capacity_by_charge() {
+        assert_eq!(remaining_wh(60.0, 50.0), Some(30.0));
+        assert_eq!(remaining_wh(60.0, 104.0), Some(60.0));
+        assert_eq!(remaining_wh(0.0, 50.0), None);
+    }
+
+    #[test]
+    fn health_condition_follows_the_gauge_colors() {
+        assert_eq!(health_condition(96.0), "good");
+        assert_eq!(health_condition(79.0), "fair");
+        assert_eq!(health_condition(49.0), "poor");
+    }
+
+    #[test]
+    fn first_reading_only_sets_baseline() {
+        let mut notifier = BatteryNotifier::new(20.0, 5.0);
+        assert!(notifier.observe(3.0, ChargeState::Discharging).is_empty());
+    }
+
+    #[test]
+    fn thresholds_fire_once_when_crossed() {
+        let mut notifier = BatteryNotifier::new(20.0, 5.0);
+        notifier.observe(21.0, ChargeState::Discharging);
+
+        assert_eq!(
+            notifier.observe(19.5, ChargeState::Discharging),
+            vec![BatteryEvent::LowBattery(19.5)]
+        );
+        assert!(notifier.observe(18.0, ChargeState::Discharging).is_empty());
+        assert_eq!(
+            notifier.observe(4.0, ChargeState::Discharging),
+            vec![BatteryEvent::CriticalBattery(4.0)]
+        );
+    }
+
+    #[test]
+    fn state_changes_are_reported() {
+        let mut notifier = BatteryNotifier::new(20.0, 5.0);
+        notifier.observe(50.0, ChargeState::Discharging);
+
+        assert_eq!(
+            notifier.observe(50.0, ChargeState::Charging),
+            vec![BatteryEvent::Charging]
+        );
+        assert_eq!(
+            notifier.observe(100.0, ChargeState::Full),
+            vec![BatteryEvent::FullyCharged]
+        );
+        assert!(notifier.observe(100.0, ChargeState::NotCharging).is_empty());
+        assert_eq!(
+            notifier.observe(99.0, ChargeState::Discharging),
+            vec![BatteryEvent::Discharging]
+        );
     }
 }
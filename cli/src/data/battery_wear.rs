@@ -0,0 +1,184 @@
+//! Capacity fade over the daily battery wear log.
+//!
+//! Fuel gauges occasionally recalibrate and report a noticeably higher full
+//! charge capacity than the day before. Those jumps are annotated rather than
+//! fitted: the fade slope is computed within the stretches between them, so a
+//! recalibration neither hides degradation nor shortens the projection.
+
+use chrono::NaiveDate;
+
+use super::BatteryHealthSnapshot;
+
+/// Capacity rise between consecutive entries, as a percentage of design
+/// capacity, above which the later entry is treated as a recalibration.
+pub const RECALIBRATION_JUMP_PERCENT: f32 = 2.0;
+
+/// Health at which the lifespan projection considers the battery worn out.
+pub const END_OF_LIFE_HEALTH_PERCENT: f32 = 80.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WearPoint {
+    pub date: NaiveDate,
+    pub full_charge_mah: f32,
+    pub design_mah: f32,
+    /// Capacity jumped up by more than [`RECALIBRATION_JUMP_PERCENT`] since
+    /// the previous entry.
+    pub recalibration: bool,
+}
+
+/// Wear log entries that carry mAh capacities, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct WearLog {
+    pub points: Vec<WearPoint>,
+}
+
+impl WearLog {
+    /// Builds the log from stored snapshots, skipping entries recorded before
+    /// mAh capacities were stored or with an unparseable date.
+    pub fn from_snapshots(snapshots: &[BatteryHealthSnapshot]) -> Self {
+        let mut points: Vec<WearPoint> = snapshots
+            .iter()
+            .filter_map(|s| {
+                let date = NaiveDate::parse_from_str(&s.date, "%Y-%m-%d").ok()?;
+                let full_charge_mah = s.full_charge_capacity_mah.filter(|&c| c > 0)? as f32;
+                let design_mah = s.design_capacity_mah.filter(|&c| c > 0)? as f32;
+                Some(WearPoint {
+                    date,
+                    full_charge_mah,
+                    design_mah,
+                    recalibration: false,
+                })
+            })
+            .collect();
+        points.sort_by_key(|p| p.date);
+
+        for i in 1..points.len() {
+            let rise = points[i].full_charge_mah - points[i - 1].full_charge_mah;
+            points[i].recalibration =
+                rise > points[i].design_mah * RECALIBRATION_JUMP_PERCENT / 100.0;
+        }
+
+        Self { points }
+    }
+
+    pub fn latest(&self) -> Option<&WearPoint> {
+        self.points.last()
+    }
+
+    pub fn recalibrations(&self) -> impl Iterator<Item = &WearPoint> {
+        self.points.iter().filter(|p| p.recalibration)
+    }
+
+    /// Capacity change in mAh per day, negative while the battery fades.
+    ///
+    /// Fitted by least squares within each stretch between recalibrations,
+    /// excluding the recalibration points themselves, with the stretches
+    /// sharing one slope. `None` without two entries in the same stretch.
+    pub fn fade_mah_per_day(&self) -> Option<f32> {
+        let mut covariance = 0.0_f64;
+        let mut variance = 0.0_f64;
+
+        for segment in self.points.split(|p| p.recalibration) {
+            if segment.len() < 2 {
+                continue;
+            }
+            let origin = segment[0].date;
+            let days: Vec<f64> = segment
+                .iter()
+                .map(|p| (p.date - origin).num_days() as f64)
+                .collect();
+            let mean_day = days.iter().sum::<f64>() / days.len() as f64;
+            let mean_mah = segment
+                .iter()
+                .map(|p| p.full_charge_mah as f64)
+                .sum::<f64>()
+                / segment.len() as f64;
+
+            for (day, point) in days.iter().zip(segment) {
+                covariance += (day - mean_day) * (point.full_charge_mah as f64 - mean_mah);
+                variance += (day - mean_day).powi(2);
+            }
+        }
+
+        (variance > 0.0).then(|| (covariance / variance) as f32)
+    }
+
+    /// Date the full charge capacity is projected to reach
+    /// [`END_OF_LIFE_HEALTH_PERCENT`] of design, continuing the fade from the
+    /// latest entry.
+    ///
+    /// Returns `None` unless the fit shows capacity fading.
+    pub fn projected_end_of_life(&self) -> Option<NaiveDate> {
+        let latest = self.latest()?;
+        let slope = self.fade_mah_per_day().filter(|&s| s < 0.0)?;
+        let target = latest.design_mah * END_OF_LIFE_HEALTH_PERCENT / 100.0;
+        let remaining_mah = (latest.full_charge_mah - target).max(0.0);
+        let days = (remaining_mah / -slope).round() as i64;
+        latest
+            .date
+            .checked_add_signed(chrono::Duration::try_days(days)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(date: &str, full_charge_mah: i32) -> BatteryHealthSnapshot {
+        BatteryHealthSnapshot {
+            id: None,
+            date: date.to_string(),
+            health_percent: full_charge_mah as f32 / 50.0,
+            cycle_count: None,
+            max_capacity_wh: 0.0,
+            design_capacity_wh: 0.0,
+            full_charge_capacity_mah: Some(full_charge_mah),
+            design_capacity_mah: Some(5000),
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn recalibration_jump_is_annotated_and_not_fitted() {
+        // Fades 2 mAh/day, then the gauge recalibrates 300 mAh upwards.
+        let log = WearLog::from_snapshots(&[
+            snapshot("2024-01-01", 4600),
+            snapshot("2024-01-11", 4580),
+            snapshot("2024-01-21", 4560),
+            snapshot("2024-01-22", 4860),
+            snapshot("2024-02-01", 4840),
+            snapshot("2024-02-11", 4820),
+        ]);
+
+        let recalibrations: Vec<_> = log.recalibrations().map(|p| p.date).collect();
+        assert_eq!(recalibrations, vec![date("2024-01-22")]);
+
+        let slope = log.fade_mah_per_day().unwrap();
+        assert!((slope + 2.0).abs() < 1e-4, "slope {}", slope);
+
+        // 4820 mAh down to 4000 mAh (80% of 5000) at 2 mAh/day.
+        assert_eq!(
+            log.projected_end_of_life(),
+            Some(date("2024-02-11") + chrono::Duration::days(410))
+        );
+    }
+
+    #[test]
+    fn entries_without_capacity_are_skipped_and_no_fade_has_no_projection() {
+        let mut legacy = snapshot("2024-01-01", 4600);
+        legacy.full_charge_capacity_mah = None;
+        let log = WearLog::from_snapshots(&[
+            legacy,
+            snapshot("2024-01-02", 4600),
+            snapshot("2024-01-03", 4600),
+        ]);
+
+        assert_eq!(log.points.len(), 2);
+        assert_eq!(log.fade_mah_per_day(), Some(0.0));
+        assert_eq!(log.projected_end_of_life(), None);
+        assert_eq!(WearLog::default().fade_mah_per_day(), None);
+    }
+}
@@ -0,0 +1,306 @@
+//! Energy Model channel listings contributed from other Macs.
+//!
+//! Every Apple Silicon variant names its channels a little differently, and
+//! the domain mapping in [`ChannelDomain::of`] only stays right if it is
+//! checked against them. `jolt contribute-channels` writes this machine's
+//! listing as a fixture for `fixtures/channels`, and every fixture there is
+//! checked against the invariants in [`ChannelFixture::violations`].
+
+use std::time::Duration;
+
+use color_eyre::eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use jolt_platform::{
+    joules_per_unit, ChannelDomain, ChannelListing, ChannelPower, OverlapDetector, OVERLAP_SAMPLES,
+};
+
+pub const ENERGY_MODEL_GROUP: &str = "Energy Model";
+
+/// Suffix of channels totalling a whole domain, e.g. "CPU Energy".
+const DOMAIN_TOTAL_SUFFIX: &str = " energy";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelFixture {
+    /// Model identifier, e.g. "Mac15,6".
+    pub model: String,
+    pub chip: String,
+    pub os_version: String,
+    pub jolt_version: String,
+    /// Energy Model channels deliberately left out of the CPU, GPU and ANE
+    /// domains, either counted as other SoC power or not counted at all.
+    #[serde(default)]
+    pub expected_other: Vec<String>,
+    pub channels: Vec<FixtureChannel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixtureChannel {
+    pub group: String,
+    #[serde(default)]
+    pub subgroup: String,
+    pub name: String,
+    pub unit: String,
+    /// Average power over the listing's interval.
+    #[serde(default)]
+    pub watts: Option<f32>,
+}
+
+impl From<ChannelListing> for FixtureChannel {
+    fn from(listing: ChannelListing) -> Self {
+        Self {
+            group: listing.group,
+            subgroup: listing.subgroup,
+            name: listing.name,
+            unit: listing.unit,
+            watts: listing.watts,
+        }
+    }
+}
+
+impl ChannelFixture {
+    /// A fixture for `channels` with `expected_other` filled in from the
+    /// current mapping, for the contributor to review.
+    pub fn new(
+        model: String,
+        chip: String,
+        os_version: String,
+        channels: Vec<ChannelListing>,
+    ) -> Self {
+        let channels: Vec<FixtureChannel> =
+            channels.into_iter().map(FixtureChannel::from).collect();
+        let expected_other = channels
+            .iter()
+            .filter(|c| c.group == ENERGY_MODEL_GROUP && !is_core_domain(&c.name))
+            .map(|c| c.name.clone())
+            .collect();
+        Self {
+            model,
+            chip,
+            os_version,
+            jolt_version: env!("CARGO_PKG_VERSION").to_string(),
+            expected_other,
+            channels,
+        }
+    }
+
+    /// Suggested file name, e.g. `mac15-6-macos-14.2.json`.
+    pub fn file_name(&self) -> String {
+        let model: String = self
+            .model
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        format!("{}-macos-{}.json", model, self.os_version)
+    }
+
+    /// Every way the domain mapping mishandles this listing; empty when it
+    /// handles it correctly:
+    ///
+    /// - each Energy Model channel lands in the CPU, GPU or ANE domain, or
+    ///   is listed in `expected_other` (and only then)
+    /// - each reports an energy unit jolt converts to joules
+    /// - once aggregates are marked, none is still counted next to its
+    ///   components, which would count their energy twice
+    pub fn violations(&self) -> Vec<String> {
+        let mut violations = Vec::new();
+        let energy_model: Vec<&FixtureChannel> = self
+            .channels
+            .iter()
+            .filter(|c| c.group == ENERGY_MODEL_GROUP)
+            .collect();
+
+        for channel in &energy_model {
+            let expected_other = self.expected_other.contains(&channel.name);
+            match (is_core_domain(&channel.name), expected_other) {
+                (false, false) => violations.push(format!(
+                    "`{}` is in no CPU, GPU or ANE domain and not listed in expected_other",
+                    channel.name
+                )),
+                (true, true) => violations.push(format!(
+                    "`{}` is listed in expected_other but counts towards {}",
+                    channel.name,
+                    domain_label(&channel.name)
+                )),
+                _ => {}
+            }
+            if joules_per_unit(&channel.unit).is_none() {
+                violations.push(format!(
+                    "`{}` reports `{}`, which is not a convertible energy unit",
+                    channel.name, channel.unit
+                ));
+            }
+        }
+        for name in &self.expected_other {
+            if !energy_model.iter().any(|c| &c.name == name) {
+                violations.push(format!(
+                    "expected_other lists `{}`, which is not in the listing",
+                    name
+                ));
+            }
+        }
+
+        violations.extend(double_counted(&energy_model));
+        violations
+    }
+}
+
+fn is_core_domain(channel: &str) -> bool {
+    matches!(
+        ChannelDomain::of(channel),
+        Some(ChannelDomain::Cpu | ChannelDomain::Gpu | ChannelDomain::Ane)
+    )
+}
+
+fn domain_label(channel: &str) -> &'static str {
+    ChannelDomain::of(channel).map_or("no domain", ChannelDomain::label)
+}
+
+/// Aggregates still counted next to their components once the overlap
+/// detector has settled on the recorded sample: a cluster channel next to
+/// its numbered cores, or a domain total ("CPU Energy") next to anything
+/// else in its domain.
+fn double_counted(channels: &[&FixtureChannel]) -> Vec<String> {
+    let mut sample: Vec<ChannelPower> = channels
+        .iter()
+        .filter(|c| joules_per_unit(&c.unit).is_some())
+        .filter_map(|c| {
+            Some(ChannelPower {
+                name: c.name.clone(),
+                watts: c.watts?,
+                aggregate: false,
+            })
+        })
+        .collect();
+    let mut detector = OverlapDetector::default();
+    for _ in 0..=OVERLAP_SAMPLES {
+        detector.mark_aggregates(&mut sample, ChannelDomain::of);
+    }
+
+    let counted: Vec<&str> = sample
+        .iter()
+        .filter(|c| !c.aggregate && ChannelDomain::of(&c.name).is_some())
+        .map(|c| c.name.as_str())
+        .collect();
+    let mut violations = Vec::new();
+    for &aggregate in &counted {
+        let domain = ChannelDomain::of(aggregate);
+        let is_total = aggregate.to_lowercase().ends_with(DOMAIN_TOTAL_SUFFIX);
+        let component = counted.iter().find(|&&other| {
+            other != aggregate
+                && ChannelDomain::of(other) == domain
+                && (is_total
+                    || other
+                        .strip_prefix(aggregate)
+                        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())))
+        });
+        if let Some(component) = component {
+            violations.push(format!(
+                "`{}` and its component `{}` are both counted",
+                aggregate, component
+            ));
+        }
+    }
+    violations
+}
+
+/// This machine's Energy Model channels, sampled over `interval`.
+#[cfg(target_os = "macos")]
+pub fn list_channels(interval: Duration) -> Result<Vec<ChannelListing>> {
+    jolt_platform::macos::list_energy_model_channels(interval)
+        .ok_or_else(|| color_eyre::eyre::eyre!("IOReport Energy Model channels are not available"))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn list_channels(_interval: Duration) -> Result<Vec<ChannelListing>> {
+    color_eyre::eyre::bail!("Energy Model channels come from IOReport, which only macOS has")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn channel(name: &str, unit: &str, watts: f32) -> ChannelListing {
+        ChannelListing {
+            group: ENERGY_MODEL_GROUP.to_string(),
+            subgroup: String::new(),
+            name: name.to_string(),
+            unit: unit.to_string(),
+            watts: Some(watts),
+        }
+    }
+
+    #[test]
+    fn contributed_fixtures_hold_every_invariant() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("channels");
+        let mut checked = 0;
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let fixture: ChannelFixture =
+                serde_json::from_str(&std::fs::read_to_string(&path).unwrap())
+                    .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+            let violations = fixture.violations();
+            assert!(
+                violations.is_empty(),
+                "{}:\n  {}",
+                path.display(),
+                violations.join("\n  ")
+            );
+            checked += 1;
+        }
+        assert!(
+            checked >= 2,
+            "only {} fixtures in {}",
+            checked,
+            dir.display()
+        );
+    }
+
+    #[test]
+    fn violations_name_each_broken_invariant() {
+        let mut fixture = ChannelFixture::new(
+            "Mac99,1".to_string(),
+            "M9".to_string(),
+            "99.0".to_string(),
+            vec![
+                // Keeps disagreeing with its cores, so it ends up counted.
+                channel("PCPU", "mJ", 5.0),
+                channel("PCPU0", "mJ", 1.0),
+                channel("PCPU1", "mJ", 1.0),
+                channel("GPU0", "counts", 1.0),
+                channel("DRAM0", "mJ", 0.5),
+                channel("PMGR", "mJ", 0.1),
+            ],
+        );
+        assert_eq!(fixture.expected_other, vec!["DRAM0", "PMGR"]);
+        assert_eq!(fixture.file_name(), "mac99-1-macos-99.0.json");
+        assert_eq!(
+            fixture.violations(),
+            vec![
+                "`GPU0` reports `counts`, which is not a convertible energy unit",
+                "`PCPU` and its component `PCPU0` are both counted",
+            ]
+        );
+
+        fixture.expected_other = vec!["GPU0".to_string(), "ISP".to_string()];
+        let violations = fixture.violations();
+        for expected in [
+            "`GPU0` is listed in expected_other but counts towards gpu",
+            "`DRAM0` is in no CPU, GPU or ANE domain and not listed in expected_other",
+            "`PMGR` is in no CPU, GPU or ANE domain and not listed in expected_other",
+            "expected_other lists `ISP`, which is not in the listing",
+        ] {
+            assert!(violations.iter().any(|v| v == expected), "{:?}", violations);
+        }
+    }
+}
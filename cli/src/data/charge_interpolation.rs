@@ -0,0 +1,135 @@
+//! Sub-percent battery charge for display.
+//!
+//! macOS reports whole percents and a percent can take minutes to drain, so
+//! the gauge sits still and then jumps. Between changes of the reported
+//! value, the displayed charge follows the battery's measured power against
+//! its full-charge capacity, never more than [`MAX_DRIFT_PERCENT`] away from
+//! what the battery reports. Each new reported value is taken as is.
+
+use std::time::SystemTime;
+
+/// Furthest the interpolated charge may move from the reported one.
+pub const MAX_DRIFT_PERCENT: f32 = 1.0;
+
+#[derive(Debug, Default)]
+pub(crate) struct ChargeInterpolator {
+    /// Last reported percent.
+    reported: Option<f32>,
+    /// Percent gained since `reported` was first seen, up to the last record.
+    drift: f32,
+    last_at: Option<SystemTime>,
+    /// Battery power at the last record, positive while charging. `None`
+    /// when the battery gave no current reading to measure it from.
+    watts: Option<f32>,
+    capacity_wh: f32,
+}
+
+impl ChargeInterpolator {
+    /// Adds a battery reading. A reported percent that differs from the last
+    /// one restarts interpolation from it.
+    pub(crate) fn record(
+        &mut self,
+        at: SystemTime,
+        reported_percent: f32,
+        watts: Option<f32>,
+        capacity_wh: f32,
+    ) {
+        if self.reported == Some(reported_percent) && self.watts.is_some() {
+            self.drift = self.drift_at(at);
+        } else {
+            self.drift = 0.0;
+        }
+        self.reported = Some(reported_percent);
+        self.last_at = Some(at);
+        self.watts = watts.filter(|w| w.is_finite());
+        self.capacity_wh = capacity_wh;
+    }
+
+    /// Interpolated charge at `now`; `None` without measured battery power
+    /// or a known capacity, where only the reported value is meaningful.
+    pub(crate) fn percent_at(&self, now: SystemTime) -> Option<f32> {
+        let reported = self.reported?;
+        self.watts?;
+        if !(self.capacity_wh.is_finite() && self.capacity_wh > 0.0) {
+            return None;
+        }
+        Some((reported + self.drift_at(now)).clamp(0.0, 100.0))
+    }
+
+    fn drift_at(&self, now: SystemTime) -> f32 {
+        let (Some(last_at), Some(watts)) = (self.last_at, self.watts) else {
+            return self.drift;
+        };
+        if !(self.capacity_wh.is_finite() && self.capacity_wh > 0.0) {
+            return self.drift;
+        }
+        let hours = now
+            .duration_since(last_at)
+            .unwrap_or_default()
+            .as_secs_f32()
+            / 3600.0;
+        let gained = watts * hours / self.capacity_wh * 100.0;
+        (self.drift + gained).clamp(-MAX_DRIFT_PERCENT, MAX_DRIFT_PERCENT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn close(actual: Option<f32>, expected: f32) -> bool {
+        actual.is_some_and(|a| (a - expected).abs() < 1e-3)
+    }
+
+    #[test]
+    fn drains_toward_the_next_percent_and_snaps_to_readings() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut charge = ChargeInterpolator::default();
+
+        // 10 W out of 50 Wh drains 1% every 180 s.
+        charge.record(start, 50.0, Some(-10.0), 50.0);
+        assert!(close(charge.percent_at(at(90)), 49.5));
+
+        // Power halves halfway through: the first 90 s keep their drain.
+        charge.record(at(90), 50.0, Some(-5.0), 50.0);
+        assert!(close(charge.percent_at(at(180)), 49.25));
+
+        // Converges on the next percent and stays there until it is reported.
+        assert!(close(charge.percent_at(at(10_000)), 49.0));
+        charge.record(at(10_000), 50.0, Some(-5.0), 50.0);
+        assert!(close(charge.percent_at(at(20_000)), 49.0));
+
+        charge.record(at(20_000), 49.0, Some(-5.0), 50.0);
+        assert!(close(charge.percent_at(at(20_000)), 49.0));
+        assert!(close(charge.percent_at(at(20_360)), 48.0));
+    }
+
+    #[test]
+    fn unmeasured_power_disables_interpolation() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let mut charge = ChargeInterpolator::default();
+        assert_eq!(charge.percent_at(start), None);
+
+        charge.record(start, 80.0, None, 50.0);
+        assert_eq!(charge.percent_at(start + Duration::from_secs(600)), None);
+
+        charge.record(start, 80.0, Some(20.0), 0.0);
+        assert_eq!(charge.percent_at(start + Duration::from_secs(600)), None);
+
+        // Charging moves up, and a reading without power starts over.
+        charge.record(start, 80.0, Some(20.0), 50.0);
+        assert!(close(
+            charge.percent_at(start + Duration::from_secs(45)),
+            80.5
+        ));
+        charge.record(start + Duration::from_secs(45), 80.0, None, 50.0);
+        charge.record(start + Duration::from_secs(60), 80.0, Some(20.0), 50.0);
+        assert!(close(
+            charge.percent_at(start + Duration::from_secs(60)),
+            80.0
+        ));
+    }
+}
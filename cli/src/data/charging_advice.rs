@@ -0,0 +1,303 @@
+//! Rules that turn battery temperature, charging power and charge-limit
+//! state into at most one piece of advice for the battery panel.
+//!
+//! Every rule has hysteresis: temperature rules clear only once the reading
+//! falls `hysteresis_c` below their threshold, the fast-charge condition
+//! clears below [`FAST_CHARGE_CLEAR_RATIO`] of its threshold, and the
+//! held-at-limit rule needs [`HELD_AT_LIMIT_READINGS`] agreeing readings to
+//! change state.
+
+use crate::config::{AdviceConfig, TemperatureUnit};
+
+/// Fraction of `fast_charging_watts` charging power must fall below before
+/// fast-charge advice clears.
+pub const FAST_CHARGE_CLEAR_RATIO: f32 = 0.8;
+
+/// Consecutive readings needed to show or clear held-at-limit advice, so a
+/// limiter briefly topping up does not make it flicker.
+pub const HELD_AT_LIMIT_READINGS: u32 = 3;
+
+/// One battery reading as seen by the rules.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AdviceInputs {
+    pub temperature_c: Option<f32>,
+    /// Power flowing into the battery; `None` while not charging.
+    pub charging_watts: Option<f32>,
+    /// The charge limit, when the battery is being held at it.
+    pub held_at_limit: Option<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Advice {
+    HotWhileFastCharging { temperature_c: f32 },
+    HotBattery { temperature_c: f32 },
+    HeldAtLimit { limit_percent: u8 },
+}
+
+impl Advice {
+    pub fn message(&self, unit: TemperatureUnit) -> String {
+        let temperature = |celsius: f32| match unit {
+            TemperatureUnit::Celsius => format!("{:.0} °C", celsius),
+            TemperatureUnit::Fahrenheit => format!("{:.0} °F", celsius * 9.0 / 5.0 + 32.0),
+        };
+        match self {
+            Advice::HotWhileFastCharging { temperature_c } => format!(
+                "Battery is {} while fast charging — consider removing the case or lowering load",
+                temperature(*temperature_c)
+            ),
+            Advice::HotBattery { temperature_c } => format!(
+                "Battery is {} — let it cool before heavy use",
+                temperature(*temperature_c)
+            ),
+            Advice::HeldAtLimit { limit_percent } => {
+                format!("Held at {}% by the charge limit", limit_percent)
+            }
+        }
+    }
+}
+
+/// Tracks which rules are active across readings.
+#[derive(Debug, Clone, Default)]
+pub struct ChargingAdvisor {
+    config: AdviceConfig,
+    latest: AdviceInputs,
+    hot_charging: bool,
+    hot_battery: bool,
+    held_at_limit: bool,
+    /// Most recent limit reported while held, kept through the readings it
+    /// takes to clear.
+    held_limit_percent: u8,
+    /// Consecutive readings disagreeing with `held_at_limit`.
+    held_streak: u32,
+}
+
+impl ChargingAdvisor {
+    pub fn new(config: AdviceConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn observe(&mut self, inputs: AdviceInputs) {
+        let config = &self.config;
+        let temperature = inputs.temperature_c;
+
+        let fast_charging = |threshold: f32| {
+            inputs
+                .charging_watts
+                .is_some_and(|watts| watts >= threshold)
+        };
+        self.hot_charging = if self.hot_charging {
+            exceeds(temperature, config.hot_charging_c - config.hysteresis_c)
+                && fast_charging(config.fast_charging_watts * FAST_CHARGE_CLEAR_RATIO)
+        } else {
+            exceeds(temperature, config.hot_charging_c) && fast_charging(config.fast_charging_watts)
+        };
+
+        self.hot_battery = if self.hot_battery {
+            exceeds(temperature, config.hot_battery_c - config.hysteresis_c)
+        } else {
+            exceeds(temperature, config.hot_battery_c)
+        };
+
+        if inputs.held_at_limit.is_some() == self.held_at_limit {
+            self.held_streak = 0;
+        } else {
+            self.held_streak += 1;
+            if self.held_streak >= HELD_AT_LIMIT_READINGS {
+                self.held_at_limit = !self.held_at_limit;
+                self.held_streak = 0;
+            }
+        }
+
+        if let Some(limit) = inputs.held_at_limit {
+            self.held_limit_percent = limit;
+        }
+        self.latest = inputs;
+    }
+
+    /// The highest-priority active, enabled rule: hot while fast charging,
+    /// then hot battery, then held at limit.
+    pub fn current(&self) -> Option<Advice> {
+        let config = &self.config;
+        if !config.enabled {
+            return None;
+        }
+        let temperature_c = self.latest.temperature_c;
+        if config.hot_charging && self.hot_charging {
+            return temperature_c
+                .map(|temperature_c| Advice::HotWhileFastCharging { temperature_c });
+        }
+        if config.hot_battery && self.hot_battery {
+            return temperature_c.map(|temperature_c| Advice::HotBattery { temperature_c });
+        }
+        if config.held_at_limit && self.held_at_limit {
+            return Some(Advice::HeldAtLimit {
+                limit_percent: self.held_limit_percent,
+            });
+        }
+        None
+    }
+}
+
+fn exceeds(temperature_c: Option<f32>, threshold: f32) -> bool {
+    temperature_c.is_some_and(|t| t >= threshold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(temperature_c: f32, charging_watts: Option<f32>) -> AdviceInputs {
+        AdviceInputs {
+            temperature_c: Some(temperature_c),
+            charging_watts,
+            held_at_limit: None,
+        }
+    }
+
+    fn advisor() -> ChargingAdvisor {
+        ChargingAdvisor::new(AdviceConfig::default())
+    }
+
+    #[test]
+    fn hot_while_fast_charging_triggers_and_clears_with_hysteresis() {
+        let mut advisor = advisor();
+
+        advisor.observe(reading(39.5, Some(45.0)));
+        assert_eq!(advisor.current(), None);
+
+        advisor.observe(reading(41.0, Some(45.0)));
+        assert_eq!(
+            advisor.current(),
+            Some(Advice::HotWhileFastCharging {
+                temperature_c: 41.0
+            })
+        );
+
+        // Inside the hysteresis band on both temperature and power.
+        advisor.observe(reading(38.5, Some(26.0)));
+        assert!(matches!(
+            advisor.current(),
+            Some(Advice::HotWhileFastCharging { .. })
+        ));
+
+        advisor.observe(reading(37.5, Some(45.0)));
+        assert_eq!(advisor.current(), None);
+
+        advisor.observe(reading(41.0, Some(45.0)));
+        advisor.observe(reading(41.0, Some(20.0)));
+        assert_eq!(advisor.current(), None, "charging slowed below the band");
+
+        advisor.observe(reading(41.0, Some(45.0)));
+        advisor.observe(reading(41.0, None));
+        assert_eq!(advisor.current(), None, "charging stopped");
+    }
+
+    #[test]
+    fn hot_battery_triggers_and_clears_with_hysteresis() {
+        let mut advisor = advisor();
+
+        advisor.observe(reading(44.0, None));
+        assert_eq!(advisor.current(), None);
+
+        advisor.observe(reading(46.0, None));
+        assert_eq!(
+            advisor.current(),
+            Some(Advice::HotBattery {
+                temperature_c: 46.0
+            })
+        );
+
+        advisor.observe(reading(43.5, None));
+        assert!(matches!(advisor.current(), Some(Advice::HotBattery { .. })));
+
+        advisor.observe(reading(42.5, None));
+        assert_eq!(advisor.current(), None);
+
+        advisor.observe(AdviceInputs::default());
+        assert_eq!(advisor.current(), None);
+    }
+
+    #[test]
+    fn held_at_limit_needs_consecutive_readings_to_change() {
+        let mut advisor = advisor();
+        let held = AdviceInputs {
+            temperature_c: Some(30.0),
+            charging_watts: None,
+            held_at_limit: Some(80),
+        };
+        let released = AdviceInputs {
+            held_at_limit: None,
+            ..held
+        };
+
+        for _ in 1..HELD_AT_LIMIT_READINGS {
+            advisor.observe(held);
+            assert_eq!(advisor.current(), None);
+        }
+        advisor.observe(held);
+        assert_eq!(
+            advisor.current(),
+            Some(Advice::HeldAtLimit { limit_percent: 80 })
+        );
+
+        // A single top-up reading does not clear it.
+        advisor.observe(released);
+        assert!(advisor.current().is_some());
+        advisor.observe(held);
+
+        for _ in 0..HELD_AT_LIMIT_READINGS {
+            advisor.observe(released);
+        }
+        assert_eq!(advisor.current(), None);
+    }
+
+    #[test]
+    fn only_the_highest_priority_enabled_rule_is_reported() {
+        let mut advisor = advisor();
+        let inputs = AdviceInputs {
+            temperature_c: Some(47.0),
+            charging_watts: Some(60.0),
+            held_at_limit: Some(80),
+        };
+        for _ in 0..HELD_AT_LIMIT_READINGS {
+            advisor.observe(inputs);
+        }
+        assert!(matches!(
+            advisor.current(),
+            Some(Advice::HotWhileFastCharging { .. })
+        ));
+
+        advisor.config.hot_charging = false;
+        assert!(matches!(advisor.current(), Some(Advice::HotBattery { .. })));
+
+        advisor.config.hot_battery = false;
+        assert!(matches!(
+            advisor.current(),
+            Some(Advice::HeldAtLimit { .. })
+        ));
+
+        advisor.config.enabled = false;
+        assert_eq!(advisor.current(), None);
+    }
+
+    #[test]
+    fn messages_follow_the_temperature_unit() {
+        let advice = Advice::HotWhileFastCharging {
+            temperature_c: 41.0,
+        };
+        assert_eq!(
+            advice.message(TemperatureUnit::Celsius),
+            "Battery is 41 °C while fast charging — consider removing the case or lowering load"
+        );
+        assert!(advice
+            .message(TemperatureUnit::Fahrenheit)
+            .starts_with("Battery is 106 °F"));
+        assert_eq!(
+            Advice::HeldAtLimit { limit_percent: 80 }.message(TemperatureUnit::Celsius),
+            "Held at 80% by the charge limit"
+        );
+    }
+}
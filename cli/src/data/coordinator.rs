@@ -0,0 +1,354 @@
+//! Coordinated refresh of battery, power, and process data.
+//!
+//! Refreshing subsystems one after another means values shown side by side
+//! were captured at different moments: a slow process scan can push the
+//! battery reading well away from the power reading it is displayed next to.
+//! The coordinator triggers the battery and process refreshes at once, each
+//! on its own thread, takes in power from its sampling thread meanwhile,
+//! waits until a shared deadline, and publishes one combined snapshot. A
+//! subsystem that misses the deadline contributes its previous value, marked
+//! stale.
+
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Result};
+use tracing::warn;
+
+use crate::daemon::{BatterySnapshot, PowerSnapshot, ProcessSnapshot};
+use crate::data::{BatteryData, PowerData, ProcessData};
+
+/// A subsystem value together with when it was captured.
+#[derive(Debug, Clone)]
+pub struct Stamped<T> {
+    pub value: T,
+    /// When the subsystem's worker finished reading the value, not when the
+    /// coordinator received it.
+    pub captured_at: Instant,
+    /// How long the worker took to read the value.
+    pub refresh_time: Duration,
+    /// `true` if this value was not refreshed for the current tick.
+    pub stale: bool,
+}
+
+/// One tick's worth of mutually consistent subsystem data.
+#[derive(Debug, Clone)]
+pub struct CoordinatedSnapshot {
+    pub battery: Stamped<BatterySnapshot>,
+    pub power: Stamped<PowerSnapshot>,
+    pub processes: Stamped<Vec<ProcessSnapshot>>,
+}
+
+impl CoordinatedSnapshot {
+    /// Subsystems that missed this tick's deadline, with the age of the
+    /// value they contributed instead.
+    pub fn stale_subsystems(&self) -> Vec<(&'static str, Duration)> {
+        [
+            ("battery", self.battery.stale, self.battery.captured_at),
+            ("power", self.power.stale, self.power.captured_at),
+            (
+                "processes",
+                self.processes.stale,
+                self.processes.captured_at,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, stale, _)| *stale)
+        .map(|(name, _, captured_at)| (name, captured_at.elapsed()))
+        .collect()
+    }
+}
+
+/// Refreshes battery and process data on their own threads and power on
+/// the caller's [`PowerData`], whose sampling already runs on a worker.
+pub struct RefreshCoordinator {
+    battery: Subsystem<BatteryData, BatterySnapshot>,
+    processes: Subsystem<ProcessData, Vec<ProcessSnapshot>>,
+    generation: u64,
+    deadline: Duration,
+}
+
+impl RefreshCoordinator {
+    pub fn new(deadline: Duration) -> Result<Self> {
+        Self::with_exclusions(deadline, Vec::new())
+    }
+
+    /// Spawns one worker per subsystem and waits for each initial reading.
+    /// Processes named in `excluded` are left out as by
+    /// [`ProcessData::with_exclusions`].
+    ///
+    /// Providers are constructed on their worker threads since some of them
+    /// cannot be moved across threads.
+    pub fn with_exclusions(deadline: Duration, excluded: Vec<String>) -> Result<Self> {
+        let battery = Subsystem::spawn("battery", BatteryData::new, |battery| {
+            battery.refresh()?;
+            Ok(BatterySnapshot::from(&*battery))
+        })?;
+        let processes = Subsystem::spawn(
+            "processes",
+            move || ProcessData::with_exclusions(excluded),
+            |processes| {
+                processes.refresh()?;
+                Ok(processes
+                    .processes
+                    .iter()
+                    .map(ProcessSnapshot::from)
+                    .collect())
+            },
+        )?;
+
+        Ok(Self {
+            battery,
+            processes,
+            generation: 0,
+            deadline,
+        })
+    }
+
+    /// Turns per-process network sampling on or off from the next refresh.
+    pub fn set_process_network_sampling(&self, enabled: bool) {
+        self.processes
+            .configure(move |processes| processes.set_network_sampling(enabled));
+    }
+
+    /// Refreshes all subsystems for a new tick and returns the combined result.
+    ///
+    /// Blocks for at most the configured deadline. `power` is refreshed in
+    /// place: its sampling thread runs on its own interval, so a tick that
+    /// brings no new reading is not stale; only a stopped sampler is.
+    pub fn refresh(&mut self, power: &mut PowerData) -> CoordinatedSnapshot {
+        self.generation += 1;
+        let deadline = Instant::now() + self.deadline;
+
+        self.battery.trigger(self.generation);
+        self.processes.trigger(self.generation);
+
+        if let Err(e) = power.refresh() {
+            warn!(subsystem = "power", error = %e, "Subsystem refresh failed");
+        }
+        let power = Stamped {
+            value: PowerSnapshot::from(&*power),
+            captured_at: power.sampled_at(),
+            refresh_time: power.last_refresh_times().iter().sum(),
+            stale: power.sampling_failure().is_some(),
+        };
+
+        self.battery.collect(self.generation, deadline);
+        self.processes.collect(self.generation, deadline);
+
+        CoordinatedSnapshot {
+            battery: self.battery.latest.clone(),
+            power,
+            processes: self.processes.latest.clone(),
+        }
+    }
+}
+
+type Response<T> = (u64, Result<Stamped<T>>);
+
+enum Request<S> {
+    Tick(u64),
+    /// Runs on the worker's state between refreshes.
+    Configure(Box<dyn FnOnce(&mut S) + Send>),
+}
+
+/// A single subsystem refreshed on a dedicated thread.
+///
+/// Requests and responses carry the tick generation, so a response that
+/// arrives after its tick's deadline is recognised as late rather than being
+/// mistaken for the current reading.
+struct Subsystem<S, T> {
+    name: &'static str,
+    request_tx: mpsc::Sender<Request<S>>,
+    response_rx: mpsc::Receiver<Response<T>>,
+    latest: Stamped<T>,
+}
+
+impl<S: 'static, T: Clone + Send + 'static> Subsystem<S, T> {
+    fn spawn<I, R>(name: &'static str, init: I, mut snapshot: R) -> Result<Self>
+    where
+        I: FnOnce() -> Result<S> + Send + 'static,
+        R: FnMut(&mut S) -> Result<T> + Send + 'static,
+    {
+        let (request_tx, request_rx) = mpsc::channel::<Request<S>>();
+        let (response_tx, response_rx) = mpsc::channel::<Response<T>>();
+
+        thread::spawn(move || {
+            let mut state = match init() {
+                Ok(s) => s,
+                Err(e) => {
+                    let _ = response_tx.send((0, Err(e)));
+                    return;
+                }
+            };
+            if response_tx
+                .send((0, capture(&mut state, &mut snapshot)))
+                .is_err()
+            {
+                return;
+            }
+
+            while let Ok(request) = request_rx.recv() {
+                let mut generation = match request {
+                    Request::Tick(generation) => generation,
+                    Request::Configure(configure) => {
+                        configure(&mut state);
+                        continue;
+                    }
+                };
+                // Skip ticks that were requested while we were still busy
+                while let Ok(newer) = request_rx.try_recv() {
+                    match newer {
+                        Request::Tick(newer) => generation = newer,
+                        Request::Configure(configure) => configure(&mut state),
+                    }
+                }
+                if response_tx
+                    .send((generation, capture(&mut state, &mut snapshot)))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        let latest = match response_rx.recv() {
+            Ok((_, Ok(value))) => value,
+            Ok((_, Err(e))) => return Err(e.wrap_err(format!("Failed to initialize {}", name))),
+            Err(_) => return Err(eyre!("{} worker exited during initialization", name)),
+        };
+
+        Ok(Self {
+            name,
+            request_tx,
+            response_rx,
+            latest,
+        })
+    }
+
+    fn trigger(&self, generation: u64) {
+        let _ = self.request_tx.send(Request::Tick(generation));
+    }
+
+    fn configure(&self, configure: impl FnOnce(&mut S) + Send + 'static) {
+        let _ = self
+            .request_tx
+            .send(Request::Configure(Box::new(configure)));
+    }
+
+    fn collect(&mut self, generation: u64, deadline: Instant) {
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match self.response_rx.recv_timeout(timeout) {
+                Ok((received, Ok(stamped))) => {
+                    self.latest = Stamped {
+                        stale: received != generation,
+                        ..stamped
+                    };
+                    if received == generation {
+                        return;
+                    }
+                }
+                Ok((received, Err(e))) => {
+                    warn!(subsystem = self.name, error = %e, "Subsystem refresh failed");
+                    if received == generation {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        self.latest.stale = true;
+    }
+}
+
+/// Reads one value on the worker, stamped as soon as it was read so time
+/// spent waiting in the channel does not count towards its age.
+fn capture<S, T>(
+    state: &mut S,
+    snapshot: &mut impl FnMut(&mut S) -> Result<T>,
+) -> Result<Stamped<T>> {
+    let started = Instant::now();
+    let value = snapshot(state)?;
+    Ok(Stamped {
+        value,
+        captured_at: Instant::now(),
+        refresh_time: started.elapsed(),
+        stale: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(delay: Duration) -> Subsystem<u32, u32> {
+        Subsystem::spawn(
+            "counter",
+            || Ok(0u32),
+            move |n| {
+                thread::sleep(delay);
+                *n += 1;
+                Ok(*n)
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn fast_subsystem_is_fresh() {
+        let mut sub = counter(Duration::ZERO);
+        sub.trigger(1);
+        sub.collect(1, Instant::now() + Duration::from_secs(5));
+
+        assert!(!sub.latest.stale);
+        assert_eq!(sub.latest.value, 2);
+    }
+
+    #[test]
+    fn slow_subsystem_keeps_previous_value_marked_stale() {
+        let mut sub = counter(Duration::from_millis(50));
+        sub.trigger(1);
+        sub.collect(1, Instant::now());
+
+        assert!(sub.latest.stale);
+        assert_eq!(sub.latest.value, 1);
+    }
+
+    #[test]
+    fn late_response_is_used_but_not_counted_as_fresh() {
+        let mut sub = counter(Duration::from_millis(50));
+        sub.trigger(1);
+        sub.collect(1, Instant::now());
+
+        thread::sleep(Duration::from_millis(120));
+        sub.trigger(2);
+        sub.collect(2, Instant::now());
+
+        assert!(sub.latest.stale);
+        assert_eq!(sub.latest.value, 2);
+    }
+
+    #[test]
+    fn values_are_stamped_when_read_not_when_collected() {
+        let mut sub = counter(Duration::ZERO);
+        sub.trigger(1);
+        thread::sleep(Duration::from_millis(50));
+        let collected_from = Instant::now();
+        sub.collect(1, collected_from + Duration::from_secs(5));
+
+        assert!(!sub.latest.stale);
+        assert!(sub.latest.captured_at < collected_from);
+    }
+
+    #[test]
+    fn configuration_applies_before_the_next_refresh() {
+        let mut sub = counter(Duration::ZERO);
+        sub.configure(|n| *n = 10);
+        sub.trigger(1);
+        sub.collect(1, Instant::now() + Duration::from_secs(5));
+
+        assert_eq!(sub.latest.value, 11);
+    }
+}
@@ -0,0 +1,259 @@
+//! Replays recorded discharge sessions through the battery estimators.
+//!
+//! Sessions are `jolt pipe` output (one JSON sample per line, the format the
+//! bug-report bundle records) kept under `fixtures/discharge`. Each sample is
+//! fed to the estimators at its recorded timestamp rather than the wall
+//! clock, and every estimate is compared with what actually happened: the
+//! time left until the session's last sample and the charge it ended at.
+//!
+//! Run `cargo test -p jolt-tui discharge_replay -- --nocapture` for the
+//! scorecard, and include it when changing an estimator.
+
+use std::time::{Duration, SystemTime};
+
+use chrono::DateTime;
+use color_eyre::eyre::{eyre, Result};
+use serde_json::Value;
+
+use super::battery::ChargeState;
+use super::history::DataPoint;
+use super::last_until::DrainTracker;
+use super::{ForecastData, HistoryData, HistoryMetric};
+
+const SESSIONS: [(&str, &str); 2] = [
+    (
+        "steady-office",
+        include_str!("../../../fixtures/discharge/steady-office.jsonl"),
+    ),
+    (
+        "bursty-build",
+        include_str!("../../../fixtures/discharge/bursty-build.jsonl"),
+    ),
+];
+
+/// One `jolt pipe` sample, reduced to what the estimators read.
+#[derive(Debug, Clone, Copy)]
+struct RecordedSample {
+    at: SystemTime,
+    percent: f32,
+    state: ChargeState,
+    capacity_wh: f32,
+    total_watts: f32,
+    /// The platform's own time-to-empty estimate.
+    os_minutes_remaining: Option<f32>,
+}
+
+fn parse_session(jsonl: &str) -> Result<Vec<RecordedSample>> {
+    jsonl
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let doc: Value = serde_json::from_str(line)?;
+            let timestamp = doc["timestamp"]
+                .as_str()
+                .ok_or_else(|| eyre!("sample without timestamp"))?;
+            let battery = &doc["battery"];
+            let number = |value: &Value| value.as_f64().map(|v| v as f32);
+            Ok(RecordedSample {
+                at: DateTime::parse_from_rfc3339(timestamp)?.into(),
+                percent: number(&battery["percent"]).ok_or_else(|| eyre!("missing percent"))?,
+                state: match battery["state"].as_str() {
+                    Some("On Battery") => ChargeState::Discharging,
+                    Some("Charging") => ChargeState::Charging,
+                    Some("Full") => ChargeState::Full,
+                    Some("Not Charging") => ChargeState::NotCharging,
+                    _ => ChargeState::Unknown,
+                },
+                capacity_wh: number(&battery["capacity_wh"]).unwrap_or(0.0),
+                total_watts: number(&doc["power"]["total_watts"]).unwrap_or(0.0),
+                os_minutes_remaining: number(&battery["time_remaining_min"]),
+            })
+        })
+        .collect()
+}
+
+/// Mean absolute error of one estimator over a session.
+#[derive(Debug, Clone, Copy)]
+struct Score {
+    estimator: &'static str,
+    unit: &'static str,
+    points: usize,
+    mean_abs_error: f32,
+}
+
+#[derive(Debug, Default)]
+struct ErrorSum {
+    total: f32,
+    points: usize,
+}
+
+impl ErrorSum {
+    fn add(&mut self, error: f32) {
+        self.total += error.abs();
+        self.points += 1;
+    }
+
+    fn score(&self, estimator: &'static str, unit: &'static str) -> Score {
+        Score {
+            estimator,
+            unit,
+            points: self.points,
+            mean_abs_error: if self.points == 0 {
+                f32::NAN
+            } else {
+                self.total / self.points as f32
+            },
+        }
+    }
+}
+
+/// Scores every estimator against the outcome of `session`.
+///
+/// Sessions rarely run down to 0%, so time-to-empty estimates are scaled to
+/// the time to reach the session's final charge, assuming the estimate's
+/// drain is linear in charge.
+fn score_session(session: &[RecordedSample]) -> Vec<Score> {
+    let Some(end) = session
+        .iter()
+        .rev()
+        .find(|s| s.state == ChargeState::Discharging)
+    else {
+        return Vec::new();
+    };
+
+    let mut tracker = DrainTracker::default();
+    let mut history = HistoryData::with_metric(HistoryMetric::Battery);
+    let mut forecast = ForecastData::new();
+    let mut os = ErrorSum::default();
+    let mut session_forecast = ErrorSum::default();
+    let mut drain = ErrorSum::default();
+    let mut last_until = ErrorSum::default();
+
+    for sample in session.iter().filter(|s| s.at < end.at) {
+        tracker.record(sample.at, sample.state, Some(sample.total_watts));
+        history.record_at(
+            sample.at,
+            sample.percent,
+            sample.total_watts,
+            0.0,
+            0.0,
+            None,
+        );
+        if sample.state != ChargeState::Discharging || sample.percent <= end.percent {
+            continue;
+        }
+
+        let actual_minutes = end
+            .at
+            .duration_since(sample.at)
+            .unwrap_or_default()
+            .as_secs_f32()
+            / 60.0;
+        let to_final = (sample.percent - end.percent) / sample.percent;
+
+        if let Some(minutes) = sample.os_minutes_remaining {
+            os.add(minutes * to_final - actual_minutes);
+        }
+
+        let points: Vec<DataPoint> = history.points.iter().copied().collect();
+        if forecast.calculate_from_session_data(&points, sample.percent, sample.capacity_wh) {
+            if let Some(duration) = forecast.duration() {
+                session_forecast.add(duration.as_secs_f32() / 60.0 * to_final - actual_minutes);
+            }
+        }
+
+        let Some(estimate) = tracker.estimate(
+            sample.at,
+            end.at.max(sample.at + Duration::from_secs(1)),
+            sample.percent,
+            sample.capacity_wh,
+        ) else {
+            continue;
+        };
+        if estimate.drain_watts > 0.0 {
+            let remaining_wh = (sample.percent - end.percent) / 100.0 * sample.capacity_wh;
+            drain.add(remaining_wh / estimate.drain_watts * 60.0 - actual_minutes);
+        }
+        last_until.add(estimate.expected_percent - end.percent);
+    }
+
+    vec![
+        os.score("platform time-to-empty", "min"),
+        session_forecast.score("forecast time-to-empty", "min"),
+        drain.score("drain-rate time-to-empty", "min"),
+        last_until.score("will-last-until charge", "%"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Loose bounds: these catch an estimator breaking, not small drift.
+    const MAX_TIME_TO_EMPTY_MAE_MINUTES: f32 = 30.0;
+    const MAX_LAST_UNTIL_MAE_PERCENT: f32 = 10.0;
+
+    #[test]
+    fn estimators_stay_within_bounds_on_recorded_sessions() {
+        println!(
+            "{:<16} {:<26} {:>6} {:>10}",
+            "session", "estimator", "points", "MAE"
+        );
+        for (name, jsonl) in SESSIONS {
+            let session = parse_session(jsonl).unwrap();
+            assert!(session.len() > 60, "{} is too short to score", name);
+
+            let scores = score_session(&session);
+            for score in &scores {
+                println!(
+                    "{:<16} {:<26} {:>6} {:>7.1} {}",
+                    name, score.estimator, score.points, score.mean_abs_error, score.unit
+                );
+                assert!(
+                    score.points > 0,
+                    "{}: no {} estimates",
+                    name,
+                    score.estimator
+                );
+            }
+
+            for score in &scores[1..3] {
+                assert!(
+                    score.mean_abs_error < MAX_TIME_TO_EMPTY_MAE_MINUTES,
+                    "{}: {:?}",
+                    name,
+                    score
+                );
+            }
+            assert!(
+                scores[3].mean_abs_error < MAX_LAST_UNTIL_MAE_PERCENT,
+                "{}: {:?}",
+                name,
+                scores[3]
+            );
+        }
+    }
+
+    #[test]
+    fn replay_follows_the_recorded_clock() {
+        let session = parse_session(SESSIONS[0].1).unwrap();
+        let span = session
+            .last()
+            .unwrap()
+            .at
+            .duration_since(session[0].at)
+            .unwrap();
+        assert_eq!(span, Duration::from_secs(260 * 60));
+        assert!(session.iter().all(|s| s.state == ChargeState::Discharging));
+
+        // Nothing discharging means nothing to score.
+        let charging: Vec<_> = session
+            .iter()
+            .map(|s| RecordedSample {
+                state: ChargeState::Charging,
+                ..*s
+            })
+            .collect();
+        assert!(score_session(&charging).is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+//! Snapshots polled from other jolt instances.
+//!
+//! Each peer runs `jolt serve` and is polled over HTTP for its `/snapshot`
+//! document. Polling happens on a background thread so a slow or unreachable
+//! machine never holds up local sampling; failures are recorded per peer and
+//! the last good snapshot is kept, with its age, until the peer recovers.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::daemon::{BatterySnapshot, PowerSnapshot, ProcessSnapshot};
+
+/// How long a peer request may take before it counts as failed.
+const PEER_TIMEOUT: Duration = Duration::from_secs(3);
+/// A snapshot older than this many poll intervals is shown as stale.
+const STALE_INTERVALS: u32 = 3;
+
+/// What one machine reports at `/snapshot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostSnapshot {
+    pub hostname: String,
+    pub timestamp: i64,
+    pub battery: BatterySnapshot,
+    pub power: PowerSnapshot,
+    #[serde(default)]
+    pub processes: Vec<ProcessSnapshot>,
+}
+
+impl HostSnapshot {
+    pub fn local_hostname() -> String {
+        sysinfo::System::host_name().unwrap_or_else(|| "localhost".to_string())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PeerStatus {
+    /// `host:port` as given on the command line or in the config.
+    pub address: String,
+    pub snapshot: Option<HostSnapshot>,
+    pub last_success: Option<Instant>,
+    /// Error from the most recent poll, cleared on success.
+    pub last_error: Option<String>,
+}
+
+impl PeerStatus {
+    fn new(address: String) -> Self {
+        Self {
+            address,
+            snapshot: None,
+            last_success: None,
+            last_error: None,
+        }
+    }
+
+    /// Time since the snapshot was fetched.
+    pub fn age(&self) -> Option<Duration> {
+        self.last_success.map(|t| t.elapsed())
+    }
+
+    /// Display name: the peer's reported hostname, or its address until the
+    /// first successful poll.
+    pub fn name(&self) -> &str {
+        self.snapshot
+            .as_ref()
+            .map_or(self.address.as_str(), |s| s.hostname.as_str())
+    }
+}
+
+/// Shared view of every peer, updated by a polling thread.
+#[derive(Clone)]
+pub struct Fleet {
+    peers: Arc<Mutex<Vec<PeerStatus>>>,
+    interval: Duration,
+}
+
+impl Fleet {
+    /// Starts polling `addresses` every `interval`.
+    pub fn spawn(addresses: Vec<String>, interval: Duration) -> Self {
+        let peers = Arc::new(Mutex::new(
+            addresses.iter().cloned().map(PeerStatus::new).collect(),
+        ));
+        let fleet = Self {
+            peers: Arc::clone(&peers),
+            interval,
+        };
+
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(PEER_TIMEOUT))
+            .build()
+            .into();
+
+        thread::spawn(move || loop {
+            for (i, address) in addresses.iter().enumerate() {
+                let result = fetch_snapshot(&agent, address);
+                let Ok(mut peers) = peers.lock() else {
+                    return;
+                };
+                let peer = &mut peers[i];
+                match result {
+                    Ok(snapshot) => {
+                        peer.snapshot = Some(snapshot);
+                        peer.last_success = Some(Instant::now());
+                        peer.last_error = None;
+                    }
+                    Err(e) => {
+                        debug!(peer = %address, error = %e, "Peer poll failed");
+                        peer.last_error = Some(format!("{:#}", e));
+                    }
+                }
+            }
+            thread::sleep(interval);
+        });
+
+        fleet
+    }
+
+    pub fn peers(&self) -> Vec<PeerStatus> {
+        self.peers.lock().map(|p| p.clone()).unwrap_or_default()
+    }
+
+    /// Whether a peer's snapshot is too old to be trusted.
+    pub fn is_stale(&self, peer: &PeerStatus) -> bool {
+        peer.age()
+            .is_none_or(|age| age > self.interval * STALE_INTERVALS)
+    }
+}
+
+fn fetch_snapshot(agent: &ureq::Agent, address: &str) -> Result<HostSnapshot> {
+    let url = format!("http://{}/snapshot", address);
+    let body = agent
+        .get(&url)
+        .call()
+        .wrap_err("Request failed")?
+        .into_body()
+        .read_to_string()
+        .wrap_err("Failed to read response")?;
+    serde_json::from_str(&body).wrap_err("Invalid snapshot")
+}
@@ -361,9 +361,15 @@ mod tests {
 
         let points: Vec<_> = (0..10)
             .map(|i| DataPoint {
+                timestamp: std::time::SystemTime::UNIX_EPOCH,
                 battery_percent: 80.0 - i as f32,
                 power_watts: 10.0 + (i % 3) as f32,
+                cpu_watts: 0.0,
+                gpu_watts: 0.0,
+                soc: Default::default(),
                 temperature_c: None,
+                memory: Default::default(),
+                gpu_top_state_percent: None,
             })
             .collect();
 
@@ -383,9 +389,15 @@ mod tests {
         let mut forecast = ForecastData::new();
 
         let points = vec![DataPoint {
+            timestamp: std::time::SystemTime::UNIX_EPOCH,
             battery_percent: 80.0,
             power_watts: 10.0,
+            cpu_watts: 0.0,
+            gpu_watts: 0.0,
+            soc: Default::default(),
             temperature_c: None,
+            memory: Default::default(),
+            gpu_top_state_percent: None,
         }];
 
         let result = forecast.calculate_from_session_data(&points, 50.0, 100.0);
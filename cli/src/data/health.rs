@@ -0,0 +1,86 @@
+//! One-line system health summary for logging.
+//!
+//! Collects the readings that explain a machine running hot or draining
+//! fast, so a single log line can be compared across runs.
+
+use std::fmt;
+
+use super::power::ThermalPressure;
+use super::{BatteryData, PowerData, ProcessData};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthReport {
+    /// `None` on machines without a battery.
+    pub battery_health_percent: Option<f32>,
+    pub thermal_pressure: ThermalPressure,
+    pub zombie_process_count: u32,
+    pub peak_power_watts: f32,
+    /// Share of physical memory in use, from 0 to 1.
+    pub memory_pressure: f32,
+}
+
+pub fn health_report(
+    power: &PowerData,
+    battery: &BatteryData,
+    processes: &ProcessData,
+) -> HealthReport {
+    HealthReport {
+        battery_health_percent: (battery.design_capacity_wh() > 0.0)
+            .then(|| battery.health_percent()),
+        thermal_pressure: power.thermal_pressure(),
+        zombie_process_count: processes.zombie_count(),
+        peak_power_watts: power.peak_total_power_watts(),
+        memory_pressure: processes.memory_pressure(),
+    }
+}
+
+impl fmt::Display for HealthReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.battery_health_percent {
+            Some(health) => write!(f, "battery health {:.0}%", health)?,
+            None => write!(f, "no battery")?,
+        }
+        write!(
+            f,
+            ", thermal pressure {}, peak power {:.1} W, memory {:.0}% used, {} zombie process{}",
+            self.thermal_pressure.label().to_lowercase(),
+            self.peak_power_watts,
+            self.memory_pressure * 100.0,
+            self.zombie_process_count,
+            if self.zombie_process_count == 1 {
+                ""
+            } else {
+                "es"
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_is_one_readable_line() {
+        let report = HealthReport {
+            battery_health_percent: Some(91.6),
+            thermal_pressure: ThermalPressure::Moderate,
+            zombie_process_count: 1,
+            peak_power_watts: 42.25,
+            memory_pressure: 0.634,
+        };
+        assert_eq!(
+            report.to_string(),
+            "battery health 92%, thermal pressure moderate, peak power 42.2 W, \
+             memory 63% used, 1 zombie process"
+        );
+
+        let desktop = HealthReport {
+            battery_health_percent: None,
+            zombie_process_count: 0,
+            ..report
+        };
+        assert!(desktop.to_string().starts_with("no battery, "));
+        assert!(desktop.to_string().ends_with("0 zombie processes"));
+    }
+}
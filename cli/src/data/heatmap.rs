@@ -0,0 +1,108 @@
+//! Average power by weekday and hour of day, built from persisted hourly
+//! stats.
+
+use chrono::{DateTime, Datelike, TimeZone, Timelike};
+
+use super::HourlyStat;
+
+pub const WEEKDAYS: usize = 7;
+pub const HOURS: usize = 24;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Cell {
+    weighted_power: f64,
+    samples: u64,
+}
+
+/// A 7×24 grid of average power, Monday first, in the local time zone used
+/// to build it.
+#[derive(Debug, Clone, Default)]
+pub struct PowerHeatmap {
+    cells: [[Cell; HOURS]; WEEKDAYS],
+}
+
+impl PowerHeatmap {
+    /// Buckets `stats` by the weekday and hour their `hour_start` falls on in
+    /// `tz`. Each hour is weighted by its sample count, so a partially
+    /// recorded hour counts less than a full one.
+    pub fn from_hourly_stats<Tz: TimeZone>(stats: &[HourlyStat], tz: &Tz) -> Self {
+        let mut heatmap = Self::default();
+
+        for stat in stats.iter().filter(|s| s.total_samples > 0) {
+            let Some(utc) = DateTime::from_timestamp(stat.hour_start, 0) else {
+                continue;
+            };
+            let local = utc.with_timezone(tz);
+            let day = local.weekday().num_days_from_monday() as usize;
+            let cell = &mut heatmap.cells[day][local.hour() as usize];
+            cell.weighted_power += stat.avg_power as f64 * stat.total_samples as f64;
+            cell.samples += stat.total_samples as u64;
+        }
+
+        heatmap
+    }
+
+    /// Average power for `weekday` (0 = Monday) at `hour`, or `None` when no
+    /// samples fell into that cell.
+    pub fn average(&self, weekday: usize, hour: usize) -> Option<f32> {
+        let cell = self.cells.get(weekday)?.get(hour)?;
+        (cell.samples > 0).then(|| (cell.weighted_power / cell.samples as f64) as f32)
+    }
+
+    /// Highest cell average, used to scale the color ramp.
+    pub fn max_average(&self) -> Option<f32> {
+        (0..WEEKDAYS)
+            .flat_map(|day| (0..HOURS).map(move |hour| (day, hour)))
+            .filter_map(|(day, hour)| self.average(day, hour))
+            .reduce(f32::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn stat(hour_start: i64, avg_power: f32, total_samples: i32) -> HourlyStat {
+        HourlyStat {
+            id: None,
+            hour_start,
+            avg_power,
+            max_power: avg_power,
+            min_power: avg_power,
+            avg_battery: 50.0,
+            battery_delta: 0.0,
+            total_samples,
+        }
+    }
+
+    // Tuesday 2024-01-02 10:00 UTC
+    const TUESDAY_10_UTC: i64 = 1_704_189_600;
+    const WEEK: i64 = 7 * 24 * 3600;
+
+    #[test]
+    fn averages_same_slot_across_weeks_weighted_by_samples() {
+        let stats = [
+            stat(TUESDAY_10_UTC, 10.0, 60),
+            stat(TUESDAY_10_UTC + WEEK, 20.0, 20),
+            stat(TUESDAY_10_UTC + 3600, 4.0, 0),
+        ];
+        let heatmap = PowerHeatmap::from_hourly_stats(&stats, &FixedOffset::east_opt(0).unwrap());
+
+        assert_eq!(heatmap.average(1, 10), Some(12.5));
+        assert_eq!(heatmap.average(1, 11), None);
+        assert_eq!(heatmap.max_average(), Some(12.5));
+    }
+
+    #[test]
+    fn buckets_by_local_time() {
+        let stats = [stat(TUESDAY_10_UTC, 8.0, 60)];
+        // UTC-11: still Monday, 23:00
+        let heatmap =
+            PowerHeatmap::from_hourly_stats(&stats, &FixedOffset::west_opt(11 * 3600).unwrap());
+
+        assert_eq!(heatmap.average(0, 23), Some(8.0));
+        assert_eq!(heatmap.average(1, 10), None);
+        assert_eq!(PowerHeatmap::default().max_average(), None);
+    }
+}
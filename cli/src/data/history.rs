@@ -1,20 +1,165 @@
 use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::time::{Duration, SystemTime};
+
+use color_eyre::eyre::{bail, eyre, Result, WrapErr};
+use serde_json::{json, Map, Value};
+use tracing::warn;
+
+use crate::data::processes::linear_slope;
 
 const MAX_HISTORY_POINTS: usize = 120;
 
+/// Leading bytes of the binary history format.
+pub const HISTORY_MAGIC: [u8; 4] = *b"JLTH";
+pub const HISTORY_FORMAT_VERSION: u16 = 1;
+/// `f32` values per record after its timestamp: battery, total, CPU, GPU,
+/// ANE and DRAM power, and temperature.
+const HISTORY_METRIC_COUNT: u16 = 7;
+/// Magic, version, metric count and record count.
+const HISTORY_HEADER_LEN: usize = 4 + 2 + 2 + 4;
+const CSV_TIMESTAMP_COLUMN: &str = "timestamp";
+/// Version in the metadata line of [`HistoryData::export_json`] output.
+pub const HISTORY_JSON_SCHEMA_VERSION: u64 = 1;
+/// `type` of the metadata object that starts a JSON Lines export.
+const JSON_METADATA_TYPE: &str = "jolt_history";
+
+/// Sparkline glyphs, lowest first.
+pub const SPARKLINE_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// Glyph index used for every value when all are equal, so a flat reading
+/// is still visible.
+pub const SPARKLINE_FLAT_LEVEL: usize = 3;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HistoryMetric {
     Power,
     Battery,
     Split,
     Merged,
+    Domains,
+}
+
+/// A single readout tracked over time, independent of the graph metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistorySeries {
+    Battery,
+    CpuPower,
+    GpuPower,
+    AnePower,
+    DramPower,
+    TotalPower,
+    Temperature,
+    /// Share of physical memory in use, in percent.
+    MemoryUsed,
+    MemoryBandwidth,
+    /// Share of time the GPU spent in its highest performance state, in
+    /// percent.
+    GpuTopState,
+}
+
+impl HistorySeries {
+    pub const ALL: [HistorySeries; 10] = [
+        HistorySeries::Battery,
+        HistorySeries::TotalPower,
+        HistorySeries::CpuPower,
+        HistorySeries::GpuPower,
+        HistorySeries::AnePower,
+        HistorySeries::DramPower,
+        HistorySeries::Temperature,
+        HistorySeries::MemoryUsed,
+        HistorySeries::MemoryBandwidth,
+        HistorySeries::GpuTopState,
+    ];
+
+    /// Column header in CSV exports.
+    pub fn csv_column(self) -> &'static str {
+        match self {
+            HistorySeries::Battery => "battery_percent",
+            HistorySeries::TotalPower => "total_watts",
+            HistorySeries::CpuPower => "cpu_watts",
+            HistorySeries::GpuPower => "gpu_watts",
+            HistorySeries::AnePower => "ane_watts",
+            HistorySeries::DramPower => "dram_watts",
+            HistorySeries::Temperature => "temperature_c",
+            HistorySeries::MemoryUsed => "memory_used_percent",
+            HistorySeries::MemoryBandwidth => "memory_bandwidth_gbps",
+            HistorySeries::GpuTopState => "gpu_top_state_percent",
+        }
+    }
+
+    fn from_csv_column(column: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.csv_column() == column)
+    }
+}
+
+/// Power domains stacked in the breakdown graph, bottom first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerDomain {
+    Cpu,
+    Gpu,
+    Ane,
+    Dram,
+    /// Whatever total power is not accounted for by the other domains.
+    Other,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl PowerDomain {
+    pub const ALL: [PowerDomain; 5] = [
+        PowerDomain::Cpu,
+        PowerDomain::Gpu,
+        PowerDomain::Ane,
+        PowerDomain::Dram,
+        PowerDomain::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PowerDomain::Cpu => "CPU",
+            PowerDomain::Gpu => "GPU",
+            PowerDomain::Ane => "ANE",
+            PowerDomain::Dram => "DRAM",
+            PowerDomain::Other => "Other",
+        }
+    }
+}
+
+/// Power of SoC blocks that only some providers break out separately.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SocPower {
+    pub ane_watts: Option<f32>,
+    pub dram_watts: Option<f32>,
+}
+
+/// Memory readings that, like [`SocPower`], only local sampling provides.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MemorySample {
+    pub used_percent: Option<f32>,
+    pub bandwidth_gbps: Option<f32>,
+}
+
+/// One sample of the domain breakdown, with every domain at the same
+/// timestamp.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DomainStack {
+    /// Watts per domain in `PowerDomain::ALL` order; missing readings are zero.
+    pub watts: [f32; PowerDomain::ALL.len()],
+    pub total_watts: f32,
+    /// Set when any domain had no reading, so the stack understates it.
+    pub partial: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct DataPoint {
+    pub timestamp: SystemTime,
     pub battery_percent: f32,
     pub power_watts: f32,
+    pub cpu_watts: f32,
+    pub gpu_watts: f32,
+    pub soc: SocPower,
     pub temperature_c: Option<f32>,
+    pub memory: MemorySample,
+    /// Only local IOReport sampling provides it, like [`SocPower`].
+    pub gpu_top_state_percent: Option<f32>,
 }
 
 #[derive(Debug, Clone)]
@@ -40,13 +185,47 @@ impl HistoryData {
         }
     }
 
-    pub fn record(&mut self, battery_percent: f32, power_watts: f32, temperature_c: Option<f32>) {
-        let point = DataPoint {
+    pub fn record(
+        &mut self,
+        battery_percent: f32,
+        power_watts: f32,
+        cpu_watts: f32,
+        gpu_watts: f32,
+        temperature_c: Option<f32>,
+    ) {
+        self.record_at(
+            SystemTime::now(),
             battery_percent,
             power_watts,
+            cpu_watts,
+            gpu_watts,
             temperature_c,
-        };
+        );
+    }
+
+    pub fn record_at(
+        &mut self,
+        timestamp: SystemTime,
+        battery_percent: f32,
+        power_watts: f32,
+        cpu_watts: f32,
+        gpu_watts: f32,
+        temperature_c: Option<f32>,
+    ) {
+        self.push(DataPoint {
+            timestamp,
+            battery_percent,
+            power_watts,
+            cpu_watts,
+            gpu_watts,
+            soc: SocPower::default(),
+            temperature_c,
+            memory: MemorySample::default(),
+            gpu_top_state_percent: None,
+        });
+    }
 
+    fn push(&mut self, point: DataPoint) {
         if self.points.len() >= MAX_HISTORY_POINTS {
             self.points.pop_front();
             for change in &mut self.battery_changes {
@@ -60,15 +239,15 @@ impl HistoryData {
         let current_index = self.points.len();
 
         if let Some(last) = self.last_battery_percent {
-            let diff = (battery_percent - last).abs();
+            let diff = (point.battery_percent - last).abs();
             if diff >= 1.0 {
                 self.battery_changes.push(BatteryChange {
                     index: current_index,
-                    value: battery_percent,
+                    value: point.battery_percent,
                 });
             }
         }
-        self.last_battery_percent = Some(battery_percent);
+        self.last_battery_percent = Some(point.battery_percent);
 
         self.points.push_back(point);
     }
@@ -78,7 +257,8 @@ impl HistoryData {
             HistoryMetric::Power => HistoryMetric::Battery,
             HistoryMetric::Battery => HistoryMetric::Merged,
             HistoryMetric::Merged => HistoryMetric::Split,
-            HistoryMetric::Split => HistoryMetric::Power,
+            HistoryMetric::Split => HistoryMetric::Domains,
+            HistoryMetric::Domains => HistoryMetric::Power,
         };
     }
 
@@ -88,21 +268,15 @@ impl HistoryData {
             HistoryMetric::Power => "Power (W)",
             HistoryMetric::Split => "Split View",
             HistoryMetric::Merged => "Combined",
+            HistoryMetric::Domains => "Domains",
         }
     }
 
     pub fn current_values(&self) -> Vec<(f64, f64)> {
-        let values: Vec<f32> = match self.current_metric {
-            HistoryMetric::Battery => self.points.iter().map(|p| p.battery_percent).collect(),
-            HistoryMetric::Power | HistoryMetric::Split | HistoryMetric::Merged => {
-                self.points.iter().map(|p| p.power_watts).collect()
-            }
-        };
-
-        values
+        self.points
             .iter()
             .enumerate()
-            .map(|(i, &v)| (i as f64, v as f64))
+            .map(|(i, p)| (i as f64, p.value(self.current_metric)))
             .collect()
     }
 
@@ -125,7 +299,10 @@ impl HistoryData {
     pub fn value_range(&self) -> (f64, f64) {
         match self.current_metric {
             HistoryMetric::Battery => (0.0, 100.0),
-            HistoryMetric::Power | HistoryMetric::Split | HistoryMetric::Merged => {
+            HistoryMetric::Power
+            | HistoryMetric::Split
+            | HistoryMetric::Merged
+            | HistoryMetric::Domains => {
                 let max = self
                     .points
                     .iter()
@@ -174,4 +351,1173 @@ impl HistoryData {
     pub fn latest_temperature(&self) -> Option<f32> {
         self.points.back().and_then(|p| p.temperature_c)
     }
+
+    /// Copies the most recent values of `series` into `out`, newest last.
+    ///
+    /// Slots before the oldest available sample, and samples without a value
+    /// (temperature is optional), are left as `None`. Writes into a caller
+    /// buffer so per-frame readouts can use a stack array.
+    pub fn recent_into(&self, series: HistorySeries, out: &mut [Option<f32>]) {
+        out.fill(None);
+        let offset = out.len().saturating_sub(self.points.len());
+        let skip = self.points.len().saturating_sub(out.len());
+        for (slot, point) in out[offset..].iter_mut().zip(self.points.iter().skip(skip)) {
+            *slot = point.series_value(series);
+        }
+    }
+
+    /// Attaches SoC block readings to the most recent point.
+    ///
+    /// Kept separate from `record` because only local IOReport sampling
+    /// breaks these out; points recorded from daemon snapshots keep `None`.
+    pub fn set_latest_soc_power(&mut self, soc: SocPower) {
+        if let Some(point) = self.points.back_mut() {
+            point.soc = soc;
+        }
+    }
+
+    /// Attaches memory readings to the most recent point, like
+    /// [`Self::set_latest_soc_power`].
+    pub fn set_latest_memory(&mut self, memory: MemorySample) {
+        if let Some(point) = self.points.back_mut() {
+            point.memory = memory;
+        }
+    }
+
+    /// Attaches the GPU's top performance state share to the most recent
+    /// point, like [`Self::set_latest_soc_power`].
+    pub fn set_latest_gpu_top_state(&mut self, percent: Option<f32>) {
+        if let Some(point) = self.points.back_mut() {
+            point.gpu_top_state_percent = percent;
+        }
+    }
+
+    /// The most recent `slots` samples split into stacked power domains,
+    /// newest last.
+    ///
+    /// Every domain is read through `recent_into`, so all layers share the
+    /// same timestamps. Slots before the oldest sample are `None`. A missing
+    /// domain reading counts as zero and marks the sample `partial`; "Other"
+    /// is the rest of total power, never negative.
+    pub fn domain_stacks(&self, slots: usize) -> Vec<Option<DomainStack>> {
+        let series = [
+            HistorySeries::CpuPower,
+            HistorySeries::GpuPower,
+            HistorySeries::AnePower,
+            HistorySeries::DramPower,
+        ];
+        let mut columns = vec![vec![None; slots]; series.len()];
+        for (column, series) in columns.iter_mut().zip(series) {
+            self.recent_into(series, column);
+        }
+        let mut totals = vec![None; slots];
+        self.recent_into(HistorySeries::TotalPower, &mut totals);
+
+        totals
+            .iter()
+            .enumerate()
+            .map(|(slot, total)| {
+                let total_watts = (*total)?;
+                let mut watts = [0.0; PowerDomain::ALL.len()];
+                let mut partial = false;
+                for (domain, column) in columns.iter().enumerate() {
+                    match column[slot] {
+                        Some(value) => watts[domain] = value.max(0.0),
+                        None => partial = true,
+                    }
+                }
+                let known: f32 = watts.iter().sum();
+                watts[PowerDomain::ALL.len() - 1] = (total_watts - known).max(0.0);
+                Some(DomainStack {
+                    watts,
+                    total_watts,
+                    partial,
+                })
+            })
+            .collect()
+    }
+
+    /// Duration of the longest contiguous run of samples whose value for `metric`
+    /// is above `threshold`, measured from the first to the last sample of the run.
+    ///
+    /// Returns `None` if no sample exceeded the threshold.
+    pub fn longest_above_threshold(
+        &self,
+        metric: HistoryMetric,
+        threshold: f64,
+    ) -> Option<Duration> {
+        let mut longest: Option<Duration> = None;
+        let mut run_start: Option<SystemTime> = None;
+
+        for point in &self.points {
+            if point.value(metric) > threshold {
+                let start = *run_start.get_or_insert(point.timestamp);
+                let run = point.timestamp.duration_since(start).unwrap_or_default();
+                longest = Some(longest.map_or(run, |l| l.max(run)));
+            } else {
+                run_start = None;
+            }
+        }
+
+        longest
+    }
+
+    /// Every span where `metric` was above `threshold`, oldest first, as its
+    /// start time and the time from its first to its last sample above.
+    ///
+    /// Spikes separated by fewer than two samples at or below the threshold
+    /// are merged into one span. Returns an empty `Vec` if no sample exceeded
+    /// the threshold.
+    pub fn peak_duration_above(
+        &self,
+        metric: HistoryMetric,
+        threshold: f64,
+    ) -> Vec<(SystemTime, Duration)> {
+        let mut spans = Vec::new();
+        // Start and latest above-threshold sample of the current span.
+        let mut current: Option<(SystemTime, SystemTime)> = None;
+        let mut samples_below = 0;
+
+        for point in &self.points {
+            if point.value(metric) > threshold {
+                match current.as_mut() {
+                    Some((_, end)) if samples_below < 2 => *end = point.timestamp,
+                    _ => {
+                        spans.extend(current);
+                        current = Some((point.timestamp, point.timestamp));
+                    }
+                }
+                samples_below = 0;
+            } else {
+                samples_below += 1;
+            }
+        }
+        spans.extend(current);
+
+        spans
+            .into_iter()
+            .map(|(start, end)| (start, end.duration_since(start).unwrap_or_default()))
+            .collect()
+    }
+
+    /// Total time `metric` spent within `[low, high]`.
+    ///
+    /// Values are assumed to change linearly between consecutive samples, so
+    /// an interval that enters, leaves or passes through the range counts
+    /// only the part spent inside it.
+    pub fn time_in_range(&self, metric: HistoryMetric, low: f64, high: f64) -> Duration {
+        if low > high {
+            return Duration::ZERO;
+        }
+
+        self.points
+            .iter()
+            .zip(self.points.iter().skip(1))
+            .map(|(a, b)| {
+                let elapsed = b.timestamp.duration_since(a.timestamp).unwrap_or_default();
+                elapsed.mul_f64(fraction_in_range(
+                    a.value(metric),
+                    b.value(metric),
+                    low,
+                    high,
+                ))
+            })
+            .sum()
+    }
+
+    /// Maximum of `metric` over every window of `window` consecutive samples,
+    /// oldest window first.
+    ///
+    /// Returns `points.len() - window + 1` values, or none if `window` is zero
+    /// or longer than the history.
+    pub fn sliding_max(&self, metric: HistoryMetric, window: usize) -> Vec<f64> {
+        if window == 0 || window > self.points.len() {
+            return Vec::new();
+        }
+
+        let values: Vec<f64> = self.points.iter().map(|p| p.value(metric)).collect();
+        let mut result = Vec::with_capacity(values.len() - window + 1);
+        // Indices whose values are strictly decreasing; the front is the
+        // maximum of the current window.
+        let mut candidates: VecDeque<usize> = VecDeque::with_capacity(window);
+
+        for (i, &value) in values.iter().enumerate() {
+            while candidates.back().is_some_and(|&j| values[j] <= value) {
+                candidates.pop_back();
+            }
+            candidates.push_back(i);
+
+            if candidates.front().is_some_and(|&j| j + window <= i) {
+                candidates.pop_front();
+            }
+
+            if i + 1 >= window {
+                result.push(values[candidates[0]]);
+            }
+        }
+
+        result
+    }
+
+    /// Calls `f` with every run of `window_size` consecutive values of
+    /// `metric`, oldest first. With `partial`, the leading edge comes first
+    /// as shorter runs growing from the oldest value, so every sample ends
+    /// exactly one call.
+    ///
+    /// The values are copied into one buffer that every call borrows from,
+    /// so rolling statistics need no allocation per window. No calls are
+    /// made when `window_size` is zero, nor without `partial` when it is
+    /// longer than the history.
+    pub fn for_each_window<F>(
+        &self,
+        metric: HistoryMetric,
+        window_size: usize,
+        partial: bool,
+        mut f: F,
+    ) where
+        F: FnMut(&[f64]),
+    {
+        if window_size == 0 {
+            return;
+        }
+
+        let values: Vec<f64> = self.points.iter().map(|p| p.value(metric)).collect();
+        if partial {
+            for end in 1..window_size.min(values.len() + 1) {
+                f(&values[..end]);
+            }
+        }
+        values.windows(window_size).for_each(f);
+    }
+
+    /// Exponentially weighted moving average of `metric` over the stored
+    /// samples, oldest first, seeded with the oldest value.
+    ///
+    /// Each sample moves the average `alpha` of the way towards its value.
+    /// Returns `None` for an empty history or an `alpha` outside `(0, 1]`.
+    pub fn ewma(&self, metric: HistoryMetric, alpha: f64) -> Option<f64> {
+        if !(alpha > 0.0 && alpha <= 1.0) {
+            return None;
+        }
+
+        let mut values = self.points.iter().map(|p| p.value(metric));
+        let first = values.next()?;
+        Some(values.fold(first, |average, value| average + alpha * (value - average)))
+    }
+
+    /// Least-squares trend of `metric` across the stored samples, in units
+    /// per second. `None` until two samples with distinct timestamps exist.
+    pub fn rate_of_change(&self, metric: HistoryMetric) -> Option<f64> {
+        let first = self.points.front()?.timestamp;
+        linear_slope(self.points.iter().map(|p| {
+            let secs = p.timestamp.duration_since(first).unwrap_or_default();
+            (secs.as_secs_f64(), p.value(metric))
+        }))
+    }
+
+    /// `metric` `horizon` from the newest sample, continuing
+    /// [`Self::rate_of_change`] in a straight line from the newest value.
+    ///
+    /// A linear extrapolation only: it knows nothing of limits such as a
+    /// full battery or of load changing, so it is a rough guide for short
+    /// horizons. `None` with fewer than 3 samples.
+    pub fn prediction(&self, metric: HistoryMetric, horizon: Duration) -> Option<f64> {
+        if self.points.len() < 3 {
+            return None;
+        }
+        let last = self.points.back()?.value(metric);
+        Some(last + self.rate_of_change(metric)? * horizon.as_secs_f64())
+    }
+
+    /// The last `width` samples of `metric` as a line of block characters,
+    /// one per sample, scaled so the lowest reads `▁` and the highest `█`.
+    /// Shorter than `width` while the history holds fewer samples.
+    pub fn to_sparkline(&self, metric: HistoryMetric, width: usize) -> String {
+        let skip = self.points.len().saturating_sub(width);
+        let values: Vec<f64> = self
+            .points
+            .iter()
+            .skip(skip)
+            .map(|p| p.value(metric))
+            .collect();
+        let (min, max) = values
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+                (min.min(v), max.max(v))
+            });
+
+        let top = (SPARKLINE_LEVELS.len() - 1) as f64;
+        values
+            .iter()
+            .map(|&v| {
+                let level = if max - min <= f64::EPSILON {
+                    SPARKLINE_FLAT_LEVEL
+                } else {
+                    ((v - min) / (max - min) * top).round() as usize
+                };
+                SPARKLINE_LEVELS[level.min(SPARKLINE_LEVELS.len() - 1)]
+            })
+            .collect()
+    }
+}
+
+/// Compact binary encoding, for monitors sampling too often for JSON or CSV.
+///
+/// All fields are little-endian: [`HISTORY_MAGIC`], the format version
+/// (`u16`), the number of metrics per record (`u16`) and the record count
+/// (`u32`), followed by fixed-size records of the timestamp in Unix
+/// milliseconds (`i64`) and one `f32` per metric. Missing readings are NaN.
+/// Records are fixed-size so a memory-mapped file can be indexed directly.
+impl HistoryData {
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            HISTORY_HEADER_LEN + self.points.len() * record_len(HISTORY_METRIC_COUNT),
+        );
+        bytes.extend_from_slice(&HISTORY_MAGIC);
+        bytes.extend_from_slice(&HISTORY_FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&HISTORY_METRIC_COUNT.to_le_bytes());
+        bytes.extend_from_slice(&(self.points.len() as u32).to_le_bytes());
+
+        for point in &self.points {
+            let millis = point
+                .timestamp
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as i64);
+            bytes.extend_from_slice(&millis.to_le_bytes());
+            for value in [
+                Some(point.battery_percent),
+                Some(point.power_watts),
+                Some(point.cpu_watts),
+                Some(point.gpu_watts),
+                point.soc.ane_watts,
+                point.soc.dram_watts,
+                point.temperature_c,
+            ] {
+                bytes.extend_from_slice(&value.unwrap_or(f32::NAN).to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Decodes [`Self::as_bytes`] output, showing the power metric.
+    ///
+    /// Metrics beyond the ones this version knows are skipped. Like
+    /// recording, only the newest points up to the history length are kept.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        if data.len() < HISTORY_HEADER_LEN || data[..4] != HISTORY_MAGIC {
+            bail!("not a jolt history file");
+        }
+        let version = u16::from_le_bytes([data[4], data[5]]);
+        if version != HISTORY_FORMAT_VERSION {
+            bail!("unsupported history format version {}", version);
+        }
+        let metric_count = u16::from_le_bytes([data[6], data[7]]);
+        if metric_count < HISTORY_METRIC_COUNT {
+            bail!(
+                "history records have {} metrics, expected at least {}",
+                metric_count,
+                HISTORY_METRIC_COUNT
+            );
+        }
+        let record_count = u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize;
+        let records = &data[HISTORY_HEADER_LEN..];
+        let record_len = record_len(metric_count);
+        if records.len() != record_count * record_len {
+            bail!(
+                "history data has {} bytes of records, expected {} for {} records",
+                records.len(),
+                record_count * record_len,
+                record_count
+            );
+        }
+
+        let mut history = Self::with_metric(HistoryMetric::Power);
+        for record in records.chunks_exact(record_len) {
+            let millis = i64::from_le_bytes(record[..8].try_into()?);
+            let value = |i: usize| {
+                let at = 8 + i * 4;
+                Some(f32::from_le_bytes(record[at..at + 4].try_into().ok()?))
+                    .filter(|v| !v.is_nan())
+            };
+            history.push(DataPoint {
+                timestamp: SystemTime::UNIX_EPOCH + Duration::from_millis(millis.max(0) as u64),
+                battery_percent: value(0).unwrap_or(0.0),
+                power_watts: value(1).unwrap_or(0.0),
+                cpu_watts: value(2).unwrap_or(0.0),
+                gpu_watts: value(3).unwrap_or(0.0),
+                soc: SocPower {
+                    ane_watts: value(4),
+                    dram_watts: value(5),
+                },
+                temperature_c: value(6),
+                memory: MemorySample::default(),
+                gpu_top_state_percent: None,
+            });
+        }
+        Ok(history)
+    }
+}
+
+/// CSV with a `timestamp` column in Unix milliseconds and one column per
+/// [`HistorySeries`], named by [`HistorySeries::csv_column`]. Missing
+/// readings are empty fields.
+impl HistoryData {
+    pub fn export_csv<W: Write>(&self, mut writer: W) -> Result<()> {
+        let columns: Vec<&str> = HistorySeries::ALL.iter().map(|s| s.csv_column()).collect();
+        writeln!(writer, "{},{}", CSV_TIMESTAMP_COLUMN, columns.join(","))?;
+
+        for point in &self.points {
+            let millis = point
+                .timestamp
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as i64);
+            let values: Vec<String> = HistorySeries::ALL
+                .iter()
+                .map(|&s| {
+                    point
+                        .series_value(s)
+                        .map_or(String::new(), |v| v.to_string())
+                })
+                .collect();
+            writeln!(writer, "{},{}", millis, values.join(","))?;
+        }
+        Ok(())
+    }
+
+    /// Parses [`Self::export_csv`] output, showing the power metric.
+    ///
+    /// Columns may come in any order and any series may be left out; a
+    /// missing battery or power column reads as zero, like a missing value
+    /// in [`Self::from_bytes`]. Only the newest points up to the history
+    /// length are kept.
+    pub fn from_csv<R: Read>(mut reader: R) -> Result<Self> {
+        let mut content = String::new();
+        reader
+            .read_to_string(&mut content)
+            .wrap_err("failed to read history CSV")?;
+        let mut lines = content.lines().enumerate();
+
+        let (_, header) = lines
+            .next()
+            .ok_or_else(|| eyre!("history CSV is empty, expected a header row"))?;
+        let mut timestamp_column = None;
+        let mut series_columns = Vec::new();
+        for (i, column) in header.split(',').map(str::trim).enumerate() {
+            if column == CSV_TIMESTAMP_COLUMN {
+                if timestamp_column.replace(i).is_some() {
+                    bail!("history CSV has more than one `timestamp` column");
+                }
+                continue;
+            }
+            let series = HistorySeries::from_csv_column(column)
+                .ok_or_else(|| eyre!("history CSV column `{}` is not a known series", column))?;
+            if series_columns.iter().any(|&(_, s)| s == series) {
+                bail!("history CSV has more than one `{}` column", column);
+            }
+            series_columns.push((i, series));
+        }
+        let timestamp_column =
+            timestamp_column.ok_or_else(|| eyre!("history CSV has no `timestamp` column"))?;
+        let column_count = series_columns.len() + 1;
+
+        let mut history = Self::with_metric(HistoryMetric::Power);
+        for (index, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line_number = index + 1;
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() != column_count {
+                bail!(
+                    "history CSV line {} has {} fields, expected {}",
+                    line_number,
+                    fields.len(),
+                    column_count
+                );
+            }
+
+            let millis: i64 = fields[timestamp_column].parse().map_err(|_| {
+                eyre!(
+                    "history CSV line {}: timestamp `{}` is not Unix milliseconds",
+                    line_number,
+                    fields[timestamp_column]
+                )
+            })?;
+            let mut point = DataPoint {
+                timestamp: SystemTime::UNIX_EPOCH + Duration::from_millis(millis.max(0) as u64),
+                battery_percent: 0.0,
+                power_watts: 0.0,
+                cpu_watts: 0.0,
+                gpu_watts: 0.0,
+                soc: SocPower::default(),
+                temperature_c: None,
+                memory: MemorySample::default(),
+                gpu_top_state_percent: None,
+            };
+            for &(column, series) in &series_columns {
+                let field = fields[column];
+                let value = if field.is_empty() {
+                    None
+                } else {
+                    Some(field.parse::<f32>().map_err(|_| {
+                        eyre!(
+                            "history CSV line {}: `{}` value `{}` is not a number",
+                            line_number,
+                            series.csv_column(),
+                            field
+                        )
+                    })?)
+                };
+                point.set_series_value(series, value);
+            }
+            history.push(point);
+        }
+        Ok(history)
+    }
+}
+
+/// JSON Lines: a metadata object first, with `type` and `schema_version`,
+/// then one object per point holding `timestamp` in Unix milliseconds and
+/// each series by its [`HistorySeries::csv_column`] name. Missing readings
+/// are left out of the object.
+impl HistoryData {
+    pub fn export_json<W: Write>(&self, mut writer: W) -> Result<()> {
+        let series: Vec<&str> = HistorySeries::ALL.iter().map(|s| s.csv_column()).collect();
+        let metadata = json!({
+            "type": JSON_METADATA_TYPE,
+            "schema_version": HISTORY_JSON_SCHEMA_VERSION,
+            "series": series,
+        });
+        writeln!(writer, "{}", metadata)?;
+
+        for point in &self.points {
+            let millis = point
+                .timestamp
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as i64);
+            let mut record = Map::new();
+            record.insert(CSV_TIMESTAMP_COLUMN.to_string(), millis.into());
+            for series in HistorySeries::ALL {
+                if let Some(value) = point.series_value(series) {
+                    record.insert(series.csv_column().to_string(), json_number(value));
+                }
+            }
+            writeln!(writer, "{}", Value::Object(record))?;
+        }
+        Ok(())
+    }
+
+    /// Parses [`Self::export_json`] output, showing the power metric.
+    ///
+    /// Fails unless the metadata line carries this version's schema. A last
+    /// line that was cut off mid-record, as when the export was interrupted,
+    /// is dropped with a warning; a malformed line anywhere else is an
+    /// error. Unknown keys are ignored. Only the newest points up to the
+    /// history length are kept.
+    pub fn from_json<R: Read>(mut reader: R) -> Result<Self> {
+        let mut content = String::new();
+        reader
+            .read_to_string(&mut content)
+            .wrap_err("failed to read history JSON")?;
+        let complete = content.ends_with('\n');
+        let lines: Vec<(usize, &str)> = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .collect();
+        let last_index = lines.last().map(|&(index, _)| index);
+
+        let mut lines = lines.into_iter();
+        let (_, metadata) = lines
+            .next()
+            .ok_or_else(|| eyre!("history JSON is empty, expected a metadata line"))?;
+        let metadata: Value = serde_json::from_str(metadata)
+            .map_err(|e| eyre!("history JSON metadata line is not valid JSON: {}", e))?;
+        if metadata.get("type").and_then(Value::as_str) != Some(JSON_METADATA_TYPE) {
+            bail!("history JSON does not start with a jolt history metadata line");
+        }
+        match metadata.get("schema_version").and_then(Value::as_u64) {
+            Some(HISTORY_JSON_SCHEMA_VERSION) => {}
+            Some(version) => bail!(
+                "unsupported history JSON schema version {}, expected {}",
+                version,
+                HISTORY_JSON_SCHEMA_VERSION
+            ),
+            None => bail!("history JSON metadata has no schema_version"),
+        }
+
+        let mut history = Self::with_metric(HistoryMetric::Power);
+        for (index, line) in lines {
+            let line_number = index + 1;
+            let record = match serde_json::from_str::<Map<String, Value>>(line) {
+                Ok(record) => record,
+                Err(e) if !complete && Some(index) == last_index && e.is_eof() => {
+                    warn!(
+                        line_number,
+                        "Ignoring incomplete last record in history JSON"
+                    );
+                    break;
+                }
+                Err(e) => bail!(
+                    "history JSON line {} is not a JSON object: {}",
+                    line_number,
+                    e
+                ),
+            };
+
+            let millis = record
+                .get(CSV_TIMESTAMP_COLUMN)
+                .and_then(Value::as_i64)
+                .ok_or_else(|| {
+                    eyre!(
+                        "history JSON line {} has no `timestamp` in Unix milliseconds",
+                        line_number
+                    )
+                })?;
+            let mut point = DataPoint {
+                timestamp: SystemTime::UNIX_EPOCH + Duration::from_millis(millis.max(0) as u64),
+                battery_percent: 0.0,
+                power_watts: 0.0,
+                cpu_watts: 0.0,
+                gpu_watts: 0.0,
+                soc: SocPower::default(),
+                temperature_c: None,
+                memory: MemorySample::default(),
+                gpu_top_state_percent: None,
+            };
+            for series in HistorySeries::ALL {
+                let value = match record.get(series.csv_column()) {
+                    None | Some(Value::Null) => None,
+                    Some(value) => Some(value.as_f64().ok_or_else(|| {
+                        eyre!(
+                            "history JSON line {}: `{}` value {} is not a number",
+                            line_number,
+                            series.csv_column(),
+                            value
+                        )
+                    })? as f32),
+                };
+                point.set_series_value(series, value);
+            }
+            history.push(point);
+        }
+        Ok(history)
+    }
+}
+
+/// `value` as written in CSV exports: `0.4`, not the `0.4000000059604645`
+/// of widening the `f32` directly. Non-finite values become `null`.
+fn json_number(value: f32) -> Value {
+    value
+        .to_string()
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map_or(Value::Null, Value::Number)
+}
+
+fn record_len(metric_count: u16) -> usize {
+    8 + metric_count as usize * 4
+}
+
+/// Fraction of a linear segment from `start` to `end` that lies within
+/// `[low, high]`.
+fn fraction_in_range(start: f64, end: f64, low: f64, high: f64) -> f64 {
+    if start == end {
+        return if (low..=high).contains(&start) {
+            1.0
+        } else {
+            0.0
+        };
+    }
+
+    let at = |value: f64| (value - start) / (end - start);
+    let (enter, leave) = (at(low).min(at(high)), at(low).max(at(high)));
+    (leave.min(1.0) - enter.max(0.0)).max(0.0)
+}
+
+impl DataPoint {
+    fn series_value(&self, series: HistorySeries) -> Option<f32> {
+        match series {
+            HistorySeries::Battery => Some(self.battery_percent),
+            HistorySeries::CpuPower => Some(self.cpu_watts),
+            HistorySeries::GpuPower => Some(self.gpu_watts),
+            HistorySeries::AnePower => self.soc.ane_watts,
+            HistorySeries::DramPower => self.soc.dram_watts,
+            HistorySeries::TotalPower => Some(self.power_watts),
+            HistorySeries::Temperature => self.temperature_c,
+            HistorySeries::MemoryUsed => self.memory.used_percent,
+            HistorySeries::MemoryBandwidth => self.memory.bandwidth_gbps,
+            HistorySeries::GpuTopState => self.gpu_top_state_percent,
+        }
+    }
+
+    /// Sets a series read back from an export; `None` clears optional
+    /// readings and zeroes the ones every point has.
+    fn set_series_value(&mut self, series: HistorySeries, value: Option<f32>) {
+        match series {
+            HistorySeries::Battery => self.battery_percent = value.unwrap_or(0.0),
+            HistorySeries::CpuPower => self.cpu_watts = value.unwrap_or(0.0),
+            HistorySeries::GpuPower => self.gpu_watts = value.unwrap_or(0.0),
+            HistorySeries::AnePower => self.soc.ane_watts = value,
+            HistorySeries::DramPower => self.soc.dram_watts = value,
+            HistorySeries::TotalPower => self.power_watts = value.unwrap_or(0.0),
+            HistorySeries::Temperature => self.temperature_c = value,
+            HistorySeries::MemoryUsed => self.memory.used_percent = value,
+            HistorySeries::MemoryBandwidth => self.memory.bandwidth_gbps = value,
+            HistorySeries::GpuTopState => self.gpu_top_state_percent = value,
+        }
+    }
+
+    /// Value of this point for the given metric. Power-based views (split and
+    /// merged) read the power series.
+    fn value(&self, metric: HistoryMetric) -> f64 {
+        match metric {
+            HistoryMetric::Battery => self.battery_percent as f64,
+            HistoryMetric::Power
+            | HistoryMetric::Split
+            | HistoryMetric::Merged
+            | HistoryMetric::Domains => self.power_watts as f64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history_with_power(values: &[f32]) -> HistoryData {
+        let mut history = HistoryData::with_metric(HistoryMetric::Power);
+        for (i, &watts) in values.iter().enumerate() {
+            let timestamp = SystemTime::UNIX_EPOCH + Duration::from_secs(i as u64 * 2);
+            history.record_at(timestamp, 80.0, watts, watts, 0.0, None);
+        }
+        history
+    }
+
+    #[test]
+    fn binary_round_trip_keeps_points_and_missing_readings() {
+        let mut history = history_with_power(&[5.0, 12.5, 8.0]);
+        history.set_latest_soc_power(SocPower {
+            ane_watts: Some(0.4),
+            dram_watts: None,
+        });
+        history.points[1].temperature_c = Some(31.5);
+
+        let bytes = history.as_bytes();
+        assert_eq!(&bytes[..4], b"JLTH");
+        assert_eq!(bytes.len(), HISTORY_HEADER_LEN + 3 * 36);
+
+        let decoded = HistoryData::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.points, history.points);
+        assert_eq!(decoded.as_bytes(), bytes);
+    }
+
+    #[test]
+    fn from_bytes_rejects_foreign_and_truncated_data() {
+        let bytes = history_with_power(&[5.0, 6.0]).as_bytes();
+
+        let error = |data: &[u8]| match HistoryData::from_bytes(data) {
+            Ok(_) => panic!("accepted {} bytes", data.len()),
+            Err(e) => e.to_string(),
+        };
+
+        assert_eq!(error(b"JSON{}"), "not a jolt history file");
+        assert_eq!(error(&bytes[..3]), "not a jolt history file");
+        assert!(error(&bytes[..bytes.len() - 1]).contains("bytes of records"));
+
+        let mut future = bytes.clone();
+        future[4] = 2;
+        assert_eq!(error(&future), "unsupported history format version 2");
+
+        let empty = HistoryData::with_metric(HistoryMetric::Battery).as_bytes();
+        assert!(HistoryData::from_bytes(&empty).unwrap().points.is_empty());
+    }
+
+    #[test]
+    fn csv_round_trip_keeps_points_and_missing_readings() {
+        let mut history = history_with_power(&[5.0, 12.5, 8.0]);
+        history.set_latest_soc_power(SocPower {
+            ane_watts: Some(0.4),
+            dram_watts: None,
+        });
+        history.set_latest_memory(MemorySample {
+            used_percent: Some(61.25),
+            bandwidth_gbps: None,
+        });
+        history.points[1].temperature_c = Some(31.5);
+
+        let mut csv = Vec::new();
+        history.export_csv(&mut csv).unwrap();
+        let decoded = HistoryData::from_csv(csv.as_slice()).unwrap();
+        assert_eq!(decoded.points, history.points);
+
+        // Columns may be reordered or left out.
+        let partial = "total_watts,timestamp\n7.5,2000\n,4000\n";
+        let decoded = HistoryData::from_csv(partial.as_bytes()).unwrap();
+        let watts: Vec<f32> = decoded.points.iter().map(|p| p.power_watts).collect();
+        assert_eq!(watts, vec![7.5, 0.0]);
+        assert_eq!(
+            decoded.points[1].timestamp,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(4)
+        );
+    }
+
+    #[test]
+    fn from_csv_names_what_is_malformed() {
+        let error = |csv: &str| match HistoryData::from_csv(csv.as_bytes()) {
+            Ok(_) => panic!("accepted {:?}", csv),
+            Err(e) => e.to_string(),
+        };
+
+        assert_eq!(error(""), "history CSV is empty, expected a header row");
+        assert_eq!(
+            error("cpu_watts\n1.0\n"),
+            "history CSV has no `timestamp` column"
+        );
+        assert_eq!(
+            error("timestamp,fan_rpm\n0,1200\n"),
+            "history CSV column `fan_rpm` is not a known series"
+        );
+        assert_eq!(
+            error("timestamp,cpu_watts,cpu_watts\n"),
+            "history CSV has more than one `cpu_watts` column"
+        );
+        assert_eq!(
+            error("timestamp,cpu_watts\n0,1.0\n1000\n"),
+            "history CSV line 3 has 1 fields, expected 2"
+        );
+        assert_eq!(
+            error("timestamp,cpu_watts\nnoon,1.0\n"),
+            "history CSV line 2: timestamp `noon` is not Unix milliseconds"
+        );
+        assert_eq!(
+            error("timestamp,cpu_watts\n0,high\n"),
+            "history CSV line 2: `cpu_watts` value `high` is not a number"
+        );
+    }
+
+    #[test]
+    fn json_round_trip_keeps_points_and_missing_readings() {
+        let mut history = history_with_power(&[5.0, 12.5, 8.0]);
+        history.set_latest_soc_power(SocPower {
+            ane_watts: Some(0.4),
+            dram_watts: None,
+        });
+        history.points[1].temperature_c = Some(31.5);
+
+        let mut json = Vec::new();
+        history.export_json(&mut json).unwrap();
+        let text = String::from_utf8(json.clone()).unwrap();
+        assert!(text.contains(r#""ane_watts":0.4,"#));
+        let decoded = HistoryData::from_json(json.as_slice()).unwrap();
+        assert_eq!(decoded.points, history.points);
+
+        // An export cut off mid-record keeps the records before it.
+        let cut = &json[..json.len() - 10];
+        let decoded = HistoryData::from_json(cut).unwrap();
+        assert_eq!(
+            decoded.points,
+            history.points.range(..2).copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn from_json_checks_the_schema_and_names_what_is_malformed() {
+        let error = |json: &str| match HistoryData::from_json(json.as_bytes()) {
+            Ok(_) => panic!("accepted {:?}", json),
+            Err(e) => e.to_string(),
+        };
+        let metadata = r#"{"type":"jolt_history","schema_version":1}"#;
+
+        assert_eq!(error(""), "history JSON is empty, expected a metadata line");
+        assert_eq!(
+            error("{\"timestamp\":0}\n"),
+            "history JSON does not start with a jolt history metadata line"
+        );
+        assert_eq!(
+            error("{\"type\":\"jolt_history\",\"schema_version\":2}\n"),
+            "unsupported history JSON schema version 2, expected 1"
+        );
+        assert_eq!(
+            error(&format!("{metadata}\n{{\"cpu_watts\":1.0}}\n")),
+            "history JSON line 2 has no `timestamp` in Unix milliseconds"
+        );
+        assert_eq!(
+            error(&format!(
+                "{metadata}\n{{\"timestamp\":0,\"cpu_watts\":\"high\"}}\n"
+            )),
+            "history JSON line 2: `cpu_watts` value \"high\" is not a number"
+        );
+        // Only a cut-off last line is forgiven.
+        assert!(error(&format!(
+            "{metadata}\n{{\"timestamp\":\n{{\"timestamp\":0}}\n"
+        ))
+        .starts_with("history JSON line 2 is not a JSON object"));
+    }
+
+    #[test]
+    fn longest_above_threshold_is_none_without_a_spike() {
+        let history = history_with_power(&[5.0, 8.0, 10.0]);
+        assert_eq!(
+            history.longest_above_threshold(HistoryMetric::Power, 20.0),
+            None
+        );
+    }
+
+    #[test]
+    fn longest_above_threshold_picks_the_longest_run() {
+        let history = history_with_power(&[25.0, 30.0, 5.0, 22.0, 24.0, 26.0, 3.0, 40.0]);
+        assert_eq!(
+            history.longest_above_threshold(HistoryMetric::Power, 20.0),
+            Some(Duration::from_secs(4))
+        );
+    }
+
+    #[test]
+    fn peak_duration_above_lists_spikes_and_merges_one_sample_gaps() {
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        assert!(history_with_power(&[5.0, 8.0])
+            .peak_duration_above(HistoryMetric::Power, 20.0)
+            .is_empty());
+
+        // 2 s between samples: [25, 30] and [22, 24] are one sample apart and
+        // merge; two samples below then separate the spike at 40.
+        let history = history_with_power(&[25.0, 30.0, 5.0, 22.0, 24.0, 3.0, 4.0, 40.0, 10.0]);
+        assert_eq!(
+            history.peak_duration_above(HistoryMetric::Power, 20.0),
+            vec![(at(0), Duration::from_secs(8)), (at(14), Duration::ZERO),]
+        );
+    }
+
+    #[test]
+    fn time_in_range_counts_intervals_inside() {
+        // 2 s between samples
+        let history = history_with_power(&[30.0, 50.0, 70.0, 40.0]);
+        assert_eq!(
+            history.time_in_range(HistoryMetric::Power, 20.0, 80.0),
+            Duration::from_secs(6)
+        );
+        assert_eq!(
+            history.time_in_range(HistoryMetric::Battery, 20.0, 80.0),
+            Duration::from_secs(6)
+        );
+        assert_eq!(
+            history.time_in_range(HistoryMetric::Power, 80.0, 20.0),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn time_in_range_splits_intervals_that_cross_a_bound() {
+        // 10 -> 30 enters at 20 halfway; 30 -> 90 leaves at 80 after 5/6;
+        // 90 -> 0 passes through the whole range: 60 of 90.
+        let history = history_with_power(&[10.0, 30.0, 90.0, 0.0]);
+        let secs = history
+            .time_in_range(HistoryMetric::Power, 20.0, 80.0)
+            .as_secs_f64();
+        let expected = 1.0 + 2.0 * 5.0 / 6.0 + 2.0 * 60.0 / 90.0;
+        assert!((secs - expected).abs() < 1e-6, "{} != {}", secs, expected);
+
+        let outside = history_with_power(&[5.0, 10.0, 5.0]);
+        assert_eq!(
+            outside.time_in_range(HistoryMetric::Power, 20.0, 80.0),
+            Duration::ZERO
+        );
+    }
+
+    fn naive_sliding_max(values: &[f32], window: usize) -> Vec<f64> {
+        values
+            .windows(window)
+            .map(|w| w.iter().copied().fold(f32::NEG_INFINITY, f32::max) as f64)
+            .collect()
+    }
+
+    #[test]
+    fn sliding_max_matches_naive_reference() {
+        let values = [
+            3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0, 5.0, 3.0, 5.0, 8.0, 9.0, 7.0, 9.0, 3.0, 2.0,
+            3.0, 8.0, 4.0, 6.0, 2.0, 6.0, 4.0, 3.0,
+        ];
+        let history = history_with_power(&values);
+
+        for window in 1..=values.len() {
+            assert_eq!(
+                history.sliding_max(HistoryMetric::Power, window),
+                naive_sliding_max(&values, window),
+                "window {}",
+                window
+            );
+        }
+    }
+
+    #[test]
+    fn sliding_max_handles_monotonic_series() {
+        let rising: Vec<f32> = (0..20).map(|i| i as f32).collect();
+        let falling: Vec<f32> = rising.iter().rev().copied().collect();
+
+        for values in [rising, falling] {
+            let history = history_with_power(&values);
+            assert_eq!(
+                history.sliding_max(HistoryMetric::Power, 4),
+                naive_sliding_max(&values, 4)
+            );
+        }
+    }
+
+    #[test]
+    fn sliding_max_is_empty_for_degenerate_windows() {
+        let history = history_with_power(&[5.0, 8.0, 10.0]);
+        assert!(history.sliding_max(HistoryMetric::Power, 0).is_empty());
+        assert!(history.sliding_max(HistoryMetric::Power, 4).is_empty());
+        assert_eq!(history.sliding_max(HistoryMetric::Battery, 3), vec![80.0]);
+    }
+
+    #[test]
+    fn ewma_matches_hand_computed_average() {
+        let history = history_with_power(&[10.0, 20.0, 0.0, 30.0]);
+        // 10 -> 10 + 0.5 * 10 = 15 -> 15 - 0.5 * 15 = 7.5 -> 7.5 + 0.5 * 22.5 = 18.75
+        assert_eq!(history.ewma(HistoryMetric::Power, 0.5), Some(18.75));
+        // 10 -> 12.5 -> 9.375 -> 14.53125
+        assert_eq!(history.ewma(HistoryMetric::Power, 0.25), Some(14.53125));
+        assert_eq!(history.ewma(HistoryMetric::Power, 1.0), Some(30.0));
+    }
+
+    #[test]
+    fn for_each_window_borrows_consecutive_runs() {
+        let history = history_with_power(&[1.0, 2.0, 3.0, 4.0]);
+        let mut sums = Vec::new();
+        history.for_each_window(HistoryMetric::Power, 3, false, |w| {
+            sums.push(w.iter().sum::<f64>())
+        });
+        assert_eq!(sums, vec![6.0, 9.0]);
+
+        let mut lens = Vec::new();
+        history.for_each_window(HistoryMetric::Power, 3, true, |w| lens.push(w.len()));
+        assert_eq!(lens, vec![1, 2, 3, 3]);
+
+        // Longer than the history: only the leading edge, if asked for.
+        lens.clear();
+        history.for_each_window(HistoryMetric::Power, 9, false, |w| lens.push(w.len()));
+        assert!(lens.is_empty());
+        history.for_each_window(HistoryMetric::Power, 9, true, |w| lens.push(w.len()));
+        assert_eq!(lens, vec![1, 2, 3, 4]);
+        lens.clear();
+        history.for_each_window(HistoryMetric::Power, 0, true, |w| lens.push(w.len()));
+        assert!(lens.is_empty());
+    }
+
+    #[test]
+    fn sparkline_scales_the_last_samples_across_the_levels() {
+        let history = history_with_power(&[99.0, 0.0, 7.0, 3.5, 14.0]);
+        assert_eq!(history.to_sparkline(HistoryMetric::Power, 4), "▁▅▃█");
+        assert_eq!(
+            history
+                .to_sparkline(HistoryMetric::Power, 10)
+                .chars()
+                .count(),
+            5
+        );
+        assert_eq!(history.to_sparkline(HistoryMetric::Power, 0), "");
+
+        let flat = history_with_power(&[5.0, 5.0]);
+        assert_eq!(flat.to_sparkline(HistoryMetric::Power, 8), "▄▄");
+    }
+
+    #[test]
+    fn prediction_extends_the_trend_from_the_newest_value() {
+        // 2 s between samples, rising 1 W/s on average
+        let history = history_with_power(&[10.0, 11.0, 14.0]);
+        assert_eq!(history.rate_of_change(HistoryMetric::Power), Some(1.0));
+        assert_eq!(
+            history.prediction(HistoryMetric::Power, Duration::from_secs(6)),
+            Some(20.0)
+        );
+
+        let short = history_with_power(&[10.0, 12.0]);
+        assert!(short.rate_of_change(HistoryMetric::Power).is_some());
+        assert_eq!(
+            short.prediction(HistoryMetric::Power, Duration::from_secs(6)),
+            None
+        );
+    }
+
+    #[test]
+    fn ewma_is_none_without_samples_or_with_invalid_alpha() {
+        let empty = HistoryData::with_metric(HistoryMetric::Power);
+        assert_eq!(empty.ewma(HistoryMetric::Power, 0.5), None);
+
+        let history = history_with_power(&[5.0, 8.0]);
+        assert_eq!(history.ewma(HistoryMetric::Power, 0.0), None);
+        assert_eq!(history.ewma(HistoryMetric::Power, 1.5), None);
+        assert_eq!(history.ewma(HistoryMetric::Power, f64::NAN), None);
+    }
+
+    #[test]
+    fn recent_into_right_aligns_and_keeps_gaps() {
+        let mut history = history_with_power(&[5.0, 6.0, 7.0]);
+        history.record_at(SystemTime::UNIX_EPOCH, 79.0, 8.0, 8.0, 0.0, Some(41.0));
+
+        let mut out = [Some(0.0); 6];
+        history.recent_into(HistorySeries::CpuPower, &mut out);
+        assert_eq!(
+            out,
+            [None, None, Some(5.0), Some(6.0), Some(7.0), Some(8.0)]
+        );
+
+        let mut out = [None; 2];
+        history.recent_into(HistorySeries::Temperature, &mut out);
+        assert_eq!(out, [None, Some(41.0)]);
+    }
+
+    #[test]
+    fn memory_series_read_the_latest_memory_sample() {
+        let mut history = history_with_power(&[5.0]);
+        history.record_at(SystemTime::UNIX_EPOCH, 80.0, 6.0, 6.0, 0.0, None);
+        history.set_latest_memory(MemorySample {
+            used_percent: Some(72.5),
+            bandwidth_gbps: None,
+        });
+
+        let mut out = [Some(0.0); 2];
+        history.recent_into(HistorySeries::MemoryUsed, &mut out);
+        assert_eq!(out, [None, Some(72.5)]);
+        history.recent_into(HistorySeries::MemoryBandwidth, &mut out);
+        assert_eq!(out, [None, None]);
+
+        history.set_latest_gpu_top_state(Some(40.0));
+        history.recent_into(HistorySeries::GpuTopState, &mut out);
+        assert_eq!(out, [None, Some(40.0)]);
+    }
+
+    #[test]
+    fn domain_stacks_fill_other_and_flag_missing_domains() {
+        let mut history = history_with_power(&[10.0]);
+        history.record_at(SystemTime::UNIX_EPOCH, 80.0, 12.0, 4.0, 3.0, None);
+        history.set_latest_soc_power(SocPower {
+            ane_watts: Some(1.0),
+            dram_watts: Some(0.5),
+        });
+
+        let stacks = history.domain_stacks(3);
+        assert_eq!(stacks[0], None);
+
+        let partial = stacks[1].unwrap();
+        assert!(partial.partial);
+        assert_eq!(partial.watts, [10.0, 0.0, 0.0, 0.0, 0.0]);
+
+        let full = stacks[2].unwrap();
+        assert!(!full.partial);
+        assert_eq!(full.watts, [4.0, 3.0, 1.0, 0.5, 3.5]);
+        assert_eq!(full.total_watts, 12.0);
+    }
+
+    #[test]
+    fn longest_above_threshold_single_sample_spike_is_zero_length() {
+        let history = history_with_power(&[5.0, 25.0, 5.0]);
+        assert_eq!(
+            history.longest_above_threshold(HistoryMetric::Power, 20.0),
+            Some(Duration::ZERO)
+        );
+    }
 }
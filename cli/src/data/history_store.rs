@@ -10,8 +10,9 @@ use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 
 use crate::config::data_dir;
+use crate::data::power::PowerMode;
 
-const CURRENT_SCHEMA_VERSION: i32 = 3;
+const CURRENT_SCHEMA_VERSION: i32 = 7;
 const DATABASE_NAME: &str = "history.db";
 
 /// Charging state for a sample
@@ -87,6 +88,45 @@ pub struct DailyTopProcess {
     pub total_energy_wh: f32, // Total energy consumed in Wh
 }
 
+/// Energy attributed to one app over a time range, summed from hourly
+/// rollups. Processes sharing a bundle id count as one app.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppEnergy {
+    /// The name recorded most recently for the app.
+    pub name: String,
+    pub bundle_id: Option<String>,
+    pub energy_wh: f32,
+    /// Part of `energy_wh` recorded while power was estimated rather than
+    /// measured.
+    pub estimated_wh: f32,
+}
+
+/// Time spent in one power mode within one hour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HourlyPowerMode {
+    pub hour_start: i64,
+    pub mode: PowerMode,
+    pub seconds: i64,
+}
+
+fn power_mode_to_i32(mode: PowerMode) -> i32 {
+    match mode {
+        PowerMode::LowPower => 0,
+        PowerMode::Automatic => 1,
+        PowerMode::HighPerformance => 2,
+        PowerMode::Unknown => 3,
+    }
+}
+
+fn power_mode_from_i32(value: i32) -> PowerMode {
+    match value {
+        0 => PowerMode::LowPower,
+        1 => PowerMode::Automatic,
+        2 => PowerMode::HighPerformance,
+        _ => PowerMode::Unknown,
+    }
+}
+
 /// Battery health snapshot (stored daily)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatteryHealthSnapshot {
@@ -96,6 +136,8 @@ pub struct BatteryHealthSnapshot {
     pub cycle_count: Option<i32>,
     pub max_capacity_wh: f32,
     pub design_capacity_wh: f32,
+    pub full_charge_capacity_mah: Option<i32>,
+    pub design_capacity_mah: Option<i32>,
 }
 
 /// Session type for charge/discharge tracking
@@ -128,6 +170,10 @@ pub struct ChargeSession {
     pub avg_power_watts: Option<f32>,
     pub session_type: SessionType,
     pub is_complete: bool,
+    /// Seconds the battery drained while external power was connected,
+    /// because the adapter could not keep up with the load.
+    #[serde(default)]
+    pub deficit_secs: i64,
 }
 
 impl ChargeSession {
@@ -144,6 +190,7 @@ impl ChargeSession {
             avg_power_watts: None,
             session_type: SessionType::Charge,
             is_complete: false,
+            deficit_secs: 0,
         }
     }
 
@@ -160,6 +207,7 @@ impl ChargeSession {
             avg_power_watts: None,
             session_type: SessionType::Discharge,
             is_complete: false,
+            deficit_secs: 0,
         }
     }
 
@@ -262,6 +310,21 @@ impl HistoryStore {
         Ok(store)
     }
 
+    /// Open an empty in-memory database with the current schema, then run
+    /// `sql` against it to load fixture rows
+    #[cfg(test)]
+    pub fn open_in_memory(sql: &str) -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        let mut store = Self {
+            conn,
+            path: PathBuf::from(":memory:"),
+        };
+        store.initialize_schema()?;
+        store.conn.execute_batch(sql)?;
+
+        Ok(store)
+    }
+
     /// Get the database file size in bytes
     pub fn size_bytes(&self) -> Result<u64> {
         if self.path.to_string_lossy() == ":memory:" {
@@ -366,6 +429,26 @@ impl HistoryStore {
                 UNIQUE(date, process_name)
             );
 
+            -- Energy attributed to processes per hour, for local-time reports
+            CREATE TABLE hourly_process_energy (
+                id INTEGER PRIMARY KEY,
+                hour_start INTEGER NOT NULL,
+                process_name TEXT NOT NULL,
+                energy_wh REAL NOT NULL,
+                bundle_id TEXT,
+                estimated_wh REAL NOT NULL DEFAULT 0.0,
+                UNIQUE(hour_start, process_name)
+            );
+
+            -- Time spent in each power mode per hour
+            CREATE TABLE hourly_power_modes (
+                id INTEGER PRIMARY KEY,
+                hour_start INTEGER NOT NULL,
+                power_mode INTEGER NOT NULL,
+                seconds INTEGER NOT NULL,
+                UNIQUE(hour_start, power_mode)
+            );
+
             -- Battery health snapshots (daily)
             CREATE TABLE battery_health (
                 id INTEGER PRIMARY KEY,
@@ -373,7 +456,9 @@ impl HistoryStore {
                 health_percent REAL NOT NULL,
                 cycle_count INTEGER,
                 max_capacity_wh REAL NOT NULL,
-                design_capacity_wh REAL NOT NULL
+                design_capacity_wh REAL NOT NULL,
+                full_charge_capacity_mah INTEGER,
+                design_capacity_mah INTEGER
             );
 
             -- Charge/discharge session tracking
@@ -387,7 +472,8 @@ impl HistoryStore {
                 charger_watts INTEGER,
                 avg_power_watts REAL,
                 session_type INTEGER NOT NULL,
-                is_complete INTEGER NOT NULL DEFAULT 0
+                is_complete INTEGER NOT NULL DEFAULT 0,
+                deficit_secs INTEGER NOT NULL DEFAULT 0
             );
 
             -- Daily cycle summary (aggregated from sessions)
@@ -426,6 +512,8 @@ impl HistoryStore {
             CREATE INDEX idx_charge_sessions_type ON charge_sessions(session_type);
             CREATE INDEX idx_daily_cycles_date ON daily_cycles(date);
             CREATE INDEX idx_cycle_snapshots_date ON cycle_snapshots(date);
+            CREATE INDEX idx_hourly_process_energy_hour ON hourly_process_energy(hour_start);
+            CREATE INDEX idx_hourly_power_modes_hour ON hourly_power_modes(hour_start);
             "#,
         )?;
 
@@ -502,6 +590,57 @@ impl HistoryStore {
             )?;
         }
 
+        if from_version < 4 {
+            tx.execute_batch(
+                r#"
+                ALTER TABLE battery_health ADD COLUMN full_charge_capacity_mah INTEGER;
+                ALTER TABLE battery_health ADD COLUMN design_capacity_mah INTEGER;
+                "#,
+            )?;
+        }
+
+        if from_version < 5 {
+            tx.execute_batch(
+                r#"
+                ALTER TABLE charge_sessions ADD COLUMN deficit_secs INTEGER NOT NULL DEFAULT 0;
+                "#,
+            )?;
+        }
+
+        if from_version < 6 {
+            tx.execute_batch(
+                r#"
+                CREATE TABLE hourly_process_energy (
+                    id INTEGER PRIMARY KEY,
+                    hour_start INTEGER NOT NULL,
+                    process_name TEXT NOT NULL,
+                    energy_wh REAL NOT NULL,
+                    UNIQUE(hour_start, process_name)
+                );
+
+                CREATE TABLE hourly_power_modes (
+                    id INTEGER PRIMARY KEY,
+                    hour_start INTEGER NOT NULL,
+                    power_mode INTEGER NOT NULL,
+                    seconds INTEGER NOT NULL,
+                    UNIQUE(hour_start, power_mode)
+                );
+
+                CREATE INDEX idx_hourly_process_energy_hour ON hourly_process_energy(hour_start);
+                CREATE INDEX idx_hourly_power_modes_hour ON hourly_power_modes(hour_start);
+                "#,
+            )?;
+        }
+
+        if from_version < 7 {
+            tx.execute_batch(
+                r#"
+                ALTER TABLE hourly_process_energy ADD COLUMN bundle_id TEXT;
+                ALTER TABLE hourly_process_energy ADD COLUMN estimated_wh REAL NOT NULL DEFAULT 0.0;
+                "#,
+            )?;
+        }
+
         tx.execute(
             "UPDATE schema_version SET version = ?",
             [CURRENT_SCHEMA_VERSION],
@@ -553,6 +692,14 @@ impl HistoryStore {
         Ok(samples)
     }
 
+    /// Timestamp of the oldest stored sample
+    pub fn oldest_sample_timestamp(&self) -> Result<Option<i64>> {
+        let oldest = self
+            .conn
+            .query_row("SELECT MIN(timestamp) FROM samples", [], |row| row.get(0))?;
+        Ok(oldest)
+    }
+
     /// Delete samples older than the given timestamp
     pub fn delete_samples_before(&self, before: i64) -> Result<usize> {
         let deleted = self
@@ -793,30 +940,215 @@ impl HistoryStore {
         Ok(deleted)
     }
 
-    pub fn upsert_battery_health(&self, snapshot: &BatteryHealthSnapshot) -> Result<()> {
+    /// Add `energy_wh` to a process's total for the hour starting at
+    /// `hour_start`, counting it as estimated too when `estimated` is set
+    pub fn add_hourly_process_energy(
+        &self,
+        hour_start: i64,
+        process_name: &str,
+        bundle_id: Option<&str>,
+        energy_wh: f32,
+        estimated: bool,
+    ) -> Result<()> {
+        let estimated_wh = if estimated { energy_wh } else { 0.0 };
         self.conn.execute(
-            "INSERT INTO battery_health (date, health_percent, cycle_count, max_capacity_wh, design_capacity_wh)
+            "INSERT INTO hourly_process_energy (hour_start, process_name, energy_wh, bundle_id, estimated_wh)
              VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(hour_start, process_name) DO UPDATE SET
+                energy_wh = hourly_process_energy.energy_wh + excluded.energy_wh,
+                bundle_id = COALESCE(excluded.bundle_id, hourly_process_energy.bundle_id),
+                estimated_wh = hourly_process_energy.estimated_wh + excluded.estimated_wh",
+            params![hour_start, process_name, energy_wh, bundle_id, estimated_wh],
+        )?;
+        Ok(())
+    }
+
+    /// Apps by energy used in hours starting in a time range, highest
+    /// first. Processes sharing a bundle id are merged under the name
+    /// recorded last, so a renamed app stays one entry. Without
+    /// `include_estimated`, energy recorded while power was estimated is
+    /// left out.
+    pub fn top_apps_by_energy(
+        &self,
+        from: i64,
+        to: i64,
+        include_estimated: bool,
+        limit: usize,
+    ) -> Result<Vec<AppEnergy>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT bundle_id, process_name, MAX(hour_start),
+                    SUM(energy_wh), SUM(estimated_wh)
+             FROM hourly_process_energy
+             WHERE hour_start >= ? AND hour_start <= ?
+             GROUP BY COALESCE(bundle_id, process_name), process_name",
+        )?;
+        let groups = stmt
+            .query_map(params![from, to], |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, f64>(3)?,
+                    row.get::<_, f64>(4)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // Per app: the latest hour seen, with the name recorded then.
+        let mut apps: Vec<(i64, AppEnergy)> = Vec::new();
+        for (bundle_id, name, last_hour, energy_wh, estimated_wh) in groups {
+            let key = bundle_id.as_deref().unwrap_or(&name);
+            let existing = apps
+                .iter_mut()
+                .find(|(_, app)| app.bundle_id.as_deref().unwrap_or(&app.name) == key);
+            match existing {
+                Some((latest, app)) => {
+                    if last_hour > *latest {
+                        *latest = last_hour;
+                        app.name = name;
+                    }
+                    app.energy_wh += energy_wh as f32;
+                    app.estimated_wh += estimated_wh as f32;
+                }
+                None => apps.push((
+                    last_hour,
+                    AppEnergy {
+                        name,
+                        bundle_id,
+                        energy_wh: energy_wh as f32,
+                        estimated_wh: estimated_wh as f32,
+                    },
+                )),
+            }
+        }
+
+        let mut apps: Vec<AppEnergy> = apps
+            .into_iter()
+            .map(|(_, mut app)| {
+                if !include_estimated {
+                    app.energy_wh -= app.estimated_wh;
+                    app.estimated_wh = 0.0;
+                }
+                app
+            })
+            .filter(|app| app.energy_wh > 0.0)
+            .collect();
+        apps.sort_by(|a, b| {
+            b.energy_wh
+                .total_cmp(&a.energy_wh)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        apps.truncate(limit);
+        Ok(apps)
+    }
+
+    /// Add `seconds` spent in `mode` to the hour starting at `hour_start`
+    pub fn add_hourly_power_mode(
+        &self,
+        hour_start: i64,
+        mode: PowerMode,
+        seconds: i64,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO hourly_power_modes (hour_start, power_mode, seconds)
+             VALUES (?, ?, ?)
+             ON CONFLICT(hour_start, power_mode) DO UPDATE SET
+                seconds = hourly_power_modes.seconds + excluded.seconds",
+            params![hour_start, power_mode_to_i32(mode), seconds],
+        )?;
+        Ok(())
+    }
+
+    /// Time per power mode for hours starting in a time range
+    pub fn get_hourly_power_modes(&self, from: i64, to: i64) -> Result<Vec<HourlyPowerMode>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT hour_start, power_mode, seconds
+             FROM hourly_power_modes
+             WHERE hour_start >= ? AND hour_start <= ?
+             ORDER BY hour_start ASC, power_mode ASC",
+        )?;
+
+        let entries = stmt
+            .query_map(params![from, to], |row| {
+                Ok(HourlyPowerMode {
+                    hour_start: row.get(0)?,
+                    mode: power_mode_from_i32(row.get(1)?),
+                    seconds: row.get(2)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// Delete hourly process energy and power mode entries older than the given timestamp
+    pub fn delete_hourly_attribution_before(&self, before: i64) -> Result<usize> {
+        let processes = self.conn.execute(
+            "DELETE FROM hourly_process_energy WHERE hour_start < ?",
+            [before],
+        )?;
+        let modes = self.conn.execute(
+            "DELETE FROM hourly_power_modes WHERE hour_start < ?",
+            [before],
+        )?;
+        Ok(processes + modes)
+    }
+
+    pub fn upsert_battery_health(&self, snapshot: &BatteryHealthSnapshot) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO battery_health (date, health_percent, cycle_count, max_capacity_wh, design_capacity_wh, full_charge_capacity_mah, design_capacity_mah)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
              ON CONFLICT(date) DO UPDATE SET
                 health_percent = excluded.health_percent,
                 cycle_count = excluded.cycle_count,
                 max_capacity_wh = excluded.max_capacity_wh,
-                design_capacity_wh = excluded.design_capacity_wh",
+                design_capacity_wh = excluded.design_capacity_wh,
+                full_charge_capacity_mah = excluded.full_charge_capacity_mah,
+                design_capacity_mah = excluded.design_capacity_mah",
             params![
                 snapshot.date,
                 snapshot.health_percent,
                 snapshot.cycle_count,
                 snapshot.max_capacity_wh,
                 snapshot.design_capacity_wh,
+                snapshot.full_charge_capacity_mah,
+                snapshot.design_capacity_mah,
             ],
         )?;
         Ok(())
     }
 
+    /// Battery wear log entries in a date range, oldest first.
+    pub fn get_battery_health(&self, from: &str, to: &str) -> Result<Vec<BatteryHealthSnapshot>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, date, health_percent, cycle_count, max_capacity_wh, design_capacity_wh, full_charge_capacity_mah, design_capacity_mah
+             FROM battery_health
+             WHERE date >= ? AND date <= ?
+             ORDER BY date ASC",
+        )?;
+
+        let entries = stmt
+            .query_map(params![from, to], |row| {
+                Ok(BatteryHealthSnapshot {
+                    id: Some(row.get(0)?),
+                    date: row.get(1)?,
+                    health_percent: row.get(2)?,
+                    cycle_count: row.get(3)?,
+                    max_capacity_wh: row.get(4)?,
+                    design_capacity_wh: row.get(5)?,
+                    full_charge_capacity_mah: row.get(6)?,
+                    design_capacity_mah: row.get(7)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
     pub fn insert_charge_session(&self, session: &ChargeSession) -> Result<i64> {
         self.conn.execute(
-            "INSERT INTO charge_sessions (start_time, end_time, start_percent, end_percent, energy_wh, charger_watts, avg_power_watts, session_type, is_complete)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO charge_sessions (start_time, end_time, start_percent, end_percent, energy_wh, charger_watts, avg_power_watts, session_type, is_complete, deficit_secs)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             params![
                 session.start_time,
                 session.end_time,
@@ -827,6 +1159,7 @@ impl HistoryStore {
                 session.avg_power_watts,
                 session.session_type as i32,
                 session.is_complete as i32,
+                session.deficit_secs,
             ],
         )?;
         Ok(self.conn.last_insert_rowid())
@@ -845,7 +1178,8 @@ impl HistoryStore {
                 end_percent = ?,
                 energy_wh = ?,
                 avg_power_watts = ?,
-                is_complete = ?
+                is_complete = ?,
+                deficit_secs = ?
              WHERE id = ?",
             params![
                 session.end_time,
@@ -853,6 +1187,7 @@ impl HistoryStore {
                 session.energy_wh,
                 session.avg_power_watts,
                 session.is_complete as i32,
+                session.deficit_secs,
                 id,
             ],
         )?;
@@ -867,13 +1202,13 @@ impl HistoryStore {
     ) -> Result<Vec<ChargeSession>> {
         let sql = match session_type {
             Some(_) => {
-                "SELECT id, start_time, end_time, start_percent, end_percent, energy_wh, charger_watts, avg_power_watts, session_type, is_complete
+                "SELECT id, start_time, end_time, start_percent, end_percent, energy_wh, charger_watts, avg_power_watts, session_type, is_complete, deficit_secs
                  FROM charge_sessions
                  WHERE start_time >= ? AND start_time <= ? AND session_type = ?
                  ORDER BY start_time DESC"
             }
             None => {
-                "SELECT id, start_time, end_time, start_percent, end_percent, energy_wh, charger_watts, avg_power_watts, session_type, is_complete
+                "SELECT id, start_time, end_time, start_percent, end_percent, energy_wh, charger_watts, avg_power_watts, session_type, is_complete, deficit_secs
                  FROM charge_sessions
                  WHERE start_time >= ? AND start_time <= ?
                  ORDER BY start_time DESC"
@@ -898,7 +1233,7 @@ impl HistoryStore {
         let session = self
             .conn
             .query_row(
-                "SELECT id, start_time, end_time, start_percent, end_percent, energy_wh, charger_watts, avg_power_watts, session_type, is_complete
+                "SELECT id, start_time, end_time, start_percent, end_percent, energy_wh, charger_watts, avg_power_watts, session_type, is_complete, deficit_secs
                  FROM charge_sessions
                  WHERE is_complete = 0
                  ORDER BY start_time DESC
@@ -923,6 +1258,7 @@ impl HistoryStore {
             avg_power_watts: row.get(7)?,
             session_type: SessionType::from(row.get::<_, i32>(8)?),
             is_complete: row.get::<_, i32>(9)? != 0,
+            deficit_secs: row.get(10)?,
         })
     }
 
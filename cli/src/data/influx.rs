@@ -0,0 +1,104 @@
+//! InfluxDB line protocol output.
+//!
+//! One line per reading: `measurement,tag=value field=value timestamp`,
+//! with every field written as a float and the timestamp in Unix
+//! nanoseconds. Missing readings are left out rather than written as zero.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One line for `measurement`. Fields without a finite value are dropped,
+/// as line protocol has no way to write NaN or infinity.
+pub fn line(
+    measurement: &str,
+    tags: &[(&str, &str)],
+    fields: &[(&str, Option<f32>)],
+    timestamp_ns: i128,
+) -> String {
+    let mut out = escape(measurement, &[',', ' ']);
+    for (key, value) in tags {
+        out.push(',');
+        out.push_str(&escape(key, &[',', '=', ' ']));
+        out.push('=');
+        out.push_str(&escape(value, &[',', '=', ' ']));
+    }
+
+    let fields: Vec<String> = fields
+        .iter()
+        .filter_map(|(key, value)| {
+            let value = value.filter(|v| v.is_finite())?;
+            Some(format!("{}={:?}", escape(key, &[',', '=', ' ']), value))
+        })
+        .collect();
+    out.push(' ');
+    out.push_str(&fields.join(","));
+    out.push(' ');
+    out.push_str(&timestamp_ns.to_string());
+    out
+}
+
+/// The current time in Unix nanoseconds.
+pub fn now_ns() -> i128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as i128)
+}
+
+fn escape(s: &str, special: &[char]) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c == '\\' || special.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_tags_float_fields_and_timestamp() {
+        let line = line(
+            "battery",
+            &[("power_source", "ac"), ("health_condition", "good")],
+            &[
+                ("charge_percent", Some(80.0)),
+                ("voltage_mv", Some(12450.5)),
+            ],
+            1_700_000_000_000_000_000,
+        );
+        assert_eq!(
+            line,
+            "battery,power_source=ac,health_condition=good \
+             charge_percent=80.0,voltage_mv=12450.5 1700000000000000000"
+        );
+    }
+
+    #[test]
+    fn missing_and_non_finite_fields_are_left_out() {
+        let line = line(
+            "power",
+            &[],
+            &[
+                ("cpu_watts", Some(3.5)),
+                ("gpu_watts", None),
+                ("ane_watts", Some(f32::NAN)),
+            ],
+            1,
+        );
+        assert_eq!(line, "power cpu_watts=3.5 1");
+    }
+
+    #[test]
+    fn special_characters_are_escaped() {
+        let line = line(
+            "power",
+            &[("power_mode", "Low Power"), ("a,b", "c=d")],
+            &[("x y", Some(1.0))],
+            1,
+        );
+        assert_eq!(line, r"power,power_mode=Low\ Power,a\,b=c\=d x\ y=1.0 1");
+    }
+}
@@ -0,0 +1,258 @@
+//! "Will the battery last until …?" estimates.
+//!
+//! The projection starts from the average drain over the last hour of
+//! discharge and, for each hour between now and the target, blends in what
+//! this machine typically draws at that hour of day according to the daemon's
+//! hourly rollups. The spread of the recent drain readings gives the
+//! confidence band: a workload that jumps between 4 W and 20 W produces a much
+//! wider range than a steady one.
+
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Local, Timelike};
+
+use super::battery::ChargeState;
+use super::HourlyStat;
+
+/// Less discharge history than this is too little to extrapolate from.
+const MIN_DISCHARGE_HISTORY: Duration = Duration::from_secs(10 * 60);
+const MAX_DISCHARGE_HISTORY: Duration = Duration::from_secs(60 * 60);
+const HOURS_PER_DAY: usize = 24;
+/// Weight of the typical power for an hour versus the current drain.
+const HOURLY_PROFILE_WEIGHT: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LastUntilVerdict {
+    /// Lasts even at the pessimistic end of the band.
+    Yes,
+    /// Lasts at the expected drain but not at the pessimistic end.
+    Probably,
+    /// Runs out at the expected drain but lasts at the optimistic end.
+    ProbablyNot,
+    /// Runs out even at the optimistic end of the band.
+    No,
+}
+
+impl LastUntilVerdict {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LastUntilVerdict::Yes => "Yes",
+            LastUntilVerdict::Probably => "Probably",
+            LastUntilVerdict::ProbablyNot => "Probably not",
+            LastUntilVerdict::No => "No",
+        }
+    }
+}
+
+/// Projected charge at a target time.
+///
+/// Percentages below zero are a shortfall: `-8.0` means the battery would
+/// need 8% more charge to make it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LastUntilEstimate {
+    pub verdict: LastUntilVerdict,
+    pub expected_percent: f32,
+    pub pessimistic_percent: f32,
+    pub optimistic_percent: f32,
+    /// Average drain over the recent discharge history.
+    pub drain_watts: f32,
+}
+
+/// Recent discharge readings plus the typical power for each hour of day.
+#[derive(Debug, Default)]
+pub(crate) struct DrainTracker {
+    samples: VecDeque<(SystemTime, f32)>,
+    hourly_watts: [Option<f32>; HOURS_PER_DAY],
+}
+
+impl DrainTracker {
+    /// Adds a reading. Anything other than discharging breaks the series, so
+    /// the history restarts the next time the battery discharges.
+    pub(crate) fn record(&mut self, at: SystemTime, state: ChargeState, watts: Option<f32>) {
+        if state != ChargeState::Discharging {
+            self.samples.clear();
+            return;
+        }
+        let Some(watts) = watts.filter(|w| w.is_finite() && *w >= 0.0) else {
+            return;
+        };
+
+        self.samples.push_back((at, watts));
+        while let Some(&(oldest, _)) = self.samples.front() {
+            match at.duration_since(oldest) {
+                Ok(age) if age > MAX_DISCHARGE_HISTORY => {
+                    self.samples.pop_front();
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// Replaces the hour-of-day profile with the average power of
+    /// discharging hours in `stats`.
+    pub(crate) fn set_hourly_profile(&mut self, stats: &[HourlyStat]) {
+        let mut sums = [(0.0_f32, 0_u32); HOURS_PER_DAY];
+        for stat in stats.iter().filter(|s| s.battery_delta < 0.0) {
+            let Some(start) = DateTime::from_timestamp(stat.hour_start, 0) else {
+                continue;
+            };
+            let hour = start.with_timezone(&Local).hour() as usize;
+            sums[hour].0 += stat.avg_power;
+            sums[hour].1 += 1;
+        }
+
+        self.hourly_watts = sums.map(|(sum, count)| (count > 0).then(|| sum / count as f32));
+    }
+
+    fn discharge_history(&self) -> Duration {
+        match (self.samples.front(), self.samples.back()) {
+            (Some(&(first, _)), Some(&(last, _))) => last.duration_since(first).unwrap_or_default(),
+            _ => Duration::ZERO,
+        }
+    }
+
+    pub(crate) fn estimate(
+        &self,
+        now: SystemTime,
+        target: SystemTime,
+        charge_percent: f32,
+        capacity_wh: f32,
+    ) -> Option<LastUntilEstimate> {
+        if self.discharge_history() < MIN_DISCHARGE_HISTORY || capacity_wh <= 0.0 {
+            return None;
+        }
+        let total = target.duration_since(now).ok()?;
+
+        let count = self.samples.len() as f32;
+        let mean = self.samples.iter().map(|(_, w)| w).sum::<f32>() / count;
+        let variance = self
+            .samples
+            .iter()
+            .map(|(_, w)| (w - mean).powi(2))
+            .sum::<f32>()
+            / count;
+        let std_dev = variance.sqrt();
+
+        let mut expected_wh = 0.0_f32;
+        let mut at = now;
+        while at < target {
+            let local: DateTime<Local> = at.into();
+            let into_hour = Duration::from_secs(local.minute() as u64 * 60 + local.second() as u64);
+            let segment_end = (at + (Duration::from_secs(3600) - into_hour)).min(target);
+            let hours = segment_end
+                .duration_since(at)
+                .unwrap_or_default()
+                .as_secs_f32()
+                / 3600.0;
+
+            let watts = match self.hourly_watts[local.hour() as usize] {
+                Some(typical) => {
+                    mean * (1.0 - HOURLY_PROFILE_WEIGHT) + typical * HOURLY_PROFILE_WEIGHT
+                }
+                None => mean,
+            };
+            expected_wh += watts * hours;
+            at = segment_end;
+        }
+
+        let band_wh = std_dev * total.as_secs_f32() / 3600.0;
+        let percent_after = |wh: f32| charge_percent - wh.max(0.0) / capacity_wh * 100.0;
+
+        let expected_percent = percent_after(expected_wh);
+        let pessimistic_percent = percent_after(expected_wh + band_wh);
+        let optimistic_percent = percent_after(expected_wh - band_wh);
+
+        let verdict = if pessimistic_percent > 0.0 {
+            LastUntilVerdict::Yes
+        } else if expected_percent > 0.0 {
+            LastUntilVerdict::Probably
+        } else if optimistic_percent > 0.0 {
+            LastUntilVerdict::ProbablyNot
+        } else {
+            LastUntilVerdict::No
+        };
+
+        Some(LastUntilEstimate {
+            verdict,
+            expected_percent,
+            pessimistic_percent,
+            optimistic_percent,
+            drain_watts: mean,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: SystemTime = SystemTime::UNIX_EPOCH;
+
+    fn tracker(minutes: u64, watts: impl Fn(u64) -> f32) -> DrainTracker {
+        let mut tracker = DrainTracker::default();
+        for minute in 0..=minutes {
+            tracker.record(
+                START + Duration::from_secs(minute * 60),
+                ChargeState::Discharging,
+                Some(watts(minute)),
+            );
+        }
+        tracker
+    }
+
+    fn end_of(minutes: u64) -> SystemTime {
+        START + Duration::from_secs(minutes * 60)
+    }
+
+    #[test]
+    fn refuses_with_too_little_history() {
+        let tracker = tracker(5, |_| 10.0);
+        let now = end_of(5);
+        assert!(tracker
+            .estimate(now, now + Duration::from_secs(3600), 50.0, 50.0)
+            .is_none());
+    }
+
+    #[test]
+    fn charging_resets_the_history() {
+        let mut tracker = tracker(20, |_| 10.0);
+        tracker.record(end_of(21), ChargeState::Charging, Some(0.0));
+        let now = end_of(21);
+        assert!(tracker
+            .estimate(now, now + Duration::from_secs(3600), 50.0, 50.0)
+            .is_none());
+    }
+
+    #[test]
+    fn steady_drain_projects_linearly() {
+        let tracker = tracker(20, |_| 10.0);
+        let now = end_of(20);
+
+        // 10 W for 2 hours out of a 50 Wh battery is 40%.
+        let estimate = tracker
+            .estimate(now, now + Duration::from_secs(2 * 3600), 50.0, 50.0)
+            .unwrap();
+        assert!((estimate.expected_percent - 10.0).abs() < 0.01);
+        assert_eq!(estimate.pessimistic_percent, estimate.optimistic_percent);
+        assert_eq!(estimate.verdict, LastUntilVerdict::Yes);
+
+        let estimate = tracker
+            .estimate(now, now + Duration::from_secs(3 * 3600), 50.0, 50.0)
+            .unwrap();
+        assert_eq!(estimate.verdict, LastUntilVerdict::No);
+    }
+
+    #[test]
+    fn noisy_drain_widens_the_band() {
+        let tracker = tracker(20, |m| if m % 2 == 0 { 4.0 } else { 16.0 });
+        let now = end_of(20);
+
+        let estimate = tracker
+            .estimate(now, now + Duration::from_secs(2 * 3600), 50.0, 50.0)
+            .unwrap();
+        assert!(estimate.pessimistic_percent < estimate.expected_percent);
+        assert!(estimate.optimistic_percent > estimate.expected_percent);
+        assert_eq!(estimate.verdict, LastUntilVerdict::Probably);
+    }
+}
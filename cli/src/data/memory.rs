@@ -0,0 +1,55 @@
+//! Memory pressure and bandwidth for the overview.
+//!
+//! Readings the platform cannot provide stay `None` rather than showing a
+//! made-up zero; on Linux that is always the case for bandwidth.
+
+use color_eyre::eyre::Result;
+use jolt_platform::MemoryProvider;
+
+use crate::data::history::MemorySample;
+
+pub use jolt_platform::MemoryPressure;
+
+#[cfg(target_os = "macos")]
+type PlatformMemory = jolt_platform::macos::MacOSMemory;
+
+#[cfg(target_os = "linux")]
+type PlatformMemory = jolt_platform::linux::LinuxMemory;
+
+pub struct MemoryData {
+    provider: PlatformMemory,
+}
+
+impl MemoryData {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            provider: PlatformMemory::new()?,
+        })
+    }
+
+    pub fn refresh(&mut self) -> Result<()> {
+        self.provider.refresh()
+    }
+
+    pub fn pressure(&self) -> MemoryPressure {
+        self.provider.info().pressure
+    }
+
+    /// Share of physical memory in use, in percent.
+    pub fn used_percent(&self) -> Option<f32> {
+        self.provider.info().used_fraction.map(|used| used * 100.0)
+    }
+
+    /// DRAM read plus write traffic in GB/s.
+    pub fn bandwidth_gbps(&self) -> Option<f32> {
+        self.provider.info().bandwidth_gbps
+    }
+
+    /// Current readings as recorded in [`crate::data::HistoryData`].
+    pub fn sample(&self) -> MemorySample {
+        MemorySample {
+            used_percent: self.used_percent(),
+            bandwidth_gbps: self.bandwidth_gbps(),
+        }
+    }
+}
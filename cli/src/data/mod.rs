@@ -1,26 +1,91 @@
 pub mod aggregator;
 pub mod battery;
+pub mod battery_wear;
+pub mod channel_fixture;
+pub mod charge_interpolation;
+pub mod charging_advice;
+pub mod coordinator;
+#[cfg(test)]
+mod discharge_replay;
+pub mod fleet;
 pub mod forecast;
+pub mod health;
+pub mod heatmap;
 pub mod history;
 pub mod history_store;
+pub mod influx;
+pub mod last_until;
+pub mod memory;
+pub mod network;
 pub mod power;
+pub mod power_balance;
+pub mod power_worker;
+pub mod powermetrics;
 pub mod processes;
 pub mod recorder;
+pub mod redaction;
+pub mod refresh_timings;
+pub mod report;
+pub mod session_state;
 pub mod session_tracker;
 pub mod system;
 pub mod system_stats;
+pub mod timeline;
+pub mod wake_log;
 
 pub use battery::BatteryData;
+pub use coordinator::RefreshCoordinator;
 pub use forecast::{ForecastData, ForecastSource};
-pub use history::{HistoryData, HistoryMetric};
+pub use history::{HistoryData, HistoryMetric, HistorySeries, SocPower};
 pub use history_store::{
-    BatteryHealthSnapshot, ChargeSession, ChargingState, CycleSnapshot, DailyCycle, DailyStat,
-    DailyTopProcess, DatabaseStats, HistoryStore, HistoryStoreError, HourlyStat, Sample,
+    AppEnergy, BatteryHealthSnapshot, ChargeSession, ChargingState, CycleSnapshot, DailyCycle,
+    DailyStat, DailyTopProcess, DatabaseStats, HistoryStore, HistoryStoreError, HourlyStat, Sample,
     SessionType,
 };
+pub use memory::MemoryData;
 pub use power::PowerData;
-pub use processes::{ProcessData, ProcessInfo, ProcessState};
+pub use processes::{normalize_cpu, NormalizedCpu, ProcessData, ProcessInfo, ProcessState};
 pub use recorder::Recorder;
+pub use redaction::ProcessRedactor;
+pub use refresh_timings::{RefreshStage, RefreshTimings};
 pub use session_tracker::{SessionEvent, SessionTracker};
 pub use system::SystemInfo;
 pub use system_stats::SystemStatsData;
+pub use wake_log::WakeLog;
+
+use color_eyre::eyre::Result;
+
+/// One of each data source, as used by the TUI.
+pub struct AllData {
+    pub power: PowerData,
+    pub battery: BatteryData,
+    pub history: HistoryData,
+    pub processes: ProcessData,
+}
+
+/// Creates every data source and refreshes it once, so all readings are
+/// populated and `history` holds its first point.
+pub fn all_data() -> Result<AllData> {
+    let mut battery = BatteryData::new()?;
+    let mut power = PowerData::new()?;
+    let mut processes = ProcessData::new()?;
+    battery.refresh()?;
+    power.refresh()?;
+    processes.refresh()?;
+
+    let mut history = HistoryData::with_metric(HistoryMetric::Battery);
+    history.record(
+        battery.charge_percent(),
+        power.total_power_watts(),
+        power.cpu_power_watts(),
+        power.gpu_power_watts(),
+        battery.temperature_c(),
+    );
+
+    Ok(AllData {
+        power,
+        battery,
+        history,
+        processes,
+    })
+}
@@ -0,0 +1,188 @@
+//! Per-process network throughput.
+//!
+//! Byte counters come from `nettop`, which reads the kernel's per-socket
+//! statistics through the private NetworkStatistics framework. It reports
+//! cumulative bytes per process, so rates are derived from the difference
+//! between two consecutive samples. `nettop` only exists on macOS and may
+//! need extra privileges on managed machines; whenever it cannot be read the
+//! sampler reports `None` instead of zero.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+#[derive(Debug, Default)]
+pub struct NetworkSampler {
+    totals: HashMap<u32, u64>,
+    last_sample: Option<Instant>,
+    /// `None` until two samples have been taken or while unavailable.
+    rates: Option<HashMap<u32, f64>>,
+}
+
+impl NetworkSampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn refresh(&mut self) {
+        match read_byte_totals() {
+            Some(totals) => self.update(totals, Instant::now()),
+            None => *self = Self::default(),
+        }
+    }
+
+    /// Drops the byte counters of `pids`, which now belong to different
+    /// processes, so the next refresh does not take the new process's bytes
+    /// as traffic since the old one's last sample.
+    pub fn forget(&mut self, pids: &[u32]) {
+        for pid in pids {
+            self.totals.remove(pid);
+        }
+    }
+
+    fn update(&mut self, totals: HashMap<u32, u64>, now: Instant) {
+        self.rates = self.last_sample.map(|last| {
+            compute_rates(
+                &self.totals,
+                &totals,
+                now.duration_since(last).as_secs_f64(),
+            )
+        });
+        self.totals = totals;
+        self.last_sample = Some(now);
+    }
+
+    /// Bytes per second sent and received by `pid` since the previous refresh.
+    ///
+    /// Processes without network activity report `Some(0.0)`.
+    pub fn bytes_per_sec(&self, pid: u32) -> Option<f64> {
+        self.rates
+            .as_ref()
+            .map(|rates| rates.get(&pid).copied().unwrap_or(0.0))
+    }
+}
+
+fn compute_rates(
+    previous: &HashMap<u32, u64>,
+    current: &HashMap<u32, u64>,
+    elapsed_secs: f64,
+) -> HashMap<u32, f64> {
+    if elapsed_secs <= 0.0 {
+        return HashMap::new();
+    }
+
+    current
+        .iter()
+        .filter_map(|(pid, &bytes)| {
+            // A process seen for the first time has no baseline yet, and a
+            // shrinking counter means the PID was reused.
+            let prev = *previous.get(pid)?;
+            Some((*pid, bytes.saturating_sub(prev) as f64 / elapsed_secs))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn read_byte_totals() -> Option<HashMap<u32, u64>> {
+    use std::process::Command;
+
+    use jolt_platform::command::{self, CommandLimits};
+
+    let output = command::run(
+        Command::new("nettop").args(["-P", "-L", "1", "-x", "-J", "bytes_in,bytes_out"]),
+        CommandLimits::DEFAULT,
+    )
+    .ok()?;
+
+    if !output.success() {
+        return None;
+    }
+
+    Some(parse_nettop_csv(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_byte_totals() -> Option<HashMap<u32, u64>> {
+    None
+}
+
+/// Parses `nettop -P -L 1 -x -J bytes_in,bytes_out` output.
+///
+/// Each data row looks like `time,name.pid,bytes_in,bytes_out,`. The process
+/// name may itself contain dots, so the PID is taken after the last one.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_nettop_csv(output: &str) -> HashMap<u32, u64> {
+    let mut totals = HashMap::new();
+
+    for line in output.lines() {
+        let mut fields = line.split(',');
+        let (Some(_time), Some(process), Some(bytes_in), Some(bytes_out)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+
+        let Some(pid) = process
+            .rsplit_once('.')
+            .and_then(|(_, pid)| pid.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        if let (Ok(bytes_in), Ok(bytes_out)) = (
+            bytes_in.trim().parse::<u64>(),
+            bytes_out.trim().parse::<u64>(),
+        ) {
+            *totals.entry(pid).or_insert(0) += bytes_in + bytes_out;
+        }
+    }
+
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nettop_rows_and_skips_header() {
+        let output = "time,,bytes_in,bytes_out,\n\
+                      10:15:02.123456,launchd.1,0,0,\n\
+                      10:15:02.123456,com.apple.WebKit.Networking.812,4096,1024,\n\
+                      10:15:02.123456,garbage,1,2,\n";
+
+        let totals = parse_nettop_csv(output);
+
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[&1], 0);
+        assert_eq!(totals[&812], 5120);
+    }
+
+    #[test]
+    fn rates_need_a_baseline_and_ignore_counter_resets() {
+        let previous = HashMap::from([(1, 1000), (2, 5000)]);
+        let current = HashMap::from([(1, 3000), (2, 100), (3, 9000)]);
+
+        let rates = compute_rates(&previous, &current, 2.0);
+
+        assert_eq!(rates[&1], 1000.0);
+        assert_eq!(rates[&2], 0.0);
+        assert!(!rates.contains_key(&3));
+    }
+
+    #[test]
+    fn forgotten_pids_start_without_a_baseline() {
+        let start = Instant::now();
+        let mut sampler = NetworkSampler::new();
+        sampler.update(HashMap::from([(42, 10_000), (7, 500)]), start);
+
+        // PID 42 exits and a new process with more traffic takes its PID.
+        sampler.forget(&[42]);
+        sampler.update(
+            HashMap::from([(42, 12_000), (7, 1500)]),
+            start + std::time::Duration::from_secs(1),
+        );
+
+        assert_eq!(sampler.bytes_per_sec(42), Some(0.0));
+        assert_eq!(sampler.bytes_per_sec(7), Some(1000.0));
+    }
+}
@@ -1,11 +1,18 @@
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
 
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{bail, Result};
 use jolt_platform::PowerProvider;
 
+use crate::app::{MAX_REFRESH_MS, MIN_REFRESH_MS};
 use crate::daemon::PowerSnapshot;
+use crate::data::power_worker::{PowerReading, PowerWorker, DEFAULT_SAMPLE_INTERVAL};
+use crate::data::{influx, BatteryData, SocPower};
 
-pub use jolt_platform::PowerMode;
+use jolt_platform::{
+    classify, ChannelDomain, PowerInfo, PowerReadings, PowerStats, DEFAULT_CHANNEL_RULES,
+};
+pub use jolt_platform::{ChannelPower, GpuResidency, PowerMode, ThermalPressure};
 
 #[cfg(target_os = "macos")]
 type PlatformPower = jolt_platform::macos::MacOSPower;
@@ -16,57 +23,249 @@ type PlatformPower = jolt_platform::linux::LinuxPower;
 #[cfg(not(any(target_os = "macos", target_os = "linux")))]
 compile_error!("PlatformPower is only defined for macOS and Linux targets.");
 
-const SMOOTHING_SAMPLE_COUNT: usize = 5;
-const MIN_WARMUP_SAMPLES: usize = 3;
+/// Hardware block a power channel belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelCategory {
+    Cpu,
+    Gpu,
+    /// Apple Neural Engine.
+    Ane,
+    Dram,
+    /// Fabric, ISP, platform (`psys`) and any channel not recognised above.
+    Other,
+}
+
+impl ChannelCategory {
+    /// Every category, in the order [`PowerData::component_breakdown`]
+    /// lists them.
+    pub const ALL: [ChannelCategory; 5] = [
+        ChannelCategory::Cpu,
+        ChannelCategory::Gpu,
+        ChannelCategory::Ane,
+        ChannelCategory::Dram,
+        ChannelCategory::Other,
+    ];
+
+    /// Categorises an IOReport Energy Model channel or RAPL domain by name,
+    /// with the platform's [`DEFAULT_CHANNEL_RULES`] so it agrees with the
+    /// provider's totals. Memory channels are split out of the platform's
+    /// Other domain; channels no domain counts are Other too.
+    pub fn of(channel: &str) -> Self {
+        match classify(channel, DEFAULT_CHANNEL_RULES) {
+            Some(ChannelDomain::Cpu) => ChannelCategory::Cpu,
+            Some(ChannelDomain::Gpu) => ChannelCategory::Gpu,
+            Some(ChannelDomain::Ane) => ChannelCategory::Ane,
+            Some(ChannelDomain::Other) if channel.to_lowercase().contains("dram") => {
+                ChannelCategory::Dram
+            }
+            Some(ChannelDomain::Other) | None => ChannelCategory::Other,
+        }
+    }
+}
+
+/// Apple Silicon CPU cluster a power channel belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuCluster {
+    /// Efficiency cores: "ECPU*", "EACC*" and "E-Cluster*" channels.
+    Efficiency,
+    /// Performance cores: "PCPU*", "PACC*" and "P-Cluster*" channels.
+    Performance,
+}
+
+impl CpuCluster {
+    /// The cluster a channel belongs to, by name; `None` for channels not
+    /// tied to one cluster or counted in another domain.
+    pub fn of(channel: &str) -> Option<Self> {
+        if !matches!(ChannelDomain::of(channel), Some(ChannelDomain::Cpu) | None) {
+            return None;
+        }
+        let channel = channel.to_lowercase();
+        if ["ecpu", "eacc", "e-cluster"]
+            .iter()
+            .any(|token| channel.starts_with(token))
+        {
+            Some(CpuCluster::Efficiency)
+        } else if ["pcpu", "pacc", "p-cluster"]
+            .iter()
+            .any(|token| channel.starts_with(token))
+        {
+            Some(CpuCluster::Performance)
+        } else {
+            None
+        }
+    }
+}
+
+/// Samples averaged into the displayed power unless configured otherwise.
+pub const DEFAULT_SMOOTHING_SAMPLES: usize = 5;
+/// Fewest samples before readings are shown; also the smallest smoothing
+/// window, so a narrow window never keeps the display warming up.
+pub const MIN_WARMUP_SAMPLES: usize = 3;
+pub const MAX_SMOOTHING_SAMPLES: usize = 30;
+/// Raw readings in [`PowerData::rolling_average`] unless
+/// [`PowerData::with_average_window`] sets another count.
+pub const DEFAULT_AVERAGE_WINDOW: usize = 10;
+/// Usage above which a core counts as active for [`PowerData::cpu_power_density`].
+pub const ACTIVE_CORE_THRESHOLD_PERCENT: f32 = 5.0;
 
 #[derive(Debug, Clone, Copy)]
 struct PowerSample {
     cpu_power: f32,
+    ecpu_power: f32,
+    pcpu_power: f32,
     gpu_power: f32,
+    ane_power: f32,
     system_power: f32,
+    taken_at: Instant,
 }
 
 pub struct PowerData {
-    provider: PlatformPower,
+    /// Samples the platform provider on its own thread.
+    worker: PowerWorker,
+    /// Provider readings as of the newest sample from the worker.
+    info: PowerInfo,
     samples: VecDeque<PowerSample>,
+    /// Most samples kept for the moving average.
+    smoothing_samples: usize,
+    /// Power mode seen by the last [`PowerData::reset_on_mode_change`] call.
+    last_known_mode: PowerMode,
+    /// Peaks and rolling average of the provider's unsmoothed readings.
+    stats: PowerStats,
+    /// Whether fallback estimates count toward `stats`.
+    stats_include_fallback: bool,
+    cpus: sysinfo::System,
+    /// Per-core usage percent at the last refresh; empty while showing
+    /// daemon data, whose cores may not be this machine's.
+    core_usages: Vec<f32>,
+    /// Provider energy totals at the last [`PowerData::reset_energy`], as
+    /// CPU, GPU and ANE joules.
+    energy_baseline: [f64; 3],
+    /// Provider refresh times of the readings the last refresh took in.
+    refresh_times: Vec<Duration>,
 }
 
 impl PowerData {
     pub fn new() -> Result<Self> {
-        let provider = PlatformPower::new()?;
-        let info = provider.info();
-        let mut samples = VecDeque::with_capacity(SMOOTHING_SAMPLE_COUNT);
+        Self::with_average_window(DEFAULT_AVERAGE_WINDOW)
+    }
 
-        let sample = PowerSample {
-            cpu_power: info.cpu_power_watts,
-            gpu_power: info.gpu_power_watts,
-            system_power: info.system_power_watts,
-        };
-        samples.push_back(sample);
+    /// Like [`Self::new`], with [`Self::rolling_average`] over the last
+    /// `average_window` raw readings.
+    pub fn with_average_window(average_window: usize) -> Result<Self> {
+        let (worker, first) = PowerWorker::spawn(PlatformPower::new, DEFAULT_SAMPLE_INTERVAL)?;
+        let mut samples = VecDeque::with_capacity(DEFAULT_SMOOTHING_SAMPLES);
+        samples.push_back(sample_of(&first));
 
-        Ok(Self { provider, samples })
+        let mut power = Self {
+            last_known_mode: first.info.power_mode,
+            worker,
+            info: first.info,
+            samples,
+            smoothing_samples: DEFAULT_SMOOTHING_SAMPLES,
+            stats: PowerStats::new(average_window),
+            stats_include_fallback: false,
+            cpus: sysinfo::System::new(),
+            core_usages: Vec::new(),
+            energy_baseline: [0.0; 3],
+            refresh_times: Vec::new(),
+        };
+        power.record_stats();
+        Ok(power)
     }
 
+    /// Takes in the readings the sampling thread made since the last call.
+    /// Does not wait for a new one, so it may add no sample at all; once
+    /// the thread stopped, the last readings stay and
+    /// [`Self::sampling_failure`] says why.
     pub fn refresh(&mut self) -> Result<()> {
-        self.provider.refresh()?;
-        self.record_sample();
+        let readings = self.worker.drain();
+        self.take_readings(readings);
         Ok(())
     }
 
-    fn record_sample(&mut self) {
-        let info = self.provider.info();
-        let sample = PowerSample {
-            cpu_power: info.cpu_power_watts,
-            gpu_power: info.gpu_power_watts,
-            system_power: info.system_power_watts,
-        };
+    /// Like [`Self::refresh`], but waits up to `timeout` for the sampling
+    /// thread's next reading, for one-shot commands that need a fresh one.
+    pub fn wait_for_sample(&mut self, timeout: Duration) -> Result<()> {
+        let readings = self.worker.wait(timeout);
+        if readings.is_empty() {
+            match self.sampling_failure() {
+                Some(reason) => bail!("Power sampling stopped: {}", reason),
+                None => bail!("No power sample within {}ms", timeout.as_millis()),
+            }
+        }
+        self.take_readings(readings);
+        Ok(())
+    }
+
+    fn take_readings(&mut self, readings: Vec<PowerReading>) {
+        self.refresh_times.clear();
+        for reading in readings {
+            self.refresh_times.extend(reading.refresh_time);
+            self.push_sample(sample_of(&reading));
+            self.info = reading.info;
+            self.record_stats();
+        }
+        self.cpus.refresh_cpu_usage();
+        self.core_usages = self.cpus.cpus().iter().map(|cpu| cpu.cpu_usage()).collect();
+    }
 
-        if self.samples.len() >= SMOOTHING_SAMPLE_COUNT {
+    /// Wait between provider samples on the sampling thread, independent
+    /// of how often the UI refreshes.
+    pub fn set_sample_interval(&mut self, interval: Duration) {
+        self.worker.set_interval(interval);
+    }
+
+    /// How long the provider took to refresh for each reading the last
+    /// [`Self::refresh`] or [`Self::wait_for_sample`] took in: the sampling
+    /// cost, which `refresh` itself no longer pays.
+    pub fn last_refresh_times(&self) -> &[Duration] {
+        &self.refresh_times
+    }
+
+    /// When the sampling thread took the newest reading.
+    pub fn sampled_at(&self) -> Instant {
+        self.samples
+            .back()
+            .map_or_else(Instant::now, |s| s.taken_at)
+    }
+
+    /// Why the sampling thread stopped; `None` while readings are current.
+    pub fn sampling_failure(&self) -> Option<&str> {
+        self.worker.failure()
+    }
+
+    fn push_sample(&mut self, sample: PowerSample) {
+        while self.samples.len() >= self.smoothing_samples {
             self.samples.pop_front();
         }
         self.samples.push_back(sample);
     }
 
+    /// Adds the newest reading's raw values to the peaks and rolling
+    /// average. Fallback estimates are left out unless
+    /// [`Self::set_stats_include_fallback`] opted in.
+    fn record_stats(&mut self) {
+        if self.info.is_fallback_estimate && !self.stats_include_fallback {
+            return;
+        }
+        if let Some(readings) = self.info.raw_readings {
+            self.stats.record(readings);
+        }
+    }
+
+    pub fn smoothing_samples(&self) -> usize {
+        self.smoothing_samples
+    }
+
+    /// Sets how many samples the displayed power averages, clamped to
+    /// [`MIN_WARMUP_SAMPLES`]..=[`MAX_SMOOTHING_SAMPLES`]. Shrinking the
+    /// window drops the oldest samples right away.
+    pub fn set_smoothing_samples(&mut self, count: usize) {
+        self.smoothing_samples = count.clamp(MIN_WARMUP_SAMPLES, MAX_SMOOTHING_SAMPLES);
+        let excess = self.samples.len().saturating_sub(self.smoothing_samples);
+        self.samples.drain(..excess);
+    }
+
     fn smoothed_value<F>(&self, extractor: F) -> f32
     where
         F: Fn(&PowerSample) -> f32,
@@ -82,42 +281,910 @@ impl PowerData {
         self.smoothed_value(|s| s.cpu_power)
     }
 
+    /// Smoothed power of the efficiency core cluster(s). Zero where the
+    /// channels do not name clusters: Linux, Intel Macs, the macOS fallback
+    /// estimate and daemon data.
+    pub fn ecpu_power_watts(&self) -> f32 {
+        self.smoothed_value(|s| s.ecpu_power)
+    }
+
+    /// Smoothed power of the performance core cluster(s); zero in the same
+    /// cases as [`Self::ecpu_power_watts`].
+    pub fn pcpu_power_watts(&self) -> f32 {
+        self.smoothed_value(|s| s.pcpu_power)
+    }
+
+    /// Watts per CPU cluster in the last sample's raw channels, keyed by
+    /// cluster name ("ECPU", "PACC0", ...) in name order, so numbered
+    /// clusters on Max and Ultra chips are listed apart. Per-core channels
+    /// are counted towards their cluster. Empty where the channels do not
+    /// name clusters; see [`Self::ecpu_power_watts`].
+    pub fn cpu_cluster_power(&self) -> Vec<(String, f32)> {
+        cluster_power(self.raw_channels())
+    }
+
     pub fn gpu_power_watts(&self) -> f32 {
         self.smoothed_value(|s| s.gpu_power)
     }
 
+    /// Smoothed Neural Engine power. Zero on Linux, with the macOS fallback
+    /// estimate and while showing daemon data, whose snapshots carry no ANE
+    /// reading.
+    pub fn ane_power_watts(&self) -> f32 {
+        self.smoothed_value(|s| s.ane_power)
+    }
+
+    /// Smoothed whole-system power, which includes the CPU, GPU and
+    /// [Neural Engine](Self::ane_power_watts) rather than adding to them.
+    /// On macOS it is the SMC system reading when available, and never less
+    /// than the summed package channels (CPU, GPU, ANE and other SoC blocks);
+    /// on Linux it is the sum of the RAPL CPU and GPU domains.
     pub fn total_power_watts(&self) -> f32 {
         self.smoothed_value(|s| s.system_power)
     }
 
+    /// Power per hardware block, one entry for each of
+    /// [`ChannelCategory::ALL`] in that order, so blocks drawing nothing
+    /// still appear.
+    ///
+    /// Taken from the last sample's raw channels, where `Other` collects
+    /// every channel the name heuristics do not place, including ones (such
+    /// as PCIe) left out of [`Self::total_power_watts`]; the entries can
+    /// therefore sum to more than the total. Without raw channels (daemon
+    /// data, the macOS fallback estimate) CPU and GPU are the smoothed
+    /// readings and the rest of the total counts as `Other`.
+    pub fn component_breakdown(&self) -> Vec<(ChannelCategory, f32)> {
+        let channels = self.raw_channels();
+        if channels.is_empty() {
+            let (cpu, gpu) = (self.cpu_power_watts(), self.gpu_power_watts());
+            let other = (self.total_power_watts() - cpu - gpu).max(0.0);
+            return ChannelCategory::ALL
+                .into_iter()
+                .map(|category| {
+                    let watts = match category {
+                        ChannelCategory::Cpu => cpu,
+                        ChannelCategory::Gpu => gpu,
+                        ChannelCategory::Other => other,
+                        ChannelCategory::Ane | ChannelCategory::Dram => 0.0,
+                    };
+                    (category, watts)
+                })
+                .collect();
+        }
+        breakdown_from(channels)
+    }
+
+    /// Hours `battery` would last at the current total power; see
+    /// [`estimated_battery_hours`].
+    pub fn battery_drain_model(&self, battery: &BatteryData) -> Option<f32> {
+        estimated_battery_hours(self, battery)
+    }
+
+    /// CPU energy used since startup or the last [`Self::reset_energy`],
+    /// summed from the hardware energy counters at every `refresh()`. Not
+    /// counted while showing daemon data.
+    pub fn cpu_energy_joules(&self) -> f64 {
+        self.energy_totals()[0] - self.energy_baseline[0]
+    }
+
+    /// GPU energy, counted like [`Self::cpu_energy_joules`]. Zero on Linux,
+    /// where the GPU has no energy counter.
+    pub fn gpu_energy_joules(&self) -> f64 {
+        self.energy_totals()[1] - self.energy_baseline[1]
+    }
+
+    /// Neural Engine energy, counted like [`Self::cpu_energy_joules`]. Zero
+    /// on Linux.
+    pub fn ane_energy_joules(&self) -> f64 {
+        self.energy_totals()[2] - self.energy_baseline[2]
+    }
+
+    /// Starts the energy counters over from zero, e.g. before a benchmark.
+    pub fn reset_energy(&mut self) {
+        self.energy_baseline = self.energy_totals();
+    }
+
+    fn energy_totals(&self) -> [f64; 3] {
+        let info = &self.info;
+        [
+            info.cpu_energy_joules,
+            info.gpu_energy_joules,
+            info.ane_energy_joules,
+        ]
+    }
+
+    /// Smoothed CPU power per active core, in watts: cores above
+    /// [`ACTIVE_CORE_THRESHOLD_PERCENT`] usage at the last refresh share the
+    /// whole of [`Self::cpu_power_watts`]. Falls back to the undivided CPU
+    /// power when no core usage is known or no core is active.
+    pub fn cpu_power_density(&self) -> f32 {
+        power_density(self.cpu_power_watts(), &self.core_usages)
+    }
+
+    /// Highest unsmoothed CPU power since startup or the last
+    /// [`Self::reset_peaks`], catching spikes the smoothed readings flatten.
+    pub fn peak_cpu_power_watts(&self) -> f32 {
+        self.stats.peaks().cpu_watts
+    }
+
+    /// Highest unsmoothed GPU power; see [`Self::peak_cpu_power_watts`].
+    pub fn peak_gpu_power_watts(&self) -> f32 {
+        self.stats.peaks().gpu_watts
+    }
+
+    /// Highest unsmoothed package power; see [`Self::peak_cpu_power_watts`].
+    pub fn peak_total_power_watts(&self) -> f32 {
+        self.stats.peaks().total_watts
+    }
+
+    pub fn reset_peaks(&mut self) {
+        self.stats.reset_peaks();
+    }
+
+    /// Mean of the last raw readings, as many as the average window holds.
+    pub fn rolling_average(&self) -> PowerReadings {
+        self.stats.average()
+    }
+
+    /// Whether CPU-usage estimates count toward the peaks and rolling
+    /// average; off by default, as the estimate's 2-17 W range would set
+    /// peaks no counter measured.
+    pub fn set_stats_include_fallback(&mut self, include: bool) {
+        self.stats_include_fallback = include;
+    }
+
+    /// CPU and GPU power from the most recent sample, without smoothing.
+    pub fn latest_cpu_gpu_watts(&self) -> (f32, f32) {
+        self.samples
+            .back()
+            .map_or((0.0, 0.0), |s| (s.cpu_power, s.gpu_power))
+    }
+
+    /// Total power averaged over the smoothing window with custom weights.
+    ///
+    /// `weights` are matched to the most recent samples, oldest first, so the
+    /// last weight applies to the newest sample; increasing weights emphasise
+    /// recent readings. Extra weights beyond the number of buffered samples
+    /// are ignored, and fewer weights average only the most recent samples.
+    /// Weights are normalized to sum to 1.0.
+    ///
+    /// Returns 0.0 when `weights` is empty or holds a negative or non-finite
+    /// value, and when no buffered sample gets a positive weight (which
+    /// includes an empty buffer).
+    pub fn weighted_average_watts(&self, weights: &[f32]) -> f32 {
+        let values: Vec<f32> = self.samples.iter().map(|s| s.system_power).collect();
+        weighted_average(&values, weights).unwrap_or(0.0)
+    }
+
+    /// Per-channel readings from the provider's most recent sample.
+    ///
+    /// Empty while showing daemon data, since snapshots only carry totals.
+    pub fn raw_channels(&self) -> &[ChannelPower] {
+        &self.info.channels
+    }
+
+    /// Share of total power drawn by the named channel in the last sample,
+    /// as a percentage.
+    ///
+    /// Returns `None` if the channel was not captured, or if total power is
+    /// not positive.
+    pub fn channel_contribution_percent(&self, channel_name: &str) -> Option<f32> {
+        contribution_percent(self.raw_channels(), channel_name, self.total_power_watts())
+    }
+
+    /// Summed watts of the last sample's raw channels in `category`.
+    ///
+    /// Zero when no channel of that category was captured, which includes
+    /// daemon data and the macOS fallback estimate. Aggregate channels are
+    /// skipped, so overlapping channels are counted once.
+    pub fn channel_category_watts(&self, category: ChannelCategory) -> f32 {
+        category_watts(self.raw_channels(), category).unwrap_or(0.0)
+    }
+
+    /// Neural Engine and DRAM power from the last sample's raw channels.
+    ///
+    /// Each is `None` when no matching channel was captured, which includes
+    /// daemon data, Linux and the macOS fallback estimate.
+    pub fn soc_power(&self) -> SocPower {
+        soc_power_from(self.raw_channels())
+    }
+
+    /// GPU performance state residency since the previous refresh. `None`
+    /// on machines without the IOReport "GPU Stats" group, on Linux, and
+    /// while showing daemon data.
+    pub fn gpu_residency(&self) -> Option<&GpuResidency> {
+        self.info.gpu_residency.as_ref()
+    }
+
+    /// Share of GPU busy time spent on compute work (Metal compute, ML)
+    /// since the previous refresh, from 0 to 1. Together with
+    /// [`Self::gpu_render_fraction`] and [`Self::gpu_power_watts`] it tells
+    /// what kind of load the GPU is under. `None` where "GPU Stats" has no
+    /// compute and render channels, on Linux, with daemon data and while the
+    /// GPU was idle.
+    pub fn gpu_compute_fraction(&self) -> Option<f32> {
+        self.info
+            .gpu_workload
+            .map(|workload| workload.compute_fraction)
+    }
+
+    /// Share of GPU busy time spent drawing, from 0 to 1; `None` in the same
+    /// cases as [`Self::gpu_compute_fraction`].
+    pub fn gpu_render_fraction(&self) -> Option<f32> {
+        self.info
+            .gpu_workload
+            .map(|workload| workload.render_fraction)
+    }
+
+    /// Starts sampling CPU cluster frequencies on every later `refresh()`,
+    /// read back with [`PowerData::cpu_freq_mhz`]. Fails on Linux and on
+    /// Macs without the IOReport "CPU Stats" group.
+    pub fn subscribe_to_cpu_stats(&mut self) -> Result<()> {
+        self.worker.subscribe_cpu_stats()
+    }
+
+    /// Average MHz per CPU cluster since the previous refresh; empty until
+    /// [`PowerData::subscribe_to_cpu_stats`] succeeded.
+    pub fn cpu_freq_mhz(&self) -> &[(String, f32)] {
+        &self.info.cpu_freq_mhz
+    }
+
+    /// Energy Model subgroups the provider narrowed its subscription to;
+    /// `None` when it reads the whole group.
+    pub fn energy_model_subgroups(&self) -> Option<&[String]> {
+        self.info.energy_model_subgroups.as_deref()
+    }
+
+    /// Recommended wait before the next `refresh()` so total power moves by
+    /// no more than `target_accuracy_watts` in between.
+    ///
+    /// The rate of change is the least-squares trend of total power across
+    /// the smoothing window. Rapidly changing power shortens the interval and
+    /// stable power lengthens it, within the TUI's refresh bounds. Until two
+    /// distinct samples exist the shortest interval is returned.
+    pub fn adaptive_refresh_interval(&self, target_accuracy_watts: f32) -> Duration {
+        let Some(first) = self.samples.front() else {
+            return Duration::from_millis(MIN_REFRESH_MS);
+        };
+        let points: Vec<(f32, f32)> = self
+            .samples
+            .iter()
+            .map(|s| {
+                let secs = s.taken_at.duration_since(first.taken_at).as_secs_f32();
+                (secs, s.system_power)
+            })
+            .collect();
+        refresh_interval_for(&points, target_accuracy_watts)
+    }
+
+    /// Drops the smoothing window except for the newest sample, so averages
+    /// start over from the latest reading.
+    pub fn reset(&mut self) {
+        let newest = self.samples.pop_back();
+        self.samples.clear();
+        self.samples.extend(newest);
+    }
+
+    /// Calls [`Self::reset`] if the power mode changed since the last call,
+    /// so averages never mix readings from two modes. Returns whether a reset
+    /// happened.
+    pub fn reset_on_mode_change(&mut self) -> bool {
+        let mode = self.power_mode();
+        if mode == self.last_known_mode {
+            return false;
+        }
+        self.last_known_mode = mode;
+        self.reset();
+        true
+    }
+
+    /// Whether CPU and GPU power come from the CPU-usage estimate instead of
+    /// IOReport energy counters. The estimate scales a fixed 2-17 W CPU range
+    /// by CPU usage and assumes 1 W of GPU, so it only shows trends; absolute
+    /// values and the CPU/GPU split should not be trusted. Always `false` on
+    /// Linux, where RAPL is read directly.
+    pub fn is_using_fallback(&self) -> bool {
+        self.info.is_fallback_estimate
+    }
+
     pub fn is_warmed_up(&self) -> bool {
         self.samples.len() >= MIN_WARMUP_SAMPLES
     }
 
     pub fn power_mode(&self) -> PowerMode {
-        self.provider.info().power_mode
+        self.info.power_mode
+    }
+
+    /// Calls `on_change` from a background thread each time the power mode
+    /// changes, until the handle is dropped. Never fires on Linux, where the
+    /// mode is not known.
+    pub fn subscribe_notification(
+        on_change: impl Fn(PowerMode) + Send + 'static,
+    ) -> jolt_platform::NotificationHandle {
+        PlatformPower::subscribe_notification(on_change)
+    }
+
+    pub fn thermal_pressure(&self) -> ThermalPressure {
+        self.info.thermal_pressure
     }
 
     pub fn power_mode_label(&self) -> &'static str {
-        self.provider.info().power_mode.label()
+        self.info.power_mode.label()
+    }
+
+    /// The smoothed readings as one InfluxDB line protocol line in the
+    /// `power` measurement, tagged with `power_mode` and whether the values
+    /// are a fallback `estimate`.
+    pub fn serialize_to_influx(&self) -> String {
+        let soc = self.soc_power();
+        influx::line(
+            "power",
+            &[
+                ("power_mode", self.power_mode_label()),
+                (
+                    "estimate",
+                    if self.is_using_fallback() {
+                        "true"
+                    } else {
+                        "false"
+                    },
+                ),
+            ],
+            &[
+                ("cpu_power_watts", Some(self.cpu_power_watts())),
+                ("gpu_power_watts", Some(self.gpu_power_watts())),
+                ("total_power_watts", Some(self.total_power_watts())),
+                ("ane_power_watts", soc.ane_watts),
+                ("dram_power_watts", soc.dram_watts),
+                ("peak_power_watts", Some(self.peak_total_power_watts())),
+            ],
+            influx::now_ns(),
+        )
+    }
+
+    /// The smoothed readings on one line for people to read, such as
+    /// `CPU: 8.3W, GPU: 2.1W, ANE: 0.4W, Total: 10.8W [Automatic]`. Watts
+    /// have one decimal; the power mode is in brackets.
+    pub fn formatted_summary(&self) -> String {
+        summary_line(
+            self.cpu_power_watts(),
+            self.gpu_power_watts(),
+            self.ane_power_watts(),
+            self.total_power_watts(),
+            self.power_mode(),
+        )
     }
 
     pub fn update_from_snapshot(&mut self, snapshot: &PowerSnapshot) {
+        // The daemon's readings replace this machine's sampler; drop its
+        // readings so they do not pile up in the channel
+        self.worker.drain();
         let sample = PowerSample {
             cpu_power: snapshot.cpu_power_watts,
+            ecpu_power: 0.0,
+            pcpu_power: 0.0,
             gpu_power: snapshot.gpu_power_watts,
+            ane_power: 0.0,
             system_power: snapshot.total_power_watts,
+            taken_at: Instant::now(),
         };
+        self.core_usages.clear();
 
         if self.samples.is_empty() {
             for _ in 0..MIN_WARMUP_SAMPLES {
                 self.samples.push_back(sample);
             }
         } else {
-            if self.samples.len() >= SMOOTHING_SAMPLE_COUNT {
-                self.samples.pop_front();
+            self.push_sample(sample);
+        }
+    }
+}
+
+/// Names of the IOReport channels in `group` on this machine, such as
+/// "Energy Model" or "GPU Stats". They differ between chip generations and
+/// macOS releases, so this shows what can be sampled before relying on it.
+/// Nothing stays subscribed afterwards.
+#[cfg(target_os = "macos")]
+pub fn list_ioreport_channels(group: &str) -> Result<Vec<String>> {
+    jolt_platform::macos::list_channel_names(group)
+        .ok_or_else(|| color_eyre::eyre::eyre!("IOReport group \"{}\" is not available", group))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn list_ioreport_channels(_group: &str) -> Result<Vec<String>> {
+    bail!("IOReport channels are only available on macOS")
+}
+
+/// Remaining battery runtime in hours: the energy left divided by the
+/// smoothed total power. This assumes the whole draw comes out of the
+/// battery, so it ignores charging efficiency and any share supplied by the
+/// adapter. `None` without a capacity reading or when no power is drawn.
+pub fn estimated_battery_hours(power: &PowerData, battery: &BatteryData) -> Option<f32> {
+    drain_hours(battery.watt_hours_remaining()?, power.total_power_watts())
+}
+
+fn summary_line(cpu: f32, gpu: f32, ane: f32, total: f32, mode: PowerMode) -> String {
+    format!(
+        "CPU: {:.1}W, GPU: {:.1}W, ANE: {:.1}W, Total: {:.1}W [{}]",
+        cpu,
+        gpu,
+        ane,
+        total,
+        mode.label()
+    )
+}
+
+fn drain_hours(watt_hours: f32, watts: f32) -> Option<f32> {
+    (watts.is_finite() && watts > 0.0).then(|| watt_hours / watts)
+}
+
+fn power_density(cpu_watts: f32, core_usages: &[f32]) -> f32 {
+    let active = core_usages
+        .iter()
+        .filter(|&&usage| usage > ACTIVE_CORE_THRESHOLD_PERCENT)
+        .count();
+    if active == 0 {
+        cpu_watts
+    } else {
+        cpu_watts / active as f32
+    }
+}
+
+fn contribution_percent(channels: &[ChannelPower], name: &str, total_watts: f32) -> Option<f32> {
+    let channel = channels.iter().find(|c| c.name == name)?;
+    (total_watts > 0.0).then(|| channel.watts / total_watts * 100.0)
+}
+
+/// Interval over which the trend through `points` (seconds, watts) changes
+/// by `target_watts`, clamped to the refresh bounds.
+fn refresh_interval_for(points: &[(f32, f32)], target_watts: f32) -> Duration {
+    let min = Duration::from_millis(MIN_REFRESH_MS);
+    let max = Duration::from_millis(MAX_REFRESH_MS);
+    if !(target_watts.is_finite() && target_watts > 0.0) || points.len() < 2 {
+        return min;
+    }
+
+    let n = points.len() as f32;
+    let mean_t = points.iter().map(|(t, _)| t).sum::<f32>() / n;
+    let mean_w = points.iter().map(|(_, w)| w).sum::<f32>() / n;
+    let (cov, var) = points.iter().fold((0.0, 0.0), |(cov, var), (t, w)| {
+        let dt = t - mean_t;
+        (cov + dt * (w - mean_w), var + dt * dt)
+    });
+    if var <= f32::EPSILON {
+        return min;
+    }
+
+    let watts_per_sec = (cov / var).abs();
+    if watts_per_sec <= f32::EPSILON {
+        return max;
+    }
+    Duration::from_secs_f32((target_watts / watts_per_sec).min(max.as_secs_f32())).clamp(min, max)
+}
+
+/// `None` when no channel falls in `category`.
+fn category_watts(channels: &[ChannelPower], category: ChannelCategory) -> Option<f32> {
+    channels
+        .iter()
+        .filter(|c| !c.aggregate && ChannelCategory::of(&c.name) == category)
+        .map(|c| c.watts)
+        .reduce(|a, b| a + b)
+}
+
+/// The smoothing window's sample for one reading from the worker.
+fn sample_of(reading: &PowerReading) -> PowerSample {
+    let info = &reading.info;
+    PowerSample {
+        cpu_power: info.cpu_power_watts,
+        ecpu_power: cluster_watts(&info.channels, CpuCluster::Efficiency),
+        pcpu_power: cluster_watts(&info.channels, CpuCluster::Performance),
+        gpu_power: info.gpu_power_watts,
+        ane_power: info.ane_power_watts,
+        system_power: info.system_power_watts,
+        taken_at: reading.taken_at,
+    }
+}
+
+/// Summed watts of the non-aggregate channels of `cluster`, so a cluster
+/// total next to its per-core channels counts once.
+fn cluster_watts(channels: &[ChannelPower], cluster: CpuCluster) -> f32 {
+    channels
+        .iter()
+        .filter(|c| !c.aggregate && CpuCluster::of(&c.name) == Some(cluster))
+        .map(|c| c.watts)
+        .sum()
+}
+
+/// Watts of every non-aggregate cluster channel, summed by the cluster it
+/// belongs to.
+fn cluster_power(channels: &[ChannelPower]) -> Vec<(String, f32)> {
+    let mut clusters: BTreeMap<String, f32> = BTreeMap::new();
+    for channel in channels
+        .iter()
+        .filter(|c| !c.aggregate && CpuCluster::of(&c.name).is_some())
+    {
+        *clusters
+            .entry(cluster_name(&channel.name, channels))
+            .or_default() += channel.watts;
+    }
+    clusters.into_iter().collect()
+}
+
+/// The cluster a channel reports for: the part before `_` for names like
+/// "PACC0_CPU1", or the aggregate a per-core channel is numbered after
+/// ("ECPU" for "ECPU2"). Otherwise the channel is a cluster of its own, as
+/// "ECPU1" is on chips with two E-clusters.
+fn cluster_name(channel: &str, channels: &[ChannelPower]) -> String {
+    if let Some((cluster, _)) = channel.split_once('_') {
+        return cluster.to_string();
+    }
+    channels
+        .iter()
+        .filter(|c| c.aggregate)
+        .find(|c| {
+            channel
+                .strip_prefix(c.name.as_str())
+                .is_some_and(|rest| !rest.is_empty() && rest.chars().all(|ch| ch.is_ascii_digit()))
+        })
+        .map_or_else(|| channel.to_string(), |c| c.name.clone())
+}
+
+fn breakdown_from(channels: &[ChannelPower]) -> Vec<(ChannelCategory, f32)> {
+    ChannelCategory::ALL
+        .into_iter()
+        .map(|category| (category, category_watts(channels, category).unwrap_or(0.0)))
+        .collect()
+}
+
+fn soc_power_from(channels: &[ChannelPower]) -> SocPower {
+    SocPower {
+        ane_watts: category_watts(channels, ChannelCategory::Ane),
+        dram_watts: category_watts(channels, ChannelCategory::Dram),
+    }
+}
+
+fn weighted_average(values: &[f32], weights: &[f32]) -> Option<f32> {
+    if weights.is_empty() || weights.iter().any(|w| *w < 0.0 || !w.is_finite()) {
+        return None;
+    }
+
+    let pairs: Vec<(f32, f32)> = values
+        .iter()
+        .rev()
+        .zip(weights.iter().rev())
+        .map(|(v, w)| (*v, *w))
+        .collect();
+
+    let weight_sum: f32 = pairs.iter().map(|(_, w)| w).sum();
+    if weight_sum <= 0.0 {
+        return None;
+    }
+
+    Some(pairs.iter().map(|(v, w)| v * (w / weight_sum)).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::powermetrics::parse_samples;
+    use jolt_platform::OverlapDetector;
+
+    #[test]
+    fn weights_align_with_most_recent_samples() {
+        let values = [10.0, 20.0, 30.0];
+
+        let uniform = weighted_average(&values, &[1.0, 1.0, 1.0]).unwrap();
+        assert!((uniform - 20.0).abs() < 1e-4);
+        assert_eq!(weighted_average(&values, &[1.0, 3.0]).unwrap(), 27.5);
+        assert_eq!(
+            weighted_average(&values, &[5.0, 0.0, 0.0, 1.0]).unwrap(),
+            30.0
+        );
+    }
+
+    #[test]
+    fn drain_hours_divides_energy_by_power() {
+        assert_eq!(drain_hours(50.0, 10.0), Some(5.0));
+        assert_eq!(drain_hours(50.0, 0.0), None);
+        assert_eq!(drain_hours(50.0, f32::NAN), None);
+    }
+
+    #[test]
+    fn summary_line_rounds_to_one_decimal_and_names_the_mode() {
+        assert_eq!(
+            summary_line(8.26, 2.1, 0.42, 10.81, PowerMode::Automatic),
+            "CPU: 8.3W, GPU: 2.1W, ANE: 0.4W, Total: 10.8W [Automatic]"
+        );
+        assert_eq!(
+            summary_line(1.0, 0.0, 0.0, 1.0, PowerMode::LowPower),
+            "CPU: 1.0W, GPU: 0.0W, ANE: 0.0W, Total: 1.0W [Low Power]"
+        );
+    }
+
+    #[test]
+    fn breakdown_lists_every_category_and_keeps_unknown_channels() {
+        let channel = |name: &str, watts: f32, aggregate: bool| ChannelPower {
+            name: name.to_string(),
+            watts,
+            aggregate,
+        };
+        let channels = [
+            channel("ECPU", 0.5, false),
+            channel("PCPU", 2.0, false),
+            channel("CPU Energy", 2.5, true),
+            channel("GPU0", 1.0, false),
+            channel("DRAM0", 0.25, false),
+            channel("PCIe Port 0", 0.125, false),
+        ];
+
+        assert_eq!(
+            breakdown_from(&channels),
+            vec![
+                (ChannelCategory::Cpu, 2.5),
+                (ChannelCategory::Gpu, 1.0),
+                (ChannelCategory::Ane, 0.0),
+                (ChannelCategory::Dram, 0.25),
+                (ChannelCategory::Other, 0.125),
+            ]
+        );
+        assert!(breakdown_from(&[]).iter().all(|(_, watts)| *watts == 0.0));
+    }
+
+    #[test]
+    fn cluster_channels_split_cpu_power() {
+        assert_eq!(CpuCluster::of("ECPU1"), Some(CpuCluster::Efficiency));
+        assert_eq!(CpuCluster::of("EACC_CPU0"), Some(CpuCluster::Efficiency));
+        assert_eq!(CpuCluster::of("pcpu"), Some(CpuCluster::Performance));
+        assert_eq!(CpuCluster::of("PACC0_CPU"), Some(CpuCluster::Performance));
+        assert_eq!(CpuCluster::of("CPU Energy"), None);
+        assert_eq!(CpuCluster::of("ECPM"), None);
+        assert_eq!(CpuCluster::of("P-Cluster 1"), Some(CpuCluster::Performance));
+
+        let channel = |name: &str, watts: f32, aggregate: bool| ChannelPower {
+            name: name.to_string(),
+            watts,
+            aggregate,
+        };
+        let channels = [
+            channel("ECPU", 0.75, true),
+            channel("ECPU0", 0.5, false),
+            channel("ECPU1", 0.25, false),
+            channel("PCPU", 4.0, false),
+            channel("GPU0", 1.0, false),
+        ];
+        assert_eq!(cluster_watts(&channels, CpuCluster::Efficiency), 0.75);
+        assert_eq!(cluster_watts(&channels, CpuCluster::Performance), 4.0);
+    }
+
+    #[test]
+    fn cluster_power_groups_cores_under_their_cluster() {
+        let channel = |name: &str, watts: f32, aggregate: bool| ChannelPower {
+            name: name.to_string(),
+            watts,
+            aggregate,
+        };
+
+        // M1: one cluster total over numbered cores.
+        let m1 = [
+            channel("ECPU", 0.75, true),
+            channel("ECPU0", 0.5, false),
+            channel("ECPU1", 0.25, false),
+            channel("PCPU", 4.0, false),
+        ];
+        assert_eq!(
+            cluster_power(&m1),
+            vec![("ECPU".to_string(), 0.75), ("PCPU".to_string(), 4.0)]
+        );
+
+        // M3 Max: numbered clusters with per-core channels after `_`.
+        let m3_max = [
+            channel("EACC_CPU", 0.5, true),
+            channel("EACC_CPU0", 0.5, false),
+            channel("PACC0_CPU", 3.0, true),
+            channel("PACC0_CPU0", 1.0, false),
+            channel("PACC0_CPU1", 2.0, false),
+            channel("PACC1_CPU0", 1.5, false),
+            channel("CPU Energy", 5.0, true),
+            channel("GPU0", 2.0, false),
+        ];
+        assert_eq!(
+            cluster_power(&m3_max),
+            vec![
+                ("EACC".to_string(), 0.5),
+                ("PACC0".to_string(), 3.0),
+                ("PACC1".to_string(), 1.5),
+            ]
+        );
+
+        // Two E-clusters, neither a total of the other.
+        let two_clusters = [channel("ECPU0", 0.5, false), channel("ECPU1", 0.25, false)];
+        assert_eq!(cluster_power(&two_clusters).len(), 2);
+    }
+
+    #[test]
+    fn invalid_weights_are_rejected() {
+        let values = [10.0, 20.0];
+
+        assert_eq!(weighted_average(&values, &[]), None);
+        assert_eq!(weighted_average(&values, &[1.0, -0.5]), None);
+        assert_eq!(weighted_average(&values, &[f32::NAN]), None);
+        assert_eq!(weighted_average(&values, &[0.0, 0.0]), None);
+        assert_eq!(weighted_average(&[], &[1.0]), None);
+    }
+
+    #[test]
+    fn refresh_interval_follows_rate_of_change() {
+        let min = Duration::from_millis(MIN_REFRESH_MS);
+        let max = Duration::from_millis(MAX_REFRESH_MS);
+
+        let ramp = [(0.0, 10.0), (1.0, 11.0), (2.0, 12.0), (3.0, 13.0)];
+        assert_eq!(refresh_interval_for(&ramp, 2.0), Duration::from_secs(2));
+
+        let steep = [(0.0, 10.0), (1.0, 30.0), (2.0, 50.0)];
+        assert_eq!(refresh_interval_for(&steep, 0.5), min);
+
+        let flat = [(0.0, 10.0), (1.0, 10.0), (2.0, 10.0)];
+        assert_eq!(refresh_interval_for(&flat, 0.5), max);
+    }
+
+    #[test]
+    fn refresh_interval_without_trend_is_shortest() {
+        let min = Duration::from_millis(MIN_REFRESH_MS);
+
+        assert_eq!(refresh_interval_for(&[(0.0, 10.0)], 1.0), min);
+        assert_eq!(refresh_interval_for(&[(0.0, 10.0), (0.0, 12.0)], 1.0), min);
+        assert_eq!(refresh_interval_for(&[(0.0, 10.0), (1.0, 12.0)], 0.0), min);
+    }
+
+    #[test]
+    fn soc_power_sums_matching_channels() {
+        let channel = |name: &str, watts| ChannelPower {
+            name: name.to_string(),
+            watts,
+            aggregate: false,
+        };
+        let channels = vec![
+            channel("ANE0", 0.25),
+            channel("ANE1", 0.5),
+            channel("DRAM0", 1.0),
+            channel("GPU0", 3.0),
+        ];
+
+        let soc = soc_power_from(&channels);
+        assert_eq!(soc.ane_watts, Some(0.75));
+        assert_eq!(soc.dram_watts, Some(1.0));
+        assert_eq!(soc_power_from(&[]), SocPower::default());
+    }
+
+    #[test]
+    fn channels_are_summed_by_category() {
+        let channels: Vec<ChannelPower> = [
+            ("ECPU", 0.5),
+            ("PCPU1", 2.0),
+            ("GPU0", 3.0),
+            ("ANE0", 0.25),
+            ("DRAM0", 1.0),
+            ("AMCC", 0.5),
+            ("package-0", 4.0),
+            ("psys", 6.0),
+        ]
+        .into_iter()
+        .map(|(name, watts)| ChannelPower {
+            name: name.to_string(),
+            watts,
+            aggregate: false,
+        })
+        .collect();
+
+        let watts = |category| category_watts(&channels, category);
+        assert_eq!(watts(ChannelCategory::Cpu), Some(6.5));
+        assert_eq!(watts(ChannelCategory::Gpu), Some(3.0));
+        assert_eq!(watts(ChannelCategory::Ane), Some(0.25));
+        assert_eq!(watts(ChannelCategory::Dram), Some(1.0));
+        assert_eq!(watts(ChannelCategory::Other), Some(6.5));
+        assert_eq!(category_watts(&[], ChannelCategory::Cpu), None);
+    }
+
+    #[test]
+    fn overlapping_channels_are_counted_once_and_match_powermetrics() {
+        let captures: [(&str, &[u8]); 2] = [
+            (
+                include_str!("../../../fixtures/ioreport/m1-macos-12.jsonl"),
+                include_bytes!("../../../fixtures/powermetrics/m1-macos-12.plist"),
+            ),
+            (
+                include_str!("../../../fixtures/ioreport/m3-pro-macos-14.jsonl"),
+                include_bytes!("../../../fixtures/powermetrics/m3-pro-macos-14.plist"),
+            ),
+        ];
+
+        for (channels, reference) in captures {
+            let reference = parse_samples(reference).unwrap();
+            let samples: Vec<Vec<ChannelPower>> = channels
+                .lines()
+                .map(|line| {
+                    let readings: Vec<(String, f32)> = serde_json::from_str(line).unwrap();
+                    readings
+                        .into_iter()
+                        .map(|(name, watts)| ChannelPower {
+                            name,
+                            watts,
+                            aggregate: false,
+                        })
+                        .collect()
+                })
+                .collect();
+            assert_eq!(samples.len(), reference.len());
+
+            let mut detector = OverlapDetector::default();
+            for (mut sample, expected) in samples.into_iter().zip(&reference) {
+                let naive_cpu = category_watts(&sample, ChannelCategory::Cpu).unwrap();
+                assert!(naive_cpu > 1.9 * expected.cpu_watts);
+
+                detector.mark_aggregates(&mut sample, |name| Some(ChannelCategory::of(name)));
+                let watts = |category| category_watts(&sample, category).unwrap_or(0.0);
+                for (category, expected) in [
+                    (ChannelCategory::Cpu, expected.cpu_watts),
+                    (ChannelCategory::Gpu, expected.gpu_watts),
+                    (ChannelCategory::Ane, expected.ane_watts),
+                ] {
+                    assert!(
+                        (watts(category) - expected).abs() < 0.01,
+                        "{:?}: {} W, powermetrics {} W",
+                        category,
+                        watts(category),
+                        expected
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn categories_agree_with_the_platform_domains_on_ambiguous_names() {
+        let listing = include_str!("../../../fixtures/channels/ambiguous-names.txt");
+        for line in listing.lines() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
             }
-            self.samples.push_back(sample);
+            let (name, domain) = line.split_once('\t').unwrap();
+            let expected = match domain {
+                "cpu" => ChannelCategory::Cpu,
+                "gpu" => ChannelCategory::Gpu,
+                "ane" => ChannelCategory::Ane,
+                _ if name.to_lowercase().contains("dram") => ChannelCategory::Dram,
+                _ => ChannelCategory::Other,
+            };
+            assert_eq!(ChannelCategory::of(name), expected, "{}", name);
         }
+        assert_eq!(ChannelCategory::of("DRAM0"), ChannelCategory::Dram);
+        assert_eq!(ChannelCategory::of("GPU ANE"), ChannelCategory::Ane);
+    }
+
+    #[test]
+    fn cpu_power_is_divided_among_active_cores() {
+        assert_eq!(power_density(12.0, &[80.0, 40.0, 5.0, 0.0, 6.0]), 4.0);
+        assert_eq!(power_density(12.0, &[1.0, 0.0]), 12.0);
+        assert_eq!(power_density(12.0, &[]), 12.0);
+    }
+
+    #[test]
+    fn contribution_is_share_of_total() {
+        let channels = vec![
+            ChannelPower {
+                name: "GPU0".to_string(),
+                watts: 2.5,
+                aggregate: false,
+            },
+            ChannelPower {
+                name: "PACC_0".to_string(),
+                watts: 1.0,
+                aggregate: false,
+            },
+        ];
+
+        assert_eq!(contribution_percent(&channels, "GPU0", 10.0), Some(25.0));
+        assert_eq!(contribution_percent(&channels, "ANE0", 10.0), None);
+        assert_eq!(contribution_percent(&channels, "GPU0", 0.0), None);
     }
 }
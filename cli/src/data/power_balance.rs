@@ -0,0 +1,145 @@
+//! Whether the adapter keeps up with the load while plugged in.
+//!
+//! A 20 W charger under a 60 W load leaves the battery draining on AC, which
+//! reads as a failing battery unless it is called out. The balance is the
+//! power flowing into the battery, with the adapter's rating and input next
+//! to it so the shortfall can be attributed to the charger.
+
+use std::time::{Duration, SystemTime};
+
+use crate::daemon::NetPowerBalance;
+
+/// Battery power within which readings count as balanced: gauges report a
+/// trickle in or out while the battery rests at full or at its limit.
+pub const BALANCE_DEADBAND_WATTS: f32 = 0.5;
+
+/// How long a deficit has to last before the overview warns about it.
+pub const DEFICIT_WARNING_AFTER: Duration = Duration::from_secs(60);
+
+/// One battery reading, as needed to compute the balance.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BalanceInputs {
+    pub external_connected: bool,
+    /// Positive into the battery, negative out of it.
+    pub amperage_ma: i32,
+    pub voltage_mv: u32,
+    pub adapter_rated_watts: Option<u32>,
+    pub adapter_input_watts: Option<f32>,
+    /// Energy left in the battery, for the time to empty.
+    pub remaining_wh: Option<f32>,
+}
+
+/// The balance for `inputs`, or `None` while on battery power.
+pub fn net_power_balance(inputs: BalanceInputs) -> Option<NetPowerBalance> {
+    if !inputs.external_connected {
+        return None;
+    }
+
+    let watts = inputs.amperage_ma as f32 / 1000.0 * inputs.voltage_mv as f32 / 1000.0;
+    let net_watts = if watts.abs() < BALANCE_DEADBAND_WATTS {
+        0.0
+    } else {
+        watts
+    };
+    let minutes_to_empty = inputs
+        .remaining_wh
+        .filter(|_| net_watts < 0.0)
+        .map(|wh| wh / -net_watts * 60.0);
+
+    Some(NetPowerBalance {
+        net_watts,
+        adapter_rated_watts: inputs.adapter_rated_watts,
+        adapter_input_watts: inputs.adapter_input_watts,
+        minutes_to_empty,
+    })
+}
+
+/// Remembers when the current deficit started.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeficitTracker {
+    since: Option<SystemTime>,
+}
+
+impl DeficitTracker {
+    pub fn record(&mut self, at: SystemTime, balance: Option<&NetPowerBalance>) {
+        if balance.is_some_and(NetPowerBalance::is_deficit) {
+            self.since.get_or_insert(at);
+        } else {
+            self.since = None;
+        }
+    }
+
+    /// How long the battery has been draining on external power, zero when
+    /// it is not.
+    pub fn duration(&self, now: SystemTime) -> Duration {
+        self.since
+            .and_then(|since| now.duration_since(since).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugged_in(amperage_ma: i32) -> BalanceInputs {
+        BalanceInputs {
+            external_connected: true,
+            amperage_ma,
+            voltage_mv: 12_000,
+            adapter_rated_watts: Some(20),
+            adapter_input_watts: Some(19.4),
+            remaining_wh: Some(30.0),
+        }
+    }
+
+    #[test]
+    fn weak_charger_under_load_is_a_deficit_with_time_to_empty() {
+        // 20 W adapter, 60 W load: 2.5 A out of a 12 V pack is 30 W.
+        let balance = net_power_balance(plugged_in(-2500)).unwrap();
+        assert!(balance.is_deficit());
+        assert_eq!(balance.net_watts, -30.0);
+        assert_eq!(balance.minutes_to_empty, Some(60.0));
+        assert_eq!(balance.adapter_rated_watts, Some(20));
+
+        let charging = net_power_balance(plugged_in(1500)).unwrap();
+        assert!(!charging.is_deficit());
+        assert_eq!(charging.net_watts, 18.0);
+        assert_eq!(charging.minutes_to_empty, None);
+    }
+
+    #[test]
+    fn trickle_is_balanced_and_battery_power_has_no_balance() {
+        let resting = net_power_balance(plugged_in(-20)).unwrap();
+        assert_eq!(resting.net_watts, 0.0);
+        assert!(!resting.is_deficit());
+
+        let unplugged = BalanceInputs {
+            external_connected: false,
+            ..plugged_in(-2500)
+        };
+        assert_eq!(net_power_balance(unplugged), None);
+    }
+
+    #[test]
+    fn deficit_duration_restarts_after_recovering() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let deficit = net_power_balance(plugged_in(-2500));
+        let surplus = net_power_balance(plugged_in(1500));
+        let mut tracker = DeficitTracker::default();
+
+        tracker.record(start, deficit.as_ref());
+        tracker.record(start + Duration::from_secs(90), deficit.as_ref());
+        assert_eq!(
+            tracker.duration(start + Duration::from_secs(90)),
+            Duration::from_secs(90)
+        );
+
+        tracker.record(start + Duration::from_secs(100), surplus.as_ref());
+        tracker.record(start + Duration::from_secs(110), deficit.as_ref());
+        assert_eq!(
+            tracker.duration(start + Duration::from_secs(120)),
+            Duration::from_secs(10)
+        );
+    }
+}
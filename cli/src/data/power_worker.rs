@@ -0,0 +1,366 @@
+//! Power sampling on a dedicated thread.
+//!
+//! The platform provider holds IOReport subscriptions (raw CoreFoundation
+//! pointers) that must stay on the thread that created them, and a refresh
+//! can block: the macOS fallback estimate waits for sysinfo to measure CPU
+//! usage. The worker owns the provider, refreshes it every sample interval
+//! and sends each reading back, so [`crate::data::PowerData::refresh`] only
+//! drains what arrived and never stalls the UI.
+//!
+//! A worker whose refreshes keep failing gives up and says why, so the UI
+//! can show that power readings stopped rather than freezing on the last
+//! ones. Dropping the handle stops the thread and releases the provider.
+
+use std::sync::mpsc::{self, RecvTimeoutError, TryRecvError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Result};
+use jolt_platform::{PowerInfo, PowerProvider};
+use tracing::warn;
+
+/// Wait between samples unless configured otherwise.
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Bounds for [`PowerWorker::set_interval`]. IOReport deltas over less
+/// than 100 ms are mostly noise.
+pub const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+pub const MAX_SAMPLE_INTERVAL: Duration = Duration::from_millis(10_000);
+
+/// Failed refreshes in a row after which the worker stops.
+pub const MAX_CONSECUTIVE_FAILURES: u32 = 5;
+
+/// One refresh of the provider.
+#[derive(Debug, Clone)]
+pub struct PowerReading {
+    pub info: PowerInfo,
+    pub taken_at: Instant,
+    /// How long the provider's refresh took on the worker; `None` for the
+    /// reading taken when the provider was created.
+    pub refresh_time: Option<Duration>,
+}
+
+enum Command {
+    SetInterval(Duration),
+    SubscribeCpuStats(mpsc::Sender<Result<()>>),
+}
+
+enum Event {
+    Reading(PowerReading),
+    /// The worker stopped; no readings follow.
+    Stopped(String),
+}
+
+/// Handle to the sampling thread.
+pub struct PowerWorker {
+    /// Dropped first on shutdown, which ends the worker's loop.
+    command_tx: Option<mpsc::Sender<Command>>,
+    event_rx: mpsc::Receiver<Event>,
+    thread: Option<JoinHandle<()>>,
+    /// Why the worker stopped, once it has.
+    failure: Option<String>,
+}
+
+impl PowerWorker {
+    /// Starts sampling every `interval` with the provider `init` builds on
+    /// the new thread, and waits for the first reading.
+    pub fn spawn<P, I>(init: I, interval: Duration) -> Result<(Self, PowerReading)>
+    where
+        P: PowerProvider + 'static,
+        I: FnOnce() -> Result<P> + Send + 'static,
+    {
+        let (command_tx, command_rx) = mpsc::channel::<Command>();
+        let (event_tx, event_rx) = mpsc::channel::<Event>();
+        let (init_tx, init_rx) = mpsc::channel::<Result<PowerReading>>();
+
+        let thread = thread::Builder::new()
+            .name("power-sampler".to_string())
+            .spawn(move || {
+                let mut provider = match init() {
+                    Ok(provider) => provider,
+                    Err(e) => {
+                        let _ = init_tx.send(Err(e));
+                        return;
+                    }
+                };
+                if init_tx.send(Ok(reading(&provider, None))).is_err() {
+                    return;
+                }
+                run(&mut provider, interval, &command_rx, &event_tx);
+            })?;
+
+        let first = match init_rx.recv() {
+            Ok(Ok(reading)) => reading,
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                return Err(e.wrap_err("Failed to initialize power sampling"));
+            }
+            Err(_) => {
+                let _ = thread.join();
+                return Err(eyre!("Power sampler exited during initialization"));
+            }
+        };
+
+        let worker = Self {
+            command_tx: Some(command_tx),
+            event_rx,
+            thread: Some(thread),
+            failure: None,
+        };
+        Ok((worker, first))
+    }
+
+    /// Readings taken since the last call, oldest first.
+    pub fn drain(&mut self) -> Vec<PowerReading> {
+        let mut readings = Vec::new();
+        while self.failure.is_none() {
+            match self.event_rx.try_recv() {
+                Ok(Event::Reading(reading)) => readings.push(reading),
+                Ok(Event::Stopped(reason)) => self.failure = Some(reason),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.failure = Some("power sampler exited".to_string());
+                }
+            }
+        }
+        readings
+    }
+
+    /// Waits up to `timeout` for a reading, then returns it with any taken
+    /// since; empty on timeout or once the worker stopped.
+    pub fn wait(&mut self, timeout: Duration) -> Vec<PowerReading> {
+        if self.failure.is_some() {
+            return Vec::new();
+        }
+        match self.event_rx.recv_timeout(timeout) {
+            Ok(Event::Reading(reading)) => {
+                let mut readings = vec![reading];
+                readings.extend(self.drain());
+                readings
+            }
+            Ok(Event::Stopped(reason)) => {
+                self.failure = Some(reason);
+                Vec::new()
+            }
+            Err(RecvTimeoutError::Timeout) => Vec::new(),
+            Err(RecvTimeoutError::Disconnected) => {
+                self.failure = Some("power sampler exited".to_string());
+                Vec::new()
+            }
+        }
+    }
+
+    /// Why sampling stopped; `None` while the worker runs.
+    pub fn failure(&self) -> Option<&str> {
+        self.failure.as_deref()
+    }
+
+    /// Samples every `interval`, clamped to [`MIN_SAMPLE_INTERVAL`] and
+    /// [`MAX_SAMPLE_INTERVAL`], from the next sample on.
+    pub fn set_interval(&self, interval: Duration) {
+        let interval = interval.clamp(MIN_SAMPLE_INTERVAL, MAX_SAMPLE_INTERVAL);
+        self.send(Command::SetInterval(interval));
+    }
+
+    /// Runs [`PowerProvider::subscribe_cpu_stats`] on the worker's provider.
+    pub fn subscribe_cpu_stats(&self) -> Result<()> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        self.send(Command::SubscribeCpuStats(reply_tx));
+        reply_rx
+            .recv()
+            .map_err(|_| eyre!("Power sampler is not running"))?
+    }
+
+    fn send(&self, command: Command) {
+        if let Some(tx) = &self.command_tx {
+            let _ = tx.send(command);
+        }
+    }
+}
+
+impl Drop for PowerWorker {
+    fn drop(&mut self) {
+        self.command_tx.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn reading<P: PowerProvider>(provider: &P, refresh_time: Option<Duration>) -> PowerReading {
+    PowerReading {
+        info: provider.info().clone(),
+        taken_at: Instant::now(),
+        refresh_time,
+    }
+}
+
+/// Samples until the handle is dropped or refreshes keep failing.
+fn run<P: PowerProvider>(
+    provider: &mut P,
+    mut interval: Duration,
+    commands: &mpsc::Receiver<Command>,
+    events: &mpsc::Sender<Event>,
+) {
+    let mut next_sample = Instant::now() + interval;
+    let mut failures = 0;
+
+    loop {
+        match commands.recv_timeout(next_sample.saturating_duration_since(Instant::now())) {
+            Ok(Command::SetInterval(new_interval)) => {
+                next_sample = next_sample - interval + new_interval;
+                interval = new_interval;
+                continue;
+            }
+            Ok(Command::SubscribeCpuStats(reply)) => {
+                let _ = reply.send(provider.subscribe_cpu_stats());
+                continue;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+        next_sample = Instant::now() + interval;
+
+        let started = Instant::now();
+        let event = match provider.refresh() {
+            Ok(()) => {
+                failures = 0;
+                Event::Reading(reading(provider, Some(started.elapsed())))
+            }
+            Err(e) => {
+                failures += 1;
+                warn!(error = %e, failures, "Power refresh failed");
+                if failures < MAX_CONSECUTIVE_FAILURES {
+                    continue;
+                }
+                Event::Stopped(format!(
+                    "{} refreshes in a row failed, last: {}",
+                    failures, e
+                ))
+            }
+        };
+        let stopped = matches!(event, Event::Stopped(_));
+        if events.send(event).is_err() || stopped {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use color_eyre::eyre::bail;
+    use jolt_platform::{NotificationHandle, PowerMode};
+
+    const INTERVAL: Duration = Duration::from_millis(5);
+
+    /// Reports its refresh count as CPU watts; refreshes after `fail_from`
+    /// fail.
+    struct Counting {
+        info: PowerInfo,
+        fail_from: u32,
+        dropped: Arc<AtomicU32>,
+    }
+
+    impl PowerProvider for Counting {
+        fn new() -> Result<Self> {
+            bail!("built by the tests")
+        }
+
+        fn refresh(&mut self) -> Result<()> {
+            if self.info.cpu_power_watts as u32 >= self.fail_from {
+                bail!("no sample");
+            }
+            self.info.cpu_power_watts += 1.0;
+            Ok(())
+        }
+
+        fn info(&self) -> &PowerInfo {
+            &self.info
+        }
+
+        fn subscribe_notification<F>(_on_change: F) -> NotificationHandle
+        where
+            F: Fn(PowerMode) + Send + 'static,
+        {
+            unreachable!("the worker never subscribes")
+        }
+    }
+
+    impl Drop for Counting {
+        fn drop(&mut self) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn spawn(fail_from: u32) -> (PowerWorker, PowerReading, Arc<AtomicU32>) {
+        let dropped = Arc::new(AtomicU32::new(0));
+        let provider_dropped = Arc::clone(&dropped);
+        let (worker, first) = PowerWorker::spawn(
+            move || {
+                Ok(Counting {
+                    info: PowerInfo::default(),
+                    fail_from,
+                    dropped: provider_dropped,
+                })
+            },
+            INTERVAL,
+        )
+        .unwrap();
+        (worker, first, dropped)
+    }
+
+    fn wait_for<F: FnMut(&mut PowerWorker) -> bool>(worker: &mut PowerWorker, mut done: F) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !done(worker) {
+            assert!(Instant::now() < deadline, "timed out");
+            thread::sleep(INTERVAL);
+        }
+    }
+
+    #[test]
+    fn readings_arrive_in_order_and_drop_stops_the_thread() {
+        let (mut worker, first, dropped) = spawn(u32::MAX);
+        assert_eq!(first.info.cpu_power_watts, 0.0);
+        assert_eq!(first.refresh_time, None);
+
+        let mut watts: Vec<f32> = worker
+            .wait(Duration::from_secs(5))
+            .iter()
+            .map(|r| r.info.cpu_power_watts)
+            .collect();
+        wait_for(&mut worker, |worker| {
+            watts.extend(worker.drain().iter().map(|r| r.info.cpu_power_watts));
+            watts.len() >= 3
+        });
+        assert_eq!(watts[..3], [1.0, 2.0, 3.0]);
+        assert_eq!(worker.failure(), None);
+
+        drop(worker);
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn repeated_failures_stop_the_worker_with_a_reason() {
+        let (mut worker, _, dropped) = spawn(2);
+        wait_for(&mut worker, |worker| {
+            worker.drain();
+            worker.failure().is_some()
+        });
+
+        assert!(worker.failure().unwrap().contains("no sample"));
+        assert!(worker.drain().is_empty());
+        drop(worker);
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn initialization_errors_are_returned() {
+        let result = PowerWorker::spawn(|| -> Result<Counting> { bail!("no IOReport") }, INTERVAL);
+        let error = result.err().unwrap();
+        assert!(format!("{:?}", error).contains("no IOReport"));
+    }
+}
@@ -0,0 +1,202 @@
+//! `powermetrics` output, used to cross-check jolt against Apple's tool.
+//!
+//! `powermetrics --format plist` writes one plist document per sample,
+//! separated by NUL bytes. Since macOS 13 the `processor` dictionary carries
+//! `cpu_power`, `gpu_power` and `ane_power` in milliwatts. Older releases
+//! only report the energy used over the sample (`*_energy`, millijoules),
+//! which is converted with the sample's `elapsed_ns`; on those the GPU may
+//! sit in a separate top-level `gpu` dictionary.
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use plist::{Dictionary, Value};
+
+type DomainField = fn(&DomainPower) -> f32;
+
+/// Power per domain for one sample.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DomainPower {
+    pub cpu_watts: f32,
+    pub gpu_watts: f32,
+    pub ane_watts: f32,
+}
+
+/// How far jolt's readings for one domain are from the reference.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DomainComparison {
+    pub domain: &'static str,
+    pub jolt_mean: f32,
+    pub reference_mean: f32,
+    pub mean_abs_error: f32,
+}
+
+/// Parses every sample in a `powermetrics --format plist` capture.
+pub fn parse_samples(output: &[u8]) -> Result<Vec<DomainPower>> {
+    output
+        .split(|&b| b == 0)
+        .filter(|doc| doc.iter().any(|b| !b.is_ascii_whitespace()))
+        .enumerate()
+        .map(|(i, doc)| {
+            parse_sample(doc).wrap_err_with(|| format!("Invalid powermetrics sample {}", i + 1))
+        })
+        .collect()
+}
+
+fn parse_sample(doc: &[u8]) -> Result<DomainPower> {
+    let value: Value = plist::from_bytes(doc)?;
+    let root = value
+        .as_dictionary()
+        .ok_or_else(|| eyre!("Sample is not a dictionary"))?;
+    let processor = root
+        .get("processor")
+        .and_then(Value::as_dictionary)
+        .ok_or_else(|| eyre!("Missing processor section"))?;
+    let elapsed_secs = root
+        .get("elapsed_ns")
+        .and_then(number)
+        .map(|ns| ns / 1e9)
+        .filter(|secs| *secs > 0.0);
+
+    let domain = |name: &str, fallback: Option<&Dictionary>| -> Option<f32> {
+        [Some(processor), fallback]
+            .into_iter()
+            .flatten()
+            .find_map(|dict| domain_watts(dict, name, elapsed_secs))
+    };
+    let gpu_section = root.get("gpu").and_then(Value::as_dictionary);
+
+    Ok(DomainPower {
+        cpu_watts: domain("cpu", None).ok_or_else(|| eyre!("Missing CPU power"))?,
+        gpu_watts: domain("gpu", gpu_section).unwrap_or(0.0),
+        ane_watts: domain("ane", None).unwrap_or(0.0),
+    })
+}
+
+/// `<name>_power` in milliwatts, or `<name>_energy` in millijoules spread
+/// over the sample.
+fn domain_watts(dict: &Dictionary, name: &str, elapsed_secs: Option<f64>) -> Option<f32> {
+    if let Some(mw) = dict.get(&format!("{}_power", name)).and_then(number) {
+        return Some((mw / 1000.0) as f32);
+    }
+    let mj = dict.get(&format!("{}_energy", name)).and_then(number)?;
+    Some((mj / 1000.0 / elapsed_secs?) as f32)
+}
+
+fn number(value: &Value) -> Option<f64> {
+    value
+        .as_real()
+        .or_else(|| value.as_signed_integer().map(|i| i as f64))
+        .or_else(|| value.as_unsigned_integer().map(|u| u as f64))
+}
+
+/// Compares samples pairwise, in order, over the shorter of the two series.
+///
+/// Returns nothing when either series is empty.
+pub fn compare(jolt: &[DomainPower], reference: &[DomainPower]) -> Vec<DomainComparison> {
+    let pairs = jolt.len().min(reference.len());
+    if pairs == 0 {
+        return Vec::new();
+    }
+
+    let domains: [(&'static str, DomainField); 3] = [
+        ("CPU", |d| d.cpu_watts),
+        ("GPU", |d| d.gpu_watts),
+        ("ANE", |d| d.ane_watts),
+    ];
+
+    domains
+        .into_iter()
+        .map(|(domain, watts)| {
+            let mean = |samples: &[DomainPower]| {
+                samples[..pairs].iter().map(watts).sum::<f32>() / pairs as f32
+            };
+            let abs_error = jolt[..pairs]
+                .iter()
+                .zip(&reference[..pairs])
+                .map(|(j, r)| (watts(j) - watts(r)).abs())
+                .sum::<f32>();
+
+            DomainComparison {
+                domain,
+                jolt_mean: mean(jolt),
+                reference_mean: mean(reference),
+                mean_abs_error: abs_error / pairs as f32,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const M1_MACOS_12: &[u8] = include_bytes!("../../../fixtures/powermetrics/m1-macos-12.plist");
+    const M3_PRO_MACOS_14: &[u8] =
+        include_bytes!("../../../fixtures/powermetrics/m3-pro-macos-14.plist");
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.001,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn parses_energy_based_samples() {
+        let samples = parse_samples(M1_MACOS_12).unwrap();
+
+        assert_eq!(samples.len(), 3);
+        // 1523 mJ over 1.004 s
+        assert_close(samples[0].cpu_watts, 1.523 / 1.004);
+        assert_close(samples[0].gpu_watts, 0.041 / 1.004);
+        assert_close(samples[0].ane_watts, 0.0);
+        assert_close(samples[2].cpu_watts, 4.870 / 1.002);
+    }
+
+    #[test]
+    fn parses_power_based_samples() {
+        let samples = parse_samples(M3_PRO_MACOS_14).unwrap();
+
+        assert_eq!(samples.len(), 2);
+        assert_close(samples[0].cpu_watts, 2.315);
+        assert_close(samples[0].gpu_watts, 0.187);
+        assert_close(samples[0].ane_watts, 0.0);
+        assert_close(samples[1].cpu_watts, 6.902);
+        assert_close(samples[1].ane_watts, 0.412);
+    }
+
+    #[test]
+    fn rejects_samples_without_cpu_power() {
+        let doc = br#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0"><dict><key>processor</key><dict></dict></dict></plist>"#;
+        assert!(parse_samples(doc).is_err());
+    }
+
+    #[test]
+    fn compares_paired_samples() {
+        let reference = parse_samples(M3_PRO_MACOS_14).unwrap();
+        let jolt = [
+            DomainPower {
+                cpu_watts: 2.415,
+                gpu_watts: 0.187,
+                ane_watts: 0.0,
+            },
+            DomainPower {
+                cpu_watts: 6.702,
+                gpu_watts: 0.250,
+                ane_watts: 0.412,
+            },
+            DomainPower::default(),
+        ];
+
+        let comparison = compare(&jolt, &reference);
+
+        assert_eq!(comparison.len(), 3);
+        assert_eq!(comparison[0].domain, "CPU");
+        assert_close(comparison[0].mean_abs_error, 0.15);
+        assert_close(comparison[0].jolt_mean, (2.415 + 6.702) / 2.0);
+        assert_close(comparison[2].mean_abs_error, 0.0);
+        assert!(compare(&[], &reference).is_empty());
+    }
+}
@@ -1,11 +1,17 @@
 use color_eyre::eyre::Result;
-use std::collections::HashMap;
+use jolt_platform::command::{self, CommandLimits};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
-use std::path::Path;
-use sysinfo::{ProcessStatus, ProcessesToUpdate, System};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use sysinfo::{Pid, ProcessStatus, ProcessesToUpdate, System};
 
-use crate::config::cache_dir;
+use crate::config::{cache_dir, CpuNormalization};
+pub use crate::daemon::BinaryArch;
 use crate::daemon::{KillSignal, ProcessSnapshot, ProcessState as ProtocolProcessState};
+use crate::data::network::NetworkSampler;
+use crate::data::PowerData;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ProcessState {
@@ -64,6 +70,16 @@ impl From<ProcessSnapshot> for ProcessInfo {
             status: snapshot.status.into(),
             run_time_secs: snapshot.run_time_secs,
             total_cpu_time_secs: snapshot.total_cpu_time_secs,
+            network_bytes_per_sec: snapshot.network_bytes_per_sec,
+            sandbox_container: snapshot
+                .sandbox_container_id
+                .as_deref()
+                .and_then(container_path),
+            executable_path: snapshot.executable_path,
+            start_time: snapshot.start_time,
+            team_id: snapshot.team_id,
+            binary_arch: snapshot.binary_arch,
+            power_assertion_count: snapshot.power_assertion_count,
         }
     }
 }
@@ -81,6 +97,20 @@ impl ProcessState {
     }
 }
 
+/// A process, as opposed to a PID, which macOS hands out again soon after a
+/// process exits.
+///
+/// Two processes only share a PID and start time when one replaced the
+/// other within the same second; the executable path tells those apart.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProcessIdentity {
+    pub pid: u32,
+    /// Seconds since the Unix epoch.
+    pub start_time: u64,
+    /// Empty when the executable cannot be read.
+    pub executable_path: PathBuf,
+}
+
 #[derive(Debug, Clone)]
 pub struct ProcessInfo {
     pub pid: u32,
@@ -98,13 +128,178 @@ pub struct ProcessInfo {
     pub status: ProcessState,
     pub run_time_secs: u64,
     pub total_cpu_time_secs: u64,
+    /// Network bytes sent and received per second, when network sampling is
+    /// enabled and available.
+    pub network_bytes_per_sec: Option<f64>,
+    /// App Sandbox container directory, for sandboxed macOS apps.
+    pub sandbox_container: Option<PathBuf>,
+    /// Full path to the process binary; empty when it is not accessible.
+    pub executable_path: PathBuf,
+    /// Seconds since the Unix epoch.
+    pub start_time: u64,
+    /// Apple Developer Team ID from the code signature, resolved for listed
+    /// processes only.
+    pub team_id: Option<String>,
+    /// Architecture of the executable file, resolved for listed processes
+    /// only.
+    pub binary_arch: BinaryArch,
+    /// Power assertions (sleep or display-sleep blockers) the process holds,
+    /// resolved for listed processes on macOS only.
+    pub power_assertion_count: Option<u32>,
+}
+
+impl ProcessInfo {
+    /// Where a sandboxed app keeps its data, e.g.
+    /// `~/Library/Containers/com.apple.Safari/Data`. `None` for processes
+    /// that are not sandboxed or on platforms without App Sandbox.
+    pub fn sandbox_container_path(&self) -> Option<&Path> {
+        self.sandbox_container.as_deref()
+    }
+
+    /// Name of the App Sandbox container directory, which is the app's
+    /// bundle id. The daemon sends only this, not the path into the user's
+    /// home.
+    pub fn sandbox_container_id(&self) -> Option<String> {
+        let container = self.sandbox_container.as_deref()?.parent()?;
+        Some(container.file_name()?.to_string_lossy().into_owned())
+    }
+
+    /// Full path to the process binary. Empty for processes whose executable
+    /// cannot be read (kernel tasks, other users' processes, some sandboxed
+    /// apps), so callers can always display it.
+    pub fn executable_path(&self) -> &Path {
+        &self.executable_path
+    }
+
+    /// Team identifier of the Apple Developer account that signed the
+    /// process, e.g. `EQHXZ8M8AV` for Google. `None` for unsigned, ad-hoc
+    /// signed and Apple platform binaries, and on other platforms.
+    pub fn signed_team_id(&self) -> Option<&str> {
+        self.team_id.as_deref()
+    }
+
+    /// Architecture the executable was built for, from its Mach-O (or ELF)
+    /// header. An `X86_64` binary on Apple Silicon runs under Rosetta; a
+    /// `Universal` one runs natively. `Unknown` when the executable cannot
+    /// be read.
+    pub fn cpu_type(&self) -> BinaryArch {
+        self.binary_arch
+    }
+
+    /// Number of power assertions the process holds, such as a video player
+    /// keeping the display awake. `None` on Linux and when the assertion
+    /// list could not be read.
+    pub fn power_assertion_count(&self) -> Option<u32> {
+        self.power_assertion_count
+    }
+
+    /// Ancestors of this process in `data`, nearest first, ending at the
+    /// first process without a parent (usually PID 1) or whose parent is not
+    /// listed. Stops after [`MAX_PARENT_CHAIN_DEPTH`] entries in case the
+    /// parent links form a cycle.
+    pub fn parent_chain<'a>(&self, data: &'a ProcessData) -> Vec<&'a ProcessInfo> {
+        parent_chain(&data.processes, self)
+    }
+}
+
+/// Deepest ancestor chain [`ProcessInfo::parent_chain`] walks.
+pub const MAX_PARENT_CHAIN_DEPTH: usize = 32;
+
+/// Refreshes of CPU usage kept per process for
+/// [`ProcessData::average_cpu_over_refreshes`].
+pub const CPU_HISTORY_LEN: usize = 60;
+
+/// Refreshes of resident memory kept per process for
+/// [`ProcessData::memory_leak_candidates`].
+pub const RSS_HISTORY_LEN: usize = 30;
+
+/// Fewest refreshes a process must have been seen in before its memory
+/// growth is trusted.
+pub const MIN_LEAK_SAMPLES: usize = 3;
+
+/// Process CPU usage scaled for display.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalizedCpu {
+    pub percent: f32,
+    /// Set when the reading exceeded 100% of the machine and was clamped.
+    /// sysinfo's per-process deltas are not sampled atomically, so a process
+    /// pinning every core can briefly read slightly above the total.
+    pub clamped: bool,
+}
+
+/// Scales a sysinfo reading (100% = one core) to `mode`.
+pub fn normalize_cpu(cpu_usage: f32, mode: CpuNormalization, cores: u32) -> NormalizedCpu {
+    match mode {
+        CpuNormalization::PerCore => NormalizedCpu {
+            percent: cpu_usage,
+            clamped: false,
+        },
+        CpuNormalization::TotalMachine => {
+            let percent = cpu_usage / cores.max(1) as f32;
+            NormalizedCpu {
+                percent: percent.min(100.0),
+                clamped: percent > 100.0,
+            }
+        }
+    }
+}
+
+impl ProcessInfo {
+    pub fn normalized_cpu(&self, mode: CpuNormalization, cores: u32) -> NormalizedCpu {
+        normalize_cpu(self.cpu_usage, mode, cores)
+    }
+
+    pub fn identity(&self) -> ProcessIdentity {
+        ProcessIdentity {
+            pid: self.pid,
+            start_time: self.start_time,
+            executable_path: self.executable_path.clone(),
+        }
+    }
+}
+
+/// Scheduling policy a process's threads run under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedulingPolicy {
+    /// Time-sharing (`SCHED_OTHER`, Mach `POLICY_TIMESHARE`).
+    Default,
+    Fifo,
+    RoundRobin,
+    /// Any other platform policy, such as Linux `SCHED_BATCH` or `SCHED_IDLE`.
+    Other(i32),
 }
 
+pub type ProcessFilter = Box<dyn Fn(&ProcessInfo) -> bool + Send>;
+
 pub struct ProcessData {
     system: System,
     pub processes: Vec<ProcessInfo>,
     display_name_cache: HashMap<String, String>,
+    container_cache: HashMap<PathBuf, Option<PathBuf>>,
+    /// Sandbox container per process, so `sandbox_check` runs once for each
+    /// process rather than on every refresh. Pruned to the running processes.
+    sandbox_cache: HashMap<ProcessIdentity, Option<PathBuf>>,
+    /// Signing team per executable, looked up the first time a process
+    /// running it is listed.
+    team_id_cache: HashMap<PathBuf, Option<String>>,
+    /// Architecture per executable, read the first time a process running it
+    /// is listed.
+    binary_arch_cache: HashMap<PathBuf, BinaryArch>,
     excluded_processes: Vec<String>,
+    filter: Option<ProcessFilter>,
+    network: Option<NetworkSampler>,
+    /// The only PIDs refreshed, for data built with [`Self::new_from_pids`].
+    targets: Option<Vec<Pid>>,
+    /// Every process the last refresh saw. PID-keyed state is only carried
+    /// over while its PID still names the same process.
+    identities: HashMap<u32, ProcessIdentity>,
+    /// Last [`CPU_HISTORY_LEN`] CPU percentages per PID, oldest first.
+    cpu_history: HashMap<u32, VecDeque<f32>>,
+    /// Last [`RSS_HISTORY_LEN`] resident sizes in bytes per PID, oldest
+    /// first, taken at the matching entries of `rss_times`.
+    rss_history: HashMap<u32, VecDeque<u64>>,
+    /// When each of the last [`RSS_HISTORY_LEN`] refreshes ran, oldest first.
+    rss_times: VecDeque<Instant>,
 }
 
 impl ProcessData {
@@ -113,22 +308,91 @@ impl ProcessData {
     }
 
     pub fn with_exclusions(excluded: Vec<String>) -> Result<Self> {
+        Self::build(excluded, None)
+    }
+
+    /// Only keeps processes for which `filter` returns `true`.
+    ///
+    /// The filter runs on every refresh before processes are grouped, so
+    /// rejected processes are never stored (nor counted towards a parent's
+    /// energy impact).
+    pub fn new_with_filter(filter: ProcessFilter) -> Result<Self> {
+        Self::build(Vec::new(), Some(filter))
+    }
+
+    /// Only tracks `pids`, without enumerating every process on refresh.
+    ///
+    /// Each PID that exists is listed on its own, whatever its energy
+    /// impact, and is not nested under a parent. PIDs that do not exist, or
+    /// have exited since, are left out.
+    pub fn new_from_pids(pids: &[u32]) -> Result<Self> {
+        let targets = pids.iter().map(|&pid| Pid::from_u32(pid)).collect();
+        Self::build_with(System::new(), Vec::new(), None, Some(targets))
+    }
+
+    fn build(excluded: Vec<String>, filter: Option<ProcessFilter>) -> Result<Self> {
         let mut system = System::new_all();
         system.refresh_all();
+        Self::build_with(system, excluded, filter, None)
+    }
 
+    fn build_with(
+        system: System,
+        excluded: Vec<String>,
+        filter: Option<ProcessFilter>,
+        targets: Option<Vec<Pid>>,
+    ) -> Result<Self> {
         let display_name_cache = load_display_name_cache();
 
         let mut data = Self {
             system,
             processes: Vec::new(),
             display_name_cache,
+            container_cache: HashMap::new(),
+            sandbox_cache: HashMap::new(),
+            team_id_cache: HashMap::new(),
+            binary_arch_cache: HashMap::new(),
             excluded_processes: excluded,
+            filter,
+            network: None,
+            targets,
+            identities: HashMap::new(),
+            cpu_history: HashMap::new(),
+            rss_history: HashMap::new(),
+            rss_times: VecDeque::with_capacity(RSS_HISTORY_LEN),
         };
 
         data.refresh()?;
         Ok(data)
     }
 
+    /// Enables per-process network throughput. Sampling shells out to
+    /// `nettop` on every refresh, so it is off unless configured.
+    pub fn set_network_sampling(&mut self, enabled: bool) {
+        if enabled != self.network.is_some() {
+            self.network = enabled.then(NetworkSampler::new);
+        }
+    }
+
+    /// Replaces the binary names left out of the list. Processes already
+    /// listed under one of them are dropped right away rather than on the
+    /// next refresh.
+    pub fn set_exclusions(&mut self, excluded: Vec<String>) {
+        self.excluded_processes = excluded;
+        let mut processes = std::mem::take(&mut self.processes);
+        self.retain_included(&mut processes);
+        self.processes = processes;
+    }
+
+    fn retain_included(&self, processes: &mut Vec<ProcessInfo>) {
+        processes.retain(|p| !self.is_excluded(&p.command, p.pid));
+        for process in processes.iter_mut() {
+            if let Some(children) = process.children.as_mut() {
+                self.retain_included(children);
+            }
+        }
+    }
+
     fn is_excluded(&self, name: &str, pid: u32) -> bool {
         if pid == 1 {
             return true;
@@ -149,7 +413,48 @@ impl ProcessData {
     }
 
     pub fn refresh(&mut self) -> Result<()> {
-        self.system.refresh_processes(ProcessesToUpdate::All, true);
+        let to_update = match &self.targets {
+            Some(pids) => ProcessesToUpdate::Some(pids),
+            None => ProcessesToUpdate::All,
+        };
+        self.system.refresh_processes(to_update, true);
+        if self.targets.is_none() {
+            self.system.refresh_memory();
+        }
+
+        let identities: HashMap<u32, ProcessIdentity> = self
+            .system
+            .processes()
+            .iter()
+            .map(|(pid, process)| {
+                let identity = ProcessIdentity {
+                    pid: pid.as_u32(),
+                    start_time: process.start_time(),
+                    executable_path: process.exe().map(Path::to_path_buf).unwrap_or_default(),
+                };
+                (identity.pid, identity)
+            })
+            .collect();
+        let reused = self.track_identities(identities);
+
+        let samples = self
+            .system
+            .processes()
+            .iter()
+            .map(|(pid, process)| (pid.as_u32(), process.cpu_usage()));
+        record_history(&mut self.cpu_history, samples, &reused, CPU_HISTORY_LEN);
+        let rss: Vec<(u32, u64)> = self
+            .system
+            .processes()
+            .iter()
+            .map(|(pid, process)| (pid.as_u32(), process.memory()))
+            .collect();
+        self.record_rss(Instant::now(), rss, &reused);
+
+        if let Some(network) = self.network.as_mut() {
+            network.forget(&reused);
+            network.refresh();
+        }
 
         let mut process_map: HashMap<u32, ProcessInfo> = HashMap::new();
         let mut children_map: HashMap<u32, Vec<ProcessInfo>> = HashMap::new();
@@ -158,7 +463,7 @@ impl ProcessData {
             let pid_u32 = pid.as_u32();
             let binary_name = process.name().to_string_lossy().to_string();
 
-            if self.is_excluded(&binary_name, pid_u32) {
+            if self.targets.is_none() && self.is_excluded(&binary_name, pid_u32) {
                 continue;
             }
 
@@ -167,7 +472,13 @@ impl ProcessData {
             let cpu = process.cpu_usage();
             let memory_mb = process.memory() as f64 / (1024.0 * 1024.0);
 
-            let energy_impact = calculate_energy_impact(cpu, memory_mb as f32);
+            let network_bytes_per_sec = self
+                .network
+                .as_ref()
+                .and_then(|network| network.bytes_per_sec(pid_u32));
+
+            let energy_impact =
+                calculate_energy_impact(cpu, memory_mb as f32, network_bytes_per_sec);
             let exe_path = process.exe().map(|p| p.to_path_buf());
 
             let (display_name, cache_updated) = if let Some(ref path) = exe_path {
@@ -189,6 +500,23 @@ impl ProcessData {
 
             let is_killable = is_process_killable(pid_u32, &binary_name);
 
+            let sandbox_container = match (exe_path.as_ref(), self.identities.get(&pid_u32)) {
+                (Some(path), Some(identity)) => self
+                    .sandbox_cache
+                    .entry(identity.clone())
+                    .or_insert_with(|| {
+                        if !is_sandboxed(pid_u32) {
+                            return None;
+                        }
+                        self.container_cache
+                            .entry(path.clone())
+                            .or_insert_with(|| container_for_executable(path))
+                            .clone()
+                    })
+                    .clone(),
+                _ => None,
+            };
+
             let disk_usage = process.disk_usage();
             let status = ProcessState::from(process.status());
             let run_time_secs = process.run_time();
@@ -217,15 +545,30 @@ impl ProcessData {
                 status,
                 run_time_secs,
                 total_cpu_time_secs,
+                network_bytes_per_sec,
+                sandbox_container,
+                executable_path: exe_path.unwrap_or_default(),
+                start_time: process.start_time(),
+                team_id: None,
+                binary_arch: BinaryArch::Unknown,
+                power_assertion_count: None,
             };
 
+            if self.filter.as_ref().is_some_and(|keep| !keep(&info)) {
+                continue;
+            }
+
             process_map.insert(pid_u32, info.clone());
 
-            if let Some(parent) = parent_pid {
+            if let Some(parent) = parent_pid.filter(|_| self.targets.is_none()) {
                 children_map.entry(parent).or_default().push(info);
             }
         }
 
+        let identities = &self.identities;
+        self.sandbox_cache
+            .retain(|identity, _| identities.get(&identity.pid) == Some(identity));
+
         let mut top_processes: Vec<ProcessInfo> = Vec::new();
 
         for (pid, mut process) in process_map {
@@ -245,7 +588,7 @@ impl ProcessData {
                 }
             }
 
-            if process.energy_impact >= 0.5 {
+            if self.targets.is_some() || process.energy_impact >= 0.5 {
                 top_processes.push(process);
             }
         }
@@ -256,31 +599,422 @@ impl ProcessData {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        self.processes = top_processes.into_iter().take(50).collect();
+        let limit = if self.targets.is_some() {
+            usize::MAX
+        } else {
+            50
+        };
+        self.processes = top_processes.into_iter().take(limit).collect();
+        self.resolve_team_ids();
+        self.resolve_binary_archs();
+        self.resolve_power_assertions();
 
         Ok(())
     }
 
+    /// Fills in [`ProcessInfo::team_id`] for the listed processes and their
+    /// children. Code signatures are only checked for executables not seen
+    /// before, since validating one costs far more than a refresh.
+    fn resolve_team_ids(&mut self) {
+        fn resolve(processes: &mut [ProcessInfo], cache: &mut HashMap<PathBuf, Option<String>>) {
+            for process in processes {
+                if !process.executable_path.as_os_str().is_empty() {
+                    process.team_id = cache
+                        .entry(process.executable_path.clone())
+                        .or_insert_with(|| signing_team_id(process.pid))
+                        .clone();
+                }
+                if let Some(children) = process.children.as_mut() {
+                    resolve(children, cache);
+                }
+            }
+        }
+
+        resolve(&mut self.processes, &mut self.team_id_cache);
+    }
+
+    /// Fills in [`ProcessInfo::power_assertion_count`] for the listed
+    /// processes and their children from one system-wide assertion list.
+    fn resolve_power_assertions(&mut self) {
+        fn resolve(processes: &mut [ProcessInfo], counts: Option<&HashMap<u32, u32>>) {
+            for process in processes {
+                process.power_assertion_count =
+                    counts.map(|counts| counts.get(&process.pid).copied().unwrap_or(0));
+                if let Some(children) = process.children.as_mut() {
+                    resolve(children, counts);
+                }
+            }
+        }
+
+        resolve(&mut self.processes, power_assertion_counts().as_ref());
+    }
+
+    /// Fills in [`ProcessInfo::binary_arch`] for the listed processes and
+    /// their children, reading each executable's header once.
+    fn resolve_binary_archs(&mut self) {
+        fn resolve(processes: &mut [ProcessInfo], cache: &mut HashMap<PathBuf, BinaryArch>) {
+            for process in processes {
+                if !process.executable_path.as_os_str().is_empty() {
+                    process.binary_arch = *cache
+                        .entry(process.executable_path.clone())
+                        .or_insert_with(|| executable_arch(&process.executable_path));
+                }
+                if let Some(children) = process.children.as_mut() {
+                    resolve(children, cache);
+                }
+            }
+        }
+
+        resolve(&mut self.processes, &mut self.binary_arch_cache);
+    }
+
     pub fn kill_process(&self, pid: u32, signal: KillSignal) -> Result<()> {
         use std::process::Command;
 
-        Command::new("kill")
-            .args([signal.as_arg(), &pid.to_string()])
-            .output()?;
+        command::run(
+            Command::new("kill").args([signal.as_arg(), &pid.to_string()]),
+            CommandLimits::DEFAULT,
+        )?;
 
         Ok(())
     }
 
     pub fn update_from_snapshots(&mut self, snapshots: Vec<ProcessSnapshot>) {
         self.processes = snapshots.into_iter().map(ProcessInfo::from).collect();
+        // The daemon only sends listed processes, so identities and history
+        // are kept for those alone.
+        let identities = listed_processes(&self.processes)
+            .map(|p| (p.pid, p.identity()))
+            .collect();
+        let reused = self.track_identities(identities);
+        let samples = listed_processes(&self.processes).map(|p| (p.pid, p.cpu_usage));
+        record_history(&mut self.cpu_history, samples, &reused, CPU_HISTORY_LEN);
+        let rss: Vec<(u32, u64)> = listed_processes(&self.processes)
+            .map(|p| (p.pid, (p.memory_mb * 1024.0 * 1024.0) as u64))
+            .collect();
+        self.record_rss(Instant::now(), rss, &reused);
+    }
+
+    /// Replaces the identities of the last refresh with `identities`, and
+    /// returns the PIDs that now name a different process.
+    fn track_identities(&mut self, identities: HashMap<u32, ProcessIdentity>) -> Vec<u32> {
+        let reused = reused_pids(&self.identities, &identities);
+        self.identities = identities;
+        reused
+    }
+
+    fn record_rss(
+        &mut self,
+        at: Instant,
+        samples: impl IntoIterator<Item = (u32, u64)>,
+        reused: &[u32],
+    ) {
+        if self.rss_times.len() == RSS_HISTORY_LEN {
+            self.rss_times.pop_front();
+        }
+        self.rss_times.push_back(at);
+        record_history(&mut self.rss_history, samples, reused, RSS_HISTORY_LEN);
+    }
+
+    /// Zombie processes among every process the last refresh saw, not just
+    /// the listed ones: zombies use no CPU, so they are never listed.
+    pub fn zombie_count(&self) -> u32 {
+        self.system
+            .processes()
+            .values()
+            .filter(|p| p.status() == ProcessStatus::Zombie)
+            .count() as u32
+    }
+
+    /// Share of physical memory in use, from 0 to 1.
+    pub fn memory_pressure(&self) -> f32 {
+        let total = self.system.total_memory();
+        if total == 0 {
+            return 0.0;
+        }
+        self.system.used_memory() as f32 / total as f32
+    }
+
+    /// Which process `pid` named at the last refresh.
+    pub fn identity(&self, pid: u32) -> Option<&ProcessIdentity> {
+        self.identities.get(&pid)
+    }
+
+    /// Mean CPU usage of `pid` over its last `n` refreshes, or fewer if it
+    /// has not been seen that often. `None` for PIDs not seen at the last
+    /// refresh, or when `n` is zero.
+    pub fn average_cpu_over_refreshes(&self, pid: u32, n: usize) -> Option<f32> {
+        let values = self.cpu_history.get(&pid)?;
+        let n = n.min(values.len());
+        (n > 0).then(|| values.iter().rev().take(n).sum::<f32>() / n as f32)
+    }
+
+    /// Direct children of `pid` among the listed processes and their grouped
+    /// children, found by scanning rather than building a map of children.
+    /// A child listed both on its own and under its parent is yielded once.
+    pub fn children_of(&self, pid: u32) -> impl Iterator<Item = &ProcessInfo> {
+        let mut seen = HashSet::new();
+        listed_processes(&self.processes)
+            .filter(move |p| p.parent_pid == Some(pid) && seen.insert(p.pid))
+    }
+
+    /// Rank of `pid` by memory among the listed processes, 1 being the
+    /// largest. Processes with equal usage share a rank.
+    pub fn mem_rank(&self, pid: u32) -> Option<usize> {
+        rank_by(&self.processes, pid, |p| p.memory_mb)
+    }
+
+    /// Rank of `pid` by CPU usage among the listed processes, 1 being the
+    /// busiest. Processes with equal usage share a rank.
+    pub fn cpu_rank(&self, pid: u32) -> Option<usize> {
+        rank_by(&self.processes, pid, |p| p.cpu_usage as f64)
+    }
+
+    /// The `n` busiest listed processes and their grouped children, with the
+    /// smoothed CPU power split between all of them by share of CPU usage.
+    /// The estimate is `None` while `power` is a fallback estimate itself.
+    pub fn top_energy_consumers<'a>(
+        &'a self,
+        n: usize,
+        power: &PowerData,
+    ) -> Vec<(&'a ProcessInfo, Option<f32>)> {
+        let cpu_power = (!power.is_using_fallback()).then(|| power.cpu_power_watts());
+        split_cpu_power(&self.processes, n, cpu_power)
+    }
+
+    /// Listed processes whose resident memory grew faster than
+    /// `growth_rate_bytes_per_sec` over the refreshes they were seen in,
+    /// fastest first. The rate is the least-squares slope of those sizes,
+    /// so one large allocation among flat readings counts for little.
+    /// Processes seen in fewer than [`MIN_LEAK_SAMPLES`] refreshes are
+    /// never flagged.
+    pub fn memory_leak_candidates(&self, growth_rate_bytes_per_sec: f64) -> Vec<&ProcessInfo> {
+        let mut seen = HashSet::new();
+        let mut candidates: Vec<(&ProcessInfo, f64)> = listed_processes(&self.processes)
+            .filter(|p| seen.insert(p.pid))
+            .filter_map(|p| {
+                let rate = self.rss_growth_rate(p.pid)?;
+                (rate > growth_rate_bytes_per_sec).then_some((p, rate))
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.into_iter().map(|(p, _)| p).collect()
+    }
+
+    /// Resident memory growth of `pid` in bytes per second.
+    fn rss_growth_rate(&self, pid: u32) -> Option<f64> {
+        let sizes = self.rss_history.get(&pid)?;
+        if sizes.len() < MIN_LEAK_SAMPLES {
+            return None;
+        }
+        // A PID's sizes are its last refreshes, so they line up with the
+        // newest refresh times.
+        let times = self
+            .rss_times
+            .iter()
+            .skip(self.rss_times.len() - sizes.len());
+        let first = *times.clone().next()?;
+        let points = times
+            .zip(sizes)
+            .map(|(at, &size)| (at.duration_since(first).as_secs_f64(), size as f64));
+        linear_slope(points)
+    }
+}
+
+impl ProcessData {
+    /// Scheduling policy of `pid`, or `None` if the process is gone or may
+    /// not be inspected.
+    ///
+    /// Real-time policies keep threads running ahead of time-shared work and
+    /// tend to hold cores awake. On macOS this is the task's default policy
+    /// for new threads.
+    pub fn cpu_scheduling_policy(&self, pid: u32) -> Option<SchedulingPolicy> {
+        scheduling_policy(pid)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn scheduling_policy(pid: u32) -> Option<SchedulingPolicy> {
+    let policy = unsafe { libc::sched_getscheduler(pid as libc::pid_t) };
+    (policy >= 0).then(|| linux_policy(policy & !libc::SCHED_RESET_ON_FORK))
+}
+
+#[cfg(target_os = "linux")]
+fn linux_policy(policy: libc::c_int) -> SchedulingPolicy {
+    match policy {
+        libc::SCHED_OTHER => SchedulingPolicy::Default,
+        libc::SCHED_FIFO => SchedulingPolicy::Fifo,
+        libc::SCHED_RR => SchedulingPolicy::RoundRobin,
+        other => SchedulingPolicy::Other(other),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn scheduling_policy(pid: u32) -> Option<SchedulingPolicy> {
+    // <mach/policy.h>
+    const POLICY_TIMESHARE: i32 = 1;
+    const POLICY_RR: i32 = 2;
+    const POLICY_FIFO: i32 = 4;
+
+    let mut info: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+    let read = unsafe {
+        libc::proc_pidinfo(
+            pid as libc::c_int,
+            libc::PROC_PIDTASKINFO,
+            0,
+            &mut info as *mut _ as *mut libc::c_void,
+            size,
+        )
+    };
+    if read != size {
+        return None;
+    }
+
+    Some(match info.pti_policy {
+        POLICY_TIMESHARE => SchedulingPolicy::Default,
+        POLICY_RR => SchedulingPolicy::RoundRobin,
+        POLICY_FIFO => SchedulingPolicy::Fifo,
+        other => SchedulingPolicy::Other(other),
+    })
+}
+
+/// One pass to find the process, one to count those ahead of it.
+fn rank_by(
+    processes: &[ProcessInfo],
+    pid: u32,
+    key: impl Fn(&ProcessInfo) -> f64,
+) -> Option<usize> {
+    let value = key(processes.iter().find(|p| p.pid == pid)?);
+    Some(1 + processes.iter().filter(|p| key(p) > value).count())
+}
+
+fn split_cpu_power(
+    processes: &[ProcessInfo],
+    n: usize,
+    cpu_power: Option<f32>,
+) -> Vec<(&ProcessInfo, Option<f32>)> {
+    let mut listed: Vec<&ProcessInfo> = listed_processes(processes).collect();
+    let mut seen = HashSet::new();
+    listed.retain(|p| seen.insert(p.pid));
+    let total_cpu: f32 = listed.iter().map(|p| p.cpu_usage).sum();
+
+    listed.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+    listed
+        .into_iter()
+        .take(n)
+        .map(|p| {
+            let share = if total_cpu > 0.0 {
+                p.cpu_usage / total_cpu
+            } else {
+                0.0
+            };
+            (p, cpu_power.map(|watts| watts * share))
+        })
+        .collect()
+}
+
+fn parent_chain<'a>(processes: &'a [ProcessInfo], process: &ProcessInfo) -> Vec<&'a ProcessInfo> {
+    let mut chain = Vec::new();
+    let mut parent_pid = process.parent_pid;
+    while let Some(pid) = parent_pid {
+        if chain.len() >= MAX_PARENT_CHAIN_DEPTH {
+            break;
+        }
+        let Some(parent) = find_pid(processes, pid) else {
+            break;
+        };
+        chain.push(parent);
+        parent_pid = parent.parent_pid.filter(|_| parent.pid != 1);
     }
+    chain
+}
+
+/// PIDs seen in both refreshes that now belong to a different process.
+fn reused_pids(
+    previous: &HashMap<u32, ProcessIdentity>,
+    current: &HashMap<u32, ProcessIdentity>,
+) -> Vec<u32> {
+    current
+        .iter()
+        .filter(|(pid, identity)| previous.get(pid).is_some_and(|prev| prev != *identity))
+        .map(|(&pid, _)| pid)
+        .collect()
 }
 
-fn calculate_energy_impact(cpu_usage: f32, memory_mb: f32) -> f32 {
+/// Least-squares slope of `(x, y)` points; `None` for fewer than two
+/// distinct `x`.
+pub(crate) fn linear_slope(points: impl Iterator<Item = (f64, f64)> + Clone) -> Option<f64> {
+    let n = points.clone().count() as f64;
+    let (sum_x, sum_y) = points
+        .clone()
+        .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+    let (mean_x, mean_y) = (sum_x / n, sum_y / n);
+    let (covariance, variance) = points.fold((0.0, 0.0), |(c, v), (x, y)| {
+        (c + (x - mean_x) * (y - mean_y), v + (x - mean_x).powi(2))
+    });
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+/// Appends each sample to its PID's history, keeping the last `len`. PIDs
+/// missing from `samples` are dropped, and `reused` PIDs start over.
+fn record_history<T>(
+    history: &mut HashMap<u32, VecDeque<T>>,
+    samples: impl IntoIterator<Item = (u32, T)>,
+    reused: &[u32],
+    len: usize,
+) {
+    let mut previous = std::mem::take(history);
+    for (pid, sample) in samples {
+        if history.contains_key(&pid) {
+            continue;
+        }
+        let mut values = previous
+            .remove(&pid)
+            .filter(|_| !reused.contains(&pid))
+            .unwrap_or_default();
+        if values.len() == len {
+            values.pop_front();
+        }
+        values.push_back(sample);
+        history.insert(pid, values);
+    }
+}
+
+/// Every process in `processes` and their grouped children, depth first.
+fn listed_processes(processes: &[ProcessInfo]) -> impl Iterator<Item = &ProcessInfo> {
+    let mut stack: Vec<&ProcessInfo> = processes.iter().rev().collect();
+    std::iter::from_fn(move || {
+        let process = stack.pop()?;
+        if let Some(children) = &process.children {
+            stack.extend(children.iter().rev());
+        }
+        Some(process)
+    })
+}
+
+/// Looks `pid` up among `processes` and their grouped children.
+fn find_pid(processes: &[ProcessInfo], pid: u32) -> Option<&ProcessInfo> {
+    processes.iter().find_map(|p| {
+        if p.pid == pid {
+            Some(p)
+        } else {
+            find_pid(p.children.as_deref().unwrap_or_default(), pid)
+        }
+    })
+}
+
+fn calculate_energy_impact(
+    cpu_usage: f32,
+    memory_mb: f32,
+    network_bytes_per_sec: Option<f64>,
+) -> f32 {
     let cpu_factor = cpu_usage * 0.8;
     let memory_factor = (memory_mb / 100.0).min(20.0) * 0.2;
+    let network_mb_per_sec = network_bytes_per_sec.unwrap_or(0.0) / (1024.0 * 1024.0);
+    let network_factor = (network_mb_per_sec as f32).min(20.0) * 0.1;
 
-    cpu_factor + memory_factor
+    cpu_factor + memory_factor + network_factor
 }
 
 const SYSTEM_PROCESSES: &[&str] = &[
@@ -335,6 +1069,259 @@ fn is_process_killable(pid: u32, name: &str) -> bool {
     true
 }
 
+#[cfg(target_os = "macos")]
+fn is_sandboxed(pid: u32) -> bool {
+    extern "C" {
+        // libsystem_sandbox; a null operation asks only whether the process
+        // runs under any sandbox profile.
+        fn sandbox_check(
+            pid: libc::pid_t,
+            operation: *const libc::c_char,
+            filter_type: libc::c_int,
+            ...
+        ) -> libc::c_int;
+    }
+
+    const SANDBOX_FILTER_NONE: libc::c_int = 0;
+    unsafe { sandbox_check(pid as libc::pid_t, std::ptr::null(), SANDBOX_FILTER_NONE) == 1 }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn is_sandboxed(_pid: u32) -> bool {
+    false
+}
+
+/// Team identifier from the code signature of the running process `pid`.
+#[cfg(target_os = "macos")]
+fn signing_team_id(pid: u32) -> Option<String> {
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::dictionary::CFDictionary;
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::{CFString, CFStringRef};
+    use core_foundation_sys::base::{CFRelease, OSStatus};
+    use core_foundation_sys::dictionary::CFDictionaryRef;
+    use std::ffi::c_void;
+
+    type SecCodeRef = *const c_void;
+
+    #[link(name = "Security", kind = "framework")]
+    extern "C" {
+        static kSecGuestAttributePid: CFStringRef;
+        static kSecCodeInfoTeamIdentifier: CFStringRef;
+        fn SecCodeCopyGuestWithAttributes(
+            host: SecCodeRef,
+            attributes: CFDictionaryRef,
+            flags: u32,
+            guest: *mut SecCodeRef,
+        ) -> OSStatus;
+        fn SecCodeCopySigningInformation(
+            code: SecCodeRef,
+            flags: u32,
+            information: *mut CFDictionaryRef,
+        ) -> OSStatus;
+    }
+
+    // kSecCSSigningInformation: include the certificate chain and team.
+    const SIGNING_INFORMATION: u32 = 1 << 1;
+
+    unsafe {
+        let attributes = CFDictionary::from_CFType_pairs(&[(
+            CFString::wrap_under_get_rule(kSecGuestAttributePid).as_CFType(),
+            CFNumber::from(pid as i32).as_CFType(),
+        )]);
+        let mut guest: SecCodeRef = std::ptr::null();
+        let status = SecCodeCopyGuestWithAttributes(
+            std::ptr::null(),
+            attributes.as_concrete_TypeRef(),
+            0,
+            &mut guest,
+        );
+        if status != 0 || guest.is_null() {
+            return None;
+        }
+
+        let mut information: CFDictionaryRef = std::ptr::null();
+        let status = SecCodeCopySigningInformation(guest, SIGNING_INFORMATION, &mut information);
+        CFRelease(guest);
+        if status != 0 || information.is_null() {
+            return None;
+        }
+
+        let information: CFDictionary<CFString, CFType> =
+            CFDictionary::wrap_under_create_rule(information);
+        let team_id = information
+            .find(CFString::wrap_under_get_rule(kSecCodeInfoTeamIdentifier))?
+            .downcast::<CFString>()?
+            .to_string();
+        (!team_id.is_empty()).then_some(team_id)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn signing_team_id(_pid: u32) -> Option<String> {
+    None
+}
+
+/// Power assertions held per PID, from IOKit's list of every active
+/// assertion. `None` when the list cannot be read.
+#[cfg(target_os = "macos")]
+fn power_assertion_counts() -> Option<HashMap<u32, u32>> {
+    use core_foundation::array::CFArray;
+    use core_foundation::base::TCFType;
+    use core_foundation::dictionary::CFDictionary;
+    use core_foundation::number::CFNumber;
+    use core_foundation_sys::array::CFArrayRef;
+    use core_foundation_sys::dictionary::CFDictionaryRef;
+    use core_foundation_sys::number::CFNumberRef;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        /// Keys are PIDs, values arrays of that process's assertions.
+        fn IOPMCopyAssertionsByProcess(assertions_by_pid: *mut CFDictionaryRef) -> libc::c_int;
+    }
+
+    unsafe {
+        let mut by_pid: CFDictionaryRef = std::ptr::null();
+        if IOPMCopyAssertionsByProcess(&mut by_pid) != 0 || by_pid.is_null() {
+            return None;
+        }
+
+        let by_pid: CFDictionary = CFDictionary::wrap_under_create_rule(by_pid);
+        let (pids, assertions) = by_pid.get_keys_and_values();
+        let counts = pids
+            .into_iter()
+            .zip(assertions)
+            .filter_map(|(pid, list)| {
+                let pid = CFNumber::wrap_under_get_rule(pid as CFNumberRef).to_i32()?;
+                let list: CFArray = CFArray::wrap_under_get_rule(list as CFArrayRef);
+                Some((pid as u32, list.len() as u32))
+            })
+            .collect();
+        Some(counts)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn power_assertion_counts() -> Option<HashMap<u32, u32>> {
+    None
+}
+
+/// Fat headers list at most this many slices before the first one starts.
+const HEADER_READ_LEN: u64 = 4096;
+
+fn executable_arch(path: &Path) -> BinaryArch {
+    let mut header = Vec::new();
+    match fs::File::open(path).and_then(|f| f.take(HEADER_READ_LEN).read_to_end(&mut header)) {
+        Ok(_) => binary_arch_from_header(&header),
+        Err(_) => BinaryArch::Unknown,
+    }
+}
+
+/// Architecture from the start of an executable: a thin or fat Mach-O, or
+/// an ELF file on Linux.
+///
+/// A fat binary counts as `Universal` only when it carries both an arm64
+/// and an x86_64 slice; one with a single slice reports that slice.
+fn binary_arch_from_header(header: &[u8]) -> BinaryArch {
+    const MH_MAGIC: u32 = 0xfeedface;
+    const MH_MAGIC_64: u32 = 0xfeedfacf;
+    const FAT_MAGIC: u32 = 0xcafebabe;
+    const FAT_MAGIC_64: u32 = 0xcafebabf;
+    const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+    const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+    // Java class files share FAT_MAGIC; their version field is at least 45.
+    const MAX_FAT_ARCHS: u32 = 32;
+
+    let word = |offset: usize, big_endian: bool| -> Option<u32> {
+        let bytes: [u8; 4] = header.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    };
+    let from_cpu_type = |cpu_type: u32| match cpu_type {
+        CPU_TYPE_ARM64 => BinaryArch::Arm64,
+        CPU_TYPE_X86_64 => BinaryArch::X86_64,
+        _ => BinaryArch::Unknown,
+    };
+
+    if header.starts_with(b"\x7fELF") {
+        let machine = header.get(18..20).map(|b| match header.get(5) {
+            Some(2) => u16::from_be_bytes([b[0], b[1]]),
+            _ => u16::from_le_bytes([b[0], b[1]]),
+        });
+        return match machine {
+            Some(62) => BinaryArch::X86_64,
+            Some(183) => BinaryArch::Arm64,
+            _ => BinaryArch::Unknown,
+        };
+    }
+
+    let Some(magic) = word(0, true) else {
+        return BinaryArch::Unknown;
+    };
+    match magic {
+        FAT_MAGIC | FAT_MAGIC_64 => {
+            let count = word(4, true).unwrap_or(0);
+            if count == 0 || count > MAX_FAT_ARCHS {
+                return BinaryArch::Unknown;
+            }
+            // fat_arch is 20 bytes and fat_arch_64 32, cputype first in both.
+            let stride = if magic == FAT_MAGIC { 20 } else { 32 };
+            let archs: Vec<BinaryArch> = (0..count as usize)
+                .filter_map(|i| word(8 + i * stride, true))
+                .map(from_cpu_type)
+                .collect();
+            let has = |arch| archs.contains(&arch);
+            match (has(BinaryArch::Arm64), has(BinaryArch::X86_64)) {
+                (true, true) => BinaryArch::Universal,
+                (true, false) => BinaryArch::Arm64,
+                (false, true) => BinaryArch::X86_64,
+                (false, false) => BinaryArch::Unknown,
+            }
+        }
+        MH_MAGIC | MH_MAGIC_64 => word(4, true).map_or(BinaryArch::Unknown, from_cpu_type),
+        _ if matches!(word(0, false), Some(MH_MAGIC | MH_MAGIC_64)) => {
+            word(4, false).map_or(BinaryArch::Unknown, from_cpu_type)
+        }
+        _ => BinaryArch::Unknown,
+    }
+}
+
+/// Resolves `~/Library/Containers/<bundle id>/Data` from the app bundle that
+/// contains `exe_path`. Containers live in the owning user's home, so this
+/// only finds them for the current user's processes.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn container_for_executable(exe_path: &Path) -> Option<PathBuf> {
+    let container = container_path(&bundle_identifier(exe_path)?)?;
+    container.exists().then_some(container)
+}
+
+/// `~/Library/Containers/<id>/Data` of the current user.
+fn container_path(container_id: &str) -> Option<PathBuf> {
+    Some(
+        dirs::home_dir()?
+            .join("Library/Containers")
+            .join(container_id)
+            .join("Data"),
+    )
+}
+
+/// `CFBundleIdentifier` of the outermost app bundle containing `exe_path`,
+/// so helpers report their app's id.
+pub fn bundle_identifier(exe_path: &Path) -> Option<String> {
+    let path_str = exe_path.to_string_lossy();
+    let app_idx = path_str.find(".app/")?;
+    let info_plist_path = format!("{}/Contents/Info.plist", &path_str[..app_idx + 4]);
+
+    let info = plist::Value::from_file(info_plist_path).ok()?;
+    info.as_dictionary()?
+        .get("CFBundleIdentifier")?
+        .as_string()
+        .map(str::to_string)
+}
+
 fn get_app_display_name(exe_path: &Path) -> Option<String> {
     use std::process::Command;
 
@@ -352,11 +1339,15 @@ fn get_app_display_name(exe_path: &Path) -> Option<String> {
 
     let mut app_name: Option<String> = None;
     for key in ["CFBundleDisplayName", "CFBundleName"] {
-        if let Ok(output) = Command::new("/usr/libexec/PlistBuddy")
-            .args(["-c", &format!("Print :{}", key), &info_plist_path])
-            .output()
-        {
-            if output.status.success() {
+        if let Ok(output) = command::run(
+            Command::new("/usr/libexec/PlistBuddy").args([
+                "-c",
+                &format!("Print :{}", key),
+                &info_plist_path,
+            ]),
+            CommandLimits::DEFAULT,
+        ) {
+            if output.success() {
                 let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
                 if !name.is_empty() {
                     app_name = Some(name);
@@ -439,3 +1430,417 @@ fn save_display_name_cache(cache: &HashMap<String, String>) {
         let _ = fs::write(path, content);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn process(pid: u32, cpu_usage: f32, memory_mb: f64) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: format!("proc{}", pid),
+            command: String::new(),
+            command_args: String::new(),
+            cpu_usage,
+            memory_mb,
+            energy_impact: 0.0,
+            parent_pid: None,
+            children: None,
+            is_killable: true,
+            disk_read_bytes: 0,
+            disk_write_bytes: 0,
+            status: ProcessState::Running,
+            run_time_secs: 0,
+            total_cpu_time_secs: 0,
+            network_bytes_per_sec: None,
+            sandbox_container: None,
+            executable_path: PathBuf::new(),
+            start_time: 0,
+            team_id: None,
+            binary_arch: BinaryArch::Unknown,
+            power_assertion_count: None,
+        }
+    }
+
+    #[test]
+    fn per_core_leaves_readings_unchanged() {
+        let cpu = normalize_cpu(800.0, CpuNormalization::PerCore, 12);
+        assert_eq!(cpu.percent, 800.0);
+        assert!(!cpu.clamped);
+    }
+
+    #[test]
+    fn total_machine_divides_by_core_count() {
+        let cpu = process(1, 300.0, 0.0).normalized_cpu(CpuNormalization::TotalMachine, 12);
+        assert_eq!(cpu.percent, 25.0);
+        assert!(!cpu.clamped);
+
+        let full = normalize_cpu(1200.0, CpuNormalization::TotalMachine, 12);
+        assert_eq!(full.percent, 100.0);
+        assert!(!full.clamped);
+    }
+
+    #[test]
+    fn total_machine_clamps_and_flags_jitter_above_100() {
+        let cpu = normalize_cpu(1206.0, CpuNormalization::TotalMachine, 12);
+        assert_eq!(cpu.percent, 100.0);
+        assert!(cpu.clamped);
+
+        let no_cores = normalize_cpu(50.0, CpuNormalization::TotalMachine, 0);
+        assert_eq!(no_cores.percent, 50.0);
+    }
+
+    #[test]
+    fn rank_counts_processes_ahead_and_shares_ties() {
+        let processes = vec![
+            process(10, 5.0, 300.0),
+            process(11, 40.0, 100.0),
+            process(12, 5.0, 900.0),
+            process(13, 1.0, 50.0),
+        ];
+        let cpu = |p: &ProcessInfo| p.cpu_usage as f64;
+
+        assert_eq!(rank_by(&processes, 11, cpu), Some(1));
+        assert_eq!(rank_by(&processes, 10, cpu), Some(2));
+        assert_eq!(rank_by(&processes, 12, cpu), Some(2));
+        assert_eq!(rank_by(&processes, 13, cpu), Some(4));
+        assert_eq!(rank_by(&processes, 12, |p| p.memory_mb), Some(1));
+        assert_eq!(rank_by(&processes, 99, cpu), None);
+    }
+
+    #[test]
+    fn parent_chain_walks_grouped_children_up_to_init() {
+        let child = |pid: u32, parent: u32| ProcessInfo {
+            parent_pid: Some(parent),
+            ..process(pid, 0.0, 0.0)
+        };
+        let mut terminal = child(20, 1);
+        terminal.children = Some(vec![child(21, 20)]);
+        let processes = vec![process(1, 0.0, 0.0), terminal, child(30, 21)];
+
+        let pids: Vec<u32> = parent_chain(&processes, &processes[2])
+            .iter()
+            .map(|p| p.pid)
+            .collect();
+        assert_eq!(pids, vec![21, 20, 1]);
+
+        // An unlisted parent ends the chain.
+        assert!(parent_chain(&processes, &child(40, 99)).is_empty());
+
+        // Processes that are each other's parent stop at the depth limit.
+        let cycle = vec![child(50, 51), child(51, 50)];
+        assert_eq!(
+            parent_chain(&cycle, &cycle[0]).len(),
+            MAX_PARENT_CHAIN_DEPTH
+        );
+    }
+
+    #[test]
+    fn filter_runs_on_every_refresh() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let own_pid = std::process::id();
+        let mut data = ProcessData::new_with_filter(Box::new(move |p| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            p.pid == own_pid
+        }))
+        .unwrap();
+
+        let after_first = calls.load(Ordering::Relaxed);
+        assert!(after_first > 0);
+        assert!(listed_processes(&data.processes).all(|p| p.pid == own_pid));
+
+        data.refresh().unwrap();
+        assert!(calls.load(Ordering::Relaxed) > after_first);
+        assert!(listed_processes(&data.processes).all(|p| p.pid == own_pid));
+    }
+
+    #[test]
+    fn children_of_finds_direct_children_once() {
+        let child = |pid: u32, parent: u32| ProcessInfo {
+            parent_pid: Some(parent),
+            ..process(pid, 0.0, 0.0)
+        };
+        let mut browser = child(20, 1);
+        browser.children = Some(vec![child(21, 20), child(22, 20)]);
+        let mut data = ProcessData::new_from_pids(&[]).unwrap();
+        // 21 is busy enough to also be listed on its own; 23 is a grandchild.
+        data.processes = vec![browser, child(21, 20), child(23, 21)];
+
+        let pids: Vec<u32> = data.children_of(20).map(|p| p.pid).collect();
+        assert_eq!(pids, vec![21, 22]);
+        assert_eq!(data.children_of(21).count(), 1);
+        assert_eq!(data.children_of(99).count(), 0);
+    }
+
+    #[test]
+    fn cpu_power_is_split_by_cpu_share() {
+        let mut parent = process(1, 30.0, 0.0);
+        parent.children = Some(vec![process(2, 50.0, 0.0), process(3, 0.0, 0.0)]);
+        // 2 is busy enough to also be listed on its own.
+        let processes = vec![parent, process(2, 50.0, 0.0), process(4, 20.0, 0.0)];
+
+        let top = split_cpu_power(&processes, 2, Some(10.0));
+        let pids: Vec<u32> = top.iter().map(|(p, _)| p.pid).collect();
+        assert_eq!(pids, vec![2, 1]);
+        assert_eq!(top[0].1, Some(5.0));
+        assert_eq!(top[1].1, Some(3.0));
+
+        assert_eq!(split_cpu_power(&processes, 10, Some(10.0)).len(), 4);
+        assert!(split_cpu_power(&processes, 3, None)
+            .iter()
+            .all(|(_, watts)| watts.is_none()));
+
+        let idle = vec![process(5, 0.0, 0.0)];
+        assert_eq!(split_cpu_power(&idle, 1, Some(10.0))[0].1, Some(0.0));
+    }
+
+    #[test]
+    fn average_cpu_covers_the_last_refreshes_of_a_pid() {
+        let mut data = ProcessData::new_from_pids(&[]).unwrap();
+        for cpu in [10.0, 20.0, 30.0, 40.0] {
+            record_history(
+                &mut data.cpu_history,
+                [(7, cpu), (8, 1.0)],
+                &[],
+                CPU_HISTORY_LEN,
+            );
+        }
+        assert_eq!(data.average_cpu_over_refreshes(7, 2), Some(35.0));
+        assert_eq!(data.average_cpu_over_refreshes(7, 100), Some(25.0));
+        assert_eq!(data.average_cpu_over_refreshes(7, 0), None);
+
+        // 7 was reused by another process; 8 exited.
+        record_history(&mut data.cpu_history, [(7, 90.0)], &[7], CPU_HISTORY_LEN);
+        assert_eq!(data.average_cpu_over_refreshes(7, 4), Some(90.0));
+        assert_eq!(data.average_cpu_over_refreshes(8, 4), None);
+
+        for _ in 0..CPU_HISTORY_LEN + 5 {
+            record_history(&mut data.cpu_history, [(7, 1.0)], &[], CPU_HISTORY_LEN);
+        }
+        assert_eq!(data.cpu_history[&7].len(), CPU_HISTORY_LEN);
+    }
+
+    #[test]
+    fn set_exclusions_drops_listed_processes_and_children() {
+        let named = |pid: u32, command: &str| ProcessInfo {
+            command: command.to_string(),
+            ..process(pid, 0.0, 0.0)
+        };
+        let mut data = ProcessData::new_from_pids(&[]).unwrap();
+        data.processes = vec![
+            ProcessInfo {
+                children: Some(vec![named(11, "mdworker"), named(12, "helper")]),
+                ..named(10, "Terminal")
+            },
+            named(20, "Spotlight"),
+            named(30, "Safari"),
+        ];
+
+        data.set_exclusions(vec!["spotlight".to_string(), "MDWORKER".to_string()]);
+
+        let pids: Vec<u32> = data.processes.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, [10, 30]);
+        let children = data.processes[0].children.as_ref().unwrap();
+        assert_eq!(children.iter().map(|c| c.pid).collect::<Vec<_>>(), [12]);
+    }
+
+    #[test]
+    fn steadily_growing_memory_is_flagged_after_three_refreshes() {
+        const MB: u64 = 1024 * 1024;
+        let mut data = ProcessData::new_from_pids(&[]).unwrap();
+        data.processes = vec![
+            process(1, 0.0, 0.0),
+            process(2, 0.0, 0.0),
+            process(3, 0.0, 0.0),
+        ];
+        let start = Instant::now();
+        let at = |secs: u64| start + std::time::Duration::from_secs(secs);
+
+        // 1 grows 2 MB/s, 2 holds steady after one allocation, 3 shrinks.
+        data.record_rss(at(0), [(1, 100 * MB), (2, 100 * MB), (3, 50 * MB)], &[]);
+        data.record_rss(at(2), [(1, 104 * MB), (2, 100 * MB), (3, 40 * MB)], &[]);
+        assert!(data.memory_leak_candidates(0.0).is_empty());
+
+        data.record_rss(at(4), [(1, 108 * MB), (2, 103 * MB), (3, 30 * MB)], &[]);
+        let pids = |data: &ProcessData, rate: f64| -> Vec<u32> {
+            data.memory_leak_candidates(rate)
+                .iter()
+                .map(|p| p.pid)
+                .collect()
+        };
+        assert_eq!(pids(&data, 0.0), vec![1, 2]);
+        assert_eq!(pids(&data, MB as f64), vec![1]);
+        assert!((data.rss_growth_rate(1).unwrap() - 2.0 * MB as f64).abs() < 1.0);
+
+        // A reused PID starts its history over.
+        data.record_rss(at(6), [(1, 10 * MB)], &[1]);
+        assert!(pids(&data, 0.0).is_empty());
+    }
+
+    #[test]
+    fn reused_pids_compare_start_time_then_executable() {
+        let identity = |pid: u32, start_time: u64, exe: &str| ProcessIdentity {
+            pid,
+            start_time,
+            executable_path: PathBuf::from(exe),
+        };
+        let previous = HashMap::from([
+            (10, identity(10, 100, "/bin/a")),
+            (11, identity(11, 100, "/bin/b")),
+            (12, identity(12, 100, "/bin/c")),
+            (13, identity(13, 100, "/bin/d")),
+        ]);
+        let current = HashMap::from([
+            // Same process
+            (10, identity(10, 100, "/bin/a")),
+            // Restarted later under the same PID
+            (11, identity(11, 160, "/bin/b")),
+            // Replaced within the same second by another binary
+            (12, identity(12, 100, "/bin/e")),
+            // First seen now
+            (14, identity(14, 100, "/bin/f")),
+        ]);
+
+        let mut reused = reused_pids(&previous, &current);
+        reused.sort();
+        assert_eq!(reused, vec![11, 12]);
+    }
+
+    #[test]
+    fn daemon_snapshots_start_history_over_for_reused_pids() {
+        let snapshot =
+            |start_time: u64, exe: &str, cpu_usage: f32, network: Option<f64>| ProcessSnapshot {
+                pid: 500,
+                start_time,
+                cpu_usage,
+                memory_mb: cpu_usage as f64,
+                network_bytes_per_sec: network,
+                ..crate::data::redaction::tests::process("worker", exe)
+            };
+        let mut data = ProcessData::new_from_pids(&[]).unwrap();
+        data.update_from_snapshots(vec![snapshot(100, "/bin/old", 40.0, Some(4096.0))]);
+        data.update_from_snapshots(vec![snapshot(100, "/bin/old", 60.0, Some(2048.0))]);
+        assert_eq!(data.cpu_history[&500], [40.0, 60.0]);
+
+        // The old process exited and a new one got its PID
+        data.update_from_snapshots(vec![snapshot(160, "/bin/new", 5.0, None)]);
+
+        assert_eq!(data.cpu_history[&500], [5.0]);
+        assert_eq!(data.rss_history[&500], [5 * 1024 * 1024]);
+        assert_eq!(data.processes[0].network_bytes_per_sec, None);
+        assert_eq!(data.identity(500).map(|i| i.start_time), Some(160));
+    }
+
+    #[test]
+    fn new_from_pids_lists_only_existing_targets() {
+        let own = std::process::id();
+        // Above the kernel's PID limit, so never a live process.
+        let missing = 1 << 30;
+
+        let data = ProcessData::new_from_pids(&[own, missing]).unwrap();
+        let pids: Vec<u32> = data.processes.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, vec![own]);
+
+        assert!(ProcessData::new_from_pids(&[])
+            .unwrap()
+            .processes
+            .is_empty());
+    }
+
+    #[test]
+    fn team_ids_come_from_the_cache_per_executable() {
+        let mut data = ProcessData::new_from_pids(&[]).unwrap();
+        let exe = PathBuf::from("/Applications/Slack.app/Contents/MacOS/Slack");
+        data.team_id_cache
+            .insert(exe.clone(), Some("BQR82RBBHL".to_string()));
+
+        let mut parent = process(10, 1.0, 1.0);
+        parent.executable_path = exe.clone();
+        let mut helper = process(11, 1.0, 1.0);
+        helper.executable_path = exe;
+        parent.children = Some(vec![helper]);
+        // Without a readable executable there is nothing to look up.
+        data.processes = vec![parent, process(12, 1.0, 1.0)];
+
+        data.resolve_team_ids();
+        assert_eq!(data.processes[0].signed_team_id(), Some("BQR82RBBHL"));
+        let children = data.processes[0].children.as_ref().unwrap();
+        assert_eq!(children[0].signed_team_id(), Some("BQR82RBBHL"));
+        assert_eq!(data.processes[1].signed_team_id(), None);
+        assert_eq!(data.team_id_cache.len(), 1);
+    }
+
+    #[test]
+    fn sandbox_containers_are_sent_as_ids() {
+        let mut safari = process(1, 0.0, 0.0);
+        assert_eq!(safari.sandbox_container_id(), None);
+
+        safari.sandbox_container = container_path("com.apple.Safari");
+        let id = safari.sandbox_container_id();
+        assert_eq!(id.as_deref(), Some("com.apple.Safari"));
+        assert_eq!(container_path(&id.unwrap()), safari.sandbox_container);
+    }
+
+    #[test]
+    fn binary_arch_comes_from_the_executable_header() {
+        let thin = |cpu_type: u32| [0xfeedfacf_u32.to_le_bytes(), cpu_type.to_le_bytes()].concat();
+        let fat = |cpu_types: &[u32]| {
+            let mut header = [
+                0xcafebabe_u32.to_be_bytes(),
+                (cpu_types.len() as u32).to_be_bytes(),
+            ]
+            .concat();
+            for cpu_type in cpu_types {
+                header.extend(cpu_type.to_be_bytes());
+                header.extend([0; 16]);
+            }
+            header
+        };
+
+        assert_eq!(
+            binary_arch_from_header(&thin(0x0100_000c)),
+            BinaryArch::Arm64
+        );
+        assert_eq!(
+            binary_arch_from_header(&thin(0x0100_0007)),
+            BinaryArch::X86_64
+        );
+        assert_eq!(
+            binary_arch_from_header(&fat(&[0x0100_0007, 0x0100_000c])),
+            BinaryArch::Universal
+        );
+        assert_eq!(
+            binary_arch_from_header(&fat(&[0x0100_000c])),
+            BinaryArch::Arm64
+        );
+        // A Java class file: same magic, version 52.
+        assert_eq!(
+            binary_arch_from_header(&[0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 52]),
+            BinaryArch::Unknown
+        );
+        assert_eq!(binary_arch_from_header(b"#!/bin/sh\n"), BinaryArch::Unknown);
+
+        let mut elf = vec![0x7f, b'E', b'L', b'F', 2, 1];
+        elf.resize(18, 0);
+        elf.extend(183u16.to_le_bytes());
+        assert_eq!(binary_arch_from_header(&elf), BinaryArch::Arm64);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn linux_scheduling_policies_map_to_variants() {
+        assert_eq!(linux_policy(libc::SCHED_OTHER), SchedulingPolicy::Default);
+        assert_eq!(linux_policy(libc::SCHED_RR), SchedulingPolicy::RoundRobin);
+        assert_eq!(
+            linux_policy(libc::SCHED_IDLE),
+            SchedulingPolicy::Other(libc::SCHED_IDLE)
+        );
+        assert_eq!(
+            scheduling_policy(std::process::id()),
+            Some(SchedulingPolicy::Default)
+        );
+    }
+}
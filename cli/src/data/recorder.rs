@@ -1,9 +1,13 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 use chrono::Utc;
 use tracing::{debug, trace};
 
 use crate::config::HistoryConfig;
+use crate::data::processes::bundle_identifier;
+use crate::data::redaction::outermost_bundle;
 use crate::data::{
     BatteryData, BatteryHealthSnapshot, ChargingState, CycleSnapshot, DailyTopProcess,
     HistoryStore, HistoryStoreError, PowerData, ProcessData, Sample, SessionEvent, SessionTracker,
@@ -17,6 +21,8 @@ pub struct Recorder {
     last_cycle_snapshot_date: Option<String>,
     excluded_processes: Vec<String>,
     session_tracker: SessionTracker,
+    /// Bundle id per executable, read once from its app's Info.plist.
+    bundle_ids: HashMap<PathBuf, Option<String>>,
 }
 
 impl Recorder {
@@ -47,6 +53,7 @@ impl Recorder {
             last_cycle_snapshot_date: None,
             excluded_processes,
             session_tracker,
+            bundle_ids: HashMap::new(),
         })
     }
 
@@ -92,6 +99,11 @@ impl Recorder {
         };
 
         self.store.insert_sample(&sample)?;
+        self.store.add_hourly_power_mode(
+            hour_start(sample.timestamp),
+            power.power_mode(),
+            self.config.sample_interval_secs as i64,
+        )?;
         self.last_sample_time = Some(Instant::now());
 
         self.session_tracker
@@ -126,8 +138,11 @@ impl Recorder {
         &mut self,
         processes: &ProcessData,
         system_cpu_power: f32,
+        estimated: bool,
     ) -> Result<(), HistoryStoreError> {
-        let today = Utc::now().format("%Y-%m-%d").to_string();
+        let now = Utc::now();
+        let today = now.format("%Y-%m-%d").to_string();
+        let hour = hour_start(now.timestamp());
 
         let top_processes: Vec<_> = processes
             .processes
@@ -136,6 +151,9 @@ impl Recorder {
             .take(10)
             .collect();
 
+        // Raw per-core readings: a process's share of CPU power is a ratio, so
+        // it is the same in either display normalization, and clamping would
+        // skew it. `avg_cpu` is stored per core and normalized when shown.
         let total_cpu: f32 = top_processes.iter().map(|p| p.cpu_usage).sum();
         let sample_hours = self.config.sample_interval_secs as f32 / 3600.0;
 
@@ -159,6 +177,25 @@ impl Recorder {
                 total_energy_wh: sample_energy_wh,
             };
             self.store.upsert_daily_process(&entry)?;
+
+            // Hourly energy is kept per app: processes in a bundle count
+            // under the bundle's name and id, so helpers join their app.
+            let bundle_id = self
+                .bundle_ids
+                .entry(process.executable_path.clone())
+                .or_insert_with(|| bundle_identifier(&process.executable_path))
+                .as_deref();
+            let app_name = match bundle_id {
+                Some(_) => outermost_bundle(&process.executable_path).unwrap_or(&process.name),
+                None => &process.name,
+            };
+            self.store.add_hourly_process_energy(
+                hour,
+                app_name,
+                bundle_id,
+                sample_energy_wh,
+                estimated,
+            )?;
         }
 
         Ok(())
@@ -181,6 +218,8 @@ impl Recorder {
             cycle_count: battery.cycle_count().map(|c| c as i32),
             max_capacity_wh: battery.max_capacity_wh(),
             design_capacity_wh: battery.design_capacity_wh(),
+            full_charge_capacity_mah: battery.full_charge_capacity_mah().map(|c| c as i32),
+            design_capacity_mah: battery.design_capacity_mah().map(|c| c as i32),
         };
 
         self.store.upsert_battery_health(&snapshot)?;
@@ -231,10 +270,21 @@ impl Recorder {
     ) -> Result<(), HistoryStoreError> {
         if self.should_record() {
             self.record_sample(battery, power)?;
-            self.record_processes(processes, power.cpu_power_watts())?;
+            self.record_processes(
+                processes,
+                power.cpu_power_watts(),
+                power.is_using_fallback(),
+            )?;
             self.record_battery_health(battery)?;
             self.record_cycle_snapshot(battery)?;
         }
         Ok(())
     }
 }
+
+/// Start of the UTC hour containing `timestamp`. Whole hours line up with
+/// local midnight in nearly every time zone, so reports can regroup them by
+/// local day.
+fn hour_start(timestamp: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(3600)
+}
@@ -0,0 +1,220 @@
+//! Pseudonyms for process names in data that leaves the TUI.
+//!
+//! Processes matching one of the `redact_processes` patterns are renamed to
+//! `app-1`, `app-2`, ... in `jolt serve`, `jolt pipe`, history exports,
+//! reports and bug reports. The TUI and plain mode keep the real names since
+//! they only render locally.
+//!
+//! Aliases are handed out in order of first sight and never change for the
+//! lifetime of a redactor, so one run of a command names each app the same
+//! way throughout.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::daemon::ProcessSnapshot;
+
+#[derive(Debug, Default)]
+pub struct ProcessRedactor {
+    /// Lowercased glob patterns.
+    patterns: Vec<String>,
+    aliases: HashMap<String, String>,
+}
+
+impl ProcessRedactor {
+    /// Patterns match case-insensitively against the whole name; `*` stands
+    /// for any run of characters and `?` for one. Blank patterns are ignored.
+    pub fn new(patterns: &[String]) -> Self {
+        Self {
+            patterns: patterns
+                .iter()
+                .map(|p| p.trim().to_lowercase())
+                .filter(|p| !p.is_empty())
+                .collect(),
+            aliases: HashMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// A bare process name, as stored in the history database: its alias if
+    /// it matches, otherwise the name itself.
+    pub fn name(&mut self, name: &str) -> String {
+        if self.matches(name) {
+            self.alias(name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Name to show for a process: its alias if its name, command or any
+    /// `.app` bundle it runs from matches.
+    ///
+    /// Helpers share their app's alias because the outermost bundle is the
+    /// key, falling back to the process name outside a bundle.
+    pub fn process_name(&mut self, process: &ProcessSnapshot) -> String {
+        if self.matches_process(process) {
+            let key = outermost_bundle(&process.executable_path).unwrap_or(&process.name);
+            self.alias(key)
+        } else {
+            process.name.clone()
+        }
+    }
+
+    /// Replaces every field that could identify a matching process, in
+    /// `processes` and all their children.
+    pub fn redact_processes(&mut self, processes: &mut [ProcessSnapshot]) {
+        if self.is_empty() {
+            return;
+        }
+        for process in processes {
+            if self.matches_process(process) {
+                let alias = self.process_name(process);
+                process.command = alias.clone();
+                process.command_args = alias.clone();
+                process.name = alias;
+                process.executable_path = PathBuf::new();
+                process.sandbox_container_id = None;
+                process.team_id = None;
+            }
+            if let Some(children) = process.children.as_mut() {
+                self.redact_processes(children);
+            }
+        }
+    }
+
+    fn matches_process(&self, process: &ProcessSnapshot) -> bool {
+        self.matches(&process.name)
+            || self.matches(&process.command)
+            || bundles(&process.executable_path).any(|bundle| self.matches(bundle))
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.patterns.iter().any(|p| glob_match(p, &name))
+    }
+
+    fn alias(&mut self, key: &str) -> String {
+        let next = self.aliases.len() + 1;
+        self.aliases
+            .entry(key.to_string())
+            .or_insert_with(|| format!("app-{}", next))
+            .clone()
+    }
+}
+
+/// The outermost `.app` bundle `path` lies in, without the extension.
+pub fn outermost_bundle(path: &Path) -> Option<&str> {
+    bundles(path).next()
+}
+
+fn bundles(path: &Path) -> impl Iterator<Item = &str> {
+    path.components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .filter_map(|c| c.strip_suffix(".app"))
+        .filter(|name| !name.is_empty())
+}
+
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it currently covers up to.
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, covered)) => {
+                    p = star + 1;
+                    t = covered + 1;
+                    backtrack = Some((star, covered + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::daemon::{BinaryArch, ProcessState};
+
+    pub(crate) fn process(name: &str, exe: &str) -> ProcessSnapshot {
+        ProcessSnapshot {
+            pid: 0,
+            name: name.to_string(),
+            command: name.to_string(),
+            command_args: format!("{} --flag", exe),
+            cpu_usage: 10.0,
+            memory_mb: 0.0,
+            energy_impact: 0.0,
+            parent_pid: None,
+            children: None,
+            is_killable: true,
+            disk_read_bytes: 0,
+            disk_write_bytes: 0,
+            status: ProcessState::Running,
+            run_time_secs: 0,
+            total_cpu_time_secs: 0,
+            network_bytes_per_sec: None,
+            sandbox_container_id: Some(format!("com.example.{}", name)),
+            executable_path: PathBuf::from(exe),
+            start_time: 0,
+            team_id: Some(format!("{}-team", name)),
+            binary_arch: BinaryArch::Arm64,
+            power_assertion_count: None,
+        }
+    }
+
+    #[test]
+    fn names_match_globs_case_insensitively_with_stable_aliases() {
+        let mut redactor =
+            ProcessRedactor::new(&["slack*".to_string(), "  ".to_string(), "z?om".to_string()]);
+
+        assert_eq!(redactor.name("Slack Helper"), "app-1");
+        assert_eq!(redactor.name("zoom"), "app-2");
+        assert_eq!(redactor.name("Slack Helper"), "app-1");
+        assert_eq!(redactor.name("Zoom.us"), "Zoom.us");
+        assert_eq!(redactor.name("Safari"), "Safari");
+
+        assert!(glob_match("*a*b", "xxaxxb"));
+        assert!(!glob_match("*a*b", "xxaxxbc"));
+        assert!(ProcessRedactor::new(&[" ".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn matching_processes_lose_every_identifying_field() {
+        let mut redactor = ProcessRedactor::new(&["Signal".to_string()]);
+        let mut parent = process("launcher", "/usr/bin/launcher");
+        parent.children = Some(vec![
+            process(
+                "Signal Helper (GPU)",
+                "/Applications/Signal.app/Contents/Frameworks/Signal Helper (GPU).app/Contents/MacOS/Signal Helper (GPU)",
+            ),
+            process("Signal", "/Applications/Signal.app/Contents/MacOS/Signal"),
+        ]);
+        let mut processes = vec![parent];
+
+        redactor.redact_processes(&mut processes);
+
+        let children = processes[0].children.as_ref().unwrap();
+        assert_eq!(processes[0].name, "launcher");
+        assert_eq!(children[0].name, "app-1");
+        assert_eq!(children[1].name, "app-1");
+        let json = serde_json::to_string(&processes).unwrap().to_lowercase();
+        assert!(!json.contains("signal"), "{}", json);
+    }
+}
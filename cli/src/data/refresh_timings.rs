@@ -0,0 +1,138 @@
+//! Rolling refresh-duration statistics per subsystem.
+//!
+//! Each tick records how long the battery, power, and process refreshes (and
+//! the frame render) took. Only the most recent samples are kept, so the
+//! percentiles describe current behavior rather than the whole session.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+const MAX_TIMING_SAMPLES: usize = 120;
+
+/// A stage of the refresh/render cycle that is timed independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshStage {
+    Battery,
+    Power,
+    Processes,
+    Render,
+}
+
+impl RefreshStage {
+    pub const ALL: [RefreshStage; 4] = [
+        RefreshStage::Battery,
+        RefreshStage::Power,
+        RefreshStage::Processes,
+        RefreshStage::Render,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RefreshStage::Battery => "Battery",
+            RefreshStage::Power => "Power",
+            RefreshStage::Processes => "Processes",
+            RefreshStage::Render => "Render",
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            RefreshStage::Battery => 0,
+            RefreshStage::Power => 1,
+            RefreshStage::Processes => 2,
+            RefreshStage::Render => 3,
+        }
+    }
+}
+
+/// Summary of the recorded durations for one stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageStats {
+    pub p50: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RefreshTimings {
+    stages: [VecDeque<Duration>; 4],
+}
+
+impl RefreshTimings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, stage: RefreshStage, duration: Duration) {
+        let samples = &mut self.stages[stage.index()];
+        if samples.len() >= MAX_TIMING_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(duration);
+    }
+
+    /// Returns p50/p95/max for a stage, or `None` if it has not been timed yet
+    /// (e.g. local refreshes are skipped while daemon data is in use).
+    pub fn stats(&self, stage: RefreshStage) -> Option<StageStats> {
+        let samples = &self.stages[stage.index()];
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<Duration> = samples.iter().copied().collect();
+        sorted.sort_unstable();
+
+        Some(StageStats {
+            p50: percentile(&sorted, 0.50),
+            p95: percentile(&sorted, 0.95),
+            max: sorted[sorted.len() - 1],
+            samples: sorted.len(),
+        })
+    }
+}
+
+/// Nearest-rank percentile over an already sorted, non-empty slice.
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_are_none_before_any_sample() {
+        let timings = RefreshTimings::new();
+        assert!(timings.stats(RefreshStage::Power).is_none());
+    }
+
+    #[test]
+    fn stats_report_nearest_rank_percentiles() {
+        let mut timings = RefreshTimings::new();
+        for ms in 1..=100 {
+            timings.record(RefreshStage::Processes, Duration::from_millis(ms));
+        }
+
+        let stats = timings.stats(RefreshStage::Processes).unwrap();
+        assert_eq!(stats.p50, Duration::from_millis(50));
+        assert_eq!(stats.p95, Duration::from_millis(95));
+        assert_eq!(stats.max, Duration::from_millis(100));
+        assert_eq!(stats.samples, 100);
+        assert!(timings.stats(RefreshStage::Battery).is_none());
+    }
+
+    #[test]
+    fn old_samples_roll_out_of_the_window() {
+        let mut timings = RefreshTimings::new();
+        timings.record(RefreshStage::Render, Duration::from_secs(5));
+        for _ in 0..MAX_TIMING_SAMPLES {
+            timings.record(RefreshStage::Render, Duration::from_millis(1));
+        }
+
+        let stats = timings.stats(RefreshStage::Render).unwrap();
+        assert_eq!(stats.max, Duration::from_millis(1));
+        assert_eq!(stats.samples, MAX_TIMING_SAMPLES);
+    }
+}
@@ -0,0 +1,250 @@
+//! Weekly energy report built from the history database.
+//!
+//! Samples, per-app energy and power mode time are grouped by local calendar
+//! day, so a late evening on battery counts towards the day the user saw it
+//! on the clock rather than the UTC one. Per-app energy and power mode time
+//! are stored per hour and count towards the day their hour starts in.
+
+use chrono::{Days, NaiveDate, NaiveTime, TimeZone};
+use serde::Serialize;
+
+use crate::data::power::PowerMode;
+use crate::data::{AppEnergy, ChargingState, HistoryStore, HistoryStoreError, ProcessRedactor};
+
+/// Days covered by the weekly report, ending with the given day.
+pub const REPORT_DAYS: u64 = 7;
+
+/// Apps listed in the report, by attributed energy.
+pub const TOP_APPS: usize = 10;
+
+/// Order power modes are listed in.
+const POWER_MODES: [PowerMode; 4] = [
+    PowerMode::LowPower,
+    PowerMode::Automatic,
+    PowerMode::HighPerformance,
+    PowerMode::Unknown,
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklyReport {
+    /// First and last local day covered, inclusive.
+    pub from: String,
+    pub to: String,
+    pub energy_on_battery_wh: f32,
+    /// Battery discharged over the week, in full cycles.
+    pub cycles_added: f32,
+    /// `None` when no samples were recorded.
+    pub avg_power_watts: Option<f32>,
+    pub peak_power_watts: Option<f32>,
+    pub top_apps: Vec<AppEnergy>,
+    pub power_modes: Vec<PowerModeTime>,
+    /// Every day of the week, oldest first, including days without samples.
+    pub days: Vec<DayReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PowerModeTime {
+    pub mode: String,
+    pub hours: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayReport {
+    pub date: String,
+    pub energy_wh: f32,
+    pub energy_on_battery_wh: f32,
+    pub cycles_added: f32,
+    pub avg_power_watts: Option<f32>,
+    pub peak_power_watts: Option<f32>,
+}
+
+impl WeeklyReport {
+    /// Renames apps matching the redaction patterns to their aliases and
+    /// drops their bundle ids.
+    pub fn redact(&mut self, redactor: &mut ProcessRedactor) {
+        for app in &mut self.top_apps {
+            let alias = redactor.name(&app.name);
+            if alias != app.name {
+                app.name = alias;
+                app.bundle_id = None;
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.avg_power_watts.is_none()
+    }
+}
+
+#[derive(Debug, Default)]
+struct DayTotals {
+    samples: u32,
+    power_sum: f32,
+    peak_power: f32,
+    energy_wh: f32,
+    energy_on_battery_wh: f32,
+    discharged_percent: f32,
+}
+
+/// Builds the report for the [`REPORT_DAYS`] local days ending with
+/// `last_day`, in time zone `tz`.
+///
+/// Energy is integrated from samples taken every `sample_interval_secs`, the
+/// same way the daily aggregates are. Without `include_estimated`, app
+/// energy recorded while power was estimated is left out.
+pub fn weekly_report<Tz: TimeZone>(
+    store: &HistoryStore,
+    last_day: NaiveDate,
+    tz: &Tz,
+    sample_interval_secs: u64,
+    include_estimated: bool,
+) -> Result<WeeklyReport, HistoryStoreError> {
+    let first_day = last_day - Days::new(REPORT_DAYS - 1);
+    // Wide enough for any UTC offset; rows are filtered by local day below.
+    let from = (first_day - Days::new(1)).and_time(NaiveTime::MIN);
+    let to = (last_day + Days::new(2)).and_time(NaiveTime::MIN);
+    let (from, to) = (from.and_utc().timestamp(), to.and_utc().timestamp() - 1);
+
+    let day_index = |timestamp: i64| {
+        let date = tz.timestamp_opt(timestamp, 0).single()?.date_naive();
+        let index = date.signed_duration_since(first_day).num_days();
+        (0..REPORT_DAYS as i64)
+            .contains(&index)
+            .then_some(index as usize)
+    };
+
+    let interval_hours = sample_interval_secs as f32 / 3600.0;
+    let mut days: Vec<DayTotals> = (0..REPORT_DAYS).map(|_| DayTotals::default()).collect();
+    let mut last_percent: Option<f32> = None;
+    for sample in store.get_samples(from, to)? {
+        let Some(index) = day_index(sample.timestamp) else {
+            continue;
+        };
+        let day = &mut days[index];
+        let energy_wh = sample.power_watts * interval_hours;
+
+        day.samples += 1;
+        day.power_sum += sample.power_watts;
+        day.peak_power = day.peak_power.max(sample.power_watts);
+        day.energy_wh += energy_wh;
+        if sample.charging_state == ChargingState::Discharging {
+            day.energy_on_battery_wh += energy_wh;
+        }
+        if let Some(last) = last_percent {
+            day.discharged_percent += (last - sample.battery_percent).max(0.0);
+        }
+        last_percent = Some(sample.battery_percent);
+    }
+
+    // Hours starting from local midnight on the first day up to local
+    // midnight after the last one.
+    let local_midnight = |day: NaiveDate| {
+        tz.from_local_datetime(&day.and_time(NaiveTime::MIN))
+            .earliest()
+            .map(|t| t.timestamp())
+    };
+    let top_apps = match (
+        local_midnight(first_day),
+        local_midnight(last_day + Days::new(1)),
+    ) {
+        (Some(start), Some(end)) => {
+            store.top_apps_by_energy(start, end - 1, include_estimated, TOP_APPS)?
+        }
+        _ => Vec::new(),
+    };
+
+    let mut mode_secs = [0_i64; POWER_MODES.len()];
+    for entry in store.get_hourly_power_modes(from, to)? {
+        if day_index(entry.hour_start).is_some() {
+            if let Some(i) = POWER_MODES.iter().position(|&m| m == entry.mode) {
+                mode_secs[i] += entry.seconds;
+            }
+        }
+    }
+    let power_modes = POWER_MODES
+        .iter()
+        .zip(mode_secs)
+        .filter(|(_, secs)| *secs > 0)
+        .map(|(mode, secs)| PowerModeTime {
+            mode: mode.label().to_string(),
+            hours: secs as f32 / 3600.0,
+        })
+        .collect();
+
+    let samples: u32 = days.iter().map(|d| d.samples).sum();
+    let power_sum: f32 = days.iter().map(|d| d.power_sum).sum();
+
+    Ok(WeeklyReport {
+        from: first_day.format("%Y-%m-%d").to_string(),
+        to: last_day.format("%Y-%m-%d").to_string(),
+        energy_on_battery_wh: days.iter().map(|d| d.energy_on_battery_wh).sum(),
+        cycles_added: days.iter().map(|d| d.discharged_percent).sum::<f32>() / 100.0,
+        avg_power_watts: (samples > 0).then(|| power_sum / samples as f32),
+        peak_power_watts: (samples > 0)
+            .then(|| days.iter().map(|d| d.peak_power).fold(0.0, f32::max)),
+        top_apps,
+        power_modes,
+        days: days
+            .iter()
+            .zip(first_day.iter_days())
+            .map(|(totals, date)| DayReport {
+                date: date.format("%Y-%m-%d").to_string(),
+                energy_wh: totals.energy_wh,
+                energy_on_battery_wh: totals.energy_on_battery_wh,
+                cycles_added: totals.discharged_percent / 100.0,
+                avg_power_watts: (totals.samples > 0)
+                    .then(|| totals.power_sum / totals.samples as f32),
+                peak_power_watts: (totals.samples > 0).then_some(totals.peak_power),
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+
+    use super::*;
+
+    /// 2024-03-18 00:00 at UTC+2, 22:00 UTC the day before.
+    const MONDAY: i64 = 1_710_712_800;
+
+    #[test]
+    fn rows_are_grouped_by_local_day() {
+        // The first two samples are on Sunday in UTC, but the second is
+        // already Monday at UTC+2.
+        let store = HistoryStore::open_in_memory(&format!(
+            "INSERT INTO samples (timestamp, battery_percent, power_watts, cpu_power, gpu_power, charging_state)
+             VALUES ({sunday}, 95.0, 50.0, 0, 0, 0), ({after_midnight}, 90.0, 10.0, 0, 0, 0),
+                    ({morning}, 80.0, 30.0, 0, 0, 0);
+             INSERT INTO hourly_process_energy (hour_start, process_name, energy_wh)
+             VALUES ({monday}, 'mds', 2.0), ({sunday_hour}, 'mds', 100.0);
+             INSERT INTO hourly_power_modes (hour_start, power_mode, seconds)
+             VALUES ({monday}, 0, 1800), ({monday}, 1, 1800), ({sunday_hour}, 2, 3600);",
+            sunday = MONDAY - 30 * 60,
+            after_midnight = MONDAY + 30 * 60,
+            morning = MONDAY + 8 * 3600,
+            monday = MONDAY,
+            sunday_hour = MONDAY - 3600,
+        ))
+        .unwrap();
+
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        let last_day = NaiveDate::from_ymd_opt(2024, 3, 24).unwrap();
+        let report = weekly_report(&store, last_day, &tz, 60, true).unwrap();
+
+        assert_eq!(report.from, "2024-03-18");
+        assert_eq!(report.days.len(), REPORT_DAYS as usize);
+        assert_eq!(report.days[0].date, "2024-03-18");
+        assert_eq!(report.peak_power_watts, Some(30.0));
+        assert_eq!(report.days[0].avg_power_watts, Some(20.0));
+        assert_eq!(report.days[1].avg_power_watts, None);
+        // Only the drop between Monday's samples counts.
+        assert!((report.cycles_added - 0.1).abs() < 1e-6);
+        assert_eq!(report.top_apps.len(), 1);
+        assert_eq!(report.top_apps[0].energy_wh, 2.0);
+        assert_eq!(report.power_modes.len(), 2);
+        assert_eq!(report.power_modes[0].mode, "Low Power");
+        assert_eq!(report.power_modes[1].hours, 0.5);
+    }
+}
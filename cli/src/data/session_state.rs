@@ -0,0 +1,350 @@
+//! Session counters handed from one run of the TUI to the next.
+//!
+//! On a clean quit the counters (energy used, per-app energy, peak power,
+//! time on battery) are written to the state directory. The next launch
+//! offers to pick them up if it starts within the resume window, so quitting
+//! for a reboot does not lose the session. A reboot is told apart by the boot
+//! time, since the hardware energy counters start over with it.
+//!
+//! The file is removed once read: only a clean quit leaves one behind.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::state_dir;
+
+/// Bumped when the file format changes; files of another version are
+/// ignored rather than resumed.
+const FORMAT_VERSION: u32 = 1;
+
+/// Boot times this close are the same boot; the reported value can move by
+/// a second as the clock is adjusted.
+const BOOT_TIME_TOLERANCE_SECS: u64 = 5;
+
+/// Ticks further apart than this are a gap (sleep, a stalled terminal) and
+/// are not integrated into the counters.
+const MAX_TICK_GAP: Duration = Duration::from_secs(60);
+
+/// Label of the marker added where a resumed session was interrupted.
+pub const RESTART_MARKER: &str = "jolt restarted";
+/// Label of the marker added where the machine rebooted in between.
+pub const REBOOT_MARKER: &str = "jolt restarted (reboot)";
+
+/// A labelled point in time kept with the session, such as a restart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionMarker {
+    /// Unix seconds.
+    pub timestamp: i64,
+    pub label: String,
+}
+
+/// Counters accumulated over one session, possibly spanning restarts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionState {
+    version: u32,
+    /// Unix seconds when the session began, before any restart.
+    pub started_at: i64,
+    /// Unix seconds when the state was last written; 0 until saved.
+    pub saved_at: i64,
+    /// Boot time (Unix seconds) of the run that wrote the state.
+    pub boot_time: u64,
+    /// Total system energy used, in watt-hours.
+    pub energy_wh: f64,
+    /// Seconds spent running on battery.
+    pub battery_secs: f64,
+    /// Highest total power seen, in watts.
+    pub peak_power_watts: f32,
+    /// Estimated energy per app name, in watt-hours.
+    pub app_energy_wh: BTreeMap<String, f64>,
+    /// Oldest first.
+    pub markers: Vec<SessionMarker>,
+}
+
+/// What to offer at launch for a saved session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResumeOffer {
+    /// Same boot: counters continue where they stopped.
+    Resume,
+    /// The machine rebooted since the save, so hardware counters were reset
+    /// and only jolt's own totals carry over.
+    ResumeAfterReboot,
+}
+
+impl SessionState {
+    pub fn new(now: i64, boot_time: u64) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            started_at: now,
+            saved_at: 0,
+            boot_time,
+            energy_wh: 0.0,
+            battery_secs: 0.0,
+            peak_power_watts: 0.0,
+            app_energy_wh: BTreeMap::new(),
+            markers: Vec::new(),
+        }
+    }
+
+    /// Adds one tick of `elapsed` at `total_watts`, with `apps` giving each
+    /// app's estimated share in watts. Gaps over a minute are skipped.
+    pub fn record<'a>(
+        &mut self,
+        elapsed: Duration,
+        total_watts: f32,
+        on_battery: bool,
+        apps: impl IntoIterator<Item = (&'a str, f32)>,
+    ) {
+        if elapsed > MAX_TICK_GAP {
+            return;
+        }
+        let hours = elapsed.as_secs_f64() / 3600.0;
+        if total_watts.is_finite() && total_watts > 0.0 {
+            self.energy_wh += f64::from(total_watts) * hours;
+            self.peak_power_watts = self.peak_power_watts.max(total_watts);
+        }
+        if on_battery {
+            self.battery_secs += elapsed.as_secs_f64();
+        }
+        for (name, watts) in apps {
+            if watts.is_finite() && watts > 0.0 {
+                *self.app_energy_wh.entry(name.to_string()).or_default() +=
+                    f64::from(watts) * hours;
+            }
+        }
+    }
+
+    /// Folds a previous run's counters into this one, which then covers both
+    /// runs. A marker goes where the previous run stopped.
+    pub fn merge_previous(&mut self, previous: SessionState, offer: ResumeOffer) {
+        self.started_at = self.started_at.min(previous.started_at);
+        self.energy_wh += previous.energy_wh;
+        self.battery_secs += previous.battery_secs;
+        self.peak_power_watts = self.peak_power_watts.max(previous.peak_power_watts);
+        for (name, wh) in previous.app_energy_wh {
+            *self.app_energy_wh.entry(name).or_default() += wh;
+        }
+
+        let label = match offer {
+            ResumeOffer::Resume => RESTART_MARKER,
+            ResumeOffer::ResumeAfterReboot => REBOOT_MARKER,
+        };
+        let mut markers = previous.markers;
+        markers.push(SessionMarker {
+            timestamp: previous.saved_at,
+            label: label.to_string(),
+        });
+        markers.append(&mut self.markers);
+        self.markers = markers;
+    }
+
+    /// Apps by energy used, highest first.
+    pub fn top_apps(&self, n: usize) -> Vec<(&str, f64)> {
+        let mut apps: Vec<(&str, f64)> = self
+            .app_energy_wh
+            .iter()
+            .map(|(name, wh)| (name.as_str(), *wh))
+            .collect();
+        apps.sort_by(|a, b| b.1.total_cmp(&a.1));
+        apps.truncate(n);
+        apps
+    }
+
+    /// Writes the state to `path` as of `now`, replacing any earlier file.
+    pub fn save(&mut self, path: &Path, now: i64) -> io::Result<()> {
+        self.saved_at = now;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, path)
+    }
+
+    /// Reads and removes the state at `path`. `None` when there is none or
+    /// it cannot be read, including files from another format version.
+    pub fn take(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        let _ = fs::remove_file(path);
+        serde_json::from_str::<Self>(&content)
+            .ok()
+            .filter(|state| state.version == FORMAT_VERSION)
+    }
+
+    /// Whether to offer resuming this saved state at `now`, given the
+    /// current boot time. `None` once `window` has passed since the save, or
+    /// when the window is zero.
+    pub fn resume_offer(&self, now: i64, boot_time: u64, window: Duration) -> Option<ResumeOffer> {
+        if window.is_zero() || self.saved_at <= 0 {
+            return None;
+        }
+        let gap = now - self.saved_at;
+        if !(0..=window.as_secs() as i64).contains(&gap) {
+            return None;
+        }
+        if self.boot_time.abs_diff(boot_time) > BOOT_TIME_TOLERANCE_SECS {
+            Some(ResumeOffer::ResumeAfterReboot)
+        } else {
+            Some(ResumeOffer::Resume)
+        }
+    }
+}
+
+/// Where the session state is kept between runs.
+pub fn session_state_path() -> PathBuf {
+    state_dir().join("session.json")
+}
+
+/// Boot time of the running system in Unix seconds.
+pub fn current_boot_time() -> u64 {
+    sysinfo::System::boot_time()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOT: u64 = 1_700_000_000;
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    fn state_file(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("jolt-session-{}-{}", name, std::process::id()))
+            .join("session.json")
+    }
+
+    fn session() -> SessionState {
+        let mut state = SessionState::new(BOOT as i64 + 100, BOOT);
+        for _ in 0..60 {
+            state.record(
+                Duration::from_secs(30),
+                12.0,
+                true,
+                [("Safari", 4.0), ("Xcode", 6.0)],
+            );
+        }
+        state.record(Duration::from_secs(30), 30.0, false, [("Xcode", 20.0)]);
+        state
+    }
+
+    #[test]
+    fn counters_integrate_power_over_ticks() {
+        let mut state = session();
+        assert!((state.energy_wh - (6.0 + 0.25)).abs() < 1e-6);
+        assert_eq!(state.battery_secs, 1800.0);
+        assert_eq!(state.peak_power_watts, 30.0);
+        assert_eq!(state.top_apps(1)[0].0, "Xcode");
+
+        let before = state.clone();
+        state.record(Duration::from_secs(600), 50.0, true, [("Safari", 5.0)]);
+        assert_eq!(state, before);
+    }
+
+    #[test]
+    fn state_round_trips_through_the_file() {
+        let path = state_file("round-trip");
+        let mut state = session();
+        state.markers.push(SessionMarker {
+            timestamp: 42,
+            label: RESTART_MARKER.to_string(),
+        });
+
+        state.save(&path, BOOT as i64 + 2000).unwrap();
+        let loaded = SessionState::take(&path).unwrap();
+        assert_eq!(loaded, state);
+        assert_eq!(loaded.saved_at, BOOT as i64 + 2000);
+
+        assert!(!path.exists());
+        assert_eq!(SessionState::take(&path), None);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn files_of_another_version_are_not_resumed() {
+        let path = state_file("version");
+        let mut state = session();
+        state.version = FORMAT_VERSION + 1;
+        state.save(&path, 1).unwrap();
+        assert_eq!(SessionState::take(&path), None);
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(SessionState::take(&path), None);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn resume_is_offered_within_the_window_on_the_same_boot() {
+        let mut state = session();
+        state.saved_at = BOOT as i64 + 5000;
+        let now = state.saved_at + 600;
+
+        assert_eq!(
+            state.resume_offer(now, BOOT, HOUR),
+            Some(ResumeOffer::Resume)
+        );
+        assert_eq!(
+            state.resume_offer(now, BOOT + 1, HOUR),
+            Some(ResumeOffer::Resume)
+        );
+        assert_eq!(state.resume_offer(state.saved_at + 3601, BOOT, HOUR), None);
+        assert_eq!(state.resume_offer(now, BOOT, Duration::ZERO), None);
+        assert_eq!(state.resume_offer(state.saved_at - 10, BOOT, HOUR), None);
+    }
+
+    #[test]
+    fn a_changed_boot_time_is_offered_as_a_reboot() {
+        let mut state = session();
+        state.saved_at = BOOT as i64 + 5000;
+        let rebooted = BOOT + 5100;
+
+        assert_eq!(
+            state.resume_offer(state.saved_at + 120, rebooted, HOUR),
+            Some(ResumeOffer::ResumeAfterReboot)
+        );
+
+        state.saved_at = 0;
+        assert_eq!(state.resume_offer(120, BOOT, HOUR), None);
+    }
+
+    #[test]
+    fn merging_adds_counters_and_marks_the_gap() {
+        let mut previous = session();
+        previous.saved_at = BOOT as i64 + 2000;
+        previous.markers.push(SessionMarker {
+            timestamp: BOOT as i64 + 1000,
+            label: RESTART_MARKER.to_string(),
+        });
+
+        let mut current = SessionState::new(BOOT as i64 + 2500, BOOT + 2400);
+        current.record(Duration::from_secs(30), 60.0, true, [("Safari", 12.0)]);
+        current.merge_previous(previous.clone(), ResumeOffer::ResumeAfterReboot);
+
+        assert_eq!(current.started_at, previous.started_at);
+        assert!((current.energy_wh - (previous.energy_wh + 0.5)).abs() < 1e-6);
+        assert_eq!(current.battery_secs, previous.battery_secs + 30.0);
+        assert_eq!(current.peak_power_watts, 60.0);
+        assert!(
+            (current.app_energy_wh["Safari"] - (previous.app_energy_wh["Safari"] + 0.1)).abs()
+                < 1e-6
+        );
+        assert_eq!(current.boot_time, BOOT + 2400);
+
+        let labels: Vec<(i64, &str)> = current
+            .markers
+            .iter()
+            .map(|m| (m.timestamp, m.label.as_str()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                (BOOT as i64 + 1000, RESTART_MARKER),
+                (BOOT as i64 + 2000, REBOOT_MARKER),
+            ]
+        );
+    }
+}
@@ -61,11 +61,19 @@ impl SessionTracker {
         let battery_percent = battery.charge_percent();
         let capacity_wh = battery.max_capacity_wh();
 
+        let in_deficit = battery
+            .net_power_balance()
+            .is_some_and(|balance| balance.is_deficit());
         if let Some(last_time) = self.last_sample_time {
             let elapsed = now - last_time;
             if battery_percent >= HIGH_SOC_THRESHOLD {
                 self.time_at_high_soc_secs += elapsed;
             }
+            if in_deficit {
+                if let Some(session) = self.current_session.as_mut() {
+                    session.deficit_secs += elapsed;
+                }
+            }
         }
         self.last_sample_time = Some(now);
 
@@ -190,6 +198,7 @@ impl SessionTracker {
             avg_power_watts: avg_power,
             session_type: session.session_type,
             is_complete: true,
+            deficit_secs: session.deficit_secs,
         };
 
         self.session_start_capacity_wh = None;
@@ -1,5 +1,7 @@
 use std::process::Command;
 
+use jolt_platform::command::{self, CommandLimits};
+
 use crate::daemon::SystemSnapshot;
 
 #[derive(Debug, Clone)]
@@ -10,6 +12,12 @@ pub struct SystemInfo {
     pub e_cores: u32,
 }
 
+impl Default for SystemInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SystemInfo {
     pub fn new() -> Self {
         let (chip, p_cores, e_cores) = get_chip_info();
@@ -31,6 +39,17 @@ impl SystemInfo {
         }
     }
 
+    /// CPU cores used to normalize process CPU usage. Falls back to the
+    /// logical CPU count when the per-cluster counts are unavailable.
+    pub fn core_count(&self) -> u32 {
+        match self.p_cores + self.e_cores {
+            0 => std::thread::available_parallelism()
+                .map(|n| n.get() as u32)
+                .unwrap_or(1),
+            cores => cores,
+        }
+    }
+
     pub fn update_from_snapshot(&mut self, snapshot: &SystemSnapshot) {
         self.chip = snapshot.chip.clone();
         self.os_version = snapshot.os_version.clone();
@@ -39,6 +58,12 @@ impl SystemInfo {
     }
 }
 
+/// Model identifier such as "Mac15,6"; `None` where `hw.model` is missing
+/// or not a Mac model.
+pub fn model_identifier() -> Option<String> {
+    get_sysctl_string("hw.model")
+}
+
 fn get_chip_info() -> (String, u32, u32) {
     let chip = get_sysctl_string("machdep.cpu.brand_string")
         .map(|s| clean_chip_name(&s))
@@ -55,18 +80,25 @@ fn clean_chip_name(name: &str) -> String {
 }
 
 fn get_os_version() -> String {
-    if let Ok(output) = Command::new("sw_vers").arg("-productVersion").output() {
-        if output.status.success() {
-            return String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if let Ok(output) = command::run(
+        Command::new("sw_vers").arg("-productVersion"),
+        CommandLimits::DEFAULT,
+    ) {
+        if output.success() {
+            return output.stdout_lossy().trim().to_string();
         }
     }
     "Unknown".to_string()
 }
 
 fn get_sysctl_string(key: &str) -> Option<String> {
-    let output = Command::new("sysctl").arg("-n").arg(key).output().ok()?;
-    if output.status.success() {
-        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    let output = command::run(
+        Command::new("sysctl").arg("-n").arg(key),
+        CommandLimits::DEFAULT,
+    )
+    .ok()?;
+    if output.success() {
+        Some(output.stdout_lossy().trim().to_string())
     } else {
         None
     }
@@ -0,0 +1,216 @@
+//! Zoomable, pannable time range over the daemon's stored samples.
+//!
+//! The range is either following live, always ending now, or pinned to an
+//! end time after panning. Only samples inside the visible range are fetched
+//! and they are averaged into at most one bucket per plotted column, so the
+//! cost of drawing does not grow with the zoom level.
+
+use crate::config::ZoomLevel;
+
+use super::Sample;
+
+/// The visible window of the history timeline, in Unix seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TimeRange {
+    pub zoom: ZoomLevel,
+    /// Pinned right edge; `None` while following live.
+    end: Option<i64>,
+}
+
+impl TimeRange {
+    pub fn new(zoom: ZoomLevel) -> Self {
+        Self { zoom, end: None }
+    }
+
+    pub fn is_following_live(&self) -> bool {
+        self.end.is_none()
+    }
+
+    /// `(from, to)` of the visible window at `now`.
+    pub fn window(&self, now: i64) -> (i64, i64) {
+        let to = self.end.map_or(now, |end| end.min(now));
+        (to - self.zoom.span_secs(), to)
+    }
+
+    /// Moves the window half a span back in time, stopping once it starts
+    /// at `oldest`, the oldest stored sample.
+    pub fn pan_back(&mut self, now: i64, oldest: Option<i64>) {
+        let (_, to) = self.window(now);
+        self.end = Some(to - self.pan_step());
+        self.clamp(now, oldest);
+    }
+
+    /// Moves the window half a span forward; reaching now resumes following
+    /// live.
+    pub fn pan_forward(&mut self, now: i64, oldest: Option<i64>) {
+        if let Some(end) = self.end {
+            self.end = Some(end + self.pan_step());
+            self.clamp(now, oldest);
+        }
+    }
+
+    pub fn follow_live(&mut self) {
+        self.end = None;
+    }
+
+    /// Zooms around the right edge, so a pinned window keeps its end time.
+    pub fn zoom_in(&mut self, now: i64, oldest: Option<i64>) {
+        self.zoom = self.zoom.zoom_in();
+        self.clamp(now, oldest);
+    }
+
+    pub fn zoom_out(&mut self, now: i64, oldest: Option<i64>) {
+        self.zoom = self.zoom.zoom_out();
+        self.clamp(now, oldest);
+    }
+
+    fn pan_step(&self) -> i64 {
+        self.zoom.span_secs() / 2
+    }
+
+    /// Keeps a pinned window between the oldest sample and now, switching
+    /// back to following live once its end reaches now.
+    fn clamp(&mut self, now: i64, oldest: Option<i64>) {
+        let Some(mut end) = self.end else {
+            return;
+        };
+        if let Some(oldest) = oldest {
+            end = end.max(oldest + self.zoom.span_secs());
+        }
+        self.end = (end < now).then_some(end);
+    }
+}
+
+/// Average of the samples that fell into one plotted column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimelineBucket {
+    /// Midpoint of the bucket, in Unix seconds.
+    pub timestamp: f64,
+    pub power_watts: f32,
+    pub battery_percent: f32,
+}
+
+/// Averages `samples` inside `[from, to]` into `buckets` equal slices of the
+/// window, oldest first, skipping slices without samples.
+pub fn resample(samples: &[Sample], from: i64, to: i64, buckets: usize) -> Vec<TimelineBucket> {
+    if buckets == 0 || to <= from {
+        return Vec::new();
+    }
+
+    let width = (to - from) as f64 / buckets as f64;
+    let mut sums = vec![(0.0_f64, 0.0_f64, 0_u32); buckets];
+    for sample in samples {
+        if sample.timestamp < from || sample.timestamp > to {
+            continue;
+        }
+        let index = (((sample.timestamp - from) as f64 / width) as usize).min(buckets - 1);
+        let (power, battery, count) = &mut sums[index];
+        *power += sample.power_watts as f64;
+        *battery += sample.battery_percent as f64;
+        *count += 1;
+    }
+
+    sums.iter()
+        .enumerate()
+        .filter(|(_, (_, _, count))| *count > 0)
+        .map(|(i, (power, battery, count))| TimelineBucket {
+            timestamp: from as f64 + (i as f64 + 0.5) * width,
+            power_watts: (power / *count as f64) as f32,
+            battery_percent: (battery / *count as f64) as f32,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::ChargingState;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn sample(timestamp: i64, power_watts: f32) -> Sample {
+        Sample {
+            id: None,
+            timestamp,
+            battery_percent: 50.0,
+            power_watts,
+            cpu_power: 0.0,
+            gpu_power: 0.0,
+            charging_state: ChargingState::Discharging,
+        }
+    }
+
+    #[test]
+    fn panning_back_and_forward_returns_to_following_live() {
+        let oldest = Some(NOW - 3600);
+        let mut range = TimeRange::new(ZoomLevel::FiveMinutes);
+        assert_eq!(range.window(NOW), (NOW - 300, NOW));
+
+        range.pan_back(NOW, oldest);
+        assert!(!range.is_following_live());
+        assert_eq!(range.window(NOW), (NOW - 450, NOW - 150));
+
+        // A pinned window stays put while time moves on.
+        assert_eq!(range.window(NOW + 60), (NOW - 450, NOW - 150));
+
+        range.pan_forward(NOW, oldest);
+        assert!(range.is_following_live());
+        assert_eq!(range.window(NOW + 60), (NOW - 240, NOW + 60));
+    }
+
+    #[test]
+    fn panning_back_stops_at_the_oldest_sample() {
+        let oldest = Some(NOW - 400);
+        let mut range = TimeRange::new(ZoomLevel::FiveMinutes);
+        for _ in 0..5 {
+            range.pan_back(NOW, oldest);
+        }
+        assert_eq!(range.window(NOW), (NOW - 400, NOW - 100));
+
+        // With less history than one span there is nowhere to pan to.
+        let mut short = TimeRange::new(ZoomLevel::FiveMinutes);
+        short.pan_back(NOW, Some(NOW - 60));
+        assert!(short.is_following_live());
+    }
+
+    #[test]
+    fn zooming_keeps_the_pinned_right_edge() {
+        let oldest = Some(NOW - 24 * 3600);
+        let mut range = TimeRange::new(ZoomLevel::ThirtyMinutes);
+        range.pan_back(NOW, oldest);
+        let (_, end) = range.window(NOW);
+
+        range.zoom_in(NOW, oldest);
+        assert_eq!(range.window(NOW), (end - 300, end));
+
+        range.zoom_out(NOW, oldest);
+        range.zoom_out(NOW, oldest);
+        assert_eq!(range.zoom, ZoomLevel::TwoHours);
+        assert_eq!(range.window(NOW), (end - 7200, end));
+
+        // Zooming out past the oldest sample pulls the window forward.
+        range.zoom_out(NOW, oldest);
+        assert!(range.is_following_live());
+        assert_eq!(ZoomLevel::Day.zoom_out(), ZoomLevel::Day);
+        assert_eq!(ZoomLevel::OneMinute.zoom_in(), ZoomLevel::OneMinute);
+    }
+
+    #[test]
+    fn resample_averages_into_bounded_buckets() {
+        let samples: Vec<Sample> = (0..=60)
+            .map(|i| sample(NOW - 60 + i, i as f32))
+            .chain([sample(NOW - 120, 99.0)])
+            .collect();
+
+        let buckets = resample(&samples, NOW - 60, NOW, 4);
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets[0].power_watts, 7.0);
+        assert_eq!(buckets[0].timestamp, (NOW - 60) as f64 + 7.5);
+        // The newest sample lands in the last bucket rather than past it.
+        assert_eq!(buckets[3].power_watts, 52.5);
+
+        let sparse = resample(&[sample(NOW - 1, 5.0)], NOW - 60, NOW, 60);
+        assert_eq!(sparse.len(), 1);
+        assert!(resample(&samples, NOW, NOW, 10).is_empty());
+    }
+}
@@ -0,0 +1,197 @@
+//! Wake events parsed from the power management log.
+//!
+//! `pmset -g log` records every wake with the hardware or software reason
+//! that caused it. Dark wakes (maintenance wakes with the display off) are the
+//! usual explanation for overnight drain, so they are tracked separately.
+//! The log layout has drifted between macOS releases; the parser only relies
+//! on the leading timestamp, the tab-separated domain column, and the
+//! ": due to <reason> Using <source> (Charge:N%)" message shape.
+
+use std::ops::Range;
+
+use chrono::DateTime;
+
+const LOG_WINDOW_SECS: i64 = 24 * 3600;
+const TIMESTAMP_LEN: usize = "2024-01-01 00:00:00 +0000".len();
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WakeEvent {
+    /// Unix timestamp of the wake.
+    pub timestamp: i64,
+    /// Wake reason as reported by the system, e.g. `EC.LidOpen/Lid Open`.
+    pub reason: String,
+    pub dark_wake: bool,
+    pub charge_percent: Option<u8>,
+    /// How long the machine stayed awake, when logged.
+    pub duration_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WakeLog {
+    events: Vec<WakeEvent>,
+}
+
+impl WakeLog {
+    /// Reads wake events from the last 24 hours.
+    ///
+    /// Returns an empty log where `pmset` is unavailable.
+    pub fn load() -> Self {
+        let Some(output) = read_pmset_log() else {
+            return Self::default();
+        };
+
+        let cutoff = chrono::Utc::now().timestamp() - LOG_WINDOW_SECS;
+        let mut log = Self::parse(&output);
+        log.events.retain(|e| e.timestamp >= cutoff);
+        log
+    }
+
+    pub fn parse(output: &str) -> Self {
+        let mut events: Vec<WakeEvent> = output.lines().filter_map(parse_line).collect();
+        events.sort_by_key(|e| e.timestamp);
+        Self { events }
+    }
+
+    pub fn events(&self) -> &[WakeEvent] {
+        &self.events
+    }
+
+    /// Wake events with a timestamp inside `range` (Unix seconds).
+    pub fn wakes_between(&self, range: Range<i64>) -> Vec<&WakeEvent> {
+        self.events
+            .iter()
+            .filter(|e| range.contains(&e.timestamp))
+            .collect()
+    }
+
+    pub fn dark_wake_count(&self) -> usize {
+        self.events.iter().filter(|e| e.dark_wake).count()
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn read_pmset_log() -> Option<String> {
+    use std::process::Command;
+    use std::time::Duration;
+
+    use jolt_platform::command::{self, CommandLimits};
+
+    // The log goes back weeks and runs to megabytes.
+    let limits = CommandLimits::DEFAULT
+        .with_timeout(Duration::from_secs(15))
+        .with_max_output(64 * 1024 * 1024);
+    let output = command::run(Command::new("pmset").args(["-g", "log"]), limits).ok()?;
+    output.success().then(|| output.stdout_lossy())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_pmset_log() -> Option<String> {
+    None
+}
+
+fn parse_line(line: &str) -> Option<WakeEvent> {
+    let timestamp_str = line.get(..TIMESTAMP_LEN)?;
+    let timestamp = DateTime::parse_from_str(timestamp_str, "%Y-%m-%d %H:%M:%S %z")
+        .ok()?
+        .timestamp();
+
+    let (domain, message) = line[TIMESTAMP_LEN..].split_once('\t')?;
+    let dark_wake = match domain.trim() {
+        "DarkWake" => true,
+        // Also covers "DarkWake to FullWake", which is the user waking a
+        // machine that was already in a dark wake.
+        "Wake" => false,
+        _ => return None,
+    };
+
+    let (_, after_due) = message.split_once(" : due to ")?;
+    let (reason, tail) = match after_due.split_once(" Using ") {
+        Some((reason, tail)) => (reason, tail),
+        None => (after_due, ""),
+    };
+    let reason = reason.trim().trim_end_matches('/').trim();
+
+    Some(WakeEvent {
+        timestamp,
+        reason: reason.to_string(),
+        dark_wake,
+        charge_percent: parse_charge(tail),
+        duration_secs: parse_duration_secs(tail),
+    })
+}
+
+fn parse_charge(tail: &str) -> Option<u8> {
+    let (_, after) = tail.split_once("(Charge:")?;
+    let (percent, _) = after.split_once('%')?;
+    percent.trim().parse().ok()
+}
+
+fn parse_duration_secs(tail: &str) -> Option<u64> {
+    let (_, after_charge) = tail.split_once(')')?;
+    let secs = after_charge.trim().strip_suffix("secs")?;
+    secs.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MACOS_10_15: &str = include_str!("../../../fixtures/pmset/macos-10.15.log");
+    const MACOS_13: &str = include_str!("../../../fixtures/pmset/macos-13.log");
+    const MACOS_14: &str = include_str!("../../../fixtures/pmset/macos-14.log");
+
+    #[test]
+    fn parses_catalina_log() {
+        let log = WakeLog::parse(MACOS_10_15);
+        let events = log.events();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(log.dark_wake_count(), 2);
+        assert_eq!(events[0].reason, "EC.RTC/Maintenance");
+        assert_eq!(events[0].charge_percent, Some(70));
+        assert_eq!(events[0].duration_secs, Some(45));
+        assert_eq!(events[1].reason, "EC.ARPT");
+        assert!(!events[2].dark_wake);
+        assert_eq!(events[2].reason, "EC.LidOpen/Lid Open");
+        assert_eq!(events[2].duration_secs, None);
+    }
+
+    #[test]
+    fn parses_ventura_log_and_skips_wake_requests() {
+        let log = WakeLog::parse(MACOS_13);
+        let events = log.events();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].reason, "SMC.OutboxNotEmpty smc.70070000 wifibt");
+        assert_eq!(events[1].reason, "RTC/Maintenance");
+        assert!(!events[2].dark_wake);
+        assert_eq!(events[2].reason, "HID Activity");
+        assert_eq!(events[2].charge_percent, Some(100));
+    }
+
+    #[test]
+    fn parses_sonoma_log() {
+        let log = WakeLog::parse(MACOS_14);
+        let events = log.events();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(log.dark_wake_count(), 2);
+        assert_eq!(
+            events[0].reason,
+            "NUB.SPMISw3IRQ nub-spmi0.0x02 rtc/Maintenance"
+        );
+        assert_eq!(events[2].reason, "smc.70070000 lid UserActivity Assertion");
+        assert_eq!(events[2].charge_percent, Some(90));
+    }
+
+    #[test]
+    fn wakes_between_uses_half_open_range() {
+        let log = WakeLog::parse(MACOS_14);
+        let first = log.events()[0].timestamp;
+        let second = log.events()[1].timestamp;
+
+        assert_eq!(log.wakes_between(first..second).len(), 1);
+        assert_eq!(log.wakes_between(first..second + 1).len(), 2);
+        assert!(log.wakes_between(0..first).is_empty());
+    }
+}
@@ -19,6 +19,18 @@ pub mod keys {
     pub const ESC: &str = "Esc";
     pub const SETTINGS: &str = "s";
     pub const BATTERY_DETAILS: &str = "b";
+    pub const DEBUG_PANEL: &str = "D";
+    pub const COPY: &str = "y";
+    pub const LAST_UNTIL: &str = "u";
+    pub const FLEET: &str = "F";
+    pub const HEATMAP: &str = "H";
+    pub const BUG_REPORT: &str = "R";
+    pub const ZOOM_IN: &str = "+";
+    pub const ZOOM_OUT: &str = "-";
+    pub const PAN_BACK: &str = "h";
+    pub const PAN_FORWARD: &str = "l";
+    pub const FOLLOW_LIVE: &str = "0";
+    pub const SAVE_SETTINGS: &str = "w";
 }
 
 pub fn handle_key(app: &App, key: KeyEvent) -> Action {
@@ -36,8 +48,15 @@ pub fn handle_key(app: &App, key: KeyEvent) -> Action {
             }
         }
         AppView::History => handle_history_keys(key),
-        AppView::Settings => handle_settings_keys(key),
+        AppView::Settings => handle_settings_keys(key, app.settings_save_prompt),
         AppView::BatteryDetails => handle_battery_details_keys(key),
+        AppView::ChargerDetails => handle_charger_details_keys(key),
+        AppView::DebugPanel => handle_debug_panel_keys(key),
+        AppView::LastUntil => handle_last_until_keys(key),
+        AppView::Fleet => handle_fleet_keys(key),
+        AppView::Heatmap => handle_heatmap_keys(key),
+        AppView::BugReport => handle_bug_report_keys(key),
+        AppView::ResumeSession => handle_resume_session_keys(key),
     }
 }
 
@@ -73,6 +92,55 @@ fn handle_main_keys(key: KeyEvent, selection_mode: bool) -> Action {
         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
         KeyCode::Char('h') => Action::ToggleHistory,
         KeyCode::Char('b') => Action::ToggleBatteryDetails,
+        KeyCode::Char('D') => Action::ToggleDebugPanel,
+        KeyCode::Char('y') => Action::CopyToClipboard,
+        KeyCode::Char('u') => Action::ToggleLastUntil,
+        KeyCode::Char('F') => Action::ToggleFleet,
+        KeyCode::Char('H') => Action::ToggleHeatmap,
+        KeyCode::Char('R') => Action::ToggleBugReport,
+        _ => Action::None,
+    }
+}
+
+fn handle_bug_report_keys(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => Action::ConfirmBugReport,
+        KeyCode::Esc | KeyCode::Char('R') | KeyCode::Char('q') | KeyCode::Char('n') => {
+            Action::ToggleBugReport
+        }
+        _ => Action::None,
+    }
+}
+
+fn handle_resume_session_keys(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => Action::ResumeSession,
+        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Char('q') => {
+            Action::DiscardSession
+        }
+        _ => Action::None,
+    }
+}
+
+fn handle_fleet_keys(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('F') | KeyCode::Char('q') => Action::ToggleFleet,
+        _ => Action::None,
+    }
+}
+
+fn handle_heatmap_keys(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('H') | KeyCode::Char('q') => Action::ToggleHeatmap,
+        _ => Action::None,
+    }
+}
+
+fn handle_last_until_keys(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => Action::ToggleLastUntil,
+        KeyCode::Backspace => Action::LastUntilBackspace,
+        KeyCode::Char(c) if c.is_ascii_digit() || c == ':' => Action::LastUntilChar(c),
         _ => Action::None,
     }
 }
@@ -149,13 +217,28 @@ fn handle_history_keys(key: KeyEvent) -> Action {
         KeyCode::Left | KeyCode::Char('[') => Action::HistoryPrevPeriod,
         KeyCode::Right | KeyCode::Char(']') => Action::HistoryNextPeriod,
         KeyCode::Tab => Action::HistoryNextPeriod,
+        KeyCode::Char('+') | KeyCode::Char('=') => Action::HistoryZoomIn,
+        KeyCode::Char('-') => Action::HistoryZoomOut,
+        KeyCode::Char('h') => Action::HistoryPanBack,
+        KeyCode::Char('l') => Action::HistoryPanForward,
+        KeyCode::Char('0') => Action::HistoryFollowLive,
         KeyCode::Char('s') => Action::ToggleSettings,
+        KeyCode::Char('y') => Action::CopyToClipboard,
         _ => Action::None,
     }
 }
 
-fn handle_settings_keys(key: KeyEvent) -> Action {
+fn handle_settings_keys(key: KeyEvent, save_prompt: bool) -> Action {
+    if save_prompt {
+        return match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => Action::SettingsSave,
+            KeyCode::Char('n') => Action::SettingsKeepUnsaved,
+            KeyCode::Esc => Action::ToggleSettings,
+            _ => Action::None,
+        };
+    }
     match key.code {
+        KeyCode::Char('w') => Action::SettingsSave,
         KeyCode::Esc | KeyCode::Char('s') | KeyCode::Char('q') => Action::ToggleSettings,
         KeyCode::Up | KeyCode::Char('k') => Action::SelectPrevious,
         KeyCode::Down | KeyCode::Char('j') => Action::SelectNext,
@@ -169,6 +252,21 @@ fn handle_settings_keys(key: KeyEvent) -> Action {
 fn handle_battery_details_keys(key: KeyEvent) -> Action {
     match key.code {
         KeyCode::Esc | KeyCode::Char('b') | KeyCode::Char('q') => Action::ToggleBatteryDetails,
+        KeyCode::Char('c') => Action::ToggleChargerDetails,
+        _ => Action::None,
+    }
+}
+
+fn handle_charger_details_keys(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('c') | KeyCode::Char('q') => Action::ToggleChargerDetails,
+        _ => Action::None,
+    }
+}
+
+fn handle_debug_panel_keys(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('D') | KeyCode::Char('q') => Action::ToggleDebugPanel,
         _ => Action::None,
     }
 }
@@ -231,6 +329,26 @@ pub const KEY_BINDINGS: &[KeyBinding] = &[
         key: keys::SORT_DIR,
         description: "Toggle sort direction",
     },
+    KeyBinding {
+        key: keys::COPY,
+        description: "Copy summary, selected row, or history stats",
+    },
+    KeyBinding {
+        key: keys::LAST_UNTIL,
+        description: "Will the battery last until a given time?",
+    },
+    KeyBinding {
+        key: keys::FLEET,
+        description: "Fleet view (peers from fleet_peers config)",
+    },
+    KeyBinding {
+        key: keys::HEATMAP,
+        description: "Power heatmap by weekday and hour",
+    },
+    KeyBinding {
+        key: keys::BUG_REPORT,
+        description: "Create a redacted bug-report bundle",
+    },
     KeyBinding {
         key: "-/+",
         description: "Decrease/increase refresh rate",
@@ -255,6 +373,10 @@ pub const KEY_BINDINGS: &[KeyBinding] = &[
         key: keys::BATTERY_DETAILS,
         description: "Battery details",
     },
+    KeyBinding {
+        key: keys::DEBUG_PANEL,
+        description: "Refresh timings (debug)",
+    },
     KeyBinding {
         key: keys::QUIT,
         description: "Quit",
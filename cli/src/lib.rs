@@ -0,0 +1,20 @@
+//! Everything behind the `jolt` binary: data sources, the TUI and the
+//! subcommands. The data sources are public so other tools can read
+//! battery, power, process and history data the way jolt does.
+
+pub mod app;
+pub mod bugreport;
+pub mod cli;
+mod clipboard;
+pub mod commands;
+pub mod config;
+mod config_watch;
+mod crash;
+pub mod daemon;
+pub mod data;
+mod input;
+pub mod logging;
+mod settings;
+pub mod terminal;
+pub mod theme;
+mod ui;
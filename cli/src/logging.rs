@@ -5,9 +5,11 @@ use tracing_appender::non_blocking::WorkerGuard;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_subscriber::fmt::time::UtcTime;
 use tracing_subscriber::prelude::*;
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::{fmt, EnvFilter, Layer};
 
 use crate::config::{runtime_dir, LogLevel};
+use crate::crash;
+use crate::terminal::{self, Stream};
 
 static INIT: OnceLock<()> = OnceLock::new();
 
@@ -29,6 +31,9 @@ pub fn init(level: LogLevel, mode: LogMode, cli_override: Option<LogLevel>) -> L
         let effective_level = cli_override.unwrap_or(level);
 
         let Some(tracing_level) = effective_level.as_tracing_level() else {
+            tracing_subscriber::registry()
+                .with(crash::log_ring_layer())
+                .init();
             return;
         };
 
@@ -84,8 +89,8 @@ fn init_file_logging(level: Level) -> Option<WorkerGuard> {
         .with_line_number(true);
 
     tracing_subscriber::registry()
-        .with(build_env_filter(level))
-        .with(file_layer)
+        .with(file_layer.with_filter(build_env_filter(level)))
+        .with(crash::log_ring_layer())
         .init();
 
     Some(guard)
@@ -95,12 +100,12 @@ fn init_stderr_logging(level: Level) {
     let stderr_layer = fmt::layer()
         .with_writer(std::io::stderr)
         .with_timer(UtcTime::rfc_3339())
-        .with_ansi(true)
+        .with_ansi(terminal::color_enabled(Stream::Stderr))
         .with_target(true);
 
     tracing_subscriber::registry()
-        .with(build_env_filter(level))
-        .with(stderr_layer)
+        .with(stderr_layer.with_filter(build_env_filter(level)))
+        .with(crash::log_ring_layer())
         .init();
 }
 
@@ -136,13 +141,13 @@ fn init_both_logging(level: Level) -> Option<WorkerGuard> {
     let stderr_layer = fmt::layer()
         .with_writer(std::io::stderr)
         .with_timer(UtcTime::rfc_3339())
-        .with_ansi(true)
+        .with_ansi(terminal::color_enabled(Stream::Stderr))
         .with_target(true);
 
     tracing_subscriber::registry()
-        .with(build_env_filter(level))
-        .with(file_layer)
-        .with(stderr_layer)
+        .with(file_layer.with_filter(build_env_filter(level)))
+        .with(stderr_layer.with_filter(build_env_filter(level)))
+        .with(crash::log_ring_layer())
         .init();
 
     Some(guard)
@@ -1,30 +1,33 @@
-mod app;
-mod cli;
-mod commands;
-mod config;
-mod daemon;
-mod data;
-mod input;
-mod logging;
-mod settings;
-mod theme;
-mod ui;
-
-use app::run_tui;
-use clap::Parser;
+use clap::{ColorChoice, CommandFactory, FromArgMatches};
 use color_eyre::eyre::Result;
 
-use cli::{Cli, Commands};
-use config::{ensure_dirs, LogLevel, UserConfig};
-use logging::LogMode;
+use jolt_tui::app::run_tui;
+use jolt_tui::cli::{Cli, Commands};
+use jolt_tui::config::{ensure_dirs, LogLevel, UserConfig};
+use jolt_tui::data::ProcessRedactor;
+use jolt_tui::logging::LogMode;
+use jolt_tui::terminal::{self, Stream};
+use jolt_tui::{commands, logging};
 
 fn main() -> Result<()> {
-    color_eyre::install()?;
+    if terminal::color_enabled(Stream::Stderr) {
+        color_eyre::install()?;
+    } else {
+        color_eyre::config::HookBuilder::default()
+            .theme(color_eyre::config::Theme::new())
+            .install()?;
+    }
     let _ = ensure_dirs();
 
-    let cli = Cli::parse();
+    let color = if terminal::color_enabled(Stream::Stdout) {
+        ColorChoice::Auto
+    } else {
+        ColorChoice::Never
+    };
+    let matches = Cli::command().color(color).get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
     let config = UserConfig::load();
-    let log_level_override = cli.log_level.as_deref().map(LogLevel::from_str);
+    let log_level_override = cli.log_level.as_deref().map(LogLevel::parse);
 
     match cli.command {
         Some(Commands::Pipe {
@@ -33,12 +36,48 @@ fn main() -> Result<()> {
             compact,
         }) => {
             let _guard = logging::init(config.log_level, LogMode::Stderr, log_level_override);
-            commands::pipe::run(samples, interval, compact)
+            commands::pipe::run(
+                samples,
+                interval,
+                compact,
+                config.units.cpu,
+                ProcessRedactor::new(&config.redact_processes),
+            )
+        }
+        Some(Commands::Serve {
+            bind,
+            peers,
+            peer_interval,
+        }) => {
+            let _guard = logging::init(config.log_level, LogMode::Stderr, log_level_override);
+            commands::serve::run(
+                &bind,
+                peers,
+                peer_interval,
+                ProcessRedactor::new(&config.redact_processes),
+            )
+        }
+        Some(Commands::Debug { list_channels }) => {
+            let _guard = logging::init(config.log_level, LogMode::Stderr, log_level_override);
+            if list_channels {
+                commands::debug::list_channels()
+            } else {
+                commands::debug::run()
+            }
+        }
+        Some(Commands::Doctor) => {
+            let _guard = logging::init(config.log_level, LogMode::Stderr, log_level_override);
+            commands::doctor::run()
+        }
+        Some(Commands::ContributeChannels { output }) => {
+            let _guard = logging::init(config.log_level, LogMode::Stderr, log_level_override);
+            commands::contribute_channels::run(output)
         }
-        Some(Commands::Debug) => {
+        Some(Commands::Validate { duration }) => {
             let _guard = logging::init(config.log_level, LogMode::Stderr, log_level_override);
-            commands::debug::run()
+            commands::validate::run(duration)
         }
+        Some(Commands::Check { config: path }) => commands::check::run(path),
         Some(Commands::Config { path, reset, edit }) => {
             let _guard = logging::init(config.log_level, LogMode::Stderr, log_level_override);
             commands::config::run(path, reset, edit)
@@ -52,10 +91,36 @@ fn main() -> Result<()> {
         }
         Some(Commands::History { command }) => {
             let _guard = logging::init(config.log_level, LogMode::Stderr, log_level_override);
-            commands::history::run(command)
+            commands::history::run(command, ProcessRedactor::new(&config.redact_processes))
+        }
+        Some(Commands::Report {
+            weekly: _,
+            json,
+            measured_only,
+        }) => {
+            let _guard = logging::init(config.log_level, LogMode::Stderr, log_level_override);
+            commands::report::run(
+                json,
+                !measured_only,
+                config.history.sample_interval_secs,
+                ProcessRedactor::new(&config.redact_processes),
+            )
         }
         Some(Commands::Logs { lines, follow }) => commands::logs::run(lines, follow),
+        Some(Commands::Bugreport {
+            minutes,
+            include_identifiers,
+            yes,
+        }) => {
+            let _guard = logging::init(config.log_level, LogMode::Stderr, log_level_override);
+            commands::bugreport::run(minutes, include_identifiers, yes)
+        }
+        Some(Commands::Ui) | None if cli.plain => {
+            let _guard = logging::init(config.log_level, LogMode::File, log_level_override);
+            commands::plain::run(&config)
+        }
         Some(Commands::Ui) | None => {
+            terminal::ensure_interactive()?;
             let _guard = logging::init(config.log_level, LogMode::File, log_level_override);
             run_tui(config)
         }
@@ -65,10 +130,10 @@ fn main() -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use clap::CommandFactory;
+    use clap::Parser;
 
-    use crate::cli::{DaemonCommands, HistoryCommands, ThemeCommands};
-    use crate::commands::history::{escape_csv, get_date_range, truncate_str};
+    use jolt_tui::cli::{DaemonCommands, HistoryCommands, ThemeCommands};
+    use jolt_tui::commands::history::{escape_csv, get_date_range, truncate_str};
 
     #[test]
     fn cli_configuration_is_valid() {
@@ -122,10 +187,76 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_parse_serve_with_peers() {
+        let cli =
+            Cli::try_parse_from(["jolt", "serve", "--peers", "air:9898,studio:9898"]).unwrap();
+        match cli.command {
+            Some(Commands::Serve {
+                bind,
+                peers,
+                peer_interval,
+            }) => {
+                assert_eq!(bind, "127.0.0.1:9898");
+                assert_eq!(peers, vec!["air:9898", "studio:9898"]);
+                assert_eq!(peer_interval, 5000);
+            }
+            _ => panic!("Expected Serve command"),
+        }
+    }
+
     #[test]
     fn cli_parse_debug_command() {
         let cli = Cli::try_parse_from(["jolt", "debug"]).unwrap();
-        assert!(matches!(cli.command, Some(Commands::Debug)));
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Debug {
+                list_channels: false
+            })
+        ));
+    }
+
+    #[test]
+    fn cli_parse_doctor_command() {
+        let cli = Cli::try_parse_from(["jolt", "doctor"]).unwrap();
+        assert!(matches!(cli.command, Some(Commands::Doctor)));
+    }
+
+    #[test]
+    fn cli_parse_bugreport_defaults_to_redacted() {
+        let cli = Cli::try_parse_from(["jolt", "bug-report", "-y"]).unwrap();
+        match cli.command {
+            Some(Commands::Bugreport {
+                minutes,
+                include_identifiers,
+                yes,
+            }) => {
+                assert_eq!(minutes, jolt_tui::bugreport::DEFAULT_LOG_MINUTES);
+                assert!(!include_identifiers);
+                assert!(yes);
+            }
+            _ => panic!("Expected Bugreport command"),
+        }
+    }
+
+    #[test]
+    fn cli_parse_validate_duration() {
+        let cli = Cli::try_parse_from(["jolt", "validate", "--duration", "30"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Validate { duration: 30 })
+        ));
+    }
+
+    #[test]
+    fn cli_parse_check_with_config() {
+        let cli = Cli::try_parse_from(["jolt", "check", "--config", "team.toml"]).unwrap();
+        match cli.command {
+            Some(Commands::Check { config }) => {
+                assert_eq!(config, Some(std::path::PathBuf::from("team.toml")));
+            }
+            _ => panic!("Expected Check command"),
+        }
     }
 
     #[test]
@@ -202,6 +333,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_parse_report_requires_a_period() {
+        assert!(Cli::try_parse_from(["jolt", "report"]).is_err());
+
+        let cli = Cli::try_parse_from(["jolt", "report", "--weekly", "--json"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Report {
+                weekly: true,
+                json: true,
+                measured_only: false,
+            })
+        ));
+    }
+
     #[test]
     fn cli_parse_global_log_level_before_subcommand() {
         let cli = Cli::try_parse_from(["jolt", "--log-level", "debug", "ui"]).unwrap();
@@ -4,6 +4,7 @@
 //! making settings type-safe, DRY, and localization-friendly.
 
 use crate::app::App;
+use crate::data::power::{MAX_SMOOTHING_SAMPLES, MIN_WARMUP_SAMPLES};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SettingId {
@@ -11,15 +12,25 @@ pub enum SettingId {
     Theme,
     Appearance,
     RefreshMs,
+    PowerSmoothing,
+    LogLevel,
     // Display
     ShowGraph,
     MergeMode,
     ProcessCount,
+    HideSystemProcesses,
+    ExcludedProcesses,
     EnergyThreshold,
+    NetworkActivity,
+    CpuSparkline,
+    GpuSparkline,
+    BatterySparkline,
+    TemperatureSparkline,
     // Units
     EnergyUnit,
     TemperatureUnit,
     DataSizeUnit,
+    CpuNormalization,
     // Recording
     BackgroundRecording,
     SampleInterval,
@@ -88,6 +99,14 @@ pub const SETTINGS_LAYOUT: &[SettingsRow] = &[
         id: SettingId::RefreshMs,
         label: "Refresh Rate (ms)",
     },
+    SettingsRow::Item {
+        id: SettingId::PowerSmoothing,
+        label: "Power Smoothing",
+    },
+    SettingsRow::Item {
+        id: SettingId::LogLevel,
+        label: "Log Level",
+    },
     // Display section
     SettingsRow::Section("Display"),
     SettingsRow::Item {
@@ -102,10 +121,38 @@ pub const SETTINGS_LAYOUT: &[SettingsRow] = &[
         id: SettingId::ProcessCount,
         label: "Process Count",
     },
+    SettingsRow::Item {
+        id: SettingId::HideSystemProcesses,
+        label: "Hide System Processes",
+    },
+    SettingsRow::Item {
+        id: SettingId::ExcludedProcesses,
+        label: "Excluded Processes",
+    },
     SettingsRow::Item {
         id: SettingId::EnergyThreshold,
         label: "Energy Threshold",
     },
+    SettingsRow::Item {
+        id: SettingId::NetworkActivity,
+        label: "Network Activity",
+    },
+    SettingsRow::Item {
+        id: SettingId::CpuSparkline,
+        label: "CPU Sparkline",
+    },
+    SettingsRow::Item {
+        id: SettingId::GpuSparkline,
+        label: "GPU Sparkline",
+    },
+    SettingsRow::Item {
+        id: SettingId::BatterySparkline,
+        label: "Battery Sparkline",
+    },
+    SettingsRow::Item {
+        id: SettingId::TemperatureSparkline,
+        label: "Temperature Sparkline",
+    },
     SettingsRow::Section("Units"),
     SettingsRow::Item {
         id: SettingId::EnergyUnit,
@@ -119,6 +166,10 @@ pub const SETTINGS_LAYOUT: &[SettingsRow] = &[
         id: SettingId::DataSizeUnit,
         label: "Data Size",
     },
+    SettingsRow::Item {
+        id: SettingId::CpuNormalization,
+        label: "Process CPU %",
+    },
     SettingsRow::Section("Recording"),
     SettingsRow::Item {
         id: SettingId::BackgroundRecording,
@@ -150,19 +201,41 @@ pub const SETTINGS_LAYOUT: &[SettingsRow] = &[
     },
 ];
 
+/// Why a setting is shown but cannot be changed from the settings panel, or
+/// `None` when it can be.
+pub const fn read_only_reason(id: SettingId) -> Option<&'static str> {
+    match id {
+        SettingId::LogLevel => Some("Read at startup; restart jolt to apply"),
+        SettingId::ExcludedProcesses => Some("Edit excluded_processes in config.toml"),
+        _ => None,
+    }
+}
+
 /// Get the current display value for a setting.
 pub fn setting_value(app: &App, id: SettingId) -> String {
     match id {
         SettingId::Theme => format!("{} \u{2192}", app.config.theme_name()),
         SettingId::Appearance => app.config.appearance_label().to_string(),
         SettingId::RefreshMs => app.refresh_ms.to_string(),
+        SettingId::PowerSmoothing => app.power.smoothing_samples().to_string(),
+        SettingId::LogLevel => format!("{:?}", app.config.user_config.log_level),
         SettingId::ShowGraph => bool_label(app.config.user_config.show_graph),
         SettingId::MergeMode => bool_label(app.merge_mode),
         SettingId::ProcessCount => app.config.user_config.process_count.to_string(),
+        SettingId::HideSystemProcesses => bool_label(app.config.user_config.hide_system_processes),
+        SettingId::ExcludedProcesses => app.config.user_config.excluded_processes.len().to_string(),
         SettingId::EnergyThreshold => format!("{:.1}", app.config.user_config.energy_threshold),
+        SettingId::NetworkActivity => bool_label(app.config.user_config.process_network),
+        SettingId::CpuSparkline => bool_label(app.config.user_config.sparklines.cpu),
+        SettingId::GpuSparkline => bool_label(app.config.user_config.sparklines.gpu),
+        SettingId::BatterySparkline => bool_label(app.config.user_config.sparklines.battery),
+        SettingId::TemperatureSparkline => {
+            bool_label(app.config.user_config.sparklines.temperature)
+        }
         SettingId::EnergyUnit => app.config.user_config.units.energy.label().to_string(),
         SettingId::TemperatureUnit => app.config.user_config.units.temperature.label().to_string(),
         SettingId::DataSizeUnit => app.config.user_config.units.data_size.label().to_string(),
+        SettingId::CpuNormalization => app.config.user_config.units.cpu.label().to_string(),
         SettingId::BackgroundRecording => {
             bool_label(app.config.user_config.history.background_recording)
         }
@@ -203,8 +276,12 @@ pub fn setting_value(app: &App, id: SettingId) -> String {
 }
 
 /// Apply an input action to a setting. Returns the outcome.
+///
+/// Changes take effect immediately but are only written to the config file
+/// once the user confirms; until then [`App::settings_unsaved`] is set.
 pub fn setting_apply(app: &mut App, id: SettingId, input: SettingInput) -> SettingOutcome {
     match id {
+        SettingId::LogLevel | SettingId::ExcludedProcesses => SettingOutcome::default(),
         SettingId::Theme => SettingOutcome { open_modal: true },
         SettingId::Appearance => {
             if input == SettingInput::Activate {
@@ -227,6 +304,18 @@ pub fn setting_apply(app: &mut App, id: SettingId, input: SettingInput) -> Setti
             10000,
             500,
         ),
+        SettingId::PowerSmoothing => apply_int(
+            app,
+            input,
+            |a| a.power.smoothing_samples() as i64,
+            |a, v| {
+                a.power.set_smoothing_samples(v as usize);
+                a.config.user_config.power_smoothing_samples = a.power.smoothing_samples();
+            },
+            MIN_WARMUP_SAMPLES as i64,
+            MAX_SMOOTHING_SAMPLES as i64,
+            1,
+        ),
         SettingId::ShowGraph => apply_bool(
             app,
             input,
@@ -251,6 +340,16 @@ pub fn setting_apply(app: &mut App, id: SettingId, input: SettingInput) -> Setti
             200,
             10,
         ),
+        SettingId::HideSystemProcesses => apply_bool(
+            app,
+            input,
+            |a| a.config.user_config.hide_system_processes,
+            |a, v| {
+                a.config.user_config.hide_system_processes = v;
+                a.selected_process_index = 0;
+                a.process_scroll_offset = 0;
+            },
+        ),
         SettingId::EnergyThreshold => apply_float(
             app,
             input,
@@ -260,6 +359,39 @@ pub fn setting_apply(app: &mut App, id: SettingId, input: SettingInput) -> Setti
             10.0,
             0.5,
         ),
+        SettingId::NetworkActivity => apply_bool(
+            app,
+            input,
+            |a| a.config.user_config.process_network,
+            |a, v| {
+                a.config.user_config.process_network = v;
+                a.set_process_network_sampling(v);
+            },
+        ),
+        SettingId::CpuSparkline => apply_bool(
+            app,
+            input,
+            |a| a.config.user_config.sparklines.cpu,
+            |a, v| a.config.user_config.sparklines.cpu = v,
+        ),
+        SettingId::GpuSparkline => apply_bool(
+            app,
+            input,
+            |a| a.config.user_config.sparklines.gpu,
+            |a, v| a.config.user_config.sparklines.gpu = v,
+        ),
+        SettingId::BatterySparkline => apply_bool(
+            app,
+            input,
+            |a| a.config.user_config.sparklines.battery,
+            |a, v| a.config.user_config.sparklines.battery = v,
+        ),
+        SettingId::TemperatureSparkline => apply_bool(
+            app,
+            input,
+            |a| a.config.user_config.sparklines.temperature,
+            |a, v| a.config.user_config.sparklines.temperature = v,
+        ),
         SettingId::EnergyUnit => apply_cycle(
             app,
             input,
@@ -278,6 +410,12 @@ pub fn setting_apply(app: &mut App, id: SettingId, input: SettingInput) -> Setti
             |a| a.config.user_config.units.data_size,
             |a, v| a.config.user_config.units.data_size = v,
         ),
+        SettingId::CpuNormalization => apply_cycle(
+            app,
+            input,
+            |a| a.config.user_config.units.cpu,
+            |a, v| a.config.user_config.units.cpu = v,
+        ),
         SettingId::BackgroundRecording => apply_bool(
             app,
             input,
@@ -379,7 +517,7 @@ where
     if input == SettingInput::Activate {
         let new_val = !get(app);
         set(app, new_val);
-        let _ = app.config.user_config.save();
+        app.settings_unsaved = true;
         SettingOutcome { open_modal: false }
     } else {
         SettingOutcome::default()
@@ -408,7 +546,7 @@ where
 
     if new_val != current {
         set(app, new_val);
-        let _ = app.config.user_config.save();
+        app.settings_unsaved = true;
         SettingOutcome { open_modal: false }
     } else {
         SettingOutcome::default()
@@ -437,7 +575,7 @@ where
 
     if (new_val - current).abs() > f64::EPSILON {
         set(app, new_val);
-        let _ = app.config.user_config.save();
+        app.settings_unsaved = true;
         SettingOutcome { open_modal: false }
     } else {
         SettingOutcome::default()
@@ -476,6 +614,15 @@ impl Cyclable for crate::config::DataSizeUnit {
     }
 }
 
+impl Cyclable for crate::config::CpuNormalization {
+    fn next(&self) -> Self {
+        crate::config::CpuNormalization::next(self)
+    }
+    fn prev(&self) -> Self {
+        crate::config::CpuNormalization::prev(self)
+    }
+}
+
 fn apply_cycle<T, G, S>(app: &mut App, input: SettingInput, get: G, set: S) -> SettingOutcome
 where
     T: Cyclable,
@@ -486,14 +633,44 @@ where
         SettingInput::Activate | SettingInput::Increment => {
             let new_val = get(app).next();
             set(app, new_val);
-            let _ = app.config.user_config.save();
+            app.settings_unsaved = true;
             SettingOutcome { open_modal: false }
         }
         SettingInput::Decrement => {
             let new_val = get(app).prev();
             set(app, new_val);
-            let _ = app.config.user_config.save();
+            app.settings_unsaved = true;
             SettingOutcome { open_modal: false }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_only_settings_are_listed_and_explained() {
+        let ids: Vec<SettingId> = SETTINGS_LAYOUT
+            .iter()
+            .filter_map(SettingsRow::setting_id)
+            .collect();
+        let read_only: Vec<SettingId> = ids
+            .iter()
+            .copied()
+            .filter(|&id| read_only_reason(id).is_some())
+            .collect();
+
+        assert_eq!(
+            read_only,
+            vec![SettingId::LogLevel, SettingId::ExcludedProcesses]
+        );
+        assert!(read_only_reason(SettingId::PowerSmoothing).is_none());
+        assert!(read_only_reason(SettingId::HideSystemProcesses).is_none());
+
+        let mut unique = ids.clone();
+        unique.sort_by_key(|id| format!("{:?}", id));
+        unique.dedup();
+        assert_eq!(unique.len(), ids.len(), "a setting is listed twice");
+    }
+}
@@ -0,0 +1,55 @@
+//! Whether output may carry ANSI escape codes.
+//!
+//! Color is used only when the stream is a terminal and `NO_COLOR` is unset
+//! or empty (<https://no-color.org>). Log formatting, error reports, clap's
+//! help and the TUI bootstrap all ask here, so piping jolt into another
+//! program or a file never writes escape codes into it.
+
+use std::ffi::OsStr;
+use std::io::{self, IsTerminal};
+
+use color_eyre::eyre::{bail, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+pub fn color_enabled(stream: Stream) -> bool {
+    let is_terminal = match stream {
+        Stream::Stdout => io::stdout().is_terminal(),
+        Stream::Stderr => io::stderr().is_terminal(),
+    };
+    allows_color(std::env::var_os("NO_COLOR").as_deref(), is_terminal)
+}
+
+fn allows_color(no_color: Option<&OsStr>, is_terminal: bool) -> bool {
+    is_terminal && no_color.is_none_or(OsStr::is_empty)
+}
+
+/// Fails unless stdin and stdout are both a terminal, which the TUI needs
+/// to read keys and draw.
+pub fn ensure_interactive() -> Result<()> {
+    if io::stdout().is_terminal() && io::stdin().is_terminal() {
+        return Ok(());
+    }
+    bail!(
+        "The terminal UI needs an interactive terminal, but stdin or stdout is not one. \
+         Use `jolt --plain` or `jolt pipe` for output that can be piped or redirected."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_needs_a_terminal_and_no_no_color() {
+        assert!(allows_color(None, true));
+        assert!(!allows_color(None, false));
+        assert!(!allows_color(Some(OsStr::new("1")), true));
+        // An empty NO_COLOR does not count as set.
+        assert!(allows_color(Some(OsStr::new("")), true));
+    }
+}
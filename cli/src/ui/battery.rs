@@ -7,11 +7,17 @@ use ratatui::{
 };
 
 use crate::app::App;
+use crate::daemon::NetPowerBalance;
 use crate::data::battery::ChargeState;
 use crate::data::power::PowerMode;
+use crate::data::HistorySeries;
 use crate::theme::ThemeColors;
 
-use super::utils::{color_for_percent, format_energy_ratio, format_temperature};
+use super::sparkline::{recent_samples, InlineSparkline, SPARKLINE_GAP, SPARKLINE_WIDTH};
+use super::utils::{
+    color_for_percent, format_duration, format_energy_ratio, format_percent, format_temperature,
+    format_watts,
+};
 
 /// Returns the icon for the given power mode.
 fn power_mode_icon(mode: PowerMode) -> &'static str {
@@ -60,24 +66,129 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
 
         render_battery_gauge(frame, chunks[0], app, theme);
 
+        let warning = app
+            .battery
+            .sustained_deficit()
+            .map(|balance| deficit_message(&balance))
+            .or_else(|| {
+                app.battery
+                    .charging_advice()
+                    .map(|advice| advice.message(app.config.user_config.units.temperature))
+            });
+        if let Some(message) = warning {
+            frame.render_widget(
+                Paragraph::new(message)
+                    .style(theme.warning_style())
+                    .alignment(Alignment::Center),
+                chunks[1],
+            );
+        }
+
         if info_card_height > 0 {
             render_battery_info_card(frame, chunks[2], app, theme);
         }
     }
 }
 
+/// Warning shown while the battery drains despite external power.
+pub(super) fn deficit_message(balance: &NetPowerBalance) -> String {
+    let adapter = balance
+        .adapter_rated_watts
+        .map(|watts| format!(" on a {}W adapter", watts))
+        .unwrap_or_default();
+    let empty_in = balance
+        .minutes_to_empty
+        .map(|minutes| {
+            let secs = (minutes.max(1.0).round() as u64) * 60;
+            format!(", empty in {}", format_duration(secs))
+        })
+        .unwrap_or_default();
+    format!(
+        "Charger can't keep up: draining {}{}{}",
+        format_watts(-balance.net_watts),
+        adapter,
+        empty_in
+    )
+}
+
+/// Label and value for the time estimate next to the charge state.
+pub(super) fn time_display(app: &App) -> (&'static str, String) {
+    match app.battery.state() {
+        ChargeState::Charging => (
+            "Full in",
+            app.battery
+                .time_remaining_formatted()
+                .unwrap_or_else(|| "—".to_string()),
+        ),
+        ChargeState::Discharging => (
+            "Runtime",
+            app.battery
+                .time_remaining_formatted()
+                .unwrap_or_else(|| "—".to_string()),
+        ),
+        ChargeState::Full => ("", "Charged".to_string()),
+        ChargeState::NotCharging => ("", "Not charging".to_string()),
+        ChargeState::Unknown => ("", "—".to_string()),
+    }
+}
+
+/// Charger input while charging, battery draw otherwise.
+pub(super) fn input_power(app: &App) -> Option<String> {
+    if app.battery.is_charging() {
+        app.battery.charging_watts().map(|w| {
+            app.battery
+                .charger_watts()
+                .map_or(format_watts(w), |c| format!("{}/{}W", format_watts(w), c))
+        })
+    } else {
+        app.battery.discharge_watts().map(format_watts)
+    }
+}
+
 fn render_battery_gauge(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
     let percent = app.battery.charge_percent();
     let gauge_color = color_for_percent(percent, 50.0, 20.0, theme);
     let unfilled_color = darken_color(theme.border, 0.6);
+    // The fill moves between reported percents; the label stays reported.
+    let fill = app.battery.display_charge_percent();
 
     let gauge = Gauge::default()
         .gauge_style(Style::default().fg(gauge_color).bg(unfilled_color))
-        .ratio((percent / 100.0).clamp(0.0, 1.0) as f64)
-        .label(format!("{:.0}%", percent))
+        .ratio((fill / 100.0).clamp(0.0, 1.0) as f64)
+        .label(format_percent(percent))
         .use_unicode(true);
 
     frame.render_widget(gauge, area);
+
+    if app.config.user_config.sparklines.battery {
+        // Just right of the gauge's centered percentage label.
+        let label_width = format_percent(percent).len() as u16;
+        let x = area.x + (area.width.saturating_sub(label_width)) / 2 + label_width + 2;
+        if x + SPARKLINE_WIDTH <= area.right() {
+            let samples = recent_samples(app, HistorySeries::Battery);
+            let spark_area = Rect::new(x, area.y + area.height / 2, SPARKLINE_WIDTH, 1);
+            frame.render_widget(InlineSparkline::new(&samples, theme.fg), spark_area);
+        }
+    }
+
+    if let Some(limit) = app.battery.charge_limit_percent() {
+        render_limit_tick(frame, area, limit, theme);
+    }
+}
+
+/// Marks the charge limit on the gauge so a battery resting there reads as
+/// intentional rather than stuck.
+fn render_limit_tick(frame: &mut Frame, area: Rect, limit: u8, theme: &ThemeColors) {
+    if area.width < 2 {
+        return;
+    }
+
+    let offset = (area.width as f32 * limit as f32 / 100.0).round() as u16;
+    let x = area.x + offset.min(area.width - 1);
+    let buf = frame.buffer_mut();
+    for y in area.top()..area.bottom() {
+        buf[(x, y)].set_symbol("│").set_fg(theme.warning);
+    }
 }
 
 fn darken_color(color: Color, factor: f32) -> Color {
@@ -112,7 +223,9 @@ fn render_battery_info_card(frame: &mut Frame, area: Rect, app: &App, theme: &Th
             .split(chunk)[1]
     };
 
+    let held_at_limit = app.battery.is_held_at_limit();
     let status_color = match app.battery.state() {
+        _ if held_at_limit => theme.success,
         ChargeState::Charging => theme.accent,
         ChargeState::Full => theme.success,
         ChargeState::Discharging => {
@@ -150,28 +263,12 @@ fn render_battery_info_card(frame: &mut Frame, area: Rect, app: &App, theme: &Th
         ChargeState::Unknown => "?",
     };
 
-    let (time_label, time_value) = match app.battery.state() {
-        ChargeState::Charging => (
-            "Full in",
-            app.battery
-                .time_remaining_formatted()
-                .unwrap_or_else(|| "—".to_string()),
-        ),
-        ChargeState::Discharging => (
-            "Runtime",
-            app.battery
-                .time_remaining_formatted()
-                .unwrap_or_else(|| "—".to_string()),
-        ),
-        ChargeState::Full => ("", "Charged".to_string()),
-        ChargeState::NotCharging => ("", "Not charging".to_string()),
-        ChargeState::Unknown => ("", "—".to_string()),
-    };
+    let (time_label, time_value) = time_display(app);
 
     let mut left_spans = vec![
         Span::styled(format!("{} ", state_icon), theme.accent_style()),
         Span::styled(
-            app.battery.state_label(),
+            app.battery.status_label(),
             theme.fg_style().add_modifier(Modifier::BOLD),
         ),
     ];
@@ -193,15 +290,7 @@ fn render_battery_info_card(frame: &mut Frame, area: Rect, app: &App, theme: &Th
         }
     }
 
-    if let Some(power) = if app.battery.is_charging() {
-        app.battery.charging_watts().map(|w| {
-            app.battery
-                .charger_watts()
-                .map_or(format!("{:.1}W", w), |c| format!("{:.1}W/{}W", w, c))
-        })
-    } else {
-        app.battery.discharge_watts().map(|w| format!("{:.1}W", w))
-    } {
+    if let Some(power) = input_power(app) {
         left_spans.push(Span::styled("  ", Style::default()));
         left_spans.push(Span::styled("Input Power: ", theme.muted_style()));
         left_spans.push(Span::styled(power, theme.accent_style()));
@@ -219,7 +308,7 @@ fn render_battery_info_card(frame: &mut Frame, area: Rect, app: &App, theme: &Th
     let mut right_spans = vec![
         Span::styled("Health: ", theme.muted_style()),
         Span::styled(
-            format!("{:.0}%", app.battery.health_percent()),
+            format_percent(app.battery.health_percent()),
             Style::default()
                 .fg(health_color)
                 .add_modifier(Modifier::BOLD),
@@ -229,6 +318,7 @@ fn render_battery_info_card(frame: &mut Frame, area: Rect, app: &App, theme: &Th
         Span::styled(cycles_text, theme.fg_style()),
     ];
 
+    let mut temperature_sparkline_offset = None;
     if let Some(temp) = app.battery.temperature_c() {
         let temp_unit = app.config.user_config.units.temperature;
         right_spans.push(Span::styled("  ", Style::default()));
@@ -237,6 +327,11 @@ fn render_battery_info_card(frame: &mut Frame, area: Rect, app: &App, theme: &Th
             format_temperature(temp, temp_unit),
             theme.warning_style(),
         ));
+        if app.config.user_config.sparklines.temperature {
+            let offset: usize = right_spans.iter().map(|s| s.width()).sum();
+            temperature_sparkline_offset = Some(offset as u16 + 1);
+            right_spans.push(Span::raw(SPARKLINE_GAP));
+        }
     }
 
     let energy_unit = app.config.user_config.units.energy;
@@ -261,6 +356,15 @@ fn render_battery_info_card(frame: &mut Frame, area: Rect, app: &App, theme: &Th
         ));
     }
 
+    let right_area = v_center(right_inner);
     let right = Paragraph::new(Line::from(right_spans)).alignment(Alignment::Left);
-    frame.render_widget(right, v_center(right_inner));
+    frame.render_widget(right, right_area);
+
+    if let Some(offset) = temperature_sparkline_offset {
+        if offset + SPARKLINE_WIDTH <= right_area.width {
+            let samples = recent_samples(app, HistorySeries::Temperature);
+            let spark_area = Rect::new(right_area.x + offset, right_area.y, SPARKLINE_WIDTH, 1);
+            frame.render_widget(InlineSparkline::new(&samples, theme.warning), spark_area);
+        }
+    }
 }
@@ -99,7 +99,7 @@ fn render_charge_info(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeCol
     let percent = app.battery.charge_percent();
     let energy = app.battery.energy_wh();
     let max_capacity = app.battery.max_capacity_wh();
-    let state = app.battery.state_label();
+    let state = app.battery.status_label();
     let energy_unit = app.config.user_config.units.energy;
 
     let percent_color = color_for_percent(percent, 50.0, 20.0, theme);
@@ -339,7 +339,7 @@ fn render_temperature_chart(frame: &mut Frame, area: Rect, app: &App, theme: &Th
 
 fn render_footer(frame: &mut Frame, area: Rect, theme: &ThemeColors) {
     let line = Line::from(vec![Span::styled(
-        "Press 'b' or Esc to close",
+        "Press 'c' for charger details, 'b' or Esc to close",
         theme.muted_style(),
     )]);
 
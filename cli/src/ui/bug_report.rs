@@ -0,0 +1,114 @@
+//! Confirmation and progress modal for the redacted bug-report bundle.
+
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::{App, BugReportStatus};
+use crate::bugreport::{self, BundleFile, DEFAULT_LOG_MINUTES};
+use crate::input::keys;
+use crate::theme::ThemeColors;
+
+use super::utils::centered_rect;
+
+pub fn render(frame: &mut Frame, app: &App, theme: &ThemeColors) {
+    let area = centered_rect(frame.area(), 64, BundleFile::ALL.len() as u16 + 11);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Bug Report ")
+        .borders(Borders::ALL)
+        .border_style(theme.accent_style())
+        .style(Style::default().bg(theme.dialog_bg));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let body = Rect {
+        x: inner.x + 1,
+        y: inner.y + 1,
+        width: inner.width.saturating_sub(2),
+        height: inner.height.saturating_sub(2),
+    };
+
+    let mut lines = vec![Line::from(Span::styled(
+        "The bundle will contain:",
+        theme.fg_style(),
+    ))];
+    lines.extend(BundleFile::ALL.iter().map(|file| {
+        Line::from(vec![
+            Span::styled(format!("  {:<22}", file.file_name()), theme.accent_style()),
+            Span::styled(file.description(), theme.muted_style()),
+        ])
+    }));
+    lines.push(Line::from(Span::styled(
+        format!("Log lines cover the last {} minutes.", DEFAULT_LOG_MINUTES),
+        theme.muted_style(),
+    )));
+    lines.push(Line::from(Span::styled(
+        bugreport::redaction_summary(false),
+        theme.muted_style(),
+    )));
+    lines.push(Line::from(""));
+    lines.push(status_line(&app.bug_report, theme));
+
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), body);
+
+    let footer_area = Rect {
+        y: inner.y + inner.height.saturating_sub(1),
+        height: 1,
+        ..inner
+    };
+    frame.render_widget(
+        Paragraph::new(footer(&app.bug_report, theme)).centered(),
+        footer_area,
+    );
+}
+
+fn status_line(status: &BugReportStatus, theme: &ThemeColors) -> Line<'static> {
+    match status {
+        BugReportStatus::Idle => Line::from(Span::styled(
+            "Nothing is collected until you confirm.",
+            theme.fg_style(),
+        )),
+        BugReportStatus::Collecting(_) => Line::from(Span::styled(
+            "Collecting… (about ten seconds)",
+            theme.warning_style(),
+        )),
+        BugReportStatus::Done(Ok(path)) => Line::from(vec![
+            Span::styled("Written to ", theme.fg_style()),
+            Span::styled(
+                path.display().to_string(),
+                theme.success_style().add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        BugReportStatus::Done(Err(e)) => {
+            Line::from(Span::styled(format!("Failed: {}", e), theme.danger_style()))
+        }
+    }
+}
+
+fn footer(status: &BugReportStatus, theme: &ThemeColors) -> Line<'static> {
+    let close = [
+        Span::styled(
+            format!("[{}/{}]", keys::BUG_REPORT, keys::ESC),
+            theme.accent_style(),
+        ),
+        Span::styled(" Close", theme.muted_style()),
+    ];
+    match status {
+        BugReportStatus::Idle => {
+            let mut spans = vec![
+                Span::styled("[Enter/y]", theme.accent_style()),
+                Span::styled(" Create  ", theme.muted_style()),
+            ];
+            spans.extend(close);
+            Line::from(spans)
+        }
+        _ => Line::from(close.to_vec()),
+    }
+}
@@ -0,0 +1,154 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::data::battery::AdapterPdInfo;
+use crate::theme::ThemeColors;
+
+use super::utils::centered_rect;
+
+pub fn render(frame: &mut Frame, app: &App, theme: &ThemeColors) {
+    let popup_width = 70;
+    let popup_height = 28;
+    let area = centered_rect(frame.area(), popup_width, popup_height);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Charger Details ")
+        .borders(Borders::ALL)
+        .border_style(theme.accent_style())
+        .style(Style::default().bg(theme.dialog_bg));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let lines = match app.battery.adapter_pd_info() {
+        Some(info) => detail_lines(info, theme),
+        None => vec![Line::from(Span::styled(
+            "No charger details reported (on battery power, or not macOS)",
+            theme.muted_style(),
+        ))],
+    };
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, chunks[0]);
+
+    render_footer(frame, chunks[1], theme);
+}
+
+fn detail_lines<'a>(info: &'a AdapterPdInfo, theme: &ThemeColors) -> Vec<Line<'a>> {
+    let field = |label: &'static str, value: String| {
+        Line::from(vec![
+            Span::styled(label, theme.muted_style()),
+            Span::styled(value, theme.fg_style()),
+        ])
+    };
+    let or_na = |value: Option<&str>| value.unwrap_or("N/A").to_string();
+
+    let negotiated = match (info.negotiated_voltage_mv, info.negotiated_current_ma) {
+        (Some(mv), Some(ma)) => format!(
+            "{:.2} V × {:.2} A = {:.1} W",
+            mv as f32 / 1000.0,
+            ma as f32 / 1000.0,
+            info.negotiated_watts().unwrap_or_default()
+        ),
+        (Some(mv), None) => format!("{:.2} V", mv as f32 / 1000.0),
+        (None, Some(ma)) => format!("{:.2} A", ma as f32 / 1000.0),
+        (None, None) => "N/A".to_string(),
+    };
+
+    let mut lines = vec![
+        field("Name:         ", or_na(info.name.as_deref())),
+        field("Manufacturer: ", or_na(info.manufacturer.as_deref())),
+        field("Model:        ", or_na(info.model.as_deref())),
+        field("Type:         ", or_na(info.description.as_deref())),
+        field(
+            "Rated:        ",
+            info.rated_watts
+                .map_or("N/A".to_string(), |w| format!("{} W", w)),
+        ),
+        Line::from(vec![
+            Span::styled("Negotiated:   ", theme.muted_style()),
+            Span::styled(
+                negotiated,
+                theme.accent_style().add_modifier(Modifier::BOLD),
+            ),
+        ]),
+    ];
+    if let Some(wireless) = info.is_wireless {
+        lines.push(field(
+            "Wireless:     ",
+            if wireless { "Yes" } else { "No" }.to_string(),
+        ));
+    }
+
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(
+        "Offered PDOs",
+        theme.fg_style().add_modifier(Modifier::BOLD),
+    )));
+    if info.pdos.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  none reported",
+            theme.muted_style(),
+        )));
+    }
+    for pdo in &info.pdos {
+        let active = info.active_pdo_index == Some(pdo.index);
+        let style = if active {
+            theme.success_style()
+        } else {
+            theme.fg_style()
+        };
+        lines.push(Line::from(vec![
+            Span::styled(if active { "▶ " } else { "  " }, style),
+            Span::styled(
+                format!(
+                    "#{}  {:>6.2} V  {:>5.2} A  {:>5.1} W",
+                    pdo.index,
+                    pdo.max_voltage_mv as f32 / 1000.0,
+                    pdo.max_current_ma as f32 / 1000.0,
+                    pdo.max_watts()
+                ),
+                style,
+            ),
+        ]));
+    }
+
+    if !info.raw.is_empty() {
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled(
+            "Other keys",
+            theme.fg_style().add_modifier(Modifier::BOLD),
+        )));
+        for (key, value) in &info.raw {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {} = ", key), theme.muted_style()),
+                Span::styled(value.as_str(), theme.fg_style()),
+            ]));
+        }
+    }
+
+    lines
+}
+
+fn render_footer(frame: &mut Frame, area: Rect, theme: &ThemeColors) {
+    let line = Line::from(vec![Span::styled(
+        "Press 'c' or Esc to return to battery details",
+        theme.muted_style(),
+    )]);
+
+    let paragraph = Paragraph::new(vec![line]).centered();
+    frame.render_widget(paragraph, area);
+}
@@ -0,0 +1,167 @@
+//! Plain-text versions of what is on screen, for copying to the clipboard.
+//!
+//! Values go through the same formatters the widgets use, so a copied number
+//! always matches the one being looked at.
+
+use crate::app::{App, AppView};
+use crate::data::power::PowerMode;
+use crate::data::{NormalizedCpu, ProcessInfo};
+
+use super::battery::{input_power, time_display};
+use super::history::SummaryStats;
+use super::power::power_readings;
+use super::utils::{
+    format_cpu, format_disk_io, format_duration, format_energy_ratio, format_memory,
+    format_network_rate, format_percent, format_temperature,
+};
+
+/// Text for the current view and a short description of what it is.
+///
+/// Returns `None` when there is nothing meaningful to copy.
+pub fn for_current_view(app: &App) -> Option<(String, String)> {
+    match app.view {
+        AppView::Main if app.selection_mode => {
+            let process = app.get_selected_process()?;
+            let cpu = app.normalized_cpu(process.cpu_usage);
+            Some((
+                process_row(&process, cpu),
+                format!("row for {}", process.name),
+            ))
+        }
+        AppView::Main => Some((overview_summary(app), "snapshot summary".to_string())),
+        AppView::History => Some((
+            history_summary(app)?,
+            format!("{} history stats", app.history_period.label()),
+        )),
+        _ => None,
+    }
+}
+
+/// One paragraph describing the battery and power panels.
+pub fn overview_summary(app: &App) -> String {
+    let mut sentences = Vec::new();
+
+    let mut battery = format!(
+        "Battery {} ({})",
+        format_percent(app.battery.charge_percent()),
+        app.battery.status_label()
+    );
+    let (time_label, time_value) = time_display(app);
+    if !time_label.is_empty() {
+        battery.push_str(&format!(", {}: {}", time_label, time_value));
+    }
+    if let Some(power) = input_power(app) {
+        battery.push_str(&format!(", Input Power: {}", power));
+    }
+    sentences.push(battery);
+
+    let (total, cpu, gpu) = power_readings(app);
+    sentences.push(format!("Power: {} total, CPU {}, GPU {}", total, cpu, gpu));
+
+    let units = &app.config.user_config.units;
+    let mut details = format!(
+        "Health: {}, Cycles: {}",
+        format_percent(app.battery.health_percent()),
+        app.battery
+            .cycle_count()
+            .map_or("—".to_string(), |c| c.to_string())
+    );
+    if let Some(temp) = app.battery.temperature_c() {
+        details.push_str(&format!(
+            ", Temp: {}",
+            format_temperature(temp, units.temperature)
+        ));
+    }
+    details.push_str(&format!(
+        ", Energy: {}",
+        format_energy_ratio(
+            app.battery.energy_wh(),
+            app.battery.max_capacity_wh(),
+            units.energy
+        )
+    ));
+    if app.power.power_mode() != PowerMode::Unknown {
+        details.push_str(&format!(", Mode: {}", app.power.power_mode_label()));
+    }
+    sentences.push(details);
+
+    format!("{}.", sentences.join(". "))
+}
+
+/// The process table columns for one process, tab-separated.
+///
+/// Names and arguments are not truncated, unlike in the table itself.
+pub fn process_row(process: &ProcessInfo, cpu: NormalizedCpu) -> String {
+    [
+        process.pid.to_string(),
+        process.status.as_char().to_string(),
+        format!("{:.1}", process.energy_impact),
+        process.name.clone(),
+        process.command_args.clone(),
+        format_cpu(cpu),
+        format_memory(process.memory_mb),
+        format_disk_io(process.disk_read_bytes, process.disk_write_bytes),
+        format_network_rate(process.network_bytes_per_sec),
+        format_duration(process.run_time_secs),
+        format_duration(process.total_cpu_time_secs),
+    ]
+    .join("\t")
+}
+
+/// The history summary block as `Label: value` lines.
+pub fn history_summary(app: &App) -> Option<String> {
+    let summary = SummaryStats::from_daily(&app.history_daily_stats)?;
+    let lines: Vec<String> = summary
+        .rows(app.config.user_config.units.energy)
+        .into_iter()
+        .map(|(label, value)| format!("{}: {}", label, value))
+        .collect();
+
+    Some(format!(
+        "{}\n{}",
+        app.history_period.label(),
+        lines.join("\n")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CpuNormalization;
+    use crate::daemon::BinaryArch;
+    use crate::data::ProcessState;
+    use std::path::PathBuf;
+
+    #[test]
+    fn process_row_is_tab_separated_and_untruncated() {
+        let process = ProcessInfo {
+            pid: 812,
+            name: "com.apple.WebKit.Networking".to_string(),
+            command: "/System/Library/Frameworks/WebKit.framework/Networking".to_string(),
+            command_args: "--type=network".to_string(),
+            cpu_usage: 3.25,
+            memory_mb: 1536.0,
+            energy_impact: 12.04,
+            parent_pid: Some(1),
+            children: None,
+            is_killable: true,
+            disk_read_bytes: 2048,
+            disk_write_bytes: 0,
+            status: ProcessState::Running,
+            run_time_secs: 3720,
+            total_cpu_time_secs: 90,
+            network_bytes_per_sec: None,
+            sandbox_container: None,
+            executable_path: PathBuf::new(),
+            start_time: 0,
+            team_id: None,
+            binary_arch: BinaryArch::Unknown,
+            power_assertion_count: None,
+        };
+
+        assert_eq!(
+            process_row(&process, process.normalized_cpu(CpuNormalization::PerCore, 8)),
+            "812\tR\t12.0\tcom.apple.WebKit.Networking\t--type=network\t3.2\t1.5G\t2K\t-\t1h 2m\t1m 30s"
+        );
+    }
+}
@@ -116,13 +116,15 @@ pub fn render_recent_sessions(frame: &mut Frame, area: Rect, app: &App, theme: &
     }
 
     let energy_unit = app.config.user_config.units.energy;
-    let header = Row::new(vec!["Type", "Start", "Duration", "Range", "Energy"])
-        .style(
-            Style::default()
-                .fg(theme.accent)
-                .add_modifier(Modifier::BOLD),
-        )
-        .bottom_margin(1);
+    let header = Row::new(vec![
+        "Type", "Start", "Duration", "Range", "Energy", "AC Drain",
+    ])
+    .style(
+        Style::default()
+            .fg(theme.accent)
+            .add_modifier(Modifier::BOLD),
+    )
+    .bottom_margin(1);
 
     let rows: Vec<Row> = app
         .recent_charge_sessions
@@ -140,6 +142,7 @@ pub fn render_recent_sessions(frame: &mut Frame, area: Rect, app: &App, theme: &
             Constraint::Length(8),
             Constraint::Length(10),
             Constraint::Length(8),
+            Constraint::Length(8),
         ],
     )
     .header(header)
@@ -180,12 +183,20 @@ fn session_to_row(
         .map(|e| format_energy_compact(e, energy_unit))
         .unwrap_or_else(|| "-".to_string());
 
+    // Time spent draining because the adapter could not keep up.
+    let deficit = if session.deficit_secs > 0 {
+        format_duration(session.deficit_secs)
+    } else {
+        "-".to_string()
+    };
+
     Row::new(vec![
         type_label.to_string(),
         start_time,
         duration,
         range,
         energy,
+        deficit,
     ])
     .style(Style::default().fg(type_color))
 }
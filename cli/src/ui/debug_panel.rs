@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use chrono::{Local, TimeZone};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::data::RefreshStage;
+use crate::theme::ThemeColors;
+
+use super::utils::{centered_rect, format_duration, format_energy, format_watts};
+
+pub fn render(frame: &mut Frame, app: &App, theme: &ThemeColors) {
+    let area = centered_rect(frame.area(), 56, 15);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Refresh Timings ")
+        .borders(Borders::ALL)
+        .border_style(theme.accent_style())
+        .style(Style::default().bg(theme.dialog_bg));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(RefreshStage::ALL.len() as u16 + 1),
+            Constraint::Length(2),
+            Constraint::Length(2),
+            Constraint::Min(1),
+        ])
+        .split(inner);
+
+    render_stage_table(frame, chunks[0], app, theme);
+
+    let source = if app.using_daemon_data {
+        "Data from daemon: local battery/power/process refreshes are idle"
+    } else {
+        "Data refreshed locally each tick"
+    };
+    let note = Paragraph::new(Line::from(Span::styled(source, theme.muted_style())));
+    frame.render_widget(note, chunks[1]);
+    frame.render_widget(Paragraph::new(session_line(app, theme)), chunks[2]);
+
+    let footer = Paragraph::new(Line::from(Span::styled(
+        "Press 'D' or Esc to close",
+        theme.muted_style(),
+    )))
+    .centered();
+    frame.render_widget(footer, chunks[3]);
+}
+
+fn render_stage_table(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
+    let header_style = Style::default()
+        .fg(theme.accent)
+        .add_modifier(Modifier::BOLD);
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled(format!("{:<11}", "Stage"), header_style),
+        Span::styled(format!("{:>9}", "p50"), header_style),
+        Span::styled(format!("{:>9}", "p95"), header_style),
+        Span::styled(format!("{:>9}", "max"), header_style),
+        Span::styled(format!("{:>9}", "samples"), header_style),
+    ])];
+
+    for stage in RefreshStage::ALL {
+        let line = match app.refresh_timings.stats(stage) {
+            Some(stats) => Line::from(vec![
+                Span::styled(format!("{:<11}", stage.label()), theme.fg_style()),
+                Span::styled(format!("{:>9}", format_ms(stats.p50)), theme.fg_style()),
+                Span::styled(format!("{:>9}", format_ms(stats.p95)), theme.fg_style()),
+                Span::styled(
+                    format!("{:>9}", format_ms(stats.max)),
+                    theme.warning_style(),
+                ),
+                Span::styled(format!("{:>9}", stats.samples), theme.muted_style()),
+            ]),
+            None => Line::from(vec![
+                Span::styled(format!("{:<11}", stage.label()), theme.fg_style()),
+                Span::styled(format!("{:>9}", "—"), theme.muted_style()),
+            ]),
+        };
+        lines.push(line);
+    }
+
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+/// Session counters, with the latest restart marker when it was resumed.
+fn session_line(app: &App, theme: &ThemeColors) -> Line<'static> {
+    let session = &app.session;
+    let mut spans = vec![
+        Span::styled("Session: ", theme.muted_style()),
+        Span::styled(
+            format!(
+                "{}, {} on battery, peak {}",
+                format_energy(
+                    session.energy_wh as f32,
+                    app.config.user_config.units.energy
+                ),
+                format_duration(session.battery_secs as u64 / 60 * 60),
+                format_watts(session.peak_power_watts)
+            ),
+            theme.fg_style(),
+        ),
+    ];
+    if let Some(marker) = session.markers.last() {
+        let at = Local
+            .timestamp_opt(marker.timestamp, 0)
+            .single()
+            .map_or_else(String::new, |t| t.format(" %H:%M").to_string());
+        spans.push(Span::styled(
+            format!(" · {}{}", marker.label, at),
+            theme.muted_style(),
+        ));
+    }
+    Line::from(spans)
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
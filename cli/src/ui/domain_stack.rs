@@ -0,0 +1,183 @@
+//! Stacked-area breakdown of power by domain over the in-memory history.
+//!
+//! Each column is one history sample with CPU, GPU, ANE, DRAM and the rest
+//! of total power stacked bottom to top, and total power drawn as the
+//! envelope. Samples missing a domain reading are hatched instead of filled.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::data::history::{DomainStack, PowerDomain};
+use crate::theme::ThemeColors;
+
+use super::utils::color_for_value;
+
+const AXIS_WIDTH: u16 = 5;
+const FILL: &str = "█";
+const HATCH: &str = "╱";
+const ENVELOPE: &str = "─";
+/// Headroom above the tallest stack so the envelope is not clipped.
+const HEADROOM: f32 = 1.1;
+
+pub fn render(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
+    let latest = app.history.domain_stacks(1)[0];
+    let total = latest.map_or(0.0, |s| s.total_watts);
+    let graph_color = color_for_value(total, 8.0, 15.0, theme);
+
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::styled(
+                " Domains ",
+                Style::default()
+                    .fg(graph_color)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!("{:.1}W", total), theme.graph_style()),
+            Span::styled(" (g: toggle) ", theme.muted_style()),
+        ]))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(graph_color))
+        .style(Style::default().bg(theme.bg));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    if inner.height < 2 || inner.width <= AXIS_WIDTH {
+        return;
+    }
+
+    let legend_area = Rect { height: 1, ..inner };
+    frame.render_widget(Paragraph::new(legend(latest, theme)), legend_area);
+
+    let plot = Rect {
+        x: inner.x + AXIS_WIDTH,
+        y: inner.y + 1,
+        width: inner.width - AXIS_WIDTH,
+        height: inner.height - 1,
+    };
+    let stacks = app.history.domain_stacks(plot.width as usize);
+    let max_watts = stacks
+        .iter()
+        .flatten()
+        .map(|s| s.watts.iter().sum::<f32>().max(s.total_watts))
+        .fold(1.0, f32::max)
+        * HEADROOM;
+
+    for (label, row) in [
+        (format!("{:.0}W", max_watts), plot.y),
+        ("0W".to_string(), plot.bottom() - 1),
+    ] {
+        let axis = Rect {
+            x: inner.x,
+            y: row,
+            width: AXIS_WIDTH - 1,
+            height: 1,
+        };
+        frame.render_widget(Paragraph::new(label).style(theme.muted_style()), axis);
+    }
+
+    let buf = frame.buffer_mut();
+    for (column, stack) in stacks.iter().enumerate() {
+        if let Some(stack) = stack {
+            draw_column(buf, plot, plot.x + column as u16, stack, max_watts, theme);
+        }
+    }
+}
+
+fn draw_column(
+    buf: &mut Buffer,
+    plot: Rect,
+    x: u16,
+    stack: &DomainStack,
+    max_watts: f32,
+    theme: &ThemeColors,
+) {
+    let row_watts = max_watts / plot.height as f32;
+    let fill = if stack.partial { HATCH } else { FILL };
+    let envelope_row = ((stack.total_watts / row_watts) as u16).min(plot.height - 1);
+
+    for row in 0..plot.height {
+        let Some(cell) = buf.cell_mut((x, plot.bottom() - 1 - row)) else {
+            continue;
+        };
+        let layer = layer_at(stack, (row as f32 + 0.5) * row_watts);
+        if let Some(domain) = layer {
+            cell.set_symbol(fill).set_fg(domain_color(domain, theme));
+        }
+        if row == envelope_row {
+            cell.set_symbol(ENVELOPE).set_fg(theme.fg);
+            if let Some(domain) = layer {
+                cell.set_bg(domain_color(domain, theme));
+            }
+        }
+    }
+}
+
+/// Domain whose band of the stack contains `watts`, if it is below the top.
+fn layer_at(stack: &DomainStack, watts: f32) -> Option<PowerDomain> {
+    let mut top = 0.0;
+    for (domain, layer) in PowerDomain::ALL.iter().zip(stack.watts) {
+        top += layer;
+        if watts < top {
+            return Some(*domain);
+        }
+    }
+    None
+}
+
+fn domain_color(domain: PowerDomain, theme: &ThemeColors) -> Color {
+    match domain {
+        PowerDomain::Cpu => theme.accent,
+        PowerDomain::Gpu => theme.accent_secondary,
+        PowerDomain::Ane => theme.success,
+        PowerDomain::Dram => theme.warning,
+        PowerDomain::Other => theme.muted,
+    }
+}
+
+fn legend(latest: Option<DomainStack>, theme: &ThemeColors) -> Line<'static> {
+    let mut spans = Vec::new();
+    for (index, domain) in PowerDomain::ALL.iter().enumerate() {
+        let color = domain_color(*domain, theme);
+        spans.push(Span::styled(FILL, Style::default().fg(color)));
+        spans.push(Span::styled(
+            format!(" {} ", domain.label()),
+            theme.muted_style(),
+        ));
+        let reading = latest
+            .map(|s| format!("{:.1}W  ", s.watts[index]))
+            .unwrap_or_default();
+        spans.push(Span::styled(reading, Style::default().fg(color)));
+    }
+    spans.push(Span::styled(ENVELOPE, theme.fg_style()));
+    spans.push(Span::styled(" total  ", theme.muted_style()));
+    spans.push(Span::styled(HATCH, theme.muted_style()));
+    spans.push(Span::styled(" missing data", theme.muted_style()));
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layer_at_walks_the_stack_bottom_up() {
+        let stack = DomainStack {
+            watts: [2.0, 0.0, 1.0, 0.5, 1.5],
+            total_watts: 5.0,
+            partial: false,
+        };
+
+        assert_eq!(layer_at(&stack, 0.0), Some(PowerDomain::Cpu));
+        assert_eq!(layer_at(&stack, 2.0), Some(PowerDomain::Ane));
+        assert_eq!(layer_at(&stack, 3.2), Some(PowerDomain::Dram));
+        assert_eq!(layer_at(&stack, 4.9), Some(PowerDomain::Other));
+        assert_eq!(layer_at(&stack, 5.0), None);
+    }
+}
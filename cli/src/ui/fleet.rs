@@ -0,0 +1,126 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::app::App;
+use crate::data::fleet::PeerStatus;
+use crate::theme::ThemeColors;
+
+use super::utils::{centered_rect, format_duration, format_percent, format_watts};
+
+pub fn render(frame: &mut Frame, app: &App, theme: &ThemeColors) {
+    let peers = app.fleet.as_ref().map(|f| f.peers()).unwrap_or_default();
+    let height = peers.len() as u16 + 7;
+    let area = centered_rect(frame.area(), 72, height);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Fleet ")
+        .borders(Borders::ALL)
+        .border_style(theme.accent_style())
+        .style(Style::default().bg(theme.dialog_bg));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(fleet) = app.fleet.as_ref() else {
+        render_unconfigured(frame, inner, theme);
+        return;
+    };
+
+    let header_style = Style::default()
+        .fg(theme.accent)
+        .add_modifier(Modifier::BOLD);
+    let header = Row::new(["Host", "Battery", "Power", "Mode", "Status"]).style(header_style);
+
+    let mut rows = vec![Row::new([
+        "this machine".to_string(),
+        format_percent(app.battery.charge_percent()),
+        format_watts(app.power.total_power_watts()),
+        app.power.power_mode_label().to_string(),
+        "live".to_string(),
+    ])
+    .style(theme.fg_style())];
+
+    rows.extend(peers.iter().map(|peer| {
+        let stale = fleet.is_stale(peer);
+        let style = if stale {
+            theme.muted_style()
+        } else {
+            theme.fg_style()
+        };
+        peer_row(peer, stale).style(style)
+    }));
+
+    let widths = [
+        Constraint::Min(20),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(12),
+        Constraint::Length(18),
+    ];
+
+    let table_area = Rect {
+        x: inner.x + 1,
+        y: inner.y + 1,
+        width: inner.width.saturating_sub(2),
+        height: inner.height.saturating_sub(3),
+    };
+    frame.render_widget(Table::new(rows, widths).header(header), table_area);
+
+    let footer_area = Rect {
+        y: inner.y + inner.height.saturating_sub(1),
+        height: 1,
+        ..inner
+    };
+    let footer = Paragraph::new(Line::from(Span::styled(
+        "Press 'F' or Esc to close",
+        theme.muted_style(),
+    )))
+    .centered();
+    frame.render_widget(footer, footer_area);
+}
+
+fn peer_row(peer: &PeerStatus, stale: bool) -> Row<'static> {
+    let dash = || "—".to_string();
+    let (battery, power, mode) = match &peer.snapshot {
+        Some(snapshot) => (
+            format_percent(snapshot.battery.charge_percent),
+            format_watts(snapshot.power.total_power_watts),
+            snapshot.power.power_mode_label.clone(),
+        ),
+        None => (dash(), dash(), dash()),
+    };
+
+    let status = match (&peer.last_error, peer.age()) {
+        (None, Some(_)) if !stale => "ok".to_string(),
+        (_, Some(age)) => format!("{} ago", format_duration(age.as_secs())),
+        (Some(_), None) => "unreachable".to_string(),
+        (None, None) => "polling…".to_string(),
+    };
+
+    Row::new([peer.name().to_string(), battery, power, mode, status])
+}
+
+fn render_unconfigured(frame: &mut Frame, area: Rect, theme: &ThemeColors) {
+    let message = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(Span::styled("No peers configured", theme.warning_style())),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Add fleet_peers = [\"host:9898\"] to the config",
+            theme.muted_style(),
+        )),
+        Line::from(Span::styled(
+            "and run `jolt serve` on each machine.",
+            theme.muted_style(),
+        )),
+    ])
+    .centered();
+    frame.render_widget(message, area);
+}
@@ -45,6 +45,7 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
         match app.history.current_metric {
             HistoryMetric::Split => render_split(frame, area, app, theme),
             HistoryMetric::Merged => render_merged(frame, area, app, theme),
+            HistoryMetric::Domains => super::domain_stack::render(frame, area, app, theme),
             _ => render_single(frame, area, app, theme),
         }
     }
@@ -59,6 +60,7 @@ fn render_with_temp_side_by_side(frame: &mut Frame, area: Rect, app: &App, theme
     match app.history.current_metric {
         HistoryMetric::Split => render_split(frame, chunks[0], app, theme),
         HistoryMetric::Merged => render_merged(frame, chunks[0], app, theme),
+        HistoryMetric::Domains => super::domain_stack::render(frame, chunks[0], app, theme),
         _ => render_single(frame, chunks[0], app, theme),
     }
 
@@ -74,6 +76,7 @@ fn render_with_temp_stacked(frame: &mut Frame, area: Rect, app: &App, theme: &Th
     match app.history.current_metric {
         HistoryMetric::Split => render_split(frame, chunks[0], app, theme),
         HistoryMetric::Merged => render_merged(frame, chunks[0], app, theme),
+        HistoryMetric::Domains => super::domain_stack::render(frame, chunks[0], app, theme),
         _ => render_single(frame, chunks[0], app, theme),
     }
 
@@ -0,0 +1,163 @@
+//! Weekday × hour heatmap of average power from the daemon's hourly stats.
+
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::data::heatmap::{PowerHeatmap, HOURS, WEEKDAYS};
+use crate::input::keys;
+use crate::theme::ThemeColors;
+
+use super::utils::{centered_rect, color_for_value};
+
+const WEEKDAY_LABELS: [&str; WEEKDAYS] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const LABEL_WIDTH: usize = 4;
+/// Each hour is two cells wide so the grid is roughly square.
+const CELL: usize = 2;
+const SHADES: [&str; 4] = ["░░", "▒▒", "▓▓", "██"];
+const EMPTY_CELL: &str = "··";
+
+pub fn render(frame: &mut Frame, app: &App, theme: &ThemeColors) {
+    let width = (LABEL_WIDTH + HOURS * CELL) as u16 + 4;
+    let area = centered_rect(frame.area(), width, WEEKDAYS as u16 + 9);
+    frame.render_widget(Clear, area);
+
+    let weeks = app.config.user_config.history.heatmap_weeks.max(1);
+    let block = Block::default()
+        .title(format!(" Power by Hour · last {} weeks ", weeks))
+        .borders(Borders::ALL)
+        .border_style(theme.accent_style())
+        .style(Style::default().bg(theme.dialog_bg));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let footer_area = Rect {
+        y: inner.y + inner.height.saturating_sub(1),
+        height: 1,
+        ..inner
+    };
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!("[{}/{}]", keys::HEATMAP, keys::ESC),
+            theme.accent_style(),
+        ),
+        Span::styled(" Close", theme.muted_style()),
+    ]))
+    .centered();
+    frame.render_widget(footer, footer_area);
+
+    let body = Rect {
+        x: inner.x + 1,
+        y: inner.y + 1,
+        width: inner.width.saturating_sub(2),
+        height: inner.height.saturating_sub(2),
+    };
+
+    let Some(max_power) = app.heatmap.max_average() else {
+        render_empty(frame, body, app.daemon_connected, theme);
+        return;
+    };
+
+    let scale = max_power.max(0.1);
+    let mut lines = vec![hour_axis(theme)];
+    lines.extend((0..WEEKDAYS).map(|day| weekday_row(&app.heatmap, day, scale, theme)));
+    lines.push(Line::from(""));
+    lines.push(legend(max_power, theme));
+
+    frame.render_widget(Paragraph::new(lines), body);
+}
+
+fn hour_axis(theme: &ThemeColors) -> Line<'static> {
+    let mut axis = " ".repeat(LABEL_WIDTH);
+    for hour in (0..HOURS).step_by(3) {
+        axis.push_str(&format!("{:<width$}", hour, width = CELL * 3));
+    }
+    Line::from(Span::styled(axis, theme.muted_style()))
+}
+
+fn weekday_row(
+    heatmap: &PowerHeatmap,
+    day: usize,
+    max_power: f32,
+    theme: &ThemeColors,
+) -> Line<'static> {
+    let mut spans = vec![Span::styled(
+        format!("{:<width$}", WEEKDAY_LABELS[day], width = LABEL_WIDTH),
+        theme.muted_style(),
+    )];
+    spans.extend((0..HOURS).map(|hour| match heatmap.average(day, hour) {
+        Some(watts) => {
+            let (shade, color) = ramp(watts / max_power, theme);
+            Span::styled(shade, Style::default().fg(color))
+        }
+        None => Span::styled(EMPTY_CELL, theme.border_style()),
+    }));
+    Line::from(spans)
+}
+
+/// Shade and color for a cell at `ratio` of the busiest cell.
+fn ramp(ratio: f32, theme: &ThemeColors) -> (&'static str, ratatui::style::Color) {
+    let ratio = ratio.clamp(0.0, 1.0);
+    let shade = ((ratio * SHADES.len() as f32) as usize).min(SHADES.len() - 1);
+    (SHADES[shade], color_for_value(ratio, 0.4, 0.7, theme))
+}
+
+fn legend(max_power: f32, theme: &ThemeColors) -> Line<'static> {
+    let mut spans = vec![Span::styled("Low ", theme.muted_style())];
+    for step in 0..SHADES.len() {
+        let (shade, color) = ramp((step as f32 + 0.5) / SHADES.len() as f32, theme);
+        spans.push(Span::styled(shade, Style::default().fg(color)));
+    }
+    spans.push(Span::styled(
+        format!(" {:.1} W   ", max_power),
+        theme.muted_style(),
+    ));
+    spans.push(Span::styled(EMPTY_CELL, theme.border_style()));
+    spans.push(Span::styled(" no data", theme.muted_style()));
+    Line::from(spans)
+}
+
+fn render_empty(frame: &mut Frame, area: Rect, daemon_connected: bool, theme: &ThemeColors) {
+    let (title, hint) = if daemon_connected {
+        (
+            "No hourly stats yet",
+            "The daemon aggregates samples once an hour.",
+        )
+    } else {
+        (
+            "Daemon not running",
+            "Start it to collect history: jolt daemon start",
+        )
+    };
+    let message = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            title,
+            theme.warning_style().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(hint, theme.muted_style())),
+    ])
+    .centered();
+    frame.render_widget(message, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ramp_covers_every_shade_and_clamps() {
+        let theme = ThemeColors::from(jolt_theme::ThemeColors::default());
+        assert_eq!(ramp(0.0, &theme).0, SHADES[0]);
+        assert_eq!(ramp(1.0, &theme).0, SHADES[3]);
+        assert_eq!(ramp(2.0, &theme), ramp(1.0, &theme));
+        assert_eq!(ramp(0.8, &theme).1, theme.danger);
+    }
+}
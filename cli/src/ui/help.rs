@@ -12,7 +12,7 @@ use crate::input::KEY_BINDINGS;
 use crate::theme::ThemeColors;
 use crate::ui::VERSION;
 
-use super::utils::centered_rect;
+use super::utils::{centered_rect, format_cpu};
 
 pub fn render_help(frame: &mut Frame, app: &App, theme: &ThemeColors) {
     let legend_lines = 6;
@@ -174,7 +174,10 @@ pub fn render_kill_confirm(frame: &mut Frame, app: &App, theme: &ThemeColors) {
             ]),
             Line::from(vec![
                 Span::styled("CPU: ", theme.muted_style()),
-                Span::styled(format!("{:.1}%", process.cpu_usage), theme.fg_style()),
+                Span::styled(
+                    format!("{}%", format_cpu(app.normalized_cpu(process.cpu_usage))),
+                    theme.fg_style(),
+                ),
             ]),
             Line::from(vec![
                 Span::styled("Memory: ", theme.muted_style()),
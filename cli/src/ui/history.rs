@@ -1,3 +1,4 @@
+use chrono::TimeZone;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
@@ -11,6 +12,11 @@ use ratatui::{
 
 use super::cycles;
 use crate::app::{App, HistoryPeriod};
+use crate::config::EnergyUnit;
+use crate::data::battery_wear::END_OF_LIFE_HEALTH_PERCENT;
+use crate::data::timeline::resample;
+use crate::data::wake_log::WakeEvent;
+use crate::data::DailyStat;
 use crate::input::keys;
 use crate::theme::ThemeColors;
 
@@ -54,7 +60,17 @@ pub fn render(frame: &mut Frame, app: &App, theme: &ThemeColors) {
         .split(inner);
 
     render_period_tabs(frame, chunks[0], app, theme);
-    render_power_chart(frame, chunks[1], app, theme);
+    let chart_row = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(45),
+            Constraint::Percentage(25),
+            Constraint::Percentage(30),
+        ])
+        .split(chunks[1]);
+    render_timeline_chart(frame, chart_row[0], app, theme, TimelineSeries::Power);
+    render_timeline_chart(frame, chart_row[1], app, theme, TimelineSeries::Battery);
+    render_capacity_chart(frame, chart_row[2], app, theme);
     render_cycle_row(frame, chunks[2], app, theme);
     render_stats_and_processes(frame, chunks[3], app, theme);
     render_footer(frame, chunks[4], theme);
@@ -138,18 +154,50 @@ fn render_period_tabs(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeCol
     frame.render_widget(tabs_para, area);
 }
 
-fn render_power_chart(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
+#[derive(Clone, Copy)]
+enum TimelineSeries {
+    Power,
+    Battery,
+}
+
+/// One metric over the shared, zoomable time range. Samples are averaged
+/// into one bucket per braille column so drawing cost stays bounded.
+fn render_timeline_chart(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    theme: &ThemeColors,
+    series: TimelineSeries,
+) {
+    let range = &app.history_range;
+    let (from, to) = range.window(chrono::Utc::now().timestamp());
+    let wakes = app.history_wakes.wakes_between(from..to + 1);
+
+    let mut title = match series {
+        TimelineSeries::Power => format!(" Power · {}", range.zoom.label()),
+        TimelineSeries::Battery => format!(" Battery · {}", range.zoom.label()),
+    };
+    if range.is_following_live() {
+        title.push_str(" · live");
+    }
+    if matches!(series, TimelineSeries::Power) && !wakes.is_empty() {
+        let dark = wakes.iter().filter(|w| w.dark_wake).count();
+        title.push_str(&format!(" · {} wakes ({} dark)", wakes.len(), dark));
+    }
+    title.push(' ');
+
     let block = Block::default()
-        .title(" Power Usage ")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(theme.border_style());
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    if app.history_daily_stats.is_empty() && app.history_hourly_stats.is_empty() {
+    let buckets = resample(&app.history_samples, from, to, inner.width as usize * 2);
+    if buckets.is_empty() {
         let no_data = Paragraph::new(vec![Line::from(vec![Span::styled(
-            "No data for this period",
+            "No samples in this range",
             theme.muted_style(),
         )])])
         .centered();
@@ -157,70 +205,197 @@ fn render_power_chart(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeCol
         return;
     }
 
-    let data_points: Vec<(f64, f64)> = if app.history_period == HistoryPeriod::Today {
-        app.history_hourly_stats
-            .iter()
-            .enumerate()
-            .map(|(i, h)| (i as f64, h.avg_power as f64))
-            .collect()
+    let x = |timestamp: f64| timestamp - from as f64;
+    let data_points: ChartPoints = buckets
+        .iter()
+        .map(|b| {
+            let value = match series {
+                TimelineSeries::Power => b.power_watts,
+                TimelineSeries::Battery => b.battery_percent,
+            };
+            (x(b.timestamp), value as f64)
+        })
+        .collect();
+
+    let (y_max, y_title, style) = match series {
+        TimelineSeries::Power => (
+            data_points
+                .iter()
+                .map(|(_, p)| *p)
+                .fold(0.0_f64, f64::max)
+                .max(1.0),
+            "Watts",
+            theme.accent_style(),
+        ),
+        TimelineSeries::Battery => (100.0, "%", theme.success_style()),
+    };
+
+    let mut datasets = vec![Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(style)
+        .data(&data_points)];
+
+    // Wakes are drawn as markers along the bottom of the power chart so
+    // overnight drain can be matched to what woke the machine.
+    let (dark_wake_points, wake_points): (ChartPoints, ChartPoints) = match series {
+        TimelineSeries::Power => {
+            let marker = |w: &&WakeEvent| (x(w.timestamp as f64), y_max * 0.05);
+            (
+                wakes.iter().filter(|w| w.dark_wake).map(marker).collect(),
+                wakes.iter().filter(|w| !w.dark_wake).map(marker).collect(),
+            )
+        }
+        TimelineSeries::Battery => (Vec::new(), Vec::new()),
+    };
+    if !dark_wake_points.is_empty() {
+        datasets.push(
+            Dataset::default()
+                .marker(symbols::Marker::Dot)
+                .graph_type(GraphType::Scatter)
+                .style(theme.warning_style())
+                .data(&dark_wake_points),
+        );
+    }
+    if !wake_points.is_empty() {
+        datasets.push(
+            Dataset::default()
+                .marker(symbols::Marker::Dot)
+                .graph_type(GraphType::Scatter)
+                .style(theme.success_style())
+                .data(&wake_points),
+        );
+    }
+
+    let time_label = |timestamp: i64| {
+        chrono::Local
+            .timestamp_opt(timestamp, 0)
+            .single()
+            .map(|t| t.format("%H:%M").to_string())
+            .unwrap_or_default()
+    };
+    let end_label = if range.is_following_live() {
+        "now".to_string()
     } else {
-        app.history_daily_stats
-            .iter()
-            .enumerate()
-            .map(|(i, d)| (i as f64, d.avg_power as f64))
-            .collect()
+        time_label(to)
     };
 
-    if data_points.is_empty() {
-        let no_data = Paragraph::new(vec![Line::from(vec![Span::styled(
-            "No data for this period",
+    let chart = Chart::new(datasets)
+        .x_axis(
+            Axis::default()
+                .style(theme.border_style())
+                .bounds([0.0, (to - from) as f64])
+                .labels(vec![Span::raw(time_label(from)), Span::raw(end_label)]),
+        )
+        .y_axis(
+            Axis::default()
+                .title(Span::styled(y_title, theme.muted_style()))
+                .style(theme.border_style())
+                .bounds([0.0, y_max * 1.1])
+                .labels(vec![
+                    Span::raw("0"),
+                    Span::raw(format!("{:.0}", y_max / 2.0)),
+                    Span::raw(format!("{:.0}", y_max)),
+                ]),
+        );
+
+    frame.render_widget(chart, inner);
+}
+
+/// Full charge capacity in mAh over the whole wear log, with gauge
+/// recalibrations marked instead of read as capacity gain.
+fn render_capacity_chart(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
+    let wear = &app.battery_wear;
+    let title = match (wear.latest(), wear.projected_end_of_life()) {
+        (Some(latest), Some(end_of_life)) => format!(
+            " Capacity · {:.0}/{:.0} mAh · {:.0}% by {} ",
+            latest.full_charge_mah,
+            latest.design_mah,
+            END_OF_LIFE_HEALTH_PERCENT,
+            end_of_life.format("%b %Y")
+        ),
+        (Some(latest), None) => format!(
+            " Capacity · {:.0}/{:.0} mAh ",
+            latest.full_charge_mah, latest.design_mah
+        ),
+        _ => " Capacity (mAh) ".to_string(),
+    };
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(theme.border_style());
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(first) = wear.points.first() else {
+        let no_data = Paragraph::new(Line::from(Span::styled(
+            "No mAh capacity recorded yet",
             theme.muted_style(),
-        )])])
+        )))
         .centered();
         frame.render_widget(no_data, inner);
         return;
-    }
+    };
+
+    let day = |date: chrono::NaiveDate| (date - first.date).num_days() as f64;
+    let capacity: ChartPoints = wear
+        .points
+        .iter()
+        .map(|p| (day(p.date), p.full_charge_mah as f64))
+        .collect();
+    let recalibrations: ChartPoints = wear
+        .recalibrations()
+        .map(|p| (day(p.date), p.full_charge_mah as f64))
+        .collect();
 
-    let max_power = data_points
+    let (low, high) = capacity
         .iter()
-        .map(|(_, p)| *p)
-        .fold(0.0_f64, f64::max)
-        .max(1.0);
+        .fold((f64::MAX, f64::MIN), |(lo, hi), (_, c)| {
+            (lo.min(*c), hi.max(*c))
+        });
+    let padding = ((high - low) * 0.1).max(10.0);
+    let bounds = [low - padding, high + padding];
 
-    let dataset = Dataset::default()
+    let mut datasets = vec![Dataset::default()
         .marker(symbols::Marker::Braille)
         .graph_type(GraphType::Line)
-        .style(theme.accent_style())
-        .data(&data_points);
-
-    let x_label = if app.history_period == HistoryPeriod::Today {
-        "Hour"
-    } else {
-        "Day"
-    };
+        .style(theme.success_style())
+        .data(&capacity)];
+    if !recalibrations.is_empty() {
+        datasets.push(
+            Dataset::default()
+                .name("recalibrated")
+                .marker(symbols::Marker::Dot)
+                .graph_type(GraphType::Scatter)
+                .style(theme.warning_style())
+                .data(&recalibrations),
+        );
+    }
 
-    let chart = Chart::new(vec![dataset])
+    let chart = Chart::new(datasets)
         .x_axis(
             Axis::default()
-                .title(Span::styled(x_label, theme.muted_style()))
+                .title(Span::styled("Day", theme.muted_style()))
                 .style(theme.border_style())
-                .bounds([0.0, data_points.len() as f64]),
+                .bounds([0.0, capacity.last().map_or(0.0, |(d, _)| *d).max(1.0)]),
         )
         .y_axis(
             Axis::default()
-                .title(Span::styled("Watts", theme.muted_style()))
                 .style(theme.border_style())
-                .bounds([0.0, max_power * 1.1])
+                .bounds(bounds)
                 .labels(vec![
-                    Span::raw("0"),
-                    Span::raw(format!("{:.0}", max_power / 2.0)),
-                    Span::raw(format!("{:.0}", max_power)),
+                    Span::raw(format!("{:.0}", bounds[0])),
+                    Span::raw(format!("{:.0}", bounds[1])),
                 ]),
         );
 
     frame.render_widget(chart, inner);
 }
 
+type ChartPoints = Vec<(f64, f64)>;
+
 fn render_cycle_row(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -245,7 +420,16 @@ fn render_stats_and_processes(frame: &mut Frame, area: Rect, app: &App, theme: &
         .split(vertical[1]);
 
     render_summary_stats(frame, horizontal[0], app, theme);
-    render_top_processes(frame, horizontal[1], app, theme);
+    if app.history_period == HistoryPeriod::Today {
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(horizontal[1]);
+        render_top_processes(frame, right[0], app, theme);
+        render_top_apps(frame, right[1], app, theme);
+    } else {
+        render_top_processes(frame, horizontal[1], app, theme);
+    }
 }
 
 fn render_sparklines(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
@@ -312,6 +496,48 @@ fn render_sparklines(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColo
     frame.render_widget(energy_sparkline, chunks[1]);
 }
 
+/// Totals shown in the history summary block for the selected period.
+pub(super) struct SummaryStats {
+    total_energy_wh: f32,
+    avg_power: f32,
+    max_power: f32,
+    screen_on_hours: f32,
+    charging_hours: f32,
+    days: usize,
+}
+
+impl SummaryStats {
+    pub(super) fn from_daily(daily: &[DailyStat]) -> Option<Self> {
+        if daily.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            total_energy_wh: daily.iter().map(|d| d.total_energy_wh).sum(),
+            avg_power: daily.iter().map(|d| d.avg_power).sum::<f32>() / daily.len() as f32,
+            max_power: daily.iter().map(|d| d.max_power).fold(0.0_f32, f32::max),
+            screen_on_hours: daily.iter().map(|d| d.screen_on_hours).sum(),
+            charging_hours: daily.iter().map(|d| d.charging_hours).sum(),
+            days: daily.len(),
+        })
+    }
+
+    /// Label/value pairs in display order.
+    pub(super) fn rows(&self, energy_unit: EnergyUnit) -> [(&'static str, String); 6] {
+        [
+            (
+                "Total Energy",
+                format_energy(self.total_energy_wh, energy_unit),
+            ),
+            ("Avg Power", format!("{:.1} W", self.avg_power)),
+            ("Max Power", format!("{:.1} W", self.max_power)),
+            ("Screen On", format!("{:.1} hrs", self.screen_on_hours)),
+            ("Charging", format!("{:.1} hrs", self.charging_hours)),
+            ("Days", self.days.to_string()),
+        ]
+    }
+}
+
 fn render_summary_stats(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
     let block = Block::default()
         .title(" Summary ")
@@ -321,7 +547,7 @@ fn render_summary_stats(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeC
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    if app.history_daily_stats.is_empty() {
+    let Some(summary) = SummaryStats::from_daily(&app.history_daily_stats) else {
         let no_data = Paragraph::new(vec![Line::from(vec![Span::styled(
             "No data",
             theme.muted_style(),
@@ -329,68 +555,30 @@ fn render_summary_stats(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeC
         .centered();
         frame.render_widget(no_data, inner);
         return;
-    }
-
-    let total_energy: f32 = app
-        .history_daily_stats
-        .iter()
-        .map(|d| d.total_energy_wh)
-        .sum();
-    let avg_power: f32 = app
-        .history_daily_stats
-        .iter()
-        .map(|d| d.avg_power)
-        .sum::<f32>()
-        / app.history_daily_stats.len() as f32;
-    let max_power = app
-        .history_daily_stats
-        .iter()
-        .map(|d| d.max_power)
-        .fold(0.0_f32, f32::max);
-    let total_screen_hours: f32 = app
-        .history_daily_stats
-        .iter()
-        .map(|d| d.screen_on_hours)
-        .sum();
-    let total_charging: f32 = app
-        .history_daily_stats
-        .iter()
-        .map(|d| d.charging_hours)
-        .sum();
+    };
 
-    let energy_unit = app.config.user_config.units.energy;
-    let stats = vec![
-        Line::from(vec![
-            Span::styled("Total Energy:  ", theme.muted_style()),
-            Span::styled(
-                format_energy(total_energy, energy_unit),
-                theme.accent_style(),
-            ),
-        ]),
-        Line::from(vec![
-            Span::styled("Avg Power:     ", theme.muted_style()),
-            Span::styled(format!("{:.1} W", avg_power), theme.fg_style()),
-        ]),
-        Line::from(vec![
-            Span::styled("Max Power:     ", theme.muted_style()),
-            Span::styled(format!("{:.1} W", max_power), theme.warning_style()),
-        ]),
-        Line::from(vec![
-            Span::styled("Screen On:     ", theme.muted_style()),
-            Span::styled(format!("{:.1} hrs", total_screen_hours), theme.fg_style()),
-        ]),
-        Line::from(vec![
-            Span::styled("Charging:      ", theme.muted_style()),
-            Span::styled(format!("{:.1} hrs", total_charging), theme.success_style()),
-        ]),
-        Line::from(vec![
-            Span::styled("Days:          ", theme.muted_style()),
-            Span::styled(
-                format!("{}", app.history_daily_stats.len()),
-                theme.fg_style(),
-            ),
-        ]),
+    let value_styles = [
+        theme.accent_style(),
+        theme.fg_style(),
+        theme.warning_style(),
+        theme.fg_style(),
+        theme.success_style(),
+        theme.fg_style(),
     ];
+    let stats: Vec<Line> = summary
+        .rows(app.config.user_config.units.energy)
+        .into_iter()
+        .zip(value_styles)
+        .map(|((label, value), style)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<15}", format!("{}:", label)),
+                    theme.muted_style(),
+                ),
+                Span::styled(value, style),
+            ])
+        })
+        .collect();
 
     let para = Paragraph::new(stats)
         .block(Block::default().padding(ratatui::widgets::Padding::horizontal(1)));
@@ -456,7 +644,7 @@ fn render_top_processes(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeC
                 truncate_name(&p.process_name, name_width),
                 format!("{:.1}", p.avg_power),
                 format_energy_compact(p.total_energy_wh, energy_unit),
-                format!("{:.0}", p.avg_cpu),
+                format!("{:.0}", app.normalized_cpu(p.avg_cpu).percent),
             ])
             .style(Style::default().fg(power_color))
         })
@@ -477,6 +665,65 @@ fn render_top_processes(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeC
     frame.render_widget(table, inner);
 }
 
+/// Energy per app today, with apps whose energy was partly estimated
+/// marked by `*`.
+fn render_top_apps(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
+    let block = Block::default()
+        .title(" App Energy Today ")
+        .borders(Borders::ALL)
+        .border_style(theme.border_style());
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if app.history_top_apps.is_empty() {
+        let no_data = Paragraph::new(vec![Line::from(vec![Span::styled(
+            "No app energy data",
+            theme.muted_style(),
+        )])])
+        .centered();
+        frame.render_widget(no_data, inner);
+        return;
+    }
+
+    let name_width = inner
+        .width
+        .saturating_sub(HISTORY_COL_TOTAL_WH + 1)
+        .max(HISTORY_COL_NAME_MIN) as usize;
+
+    let energy_unit = app.config.user_config.units.energy;
+    let max_energy = app
+        .history_top_apps
+        .iter()
+        .map(|a| a.energy_wh)
+        .fold(0.0_f32, f32::max);
+
+    let rows: Vec<Row> = app
+        .history_top_apps
+        .iter()
+        .take(inner.height as usize)
+        .map(|a| {
+            let mut energy = format_energy_compact(a.energy_wh, energy_unit);
+            if a.estimated_wh > 0.0 {
+                energy.push('*');
+            }
+            Row::new(vec![truncate_name(&a.name, name_width), energy])
+                .style(Style::default().fg(power_level_color(a.energy_wh, max_energy, theme)))
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(HISTORY_COL_NAME_MIN),
+            Constraint::Length(HISTORY_COL_TOTAL_WH),
+        ],
+    )
+    .column_spacing(1);
+
+    frame.render_widget(table, inner);
+}
+
 fn power_level_color(power: f32, max_power: f32, theme: &ThemeColors) -> ratatui::style::Color {
     const EPS: f32 = 1e-6;
     let effective_max = if max_power.abs() < EPS {
@@ -501,8 +748,22 @@ fn render_footer(frame: &mut Frame, area: Rect, theme: &ThemeColors) {
             theme.accent_style(),
         ),
         Span::styled(" Period  ", theme.muted_style()),
+        Span::styled(
+            format!("[{}/{}]", keys::ZOOM_IN, keys::ZOOM_OUT),
+            theme.accent_style(),
+        ),
+        Span::styled(" Zoom  ", theme.muted_style()),
+        Span::styled(
+            format!("[{}/{}]", keys::PAN_BACK, keys::PAN_FORWARD),
+            theme.accent_style(),
+        ),
+        Span::styled(" Pan  ", theme.muted_style()),
+        Span::styled(format!("[{}]", keys::FOLLOW_LIVE), theme.accent_style()),
+        Span::styled(" Live  ", theme.muted_style()),
         Span::styled(format!("[{}]", keys::SETTINGS), theme.accent_style()),
         Span::styled(" Settings  ", theme.muted_style()),
+        Span::styled(format!("[{}]", keys::COPY), theme.accent_style()),
+        Span::styled(" Copy  ", theme.muted_style()),
         Span::styled(format!("[{}]", keys::ESC), theme.accent_style()),
         Span::styled(" Close", theme.muted_style()),
     ])])
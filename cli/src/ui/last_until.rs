@@ -0,0 +1,139 @@
+use std::time::SystemTime;
+
+use chrono::{DateTime, Duration, Local, NaiveTime};
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::data::last_until::LastUntilVerdict;
+use crate::theme::ThemeColors;
+
+use super::utils::{centered_rect, format_percent, format_watts};
+
+pub fn render(frame: &mut Frame, app: &App, theme: &ThemeColors) {
+    let area = centered_rect(frame.area(), 50, 11);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Will it last? ")
+        .borders(Borders::ALL)
+        .border_style(theme.accent_style())
+        .style(Style::default().bg(theme.dialog_bg));
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Until (HH:MM): ", theme.muted_style()),
+            Span::styled(
+                format!("{}_", app.last_until_input),
+                theme.fg_style().add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+    ];
+    lines.extend(result_lines(app, theme));
+
+    while lines.len() < 8 {
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled(
+        "  Esc to close",
+        theme.muted_style(),
+    )));
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn result_lines<'a>(app: &App, theme: &ThemeColors) -> Vec<Line<'a>> {
+    let Some(target) = parse_target_time(&app.last_until_input, Local::now()) else {
+        return vec![muted_line("  Type a time, e.g. 18:30", theme)];
+    };
+    let Some(estimate) = app.battery.will_last_until(target) else {
+        let reason = if app.battery.is_charging() {
+            "  Not discharging"
+        } else {
+            "  Need ~10 minutes of discharge history"
+        };
+        return vec![muted_line(reason, theme)];
+    };
+
+    let verdict_style = match estimate.verdict {
+        LastUntilVerdict::Yes => theme.success_style(),
+        LastUntilVerdict::Probably | LastUntilVerdict::ProbablyNot => theme.warning_style(),
+        LastUntilVerdict::No => theme.danger_style(),
+    };
+
+    vec![
+        Line::from(vec![
+            Span::styled("  ", theme.fg_style()),
+            Span::styled(
+                estimate.verdict.label(),
+                verdict_style.add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    " · {} expected left",
+                    format_percent(estimate.expected_percent)
+                ),
+                theme.fg_style(),
+            ),
+        ]),
+        Line::from(Span::styled(
+            format!(
+                "  Range: {} to {}",
+                format_percent(estimate.pessimistic_percent),
+                format_percent(estimate.optimistic_percent)
+            ),
+            theme.muted_style(),
+        )),
+        Line::from(Span::styled(
+            format!("  Recent drain: {}", format_watts(estimate.drain_watts)),
+            theme.muted_style(),
+        )),
+    ]
+}
+
+fn muted_line<'a>(text: &'a str, theme: &ThemeColors) -> Line<'a> {
+    Line::from(Span::styled(text, theme.muted_style()))
+}
+
+/// Next occurrence of a local `HH:MM` time after `now`.
+fn parse_target_time(input: &str, now: DateTime<Local>) -> Option<SystemTime> {
+    let time = NaiveTime::parse_from_str(input, "%H:%M").ok()?;
+    let mut target = now.date_naive().and_time(time);
+    if target <= now.naive_local() {
+        target += Duration::days(1);
+    }
+    let target = target.and_local_timezone(Local).earliest()?;
+    Some(target.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn target_time_rolls_over_to_tomorrow() {
+        let now = Local.with_ymd_and_hms(2024, 3, 12, 20, 0, 0).unwrap();
+
+        let later = parse_target_time("21:15", now).unwrap();
+        let earlier = parse_target_time("07:30", now).unwrap();
+
+        assert_eq!(
+            DateTime::<Local>::from(later),
+            Local.with_ymd_and_hms(2024, 3, 12, 21, 15, 0).unwrap()
+        );
+        assert_eq!(
+            DateTime::<Local>::from(earlier),
+            Local.with_ymd_and_hms(2024, 3, 13, 7, 30, 0).unwrap()
+        );
+        assert!(parse_target_time("18", now).is_none());
+        assert!(parse_target_time("25:00", now).is_none());
+    }
+}
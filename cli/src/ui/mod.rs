@@ -1,17 +1,27 @@
 mod battery;
 mod battery_details;
+mod bug_report;
+mod charger_details;
+pub mod copy_text;
 mod cycles;
+mod debug_panel;
+mod domain_stack;
+mod fleet;
 mod graphs;
+mod heatmap;
 mod help;
 mod history;
+mod last_until;
 mod power;
 mod processes;
+mod session_resume;
 mod settings;
+mod sparkline;
 mod status_bar;
 mod system_stats;
 mod theme_importer;
 mod theme_picker;
-mod utils;
+pub mod utils;
 
 use ratatui::{
     layout::{Constraint, Direction, Layout},
@@ -150,6 +160,13 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         AppView::History => history::render(frame, app, &theme),
         AppView::Settings => settings::render(frame, app, &theme),
         AppView::BatteryDetails => battery_details::render(frame, app, &theme),
+        AppView::ChargerDetails => charger_details::render(frame, app, &theme),
+        AppView::DebugPanel => debug_panel::render(frame, app, &theme),
+        AppView::LastUntil => last_until::render(frame, app, &theme),
+        AppView::Fleet => fleet::render(frame, app, &theme),
+        AppView::Heatmap => heatmap::render(frame, app, &theme),
+        AppView::BugReport => bug_report::render(frame, app, &theme),
+        AppView::ResumeSession => session_resume::render(frame, app, &theme),
         AppView::Main => {}
     }
 }
@@ -7,9 +7,17 @@ use ratatui::{
 };
 
 use crate::app::App;
+use crate::data::memory::MemoryPressure;
+use crate::data::power::GpuResidency;
+use crate::data::HistorySeries;
 use crate::theme::ThemeColors;
 
-use super::utils::color_for_value;
+use super::sparkline::{recent_samples, render_readout};
+use super::utils::{color_for_value, format_watts};
+
+const GPU_RESIDENCY_BAR_WIDTH: usize = 12;
+/// Idle first; higher performance states get denser shades.
+const GPU_STATE_SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
 
 pub fn render(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
     let power_color = if app.power.is_warmed_up() {
@@ -18,11 +26,23 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
         theme.muted
     };
 
-    let block = Block::default()
-        .title(Span::styled(" Power ", Style::default().fg(power_color)))
+    let mut title = vec![Span::styled(" Power ", Style::default().fg(power_color))];
+    if app.power.sampling_failure().is_some() {
+        // The readings below are the last ones before the sampler gave up.
+        title.push(Span::styled("sampling stopped ", theme.warning_style()));
+    }
+
+    let mut block = Block::default()
+        .title(Line::from(title))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(power_color))
         .style(Style::default().bg(theme.bg));
+    if let Some(chip) = memory_chip(app, theme) {
+        block = block.title_top(chip.right_aligned());
+    }
+    if let Some(residency) = gpu_residency_line(app, theme) {
+        block = block.title_bottom(residency.right_aligned());
+    }
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
@@ -36,15 +56,7 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
         ])
         .split(inner);
 
-    let (total_power, cpu_power, gpu_power) = if app.power.is_warmed_up() {
-        (
-            format!("{:.1}W", app.power.total_power_watts()),
-            format!("{:.1}W", app.power.cpu_power_watts()),
-            format!("{:.1}W", app.power.gpu_power_watts()),
-        )
-    } else {
-        ("—".to_string(), "—".to_string(), "—".to_string())
-    };
+    let (total_power, cpu_power, gpu_power) = power_readings(app);
 
     let total = Paragraph::new(Line::from(vec![
         Span::styled("Total: ", theme.muted_style()),
@@ -57,17 +69,23 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
     ]))
     .centered();
 
-    let cpu = Paragraph::new(Line::from(vec![
+    let cpu = Line::from(vec![
         Span::styled("CPU: ", theme.muted_style()),
         Span::styled(cpu_power, theme.accent_style()),
-    ]))
-    .centered();
+    ]);
 
-    let gpu = Paragraph::new(Line::from(vec![
+    let gpu = Line::from(vec![
         Span::styled("GPU: ", theme.muted_style()),
         Span::styled(gpu_power, theme.accent_secondary_style()),
-    ]))
-    .centered();
+    ]);
+
+    let sparklines = app.config.user_config.sparklines;
+    let cpu_samples = sparklines
+        .cpu
+        .then(|| recent_samples(app, HistorySeries::CpuPower));
+    let gpu_samples = sparklines
+        .gpu
+        .then(|| recent_samples(app, HistorySeries::GpuPower));
 
     let v_center = |chunk: Rect| {
         Layout::default()
@@ -81,6 +99,122 @@ pub fn render(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
     };
 
     frame.render_widget(total, v_center(chunks[0]));
-    frame.render_widget(cpu, v_center(chunks[1]));
-    frame.render_widget(gpu, v_center(chunks[2]));
+    render_readout(
+        frame,
+        v_center(chunks[1]),
+        cpu,
+        cpu_samples.as_ref().map(|s| s.as_slice()),
+        theme.accent,
+    );
+    render_readout(
+        frame,
+        v_center(chunks[2]),
+        gpu,
+        gpu_samples.as_ref().map(|s| s.as_slice()),
+        theme.accent_secondary,
+    );
+}
+
+/// Total, CPU, and GPU power as displayed; dashes until readings settle.
+pub(super) fn power_readings(app: &App) -> (String, String, String) {
+    if app.power.is_warmed_up() {
+        (
+            format_watts(app.power.total_power_watts()),
+            format_watts(app.power.cpu_power_watts()),
+            format_watts(app.power.gpu_power_watts()),
+        )
+    } else {
+        ("—".to_string(), "—".to_string(), "—".to_string())
+    }
+}
+
+/// Memory pressure as a chip colored by level, followed by bandwidth where
+/// the platform reports it. `None` when pressure is unknown.
+fn memory_chip(app: &App, theme: &ThemeColors) -> Option<Line<'static>> {
+    let color = match app.memory.pressure() {
+        MemoryPressure::Normal => theme.success,
+        MemoryPressure::Warning => theme.warning,
+        MemoryPressure::Critical => theme.danger,
+        MemoryPressure::Unknown => return None,
+    };
+
+    let label = match app.memory.used_percent() {
+        Some(used) => format!(" MEM {:.0}% ", used),
+        None => format!(" MEM {} ", app.memory.pressure()),
+    };
+    let mut spans = vec![Span::styled(
+        label,
+        Style::default()
+            .fg(theme.bg)
+            .bg(color)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if let Some(gbps) = app.memory.bandwidth_gbps() {
+        spans.push(Span::styled(
+            format!(" {:.1} GB/s ", gbps),
+            theme.muted_style(),
+        ));
+    }
+    Some(Line::from(spans))
+}
+
+/// GPU performance state residency as a bar, lowest state on the left,
+/// followed by the state the GPU spent most time in. `None` where the
+/// residency is not reported.
+fn gpu_residency_line(app: &App, theme: &ThemeColors) -> Option<Line<'static>> {
+    let residency = app.power.gpu_residency()?;
+    let dominant = residency.dominant()?;
+    Some(Line::from(vec![
+        Span::styled(" GPU ", theme.muted_style()),
+        Span::styled(
+            residency_bar(residency, GPU_RESIDENCY_BAR_WIDTH),
+            theme.accent_secondary_style(),
+        ),
+        Span::styled(
+            format!(" {} {:.0}% ", dominant.name, dominant.fraction * 100.0),
+            theme.muted_style(),
+        ),
+    ]))
+}
+
+/// `width` cells, each shaded by the state covering its midpoint.
+fn residency_bar(residency: &GpuResidency, width: usize) -> String {
+    let levels = residency.states.len();
+    let shade = |index: usize| {
+        let top = GPU_STATE_SHADES.len() - 1;
+        GPU_STATE_SHADES[(index * top).div_ceil(levels.saturating_sub(1).max(1))]
+    };
+
+    (0..width)
+        .map(|cell| {
+            let midpoint = (cell as f32 + 0.5) / width as f32;
+            let mut covered = 0.0;
+            let index = residency
+                .states
+                .iter()
+                .position(|state| {
+                    covered += state.fraction;
+                    covered >= midpoint
+                })
+                .unwrap_or(levels.saturating_sub(1));
+            shade(index)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::power::GpuResidency;
+
+    #[test]
+    fn residency_bar_splits_cells_by_share() {
+        let residency =
+            GpuResidency::from_residencies([("OFF", 50), ("P1", 0), ("P2", 25), ("P3", 25)])
+                .unwrap();
+        assert_eq!(residency_bar(&residency, 8), "····▓▓██");
+
+        let two = GpuResidency::from_residencies([("OFF", 1), ("P1", 3)]).unwrap();
+        assert_eq!(residency_bar(&two, 4), "·███");
+    }
 }
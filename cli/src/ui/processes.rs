@@ -10,7 +10,9 @@ use crate::app::{App, SortColumn};
 use crate::data::ProcessState;
 use crate::theme::ThemeColors;
 
-use super::utils::format_duration;
+use super::utils::{
+    format_cpu, format_disk_io, format_duration, format_memory, format_network_rate,
+};
 
 const COL_EXPAND: u16 = 6;
 const COL_PID: u16 = 7;
@@ -19,10 +21,11 @@ const COL_IMPACT: u16 = 8;
 const COL_CPU: u16 = 6;
 const COL_MEMORY: u16 = 8;
 const COL_DISK: u16 = 9;
+const COL_NETWORK: u16 = 8;
 const COL_RUNTIME: u16 = 10;
 const COL_CPUTIME: u16 = 10;
 const COL_KILL: u16 = 4;
-const COL_SPACING: u16 = 12;
+const COL_SPACING: u16 = 13;
 const COL_NAME_MIN: u16 = 12;
 const COL_COMMAND_MIN: u16 = 15;
 
@@ -93,6 +96,7 @@ pub fn render(frame: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors)
         + COL_CPU
         + COL_MEMORY
         + COL_DISK
+        + COL_NETWORK
         + COL_RUNTIME
         + COL_CPUTIME
         + COL_IMPACT
@@ -125,7 +129,7 @@ pub fn render(frame: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors)
         }
     };
     let sort_indicator = if app.sort_ascending { "▲" } else { "▼" };
-    let header_cells: [String; 12] = [
+    let header_cells: [String; 13] = [
         "".to_string(),
         format_header("PID", SortColumn::Pid, app.sort_column, sort_indicator),
         "S".to_string(),
@@ -145,6 +149,7 @@ pub fn render(frame: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors)
             sort_indicator,
         ),
         "Disk".to_string(),
+        format_header("Net", SortColumn::Network, app.sort_column, sort_indicator),
         "Runtime".to_string(),
         "CPU".to_string(),
         "Kill".to_string(),
@@ -227,6 +232,7 @@ pub fn render(frame: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors)
             };
 
             let disk_io = format_disk_io(process.disk_read_bytes, process.disk_write_bytes);
+            let network = format_network_rate(process.network_bytes_per_sec);
             let runtime = format_duration(process.run_time_secs);
             let cpu_time = format_duration(process.total_cpu_time_secs);
 
@@ -237,9 +243,10 @@ pub fn render(frame: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors)
                 Span::styled(format!("{:.1}", process.energy_impact), style),
                 Span::styled(truncate_name(display_name, name_width), style),
                 Span::styled(truncate_name(&process.command_args, command_width), style),
-                Span::styled(format!("{:.1}", process.cpu_usage), style),
+                Span::styled(format_cpu(app.normalized_cpu(process.cpu_usage)), style),
                 Span::styled(format_memory(process.memory_mb), style),
                 Span::styled(disk_io, style),
+                Span::styled(network, style),
                 Span::styled(runtime, style),
                 Span::styled(cpu_time, style),
                 Span::styled(killable_indicator, killable_style),
@@ -259,6 +266,7 @@ pub fn render(frame: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors)
         Constraint::Length(COL_CPU),
         Constraint::Length(COL_MEMORY),
         Constraint::Length(COL_DISK),
+        Constraint::Length(COL_NETWORK),
         Constraint::Length(COL_RUNTIME),
         Constraint::Length(COL_CPUTIME),
         Constraint::Length(COL_KILL),
@@ -284,24 +292,3 @@ fn format_header(name: &str, col: SortColumn, current: SortColumn, indicator: &s
         name.to_string()
     }
 }
-
-fn format_disk_io(read_bytes: u64, write_bytes: u64) -> String {
-    let total = read_bytes + write_bytes;
-    if total == 0 {
-        "-".to_string()
-    } else if total < 1024 {
-        format!("{}B", total)
-    } else if total < 1024 * 1024 {
-        format!("{:.0}K", total as f64 / 1024.0)
-    } else {
-        format!("{:.1}M", total as f64 / (1024.0 * 1024.0))
-    }
-}
-
-fn format_memory(mb: f64) -> String {
-    if mb < 1000.0 {
-        format!("{:.0}M", mb)
-    } else {
-        format!("{:.1}G", mb / 1024.0)
-    }
-}
@@ -0,0 +1,131 @@
+//! Launch prompt offering to resume the session saved at the last quit.
+
+use chrono::{Local, TimeZone};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::data::session_state::ResumeOffer;
+use crate::theme::ThemeColors;
+
+use super::utils::{centered_rect, format_duration, format_energy, format_watts};
+
+/// Apps listed under the saved counters.
+const TOP_APPS: usize = 3;
+
+pub fn render(frame: &mut Frame, app: &App, theme: &ThemeColors) {
+    let Some(pending) = app.pending_resume.as_ref() else {
+        return;
+    };
+    let saved = &pending.saved;
+    let energy_unit = app.config.user_config.units.energy;
+
+    let area = centered_rect(frame.area(), 60, 16 + TOP_APPS as u16);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Resume Session? ")
+        .borders(Borders::ALL)
+        .border_style(theme.accent_style())
+        .style(Style::default().bg(theme.dialog_bg));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let body = Rect {
+        x: inner.x + 1,
+        y: inner.y + 1,
+        width: inner.width.saturating_sub(2),
+        height: inner.height.saturating_sub(2),
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let ago = minutes((now - saved.saved_at).max(0) as u64);
+    let span = minutes((saved.saved_at - saved.started_at).max(0) as u64);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!(
+                "jolt was quit at {} ({} ago).",
+                local_time(saved.saved_at),
+                ago
+            ),
+            theme.fg_style(),
+        )),
+        Line::from(""),
+        row(
+            "Started",
+            format!("{} ({})", local_time(saved.started_at), span),
+            theme,
+        ),
+        row(
+            "Energy",
+            format_energy(saved.energy_wh as f32, energy_unit),
+            theme,
+        ),
+        row("On battery", minutes(saved.battery_secs as u64), theme),
+        row("Peak", format_watts(saved.peak_power_watts), theme),
+    ];
+    for (name, wh) in saved.top_apps(TOP_APPS) {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<20}", name), theme.muted_style()),
+            Span::styled(format_energy(wh as f32, energy_unit), theme.fg_style()),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(match pending.offer {
+        ResumeOffer::Resume => Line::from(Span::styled(
+            "Resuming adds these counters to this session.",
+            theme.muted_style(),
+        )),
+        ResumeOffer::ResumeAfterReboot => Line::from(Span::styled(
+            "The Mac rebooted since: hardware counters were reset, so only \
+             jolt's own totals carry over.",
+            theme.warning_style(),
+        )),
+    });
+
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), body);
+
+    let footer_area = Rect {
+        y: inner.y + inner.height.saturating_sub(1),
+        height: 1,
+        ..inner
+    };
+    let footer = Line::from(vec![
+        Span::styled(
+            "[Enter/y]",
+            theme.accent_style().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(" Resume  ", theme.muted_style()),
+        Span::styled("[n/Esc]", theme.accent_style()),
+        Span::styled(" Start fresh", theme.muted_style()),
+    ]);
+    frame.render_widget(Paragraph::new(footer).centered(), footer_area);
+}
+
+fn row(label: &str, value: String, theme: &ThemeColors) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{:<12}", label), theme.muted_style()),
+        Span::styled(value, theme.fg_style()),
+    ])
+}
+
+/// `secs` to the minute, so durations read "1h 20m" rather than to the second.
+fn minutes(secs: u64) -> String {
+    if secs < 60 {
+        return "under a minute".to_string();
+    }
+    format_duration(secs / 60 * 60)
+}
+
+fn local_time(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map_or_else(|| "?".to_string(), |t| t.format("%H:%M").to_string())
+}
@@ -26,7 +26,7 @@ fn centered_fixed_rect(area: Rect, width: u16, height: u16) -> Rect {
 pub fn render(frame: &mut Frame, app: &App, theme: &ThemeColors) {
     let status_height: u16 = 4;
     let items_height = settings::row_count() as u16;
-    let footer_height: u16 = 1;
+    let footer_height: u16 = 2;
     let content_height = status_height + items_height + footer_height + 6;
     let dialog_width = CONTENT_WIDTH + 8;
     let area = centered_fixed_rect(frame.area(), dialog_width, content_height);
@@ -64,7 +64,7 @@ pub fn render(frame: &mut Frame, app: &App, theme: &ThemeColors) {
     render_status_section(frame, chunks[0], app, theme);
     render_divider(frame, chunks[1], theme);
     render_items_section(frame, chunks[2], app, theme);
-    render_footer(frame, chunks[3], theme);
+    render_footer(frame, chunks[3], app, theme);
 }
 
 fn render_status_section(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
@@ -150,18 +150,23 @@ fn render_items_section(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeC
             SettingsRow::Item { id, label } => {
                 let value = settings::setting_value(app, *id);
                 let is_selected = i == app.settings_selected_item;
+                let read_only = settings::read_only_reason(*id).is_some();
 
                 let style = if is_selected {
                     Style::default()
                         .fg(theme.selection_fg)
                         .bg(theme.selection_bg)
                         .add_modifier(Modifier::BOLD)
+                } else if read_only {
+                    theme.muted_style()
                 } else {
                     theme.fg_style()
                 };
 
                 let value_style = if is_selected {
-                    style.fg(theme.accent)
+                    style.fg(if read_only { theme.muted } else { theme.accent })
+                } else if read_only {
+                    theme.muted_style()
                 } else {
                     theme.accent_style()
                 };
@@ -179,12 +184,55 @@ fn render_items_section(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeC
     frame.render_widget(list, area);
 }
 
-fn render_footer(frame: &mut Frame, area: Rect, theme: &ThemeColors) {
-    let footer = Paragraph::new(Line::from(vec![
-        Span::styled(format!("[{}]", keys::ESC), theme.accent_style()),
-        Span::styled(" Close", theme.muted_style()),
-    ]))
-    .alignment(Alignment::Center);
+fn render_footer(frame: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
+    let hint = |key: &str, label: &str| {
+        [
+            Span::styled(format!("[{}]", key), theme.accent_style()),
+            Span::styled(format!(" {}  ", label), theme.muted_style()),
+        ]
+    };
+
+    let lines = if app.settings_save_prompt {
+        vec![
+            Line::from(Span::styled(
+                "Write changes to config.toml?",
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(
+                [
+                    hint("y", "Save"),
+                    hint("n", "This session only"),
+                    hint(keys::ESC, "Back"),
+                ]
+                .concat(),
+            ),
+        ]
+    } else {
+        let status = match settings::setting_id_at(app.settings_selected_item)
+            .and_then(settings::read_only_reason)
+        {
+            Some(reason) => Span::styled(reason, theme.muted_style()),
+            None if app.settings_unsaved => {
+                Span::styled("Unsaved changes (applied live)", theme.warning_style())
+            }
+            None => Span::raw(""),
+        };
+        vec![
+            Line::from(status),
+            Line::from(
+                [
+                    hint("\u{2190}\u{2192}", "Change"),
+                    hint(keys::SAVE_SETTINGS, "Save"),
+                    hint(keys::ESC, "Close"),
+                ]
+                .concat(),
+            ),
+        ]
+    };
+
+    let footer = Paragraph::new(lines).alignment(Alignment::Center);
     frame.render_widget(footer, area);
 }
 
@@ -0,0 +1,179 @@
+//! Tiny sparklines drawn next to overview readouts.
+//!
+//! Samples are read into a stack buffer and bucketed straight into buffer
+//! cells, so drawing one costs no allocation per frame. Only the glyph and
+//! foreground are written: a sparkline overlaid on a gauge keeps the gauge's
+//! background.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Color,
+    text::Line,
+    widgets::{Paragraph, Widget},
+    Frame,
+};
+
+use crate::app::App;
+use crate::data::history::{SPARKLINE_FLAT_LEVEL as FLAT_LEVEL, SPARKLINE_LEVELS as LEVELS};
+use crate::data::HistorySeries;
+
+/// Samples shown per sparkline (about two minutes at the default refresh).
+pub const SPARKLINE_SAMPLES: usize = 60;
+/// Cells per sparkline; each cell shows the peak of its bucket of samples.
+pub const SPARKLINE_WIDTH: u16 = 8;
+
+/// Blank span reserving room for a sparkline (plus a separating space)
+/// inside a line of text; the sparkline is drawn over it afterwards.
+pub const SPARKLINE_GAP: &str = "         ";
+
+pub type SparklineSamples = [Option<f32>; SPARKLINE_SAMPLES];
+
+/// Recent samples of `series` from the in-memory history.
+pub fn recent_samples(app: &App, series: HistorySeries) -> SparklineSamples {
+    let mut samples = [None; SPARKLINE_SAMPLES];
+    app.history.recent_into(series, &mut samples);
+    samples
+}
+
+pub struct InlineSparkline<'a> {
+    samples: &'a [Option<f32>],
+    color: Color,
+}
+
+impl<'a> InlineSparkline<'a> {
+    pub fn new(samples: &'a [Option<f32>], color: Color) -> Self {
+        Self { samples, color }
+    }
+}
+
+impl Widget for InlineSparkline<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        if area.is_empty() {
+            return;
+        }
+        let Some((min, max)) = value_range(self.samples) else {
+            return;
+        };
+
+        let width = area.width as usize;
+        for i in 0..width {
+            let Some(level) = cell_level(self.samples, i, width, min, max) else {
+                continue;
+            };
+            if let Some(cell) = buf.cell_mut((area.x + i as u16, area.y)) {
+                cell.set_char(LEVELS[level]).set_fg(self.color);
+            }
+        }
+    }
+}
+
+/// Renders `line` centered in the first row of `area` with a sparkline after
+/// it. Without samples, or when both do not fit, only the line is drawn.
+pub fn render_readout(
+    frame: &mut Frame,
+    area: Rect,
+    line: Line,
+    samples: Option<&[Option<f32>]>,
+    color: Color,
+) {
+    let text_width = line.width() as u16;
+    let total_width = text_width + 1 + SPARKLINE_WIDTH;
+
+    let Some(samples) = samples.filter(|_| area.width >= total_width) else {
+        frame.render_widget(Paragraph::new(line).centered(), area);
+        return;
+    };
+
+    let x = area.x + (area.width - total_width) / 2;
+    let text_area = Rect {
+        x,
+        width: text_width,
+        height: 1,
+        ..area
+    };
+    let spark_area = Rect {
+        x: x + text_width + 1,
+        width: SPARKLINE_WIDTH,
+        height: 1,
+        ..area
+    };
+
+    frame.render_widget(Paragraph::new(line), text_area);
+    frame.render_widget(InlineSparkline::new(samples, color), spark_area);
+}
+
+fn value_range(samples: &[Option<f32>]) -> Option<(f32, f32)> {
+    samples
+        .iter()
+        .flatten()
+        .filter(|v| v.is_finite())
+        .fold(None, |range, &v| match range {
+            None => Some((v, v)),
+            Some((min, max)) => Some((min.min(v), max.max(v))),
+        })
+}
+
+/// Glyph index for cell `index` of `width`, or `None` if its bucket has no
+/// samples.
+fn cell_level(
+    samples: &[Option<f32>],
+    index: usize,
+    width: usize,
+    min: f32,
+    max: f32,
+) -> Option<usize> {
+    let start = index * samples.len() / width;
+    let end = ((index + 1) * samples.len() / width).max(start + 1);
+    let peak = samples
+        .get(start..end.min(samples.len()))?
+        .iter()
+        .flatten()
+        .filter(|v| v.is_finite())
+        .copied()
+        .reduce(f32::max)?;
+
+    let span = max - min;
+    if span <= f32::EPSILON {
+        return Some(FLAT_LEVEL);
+    }
+    let scaled = (peak - min) / span * (LEVELS.len() - 1) as f32;
+    Some((scaled.round() as usize).min(LEVELS.len() - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(samples: &[Option<f32>], width: u16) -> String {
+        let area = Rect::new(0, 0, width, 1);
+        let mut buf = Buffer::empty(area);
+        InlineSparkline::new(samples, Color::Reset).render(area, &mut buf);
+        (0..width).map(|x| buf[(x, 0)].symbol()).collect()
+    }
+
+    #[test]
+    fn gap_fits_a_sparkline() {
+        assert_eq!(SPARKLINE_GAP.len(), SPARKLINE_WIDTH as usize + 1);
+    }
+
+    #[test]
+    fn scales_between_min_and_max() {
+        let samples = [Some(0.0), Some(7.0), Some(3.5), Some(7.0)];
+        assert_eq!(render(&samples, 4), "▁█▅█");
+    }
+
+    #[test]
+    fn buckets_show_their_peak() {
+        let samples = [Some(0.0), Some(8.0), Some(0.0), Some(0.0)];
+        assert_eq!(render(&samples, 2), "█▁");
+    }
+
+    #[test]
+    fn flat_and_missing_data_do_not_divide_by_zero() {
+        assert_eq!(render(&[Some(5.0); 4], 4), "▄▄▄▄");
+        assert_eq!(render(&[None; 4], 4), "    ");
+        assert_eq!(render(&[None, None, Some(1.0), Some(2.0)], 4), "  ▁█");
+    }
+}
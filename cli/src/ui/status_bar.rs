@@ -84,6 +84,20 @@ pub fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme
         right_spans.push(Span::styled("│ ", theme.border_style()));
     }
 
+    let pending_restart = app.config_pending_restart();
+    if !pending_restart.is_empty() {
+        right_spans.push(Span::styled(
+            format!("⟳ restart to apply {} ", pending_restart.join(", ")),
+            theme.warning_style(),
+        ));
+        right_spans.push(Span::styled("│ ", theme.border_style()));
+    }
+
+    if let Some(toast) = app.active_toast() {
+        right_spans.push(Span::styled(format!("{} ", toast), theme.success_style()));
+        right_spans.push(Span::styled("│ ", theme.border_style()));
+    }
+
     if background_recording {
         right_spans.extend(vec![
             Span::styled("background: ", theme.muted_style()),
@@ -4,6 +4,7 @@ use bytesize::ByteSize;
 use ratatui::{layout::Rect, style::Color};
 
 use crate::config::{DataSizeUnit, EnergyUnit, TemperatureUnit};
+use crate::data::NormalizedCpu;
 use crate::theme::ThemeColors;
 
 const NOMINAL_VOLTAGE: f32 = 11.4;
@@ -125,6 +126,53 @@ pub fn convert_temperature(celsius: f32, unit: TemperatureUnit) -> f32 {
     }
 }
 
+pub fn format_percent(percent: f32) -> String {
+    format!("{:.0}%", percent)
+}
+
+/// Format a power reading the way the dashboard shows it (e.g. "12.3W").
+/// CPU percentage without the `%`; a trailing `+` marks a clamped reading.
+pub fn format_cpu(cpu: NormalizedCpu) -> String {
+    let marker = if cpu.clamped { "+" } else { "" };
+    format!("{:.1}{}", cpu.percent, marker)
+}
+
+pub fn format_watts(watts: f32) -> String {
+    format!("{:.1}W", watts)
+}
+
+/// Format combined disk reads and writes for the process table.
+pub fn format_disk_io(read_bytes: u64, write_bytes: u64) -> String {
+    let total = read_bytes + write_bytes;
+    if total == 0 {
+        "-".to_string()
+    } else if total < 1024 {
+        format!("{}B", total)
+    } else if total < 1024 * 1024 {
+        format!("{:.0}K", total as f64 / 1024.0)
+    } else {
+        format!("{:.1}M", total as f64 / (1024.0 * 1024.0))
+    }
+}
+
+pub fn format_network_rate(bytes_per_sec: Option<f64>) -> String {
+    match bytes_per_sec {
+        None => "-".to_string(),
+        Some(rate) if rate < 1.0 => "0".to_string(),
+        Some(rate) if rate < 1024.0 => format!("{:.0}B/s", rate),
+        Some(rate) if rate < 1024.0 * 1024.0 => format!("{:.0}K/s", rate / 1024.0),
+        Some(rate) => format!("{:.1}M/s", rate / (1024.0 * 1024.0)),
+    }
+}
+
+pub fn format_memory(mb: f64) -> String {
+    if mb < 1000.0 {
+        format!("{:.0}M", mb)
+    } else {
+        format!("{:.1}G", mb / 1024.0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
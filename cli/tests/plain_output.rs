@@ -0,0 +1,68 @@
+//! Piped output must never contain ANSI escape sequences.
+
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// Runs jolt with stdin, stdout and stderr all piped and its directories
+/// inside the test's scratch space.
+fn run_piped(args: &[&str], no_color: Option<&str>) -> Output {
+    let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("plain-output-home");
+    let mut command = Command::new(env!("CARGO_BIN_EXE_jolt"));
+    command
+        .args(args)
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("XDG_CACHE_HOME", home.join("cache"))
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env("XDG_RUNTIME_DIR", home.join("runtime"))
+        .env_remove("NO_COLOR")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(value) = no_color {
+        command.env("NO_COLOR", value);
+    }
+    command.output().expect("failed to run jolt")
+}
+
+fn assert_plain(output: &Output) {
+    for (stream, bytes) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+        assert!(
+            !bytes.contains(&0x1b),
+            "escape sequence on {}: {:?}",
+            stream,
+            String::from_utf8_lossy(bytes)
+        );
+    }
+}
+
+#[test]
+fn help_and_errors_are_plain_when_piped() {
+    let help = run_piped(&["--help"], None);
+    assert!(help.status.success());
+    assert_plain(&help);
+
+    let usage_error = run_piped(&["--no-such-flag"], None);
+    assert!(!usage_error.status.success());
+    assert_plain(&usage_error);
+
+    let missing_config = run_piped(&["check", "--config", "/nonexistent/jolt.toml"], None);
+    assert!(!missing_config.status.success());
+    assert_plain(&missing_config);
+}
+
+#[test]
+fn logs_are_plain_when_piped() {
+    let output = run_piped(&["--log-level", "debug", "config", "--path"], Some("1"));
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("config.toml"));
+    assert_plain(&output);
+}
+
+#[test]
+fn tui_refuses_to_start_without_a_terminal() {
+    let output = run_piped(&[], None);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("needs an interactive terminal"));
+    assert_plain(&output);
+}
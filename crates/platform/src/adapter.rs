@@ -0,0 +1,194 @@
+//! USB-C Power Delivery details of the connected charger.
+//!
+//! What a charger reports varies wildly between models and firmware, so
+//! every modeled field is optional and the keys jolt does not model are kept
+//! as printed.
+
+/// One fixed-supply Power Data Object offered by the charger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerDataObject {
+    pub index: u32,
+    pub max_voltage_mv: u32,
+    pub max_current_ma: u32,
+}
+
+impl PowerDataObject {
+    pub fn max_watts(&self) -> f32 {
+        self.max_voltage_mv as f32 * self.max_current_ma as f32 / 1_000_000.0
+    }
+}
+
+/// What the charger and the Mac negotiated over USB-C PD.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdapterPdInfo {
+    pub name: Option<String>,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    /// Charger kind as reported, e.g. "pd charger".
+    pub description: Option<String>,
+    /// Rating the charger advertises.
+    pub rated_watts: Option<u32>,
+    pub negotiated_voltage_mv: Option<u32>,
+    pub negotiated_current_ma: Option<u32>,
+    /// Offered PDOs in the order reported; empty when not reported.
+    pub pdos: Vec<PowerDataObject>,
+    /// [`PowerDataObject::index`] of the PDO in use.
+    pub active_pdo_index: Option<u32>,
+    pub is_wireless: Option<bool>,
+    /// Every other key with its value as printed, sorted by key.
+    pub raw: Vec<(String, String)>,
+}
+
+impl AdapterPdInfo {
+    /// Parses an ioreg dictionary such as the value of `AdapterDetails`:
+    /// `{"Watts"=94,"Name"="96W USB-C Power Adapter","UsbHvcMenu"=({...}),...}`.
+    ///
+    /// `None` when the text is not a dictionary.
+    pub fn from_ioreg_dict(dict: &str) -> Option<Self> {
+        let mut info = Self::default();
+
+        for (key, value) in parse_dict(dict)? {
+            let number = || value.parse::<u32>().ok();
+            match key {
+                "Name" => info.name = Some(unquote(value).to_string()),
+                "Manufacturer" => info.manufacturer = Some(unquote(value).to_string()),
+                "Model" => info.model = Some(unquote(value).to_string()),
+                "Description" => info.description = Some(unquote(value).to_string()),
+                "Watts" => info.rated_watts = number(),
+                "AdapterVoltage" => info.negotiated_voltage_mv = number(),
+                "Current" => info.negotiated_current_ma = number(),
+                "UsbHvcHvcIndex" => info.active_pdo_index = number(),
+                "IsWireless" => info.is_wireless = Some(value == "Yes"),
+                "UsbHvcMenu" => info.pdos = parse_pdos(value),
+                _ => info.raw.push((key.to_string(), unquote(value).to_string())),
+            }
+        }
+
+        info.raw.sort();
+        Some(info)
+    }
+
+    /// Negotiated voltage times current.
+    pub fn negotiated_watts(&self) -> Option<f32> {
+        let volts = self.negotiated_voltage_mv? as f32 / 1000.0;
+        let amps = self.negotiated_current_ma? as f32 / 1000.0;
+        Some(volts * amps)
+    }
+
+    pub fn active_pdo(&self) -> Option<&PowerDataObject> {
+        let index = self.active_pdo_index?;
+        self.pdos.iter().find(|pdo| pdo.index == index)
+    }
+}
+
+/// `({"Index"=0,"MaxVoltage"=5000,"MaxCurrent"=3000},...)`; entries missing
+/// a field are skipped.
+fn parse_pdos(array: &str) -> Vec<PowerDataObject> {
+    let Some(items) = array
+        .trim()
+        .strip_prefix('(')
+        .and_then(|a| a.strip_suffix(')'))
+    else {
+        return Vec::new();
+    };
+
+    split_top_level(items)
+        .into_iter()
+        .filter_map(|item| {
+            let fields = parse_dict(item)?;
+            let field = |name: &str| {
+                fields
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .and_then(|(_, value)| value.parse::<u32>().ok())
+            };
+            Some(PowerDataObject {
+                index: field("Index")?,
+                max_voltage_mv: field("MaxVoltage")?,
+                max_current_ma: field("MaxCurrent")?,
+            })
+        })
+        .collect()
+}
+
+/// Key/value pairs of `{"Key"=value,...}`, values left unparsed.
+fn parse_dict(dict: &str) -> Option<Vec<(&str, &str)>> {
+    let body = dict.trim().strip_prefix('{')?.strip_suffix('}')?;
+    Some(
+        split_top_level(body)
+            .into_iter()
+            .filter_map(|entry| {
+                let (key, value) = entry.split_once('=')?;
+                Some((unquote(key.trim()), value.trim()))
+            })
+            .collect(),
+    )
+}
+
+/// Splits on commas outside quotes, braces and parentheses.
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut quoted, mut start) = (0_i32, false, 0);
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '{' | '(' if !quoted => depth += 1,
+            '}' | ')' if !quoted => depth -= 1,
+            ',' if !quoted && depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts.retain(|part| !part.trim().is_empty());
+    parts
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APPLE_96W: &str = r#"{"AdapterID"=0,"Model"="0x7019","FamilyCode"=18446744073172697098,"Description"="pd charger","Watts"=94,"UsbHvcHvcIndex"=3,"Current"=4700,"AdapterVoltage"=20000,"IsWireless"=No,"Manufacturer"="Apple Inc.","Name"="96W USB-C Power Adapter","UsbHvcMenu"=({"Index"=0,"MaxVoltage"=5000,"MaxCurrent"=3000},{"Index"=1,"MaxVoltage"=9000,"MaxCurrent"=3000},{"Index"=2,"MaxVoltage"=15000,"MaxCurrent"=3000},{"Index"=3,"MaxVoltage"=20000,"MaxCurrent"=4700})}"#;
+
+    #[test]
+    fn parses_negotiation_and_pdo_menu() {
+        let info = AdapterPdInfo::from_ioreg_dict(APPLE_96W).unwrap();
+
+        assert_eq!(info.name.as_deref(), Some("96W USB-C Power Adapter"));
+        assert_eq!(info.manufacturer.as_deref(), Some("Apple Inc."));
+        assert_eq!(info.rated_watts, Some(94));
+        assert_eq!(info.negotiated_watts(), Some(94.0));
+        assert_eq!(info.is_wireless, Some(false));
+        assert_eq!(info.pdos.len(), 4);
+        assert_eq!(info.pdos[1].max_voltage_mv, 9000);
+        assert_eq!(info.active_pdo().map(|pdo| pdo.max_watts()), Some(94.0));
+        assert_eq!(
+            info.raw,
+            vec![
+                ("AdapterID".to_string(), "0".to_string()),
+                ("FamilyCode".to_string(), "18446744073172697098".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn sparse_chargers_leave_fields_empty() {
+        let info = AdapterPdInfo::from_ioreg_dict(r#"{"Watts"=30,"Name"="a, b"}"#).unwrap();
+        assert_eq!(info.name.as_deref(), Some("a, b"));
+        assert_eq!(info.negotiated_watts(), None);
+        assert!(info.pdos.is_empty());
+        assert_eq!(info.active_pdo(), None);
+
+        assert_eq!(AdapterPdInfo::from_ioreg_dict("No"), None);
+    }
+}
@@ -4,7 +4,8 @@ use std::time::Duration;
 
 use color_eyre::eyre::Result;
 
-use crate::types::{BatteryTechnology, ChargeState};
+use crate::adapter::AdapterPdInfo;
+use crate::types::{BatteryChemistry, BatteryTechnology, ChargeState};
 
 /// Battery information snapshot.
 ///
@@ -23,6 +24,12 @@ pub struct BatteryInfo {
     /// Design capacity in watt-hours (original factory capacity).
     pub design_capacity_wh: f32,
 
+    /// Full charge capacity in mAh as reported by the fuel gauge, if available.
+    pub full_charge_capacity_mah: Option<u32>,
+
+    /// Design capacity in mAh, if available.
+    pub design_capacity_mah: Option<u32>,
+
     /// Current voltage in millivolts.
     pub voltage_mv: u32,
 
@@ -61,21 +68,44 @@ pub struct BatteryInfo {
     /// Battery technology/chemistry type.
     pub technology: BatteryTechnology,
 
+    /// Cell chemistry; from the fuel gauge on macOS, else derived from
+    /// `technology`.
+    pub chemistry: BatteryChemistry,
+
     /// Current energy remaining in watt-hours.
     pub energy_wh: f32,
 
     /// Instantaneous power rate in watts (positive = charging, negative = discharging).
     pub energy_rate_watts: f32,
 
+    /// Charge limit in percent when a limiter is active (e.g. macOS 80% limit,
+    /// AlDente, or a Linux `charge_control_end_threshold` below 100).
+    pub charge_limit_percent: Option<u8>,
+
     // === macOS-specific fields (None on other platforms) ===
     /// Charger wattage rating (e.g., 96W), macOS only.
     pub charger_watts: Option<u32>,
 
+    /// Power the system draws from the adapter in watts, macOS only.
+    pub adapter_input_watts: Option<f32>,
+
     /// Minimum state of charge today (0-100), macOS only.
     pub daily_min_soc: Option<f32>,
 
     /// Maximum state of charge today (0-100), macOS only.
     pub daily_max_soc: Option<f32>,
+
+    /// Internal resistance in milliohms from the `Impedance` key, macOS
+    /// only. Not every battery reports it.
+    pub impedance_mohm: Option<u32>,
+
+    /// Whether Optimized Battery Charging is holding the charge at 80% right
+    /// now, from the `OptimizedBatteryChargingEngaged` key, macOS only.
+    pub optimized_charging_engaged: Option<bool>,
+
+    /// USB-C PD negotiation with the connected charger, from the
+    /// `AdapterDetails` key, macOS only. `None` on battery power.
+    pub adapter_pd: Option<AdapterPdInfo>,
 }
 
 impl BatteryInfo {
@@ -0,0 +1,186 @@
+//! IOReport Energy Model channels and the power domains they add to.
+//!
+//! The mapping is plain name matching, kept apart from the macOS provider
+//! so it can be checked against channel listings from other machines on
+//! any platform (see `fixtures/channels`). It is an ordered list of
+//! [`ChannelRule`]s where the first match wins, so names carrying more than
+//! one domain's token ("GPU ANE") land where the more specific rule says.
+
+use std::borrow::Cow;
+
+/// Which power domain an Energy Model channel adds to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelDomain {
+    Cpu,
+    Gpu,
+    Ane,
+    /// Memory, fabric and other SoC blocks, counted towards package power.
+    Other,
+}
+
+/// How a rule matches a channel name, compared case-insensitively.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelMatch {
+    /// The name starts with the pattern.
+    Prefix(Cow<'static, str>),
+    /// The pattern appears anywhere in the name.
+    Contains(Cow<'static, str>),
+    /// A word of the name starts with the pattern, words being split at
+    /// anything but letters and digits: "ane" matches "ANE0" and "GPU ANE"
+    /// but not "PLANE".
+    WordPrefix(Cow<'static, str>),
+}
+
+impl ChannelMatch {
+    /// Whether `channel`, already lowercased, matches.
+    fn matches(&self, channel: &str) -> bool {
+        match self {
+            ChannelMatch::Prefix(pattern) => channel.starts_with(pattern.as_ref()),
+            ChannelMatch::Contains(pattern) => channel.contains(pattern.as_ref()),
+            ChannelMatch::WordPrefix(pattern) => channel
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .any(|word| word.starts_with(pattern.as_ref())),
+        }
+    }
+}
+
+/// One entry of a channel mapping: channels matching `pattern` add to
+/// `domain`, or to no domain when it is `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelRule {
+    pub pattern: ChannelMatch,
+    pub domain: Option<ChannelDomain>,
+}
+
+impl ChannelRule {
+    const fn new(pattern: ChannelMatch, domain: ChannelDomain) -> Self {
+        Self {
+            pattern,
+            domain: Some(domain),
+        }
+    }
+}
+
+const fn word_prefix(pattern: &'static str) -> ChannelMatch {
+    ChannelMatch::WordPrefix(Cow::Borrowed(pattern))
+}
+
+const fn prefix(pattern: &'static str) -> ChannelMatch {
+    ChannelMatch::Prefix(Cow::Borrowed(pattern))
+}
+
+const fn contains(pattern: &'static str) -> ChannelMatch {
+    ChannelMatch::Contains(Cow::Borrowed(pattern))
+}
+
+/// The built-in mapping, in order of precedence: the Neural Engine before
+/// the GPU, as its channels sometimes carry a GPU prefix; CPU cluster names
+/// before the generic tokens; SoC blocks last. Linux RAPL package domains
+/// count as CPU.
+pub const DEFAULT_CHANNEL_RULES: &[ChannelRule] = &[
+    ChannelRule::new(word_prefix("ane"), ChannelDomain::Ane),
+    ChannelRule::new(contains("neural"), ChannelDomain::Ane),
+    ChannelRule::new(prefix("pacc"), ChannelDomain::Cpu),
+    ChannelRule::new(prefix("package"), ChannelDomain::Cpu),
+    ChannelRule::new(contains("gpu"), ChannelDomain::Gpu),
+    ChannelRule::new(contains("cpu"), ChannelDomain::Cpu),
+    ChannelRule::new(contains("amcc"), ChannelDomain::Other),
+    ChannelRule::new(contains("dcs"), ChannelDomain::Other),
+    ChannelRule::new(contains("dram"), ChannelDomain::Other),
+    ChannelRule::new(contains("isp"), ChannelDomain::Other),
+    ChannelRule::new(contains("pmp"), ChannelDomain::Other),
+    ChannelRule::new(contains("nub"), ChannelDomain::Other),
+    ChannelRule::new(contains("soc"), ChannelDomain::Other),
+];
+
+/// The domain of the first rule in `rules` matching `channel`; `None` when
+/// none matches or the matching rule counts no domain.
+pub fn classify(channel: &str, rules: &[ChannelRule]) -> Option<ChannelDomain> {
+    let channel = channel.to_lowercase();
+    rules
+        .iter()
+        .find(|rule| rule.pattern.matches(&channel))
+        .and_then(|rule| rule.domain)
+}
+
+impl ChannelDomain {
+    /// The domain `channel` adds to under [`DEFAULT_CHANNEL_RULES`]; `None`
+    /// for channels no domain counts.
+    pub fn of(channel: &str) -> Option<Self> {
+        classify(channel, DEFAULT_CHANNEL_RULES)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChannelDomain::Cpu => "cpu",
+            ChannelDomain::Gpu => "gpu",
+            ChannelDomain::Ane => "ane",
+            ChannelDomain::Other => "other",
+        }
+    }
+}
+
+/// Joules in one `unit` of an energy channel; `None` for units that are
+/// not energy, whose channels are skipped.
+pub fn joules_per_unit(unit: &str) -> Option<f64> {
+    match unit {
+        "mJ" => Some(1e-3),
+        "uJ" => Some(1e-6),
+        "nJ" => Some(1e-9),
+        _ => None,
+    }
+}
+
+/// One channel as the system describes it, for channel listings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelListing {
+    /// IOReport group, e.g. "Energy Model".
+    pub group: String,
+    /// IOReport subgroup; empty when none.
+    pub subgroup: String,
+    pub name: String,
+    /// Unit label, e.g. "mJ".
+    pub unit: String,
+    /// Average power over the listing's sampling interval; `None` for
+    /// channels not reported in an energy unit.
+    pub watts: Option<f32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn ambiguous_names_map_to_the_specific_domain() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../fixtures/channels/ambiguous-names.txt");
+        let listing = std::fs::read_to_string(&path).unwrap();
+        let mut checked = 0;
+        for line in listing.lines() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, expected) = line.split_once('\t').unwrap();
+            let domain = ChannelDomain::of(name).map_or("none", ChannelDomain::label);
+            assert_eq!(domain, expected, "{}", name);
+            checked += 1;
+        }
+        assert!(checked > 0, "no names in {}", path.display());
+    }
+
+    #[test]
+    fn rules_earlier_in_the_list_win() {
+        let rules = [
+            ChannelRule {
+                pattern: ChannelMatch::Prefix("gpu".into()),
+                domain: None,
+            },
+            ChannelRule::new(contains("gpu"), ChannelDomain::Gpu),
+        ];
+        assert_eq!(classify("GPU0", &rules), None);
+        assert_eq!(classify("SOC_GPU", &rules), Some(ChannelDomain::Gpu));
+        assert_eq!(classify("ANE0", &rules), None);
+    }
+}
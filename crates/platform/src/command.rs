@@ -0,0 +1,399 @@
+//! Bounded runs of external commands.
+//!
+//! Tools like `pmset` or `ioreg` can hang when the service behind them is
+//! wedged, and a plain `Command::output` then blocks its thread forever.
+//! [`run`] waits at most [`CommandLimits::timeout`], kills the child when it
+//! overruns, optionally tries again, and keeps only the first
+//! [`CommandLimits::max_output`] bytes of stdout and stderr. Every run is
+//! timed into per-program [`CommandStats`] for the sampling health report.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How often a running child is checked for having exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Limits for one command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandLimits {
+    /// How long one attempt may run before the child is killed.
+    pub timeout: Duration,
+    /// Attempts made in all, counting the first. Only timeouts are retried.
+    pub attempts: u32,
+    /// Bytes kept from each of stdout and stderr; the rest is read and
+    /// dropped so the child never blocks on a full pipe.
+    pub max_output: usize,
+}
+
+impl CommandLimits {
+    pub const DEFAULT: Self = Self {
+        timeout: Duration::from_secs(5),
+        attempts: 1,
+        max_output: 1024 * 1024,
+    };
+
+    pub const fn with_timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    pub const fn with_attempts(self, attempts: u32) -> Self {
+        Self { attempts, ..self }
+    }
+
+    pub const fn with_max_output(self, max_output: usize) -> Self {
+        Self { max_output, ..self }
+    }
+}
+
+impl Default for CommandLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// What a command that ran to completion printed.
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub status: ExitStatus,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    /// Whether stdout or stderr was cut at [`CommandLimits::max_output`].
+    pub truncated: bool,
+    /// How long the last attempt took.
+    pub duration: Duration,
+}
+
+impl CommandOutput {
+    pub fn success(&self) -> bool {
+        self.status.success()
+    }
+
+    pub fn stdout_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.stdout).into_owned()
+    }
+
+    pub fn stderr_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.stderr).into_owned()
+    }
+}
+
+#[derive(Debug)]
+pub enum CommandError {
+    /// The command could not be started or waited on.
+    Io { program: String, source: io::Error },
+    /// Every attempt ran past the timeout and was killed.
+    TimedOut {
+        program: String,
+        timeout: Duration,
+        attempts: u32,
+    },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Io { program, source } => write!(f, "failed to run {program}: {source}"),
+            CommandError::TimedOut {
+                program,
+                timeout,
+                attempts,
+            } => write!(
+                f,
+                "{program} did not finish within {:.1}s ({attempts} attempt{})",
+                timeout.as_secs_f64(),
+                if *attempts == 1 { "" } else { "s" }
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CommandError::Io { source, .. } => Some(source),
+            CommandError::TimedOut { .. } => None,
+        }
+    }
+}
+
+/// Runs `command` within `limits`, capturing stdout and stderr.
+pub fn run(command: &mut Command, limits: CommandLimits) -> Result<CommandOutput, CommandError> {
+    run_inner(command, None, limits)
+}
+
+/// Like [`run`], writing `input` to the command's stdin first.
+pub fn run_with_input(
+    command: &mut Command,
+    input: &[u8],
+    limits: CommandLimits,
+) -> Result<CommandOutput, CommandError> {
+    run_inner(command, Some(input), limits)
+}
+
+fn run_inner(
+    command: &mut Command,
+    input: Option<&[u8]>,
+    limits: CommandLimits,
+) -> Result<CommandOutput, CommandError> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let attempts = limits.attempts.max(1);
+
+    for _ in 0..attempts {
+        let start = Instant::now();
+        let result = run_once(command, input, limits);
+        let outcome = match &result {
+            Ok(Some(output)) if output.success() => Outcome::Succeeded,
+            Ok(Some(_)) | Err(_) => Outcome::Failed,
+            Ok(None) => Outcome::TimedOut,
+        };
+        record(&program, start.elapsed(), outcome);
+
+        match result {
+            Ok(Some(mut output)) => {
+                output.duration = start.elapsed();
+                return Ok(output);
+            }
+            Ok(None) => continue,
+            Err(source) => return Err(CommandError::Io { program, source }),
+        }
+    }
+
+    Err(CommandError::TimedOut {
+        program,
+        timeout: limits.timeout,
+        attempts,
+    })
+}
+
+/// One attempt; `None` if it timed out and was killed.
+fn run_once(
+    command: &mut Command,
+    input: Option<&[u8]>,
+    limits: CommandLimits,
+) -> io::Result<Option<CommandOutput>> {
+    let stdin = if input.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    };
+    let mut child = command
+        .stdin(stdin)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .map(|pipe| capture(pipe, limits.max_output));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|pipe| capture(pipe, limits.max_output));
+    if let (Some(input), Some(mut pipe)) = (input, child.stdin.take()) {
+        // Written on its own thread so a child that stops reading cannot
+        // hold us past the deadline. One that exits without reading closes
+        // the pipe early; its exit status tells the rest.
+        let input = input.to_vec();
+        thread::spawn(move || {
+            let _ = pipe.write_all(&input);
+        });
+    }
+
+    let deadline = Instant::now() + limits.timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            // The readers are left behind: a grandchild may still hold the
+            // pipes open, and joining would block on it.
+            return Ok(None);
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let (stdout, stdout_truncated) = join(stdout);
+    let (stderr, stderr_truncated) = join(stderr);
+    Ok(Some(CommandOutput {
+        status,
+        stdout,
+        stderr,
+        truncated: stdout_truncated || stderr_truncated,
+        duration: Duration::ZERO,
+    }))
+}
+
+/// Reads `pipe` to the end on its own thread, keeping up to `max` bytes.
+fn capture(mut pipe: impl Read + Send + 'static, max: usize) -> JoinHandle<(Vec<u8>, bool)> {
+    thread::spawn(move || {
+        let mut kept = Vec::new();
+        let mut truncated = false;
+        let mut buf = [0_u8; 8192];
+        loop {
+            match pipe.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    let room = max.saturating_sub(kept.len());
+                    kept.extend_from_slice(&buf[..n.min(room)]);
+                    truncated |= n > room;
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+        (kept, truncated)
+    })
+}
+
+fn join(reader: Option<JoinHandle<(Vec<u8>, bool)>>) -> (Vec<u8>, bool) {
+    reader
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Succeeded,
+    Failed,
+    TimedOut,
+}
+
+/// Runs of one program since the process started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandStats {
+    pub program: String,
+    pub runs: u32,
+    /// Runs that could not start or exited unsuccessfully.
+    pub failures: u32,
+    pub timeouts: u32,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl CommandStats {
+    pub fn mean(&self) -> Duration {
+        if self.runs == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.runs
+        }
+    }
+}
+
+static STATS: Mutex<Vec<CommandStats>> = Mutex::new(Vec::new());
+
+fn record(program: &str, duration: Duration, outcome: Outcome) {
+    let mut stats = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    let index = match stats.iter().position(|s| s.program == program) {
+        Some(index) => index,
+        None => {
+            stats.push(CommandStats {
+                program: program.to_string(),
+                runs: 0,
+                failures: 0,
+                timeouts: 0,
+                total: Duration::ZERO,
+                max: Duration::ZERO,
+            });
+            stats.len() - 1
+        }
+    };
+    let entry = &mut stats[index];
+    entry.runs += 1;
+    entry.total += duration;
+    entry.max = entry.max.max(duration);
+    match outcome {
+        Outcome::Succeeded => {}
+        Outcome::Failed => entry.failures += 1,
+        Outcome::TimedOut => entry.timeouts += 1,
+    }
+}
+
+/// Stats for every program run so far, in the order they were first run.
+pub fn command_stats() -> Vec<CommandStats> {
+    STATS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn stats_for(program: &str) -> Option<CommandStats> {
+        command_stats().into_iter().find(|s| s.program == program)
+    }
+
+    #[test]
+    fn a_sleeping_child_is_killed_at_the_timeout() {
+        // A program name of its own keeps other tests' runs out of the stats.
+        let mut command = Command::new("/bin/sleep");
+        command.arg("10");
+        let limits = CommandLimits::DEFAULT
+            .with_timeout(Duration::from_millis(100))
+            .with_attempts(2);
+
+        let start = Instant::now();
+        let err = run(&mut command, limits).unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(matches!(err, CommandError::TimedOut { attempts: 2, .. }));
+
+        let stats = stats_for("/bin/sleep").unwrap();
+        assert_eq!(stats.runs, 2);
+        assert_eq!(stats.timeouts, 2);
+        assert!(stats.max >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn output_and_stderr_are_captured() {
+        let output = run(
+            Command::new("sh").args(["-c", "echo out; echo oops >&2; exit 3"]),
+            CommandLimits::DEFAULT,
+        )
+        .unwrap();
+        assert_eq!(output.stdout_lossy(), "out\n");
+        assert_eq!(output.stderr_lossy(), "oops\n");
+        assert_eq!(output.status.code(), Some(3));
+        assert!(!output.truncated);
+    }
+
+    #[test]
+    fn output_is_cut_at_the_limit() {
+        let output = run(
+            Command::new("sh").args(["-c", "head -c 100000 /dev/zero"]),
+            CommandLimits::DEFAULT.with_max_output(1000),
+        )
+        .unwrap();
+        assert!(output.success());
+        assert_eq!(output.stdout.len(), 1000);
+        assert!(output.truncated);
+    }
+
+    #[test]
+    fn input_is_written_to_stdin() {
+        let output =
+            run_with_input(&mut Command::new("cat"), b"hello", CommandLimits::DEFAULT).unwrap();
+        assert_eq!(output.stdout, b"hello");
+    }
+
+    #[test]
+    fn missing_programs_fail_to_start() {
+        let err = run(
+            &mut Command::new("/nonexistent/jolt-test-program"),
+            CommandLimits::DEFAULT,
+        )
+        .unwrap_err();
+        assert!(matches!(err, CommandError::Io { .. }));
+        assert_eq!(
+            stats_for("/nonexistent/jolt-test-program")
+                .unwrap()
+                .failures,
+            1
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! CPU cluster frequency from performance state residency.
+//!
+//! IOReport's "CPU Stats" group reports how long each cluster spent in each
+//! DVFS state but not what frequency a state runs at. Those come from the
+//! `voltage-states*-sram` tables of the `pmgr` device in the IORegistry,
+//! one entry per active state in the same order.
+
+/// States a cluster reports while not running; every other state is an
+/// active one with an entry in the frequency table.
+const INACTIVE_STATES: [&str; 3] = ["IDLE", "DOWN", "OFF"];
+
+/// Table entries above this are in hertz (M1 to M3); below it, in kilohertz
+/// (M4 and later).
+const HERTZ_THRESHOLD: u32 = 100_000_000;
+
+/// Frequencies in MHz from a `voltage-states*-sram` property: 8-byte entries
+/// of a little-endian `u32` frequency followed by a `u32` voltage. Zero
+/// entries, which pad some tables, are dropped.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn parse_voltage_states(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(8)
+        .filter_map(|entry| {
+            let freq = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+            match freq {
+                0 => None,
+                f if f > HERTZ_THRESHOLD => Some(f as f32 / 1_000_000.0),
+                f => Some(f as f32 / 1_000.0),
+            }
+        })
+        .collect()
+}
+
+/// Residency-weighted average frequency in MHz over the time the cluster
+/// was active, pairing the n-th active state with the n-th table entry.
+///
+/// A cluster that idled throughout reports 0. `None` when the table is
+/// empty or no time passed.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn average_frequency_mhz(states: &[(String, i64)], table_mhz: &[f32]) -> Option<f32> {
+    if table_mhz.is_empty() || states.iter().all(|(_, time)| *time <= 0) {
+        return None;
+    }
+
+    let active = states
+        .iter()
+        .filter(|(name, _)| !INACTIVE_STATES.contains(&name.as_str()))
+        .zip(table_mhz);
+    let (mut weighted, mut total) = (0.0_f64, 0_i64);
+    for ((_, time), mhz) in active {
+        let time = (*time).max(0);
+        weighted += time as f64 * f64::from(*mhz);
+        total += time;
+    }
+
+    if total == 0 {
+        Some(0.0)
+    } else {
+        Some((weighted / total as f64) as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn states(residencies: &[(&str, i64)]) -> Vec<(String, i64)> {
+        residencies
+            .iter()
+            .map(|(name, time)| (name.to_string(), *time))
+            .collect()
+    }
+
+    fn entry(freq: u32, voltage: u32) -> Vec<u8> {
+        [freq.to_le_bytes(), voltage.to_le_bytes()].concat()
+    }
+
+    #[test]
+    fn voltage_tables_in_hertz_and_kilohertz_are_read_as_mhz() {
+        let hertz = [entry(600_000_000, 550), entry(2_064_000_000, 950)].concat();
+        assert_eq!(parse_voltage_states(&hertz), vec![600.0, 2064.0]);
+
+        let kilohertz = [entry(912_000, 550), entry(0, 0), entry(4_512_000, 1000)].concat();
+        assert_eq!(parse_voltage_states(&kilohertz), vec![912.0, 4512.0]);
+    }
+
+    #[test]
+    fn frequency_is_weighted_by_active_residency() {
+        let table = [600.0, 1200.0, 2400.0];
+        let cluster = states(&[("IDLE", 500), ("V0P5", 100), ("V1P4", 0), ("V2P3", 100)]);
+        assert_eq!(average_frequency_mhz(&cluster, &table), Some(1500.0));
+
+        let idle = states(&[("IDLE", 500), ("DOWN", 10), ("V0P5", 0)]);
+        assert_eq!(average_frequency_mhz(&idle, &table), Some(0.0));
+
+        assert_eq!(average_frequency_mhz(&states(&[("V0P5", 0)]), &table), None);
+        assert_eq!(average_frequency_mhz(&cluster, &[]), None);
+    }
+}
@@ -0,0 +1,165 @@
+//! GPU performance state residency and workload type.
+
+/// Share of time the GPU spent in one performance state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuStateShare {
+    /// State name as reported, e.g. "OFF" or "P5".
+    pub name: String,
+    /// From 0 to 1.
+    pub fraction: f32,
+}
+
+/// How the GPU's time split across its performance states between two
+/// samples.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpuResidency {
+    /// Every state in the order the hardware reports them, idle first and
+    /// the highest performance state last. Fractions sum to 1.
+    pub states: Vec<GpuStateShare>,
+}
+
+impl GpuResidency {
+    /// Builds the shares from per-state residency deltas, given as
+    /// `(state, time)` in any consistent unit. `None` when no time passed.
+    pub fn from_residencies<I, S>(residencies: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = (S, i64)>,
+        S: Into<String>,
+    {
+        let residencies: Vec<(String, i64)> = residencies
+            .into_iter()
+            .map(|(name, time)| (name.into(), time.max(0)))
+            .collect();
+        let total: i64 = residencies.iter().map(|(_, time)| time).sum();
+        if total <= 0 {
+            return None;
+        }
+
+        let states = residencies
+            .into_iter()
+            .map(|(name, time)| GpuStateShare {
+                name,
+                fraction: (time as f64 / total as f64) as f32,
+            })
+            .collect();
+        Some(Self { states })
+    }
+
+    /// The state the GPU spent the most time in; the higher state on ties.
+    pub fn dominant(&self) -> Option<&GpuStateShare> {
+        self.states
+            .iter()
+            .max_by(|a, b| a.fraction.total_cmp(&b.fraction))
+    }
+
+    /// Share of time in the highest performance state, from 0 to 1.
+    pub fn top_state_fraction(&self) -> f32 {
+        self.states.last().map_or(0.0, |state| state.fraction)
+    }
+}
+
+/// GPU engine a "GPU Stats" utilization channel reports on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuEngine {
+    /// Compute kernels (Metal compute, ML).
+    Compute,
+    /// Vertex, tiling and fragment work for drawing.
+    Render,
+}
+
+impl GpuEngine {
+    /// The engine a channel or subgroup name refers to, compared
+    /// case-insensitively; `None` for anything else.
+    pub fn of(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.contains("compute") {
+            Some(GpuEngine::Compute)
+        } else if name.contains("render") {
+            Some(GpuEngine::Render)
+        } else {
+            None
+        }
+    }
+}
+
+/// How the GPU's busy time split between compute and render work between
+/// two samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpuWorkload {
+    /// From 0 to 1; sums to 1 with `render_fraction`.
+    pub compute_fraction: f32,
+    pub render_fraction: f32,
+}
+
+impl GpuWorkload {
+    /// Builds the split from busy time per engine, in any consistent unit.
+    /// `None` when neither engine was busy.
+    pub fn from_busy_times<I>(busy: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = (GpuEngine, i64)>,
+    {
+        let (mut compute, mut render) = (0_i64, 0_i64);
+        for (engine, time) in busy {
+            match engine {
+                GpuEngine::Compute => compute += time.max(0),
+                GpuEngine::Render => render += time.max(0),
+            }
+        }
+        let total = compute + render;
+        if total <= 0 {
+            return None;
+        }
+        let compute_fraction = (compute as f64 / total as f64) as f32;
+        Some(Self {
+            compute_fraction,
+            render_fraction: 1.0 - compute_fraction,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn residency_shares_sum_to_one() {
+        let residency =
+            GpuResidency::from_residencies([("OFF", 600), ("P1", 100), ("P2", -5), ("P3", 300)])
+                .unwrap();
+
+        let fractions: Vec<f32> = residency.states.iter().map(|s| s.fraction).collect();
+        assert_eq!(fractions, vec![0.6, 0.1, 0.0, 0.3]);
+        assert_eq!(residency.dominant().unwrap().name, "OFF");
+        assert_eq!(residency.top_state_fraction(), 0.3);
+
+        let tied = GpuResidency::from_residencies([("P1", 5), ("P2", 5)]).unwrap();
+        assert_eq!(tied.dominant().unwrap().name, "P2");
+
+        assert_eq!(GpuResidency::from_residencies([("OFF", 0)]), None);
+    }
+
+    #[test]
+    fn workload_splits_busy_time_between_engines() {
+        assert_eq!(
+            GpuEngine::of("GPU Compute Utilization"),
+            Some(GpuEngine::Compute)
+        );
+        assert_eq!(GpuEngine::of("RENDER0"), Some(GpuEngine::Render));
+        assert_eq!(GpuEngine::of("GPUPH"), None);
+
+        let workload = GpuWorkload::from_busy_times([
+            (GpuEngine::Render, 100),
+            (GpuEngine::Compute, 250),
+            (GpuEngine::Render, 50),
+            (GpuEngine::Compute, -10),
+        ])
+        .unwrap();
+        assert_eq!(workload.compute_fraction, 0.625);
+        assert_eq!(workload.render_fraction, 0.375);
+
+        assert_eq!(
+            GpuWorkload::from_busy_times([(GpuEngine::Compute, 0)]),
+            None
+        );
+    }
+}
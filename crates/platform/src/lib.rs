@@ -21,13 +21,33 @@
 //! println!("Charge: {}%", battery.info().charge_percent);
 //! ```
 
+mod adapter;
 mod battery;
+mod channels;
+pub mod command;
+mod cpu_freq;
+mod gpu;
+mod memory;
+mod overlap;
 mod power;
+mod power_mode;
 mod types;
 
+pub use adapter::{AdapterPdInfo, PowerDataObject};
 pub use battery::{BatteryInfo, BatteryProvider};
-pub use power::{PowerInfo, PowerProvider};
-pub use types::{BatteryTechnology, ChargeState, PowerMode};
+pub use channels::{
+    classify, joules_per_unit, ChannelDomain, ChannelListing, ChannelMatch, ChannelRule,
+    DEFAULT_CHANNEL_RULES,
+};
+pub use gpu::{GpuEngine, GpuResidency, GpuStateShare, GpuWorkload};
+pub use memory::{bandwidth_gbps, MemoryInfo, MemoryProvider};
+pub use overlap::{OverlapDetector, OVERLAP_SAMPLES, OVERLAP_TOLERANCE};
+pub use power::{
+    ChannelPower, NotificationHandle, PowerInfo, PowerProvider, PowerReadings, PowerStats,
+};
+pub use types::{
+    BatteryChemistry, BatteryTechnology, ChargeState, MemoryPressure, PowerMode, ThermalPressure,
+};
 
 #[cfg(target_os = "macos")]
 pub mod macos;
@@ -12,7 +12,7 @@ use starship_battery::units::time::second;
 use starship_battery::Manager;
 
 use crate::battery::{BatteryInfo, BatteryProvider};
-use crate::types::{BatteryTechnology, ChargeState};
+use crate::types::{BatteryChemistry, BatteryTechnology, ChargeState};
 
 const POWER_SUPPLY_PATH: &str = "/sys/class/power_supply";
 
@@ -66,9 +66,7 @@ impl LinuxBattery {
         self.info.voltage_mv = battery.voltage().get::<millivolt>() as u32;
         self.info.health_percent = battery.state_of_health().get::<percent>();
         self.info.cycle_count = battery.cycle_count();
-        self.info.temperature_c = battery
-            .temperature()
-            .map(|t| t.get::<degree_celsius>() as f32);
+        self.info.temperature_c = battery.temperature().map(|t| t.get::<degree_celsius>());
         self.info.time_to_full = battery
             .time_to_full()
             .map(|t| Duration::from_secs(t.get::<second>() as u64));
@@ -83,6 +81,7 @@ impl LinuxBattery {
         self.info.model = battery.model().map(|s| s.to_string());
         self.info.serial_number = battery.serial_number().map(|s| s.to_string());
         self.info.technology = BatteryTechnology::from(battery.technology());
+        self.info.chemistry = BatteryChemistry::from(self.info.technology);
         self.info.energy_wh = battery.energy().get::<watt_hour>();
         self.info.energy_rate_watts = battery.energy_rate().get::<watt>();
 
@@ -95,6 +94,11 @@ impl LinuxBattery {
         if let Some(path) = self.battery_path.clone() {
             self.detect_not_charging_state(&path);
             self.read_amperage(&path);
+            self.info.charge_limit_percent = read_charge_threshold(&path);
+            self.info.full_charge_capacity_mah = read_charge_mah(&path, "charge_full");
+            self.info.design_capacity_mah = read_charge_mah(&path, "charge_full_design");
+        } else {
+            self.info.charge_limit_percent = None;
         }
     }
 
@@ -112,9 +116,7 @@ impl LinuxBattery {
         let status_path = battery_path.join("status");
         if let Ok(status) = fs::read_to_string(status_path) {
             let status = status.trim();
-            if status.eq_ignore_ascii_case("Not charging") {
-                self.info.state = ChargeState::NotCharging;
-            } else if self.info.external_connected {
+            if status.eq_ignore_ascii_case("Not charging") || self.info.external_connected {
                 self.info.state = ChargeState::NotCharging;
             }
         }
@@ -135,6 +137,24 @@ impl LinuxBattery {
     }
 }
 
+/// Reads the kernel's charge stop threshold, ignoring the no-limit value of 100.
+fn read_charge_threshold(battery_path: &Path) -> Option<u8> {
+    let content = fs::read_to_string(battery_path.join("charge_control_end_threshold")).ok()?;
+    content
+        .trim()
+        .parse::<u8>()
+        .ok()
+        .filter(|limit| (1..100).contains(limit))
+}
+
+/// Reads a sysfs charge attribute (µAh) as mAh. Batteries that only report
+/// energy attributes have none.
+fn read_charge_mah(battery_path: &Path, attribute: &str) -> Option<u32> {
+    let content = fs::read_to_string(battery_path.join(attribute)).ok()?;
+    let microamp_hours = content.trim().parse::<u64>().ok()?;
+    u32::try_from(microamp_hours / 1000).ok()
+}
+
 fn find_battery_path() -> Option<PathBuf> {
     let power_supply = Path::new(POWER_SUPPLY_PATH);
     if !power_supply.exists() {
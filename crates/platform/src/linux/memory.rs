@@ -0,0 +1,72 @@
+use std::fs;
+
+use color_eyre::eyre::Result;
+
+use crate::memory::{MemoryInfo, MemoryProvider};
+use crate::types::MemoryPressure;
+
+const MEMINFO_PATH: &str = "/proc/meminfo";
+
+/// Memory usage from `/proc/meminfo`.
+///
+/// The kernel has no pressure level comparable to macOS, so it is
+/// estimated from the share of memory in use. Memory bandwidth is not
+/// exposed without perf counters and stays `None`.
+pub struct LinuxMemory {
+    info: MemoryInfo,
+}
+
+impl MemoryProvider for LinuxMemory {
+    fn new() -> Result<Self> {
+        let mut provider = Self {
+            info: MemoryInfo::default(),
+        };
+        provider.refresh()?;
+        Ok(provider)
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        let used = fs::read_to_string(MEMINFO_PATH)
+            .ok()
+            .and_then(|meminfo| used_fraction(&meminfo));
+        self.info.used_fraction = used;
+        self.info.pressure =
+            used.map_or(MemoryPressure::Unknown, MemoryPressure::from_used_fraction);
+        Ok(())
+    }
+
+    fn info(&self) -> &MemoryInfo {
+        &self.info
+    }
+}
+
+/// Share of memory not available to new allocations, from `/proc/meminfo`
+/// contents.
+fn used_fraction(meminfo: &str) -> Option<f32> {
+    let field = |name: &str| -> Option<u64> {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    };
+    let total = field("MemTotal")?;
+    let available = field("MemAvailable")?;
+    (total > 0).then(|| 1.0 - available.min(total) as f32 / total as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn used_fraction_reads_available_memory() {
+        let meminfo = "MemTotal:       16000000 kB\n\
+                       MemFree:         1000000 kB\n\
+                       MemAvailable:    4000000 kB\n";
+        assert_eq!(used_fraction(meminfo), Some(0.75));
+        assert_eq!(used_fraction("MemTotal: 16000000 kB\n"), None);
+    }
+}
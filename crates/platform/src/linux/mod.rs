@@ -1,5 +1,7 @@
 mod battery;
+mod memory;
 mod power;
 
 pub use battery::LinuxBattery;
+pub use memory::LinuxMemory;
 pub use power::LinuxPower;
@@ -5,7 +5,7 @@ use std::time::{Duration, Instant};
 
 use color_eyre::eyre::Result;
 
-use crate::power::{PowerInfo, PowerProvider};
+use crate::power::{ChannelPower, NotificationHandle, PowerInfo, PowerProvider, PowerReadings};
 use crate::types::PowerMode;
 
 const RAPL_PATH: &str = "/sys/class/powercap/intel-rapl";
@@ -23,7 +23,7 @@ struct PowerSample {
 #[derive(Debug)]
 struct RaplDomain {
     path: PathBuf,
-    _name: String,
+    name: String,
     last_energy_uj: u64,
     last_time: Instant,
 }
@@ -36,6 +36,7 @@ pub struct LinuxPower {
     cpu_power: f32,
     gpu_power: f32,
     system_power: f32,
+    channels: Vec<ChannelPower>,
 }
 
 impl PowerProvider for LinuxPower {
@@ -51,6 +52,7 @@ impl PowerProvider for LinuxPower {
             cpu_power: 0.0,
             gpu_power: 0.0,
             system_power: 0.0,
+            channels: Vec::new(),
         };
 
         std::thread::sleep(Duration::from_millis(100));
@@ -63,6 +65,11 @@ impl PowerProvider for LinuxPower {
         self.refresh_rapl_power();
         self.refresh_gpu_power();
         self.system_power = self.cpu_power + self.gpu_power;
+        self.info.raw_readings = Some(PowerReadings {
+            cpu_watts: self.cpu_power,
+            gpu_watts: self.gpu_power,
+            total_watts: self.system_power,
+        });
         self.record_sample();
         self.update_info();
         Ok(())
@@ -72,6 +79,14 @@ impl PowerProvider for LinuxPower {
         &self.info
     }
 
+    /// The power mode is always unknown on Linux, so it never changes.
+    fn subscribe_notification<F>(_on_change: F) -> NotificationHandle
+    where
+        F: Fn(PowerMode) + Send + 'static,
+    {
+        NotificationHandle::inactive()
+    }
+
     fn is_supported() -> bool {
         Path::new(RAPL_PATH).exists()
     }
@@ -84,6 +99,7 @@ impl LinuxPower {
         self.info.system_power_watts = self.smoothed_value(|s| s.system_power);
         self.info.is_warmed_up = self.samples.len() >= MIN_WARMUP_SAMPLES;
         self.info.power_mode = PowerMode::Unknown;
+        self.info.channels = self.channels.clone();
     }
 
     fn record_sample(&mut self) {
@@ -112,6 +128,7 @@ impl LinuxPower {
 
     fn refresh_rapl_power(&mut self) {
         let mut total_cpu_power = 0.0f32;
+        let mut channels = Vec::new();
         let now = Instant::now();
 
         for domain in &mut self.rapl_domains {
@@ -130,6 +147,12 @@ impl LinuxPower {
 
                         let watts = energy_delta as f32 / elapsed_us as f32;
                         total_cpu_power += watts;
+                        self.info.cpu_energy_joules += energy_delta as f64 / 1_000_000.0;
+                        channels.push(ChannelPower {
+                            name: domain.name.clone(),
+                            watts,
+                            aggregate: false,
+                        });
                     }
 
                     domain.last_energy_uj = energy_uj;
@@ -139,6 +162,7 @@ impl LinuxPower {
         }
 
         self.cpu_power = total_cpu_power;
+        self.channels = channels;
     }
 
     fn refresh_gpu_power(&mut self) {
@@ -185,7 +209,7 @@ fn discover_rapl_domains() -> Vec<RaplDomain> {
 
                     domains.push(RaplDomain {
                         path,
-                        _name: name,
+                        name,
                         last_energy_uj,
                         last_time: Instant::now(),
                     });
@@ -10,12 +10,17 @@ use starship_battery::units::thermodynamic_temperature::degree_celsius;
 use starship_battery::units::time::second;
 use starship_battery::Manager;
 
+use super::power::Smc;
+use crate::adapter::AdapterPdInfo;
 use crate::battery::{BatteryInfo, BatteryProvider};
-use crate::types::{BatteryTechnology, ChargeState};
+use crate::command::{self, CommandLimits};
+use crate::types::{BatteryChemistry, BatteryTechnology, ChargeState};
 
 pub struct MacOSBattery {
     info: BatteryInfo,
     manager: Manager,
+    smc: Option<Smc>,
+    charge_inhibited: bool,
 }
 
 impl BatteryProvider for MacOSBattery {
@@ -24,6 +29,8 @@ impl BatteryProvider for MacOSBattery {
         let mut provider = Self {
             info: BatteryInfo::default(),
             manager,
+            smc: Smc::new(),
+            charge_inhibited: false,
         };
         provider.refresh()?;
         Ok(provider)
@@ -32,6 +39,7 @@ impl BatteryProvider for MacOSBattery {
     fn refresh(&mut self) -> Result<()> {
         self.refresh_from_battery_crate()?;
         self.refresh_ioreg_extras();
+        self.refresh_charge_limit();
         Ok(())
     }
 
@@ -70,6 +78,7 @@ impl MacOSBattery {
         self.info.model = battery.model().map(|s| s.to_string());
         self.info.serial_number = battery.serial_number().map(|s| s.to_string());
         self.info.technology = BatteryTechnology::from(battery.technology());
+        self.info.chemistry = BatteryChemistry::from(self.info.technology);
         self.info.energy_wh = battery.energy().get::<watt_hour>();
         self.info.energy_rate_watts = battery.energy_rate().get::<watt>();
 
@@ -77,17 +86,35 @@ impl MacOSBattery {
     }
 
     fn refresh_ioreg_extras(&mut self) {
-        if let Ok(output) = Command::new("ioreg")
-            .args(["-rn", "AppleSmartBattery"])
-            .output()
-        {
+        if let Ok(output) = command::run(
+            Command::new("ioreg").args(["-rn", "AppleSmartBattery"]),
+            CommandLimits::DEFAULT,
+        ) {
             let stdout = String::from_utf8_lossy(&output.stdout);
             self.parse_ioreg_output(&stdout);
         }
     }
 
+    /// Prefers the configured limit from the SMC. If the charger reports that
+    /// charging is inhibited while plugged in but no limit key is readable
+    /// (e.g. a third-party limiter using other keys), the current level is
+    /// where charging is being held, so report that.
+    fn refresh_charge_limit(&mut self) {
+        let configured = self.smc.as_mut().and_then(|smc| smc.read_charge_limit());
+
+        self.info.charge_limit_percent = configured.or_else(|| {
+            (self.charge_inhibited && self.info.external_connected)
+                .then(|| self.info.charge_percent.round() as u8)
+        });
+    }
+
     fn parse_ioreg_output(&mut self, output: &str) {
         let mut is_charging = false;
+        self.charge_inhibited = false;
+        self.info.impedance_mohm = None;
+        self.info.adapter_input_watts = None;
+        self.info.optimized_charging_engaged = None;
+        self.info.adapter_pd = None;
 
         for line in output.lines() {
             let line = line.trim();
@@ -96,10 +123,52 @@ impl MacOSBattery {
                 if let Some(val) = extract_number(line) {
                     self.info.amperage_ma = val as i32;
                 }
+            } else if line.starts_with("\"AppleRawMaxCapacity\"") {
+                self.info.full_charge_capacity_mah =
+                    extract_number(line).and_then(|v| u32::try_from(v).ok());
+            } else if line.starts_with("\"Impedance\"") {
+                self.info.impedance_mohm = extract_number(line)
+                    .and_then(|v| u32::try_from(v).ok())
+                    .filter(|&v| v > 0);
+            } else if line.starts_with("\"OptimizedBatteryChargingEngaged\"") {
+                self.info.optimized_charging_engaged = extract_bool(line);
+            } else if line.starts_with("\"AdapterDetails\"") {
+                self.info.adapter_pd = line
+                    .split_once('=')
+                    .and_then(|(_, dict)| AdapterPdInfo::from_ioreg_dict(dict));
+            } else if line.starts_with("\"DesignCapacity\"") {
+                self.info.design_capacity_mah =
+                    extract_number(line).and_then(|v| u32::try_from(v).ok());
             } else if line.contains("\"ExternalConnected\"") {
                 self.info.external_connected = line.contains("Yes");
             } else if line.contains("\"IsCharging\"") {
                 is_charging = line.contains("Yes");
+            } else if line.starts_with("\"DeviceChemistry\"") {
+                if let Some(value) = line.split('=').nth(1) {
+                    let chemistry = BatteryChemistry::from_ioreg(value);
+                    if chemistry != BatteryChemistry::Unknown {
+                        self.info.chemistry = chemistry;
+                    }
+                }
+            } else if line.contains("\"ChargerData\"") {
+                if let Some(pos) = line.find("\"ChargerInhibitReason\"=") {
+                    let after = &line[pos + 23..];
+                    let end = after
+                        .find(|c: char| !c.is_ascii_digit())
+                        .unwrap_or(after.len());
+                    self.charge_inhibited = after[..end].parse::<u32>().is_ok_and(|r| r != 0);
+                }
+            } else if line.contains("\"PowerTelemetryData\"") {
+                if let Some(pos) = line.find("\"SystemPowerIn\"=") {
+                    let after = &line[pos + 16..];
+                    let end = after
+                        .find(|c: char| !c.is_ascii_digit())
+                        .unwrap_or(after.len());
+                    self.info.adapter_input_watts = after[..end]
+                        .parse::<u32>()
+                        .ok()
+                        .map(|mw| mw as f32 / 1000.0);
+                }
             } else if line.contains("\"BatteryData\"") {
                 if let Some(pos) = line.find("\"DailyMinSoc\"=") {
                     let after = &line[pos + 14..];
@@ -133,6 +202,8 @@ impl MacOSBattery {
         } else {
             self.info.state = ChargeState::Discharging;
             self.info.charger_watts = None;
+            self.info.adapter_input_watts = None;
+            self.info.adapter_pd = None;
         }
     }
 }
@@ -141,6 +212,15 @@ fn extract_number(line: &str) -> Option<i64> {
     line.split('=').nth(1)?.trim().parse::<i64>().ok()
 }
 
+/// Reads an ioreg boolean, which is printed as `Yes`/`No` or as a number.
+fn extract_bool(line: &str) -> Option<bool> {
+    match line.split('=').nth(1)?.trim() {
+        "Yes" => Some(true),
+        "No" => Some(false),
+        value => value.parse::<i64>().ok().map(|v| v != 0),
+    }
+}
+
 fn parse_charger_watts(output: &str) -> Option<u32> {
     for line in output.lines() {
         if line.contains("\"AdapterDetails\"") || line.contains("\"AppleRawAdapterDetails\"") {
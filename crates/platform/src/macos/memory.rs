@@ -0,0 +1,138 @@
+use std::ffi::{c_char, c_int, c_void};
+use std::mem::size_of;
+use std::ptr::null_mut;
+use std::time::Instant;
+
+use color_eyre::eyre::Result;
+use core_foundation_sys::base::CFRelease;
+use core_foundation_sys::dictionary::CFDictionaryRef;
+
+use super::power::{sample_delta, IOReportSubscription};
+use crate::memory::{bandwidth_gbps, MemoryInfo, MemoryProvider};
+use crate::types::MemoryPressure;
+
+/// IOReport group with the memory controller byte counters.
+const AMC_STATS_GROUP: &str = "AMC Stats";
+const PRESSURE_LEVEL_SYSCTL: &[u8] = b"kern.memorystatus_vm_pressure_level\0";
+const MEMSIZE_SYSCTL: &[u8] = b"hw.memsize\0";
+const HOST_VM_INFO64: c_int = 4;
+const KERN_SUCCESS: c_int = 0;
+
+extern "C" {
+    fn mach_host_self() -> u32;
+    fn host_statistics64(host: u32, flavor: c_int, info: *mut c_int, count: *mut u32) -> c_int;
+}
+
+/// Memory pressure from the kernel's memorystatus level, usage from
+/// `host_statistics64`, and bandwidth from the IOReport "AMC Stats" counters.
+///
+/// The AMC counters need no entitlement on the Apple Silicon machines seen
+/// so far, but the group is missing on Intel Macs and in some VMs; bandwidth
+/// then stays `None`.
+pub struct MacOSMemory {
+    info: MemoryInfo,
+    amc: Option<IOReportSubscription>,
+    last_sample: Option<(CFDictionaryRef, Instant)>,
+}
+
+impl MemoryProvider for MacOSMemory {
+    fn new() -> Result<Self> {
+        let mut provider = Self {
+            info: MemoryInfo::default(),
+            amc: IOReportSubscription::new(AMC_STATS_GROUP),
+            last_sample: None,
+        };
+        provider.refresh()?;
+        Ok(provider)
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        self.info.used_fraction = read_used_fraction();
+        self.info.pressure = match sysctl::<c_int>(PRESSURE_LEVEL_SYSCTL) {
+            Some(level) => MemoryPressure::from_level(level),
+            None => self
+                .info
+                .used_fraction
+                .map_or(MemoryPressure::Unknown, MemoryPressure::from_used_fraction),
+        };
+        self.refresh_bandwidth();
+        Ok(())
+    }
+
+    fn info(&self) -> &MemoryInfo {
+        &self.info
+    }
+}
+
+impl MacOSMemory {
+    fn refresh_bandwidth(&mut self) {
+        let Some(current) = self.amc.as_ref().and_then(|amc| amc.sample()) else {
+            self.info.bandwidth_gbps = None;
+            return;
+        };
+        let now = Instant::now();
+
+        if let Some((prev, taken_at)) = self.last_sample.replace((current, now)) {
+            self.info.bandwidth_gbps = sample_delta(prev, current).and_then(|delta| {
+                let channels: Vec<_> = delta.filter(|ch| ch.group == AMC_STATS_GROUP).collect();
+                bandwidth_gbps(
+                    channels
+                        .iter()
+                        .map(|ch| (ch.channel.as_str(), ch.unit.as_str(), ch.value)),
+                    now - taken_at,
+                )
+            });
+            unsafe { CFRelease(prev as _) };
+        }
+    }
+}
+
+impl Drop for MacOSMemory {
+    fn drop(&mut self) {
+        if let Some((sample, _)) = self.last_sample {
+            unsafe { CFRelease(sample as _) };
+        }
+    }
+}
+
+/// App, wired and compressed memory over physical memory, the same split
+/// Activity Monitor reports as "Memory Used".
+fn read_used_fraction() -> Option<f32> {
+    let total = sysctl::<u64>(MEMSIZE_SYSCTL)?;
+    let page_size = u64::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).ok()?;
+
+    let mut stats: libc::vm_statistics64 = unsafe { std::mem::zeroed() };
+    let mut count = (size_of::<libc::vm_statistics64>() / size_of::<c_int>()) as u32;
+    let status = unsafe {
+        host_statistics64(
+            mach_host_self(),
+            HOST_VM_INFO64,
+            &mut stats as *mut _ as *mut c_int,
+            &mut count,
+        )
+    };
+    if status != KERN_SUCCESS || total == 0 {
+        return None;
+    }
+
+    let app_pages = (stats.internal_page_count as u64).saturating_sub(stats.purgeable_count as u64);
+    let used_pages = app_pages + stats.wire_count as u64 + stats.compressor_page_count as u64;
+    Some(((used_pages * page_size) as f64 / total as f64).min(1.0) as f32)
+}
+
+/// Reads a fixed-size sysctl value; `None` when the name is unknown or the
+/// size does not match.
+fn sysctl<T: Default>(name: &[u8]) -> Option<T> {
+    let mut value = T::default();
+    let mut len = size_of::<T>();
+    let status = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr() as *const c_char,
+            &mut value as *mut T as *mut c_void,
+            &mut len,
+            null_mut(),
+            0,
+        )
+    };
+    (status == 0 && len == size_of::<T>()).then_some(value)
+}
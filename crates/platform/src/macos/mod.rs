@@ -1,5 +1,9 @@
 mod battery;
+mod memory;
 mod power;
 
 pub use battery::MacOSBattery;
-pub use power::MacOSPower;
+pub use memory::MacOSMemory;
+pub use power::{
+    list_channel_names, list_energy_model_channels, ChannelData, IOReportIterator, MacOSPower,
+};
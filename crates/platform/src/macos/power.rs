@@ -1,30 +1,90 @@
-use std::collections::{HashMap, VecDeque};
-use std::ffi::c_void;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::ffi::{c_char, c_int, c_void};
 use std::mem::size_of;
 use std::process::Command;
 use std::ptr::null;
+use std::sync::atomic::Ordering;
 use std::time::{Duration, Instant};
 
-use color_eyre::eyre::Result;
-use core_foundation_sys::base::{kCFAllocatorDefault, kCFAllocatorNull, CFRelease, CFTypeRef};
+use color_eyre::eyre::{bail, eyre, Result};
+use core_foundation::base::TCFType;
+use core_foundation::number::CFNumber;
+use core_foundation_sys::base::{
+    kCFAllocatorDefault, kCFAllocatorNull, CFGetTypeID, CFIndex, CFRelease, CFTypeRef,
+};
+use core_foundation_sys::data::{CFDataGetBytePtr, CFDataGetLength, CFDataRef};
 use core_foundation_sys::dictionary::{
-    CFDictionaryCreateMutableCopy, CFDictionaryGetCount, CFDictionaryGetValue, CFDictionaryRef,
-    CFMutableDictionaryRef,
+    CFDictionaryCreateMutableCopy, CFDictionaryGetCount, CFDictionaryGetKeysAndValues,
+    CFDictionaryGetTypeID, CFDictionaryGetValue, CFDictionaryRef, CFMutableDictionaryRef,
 };
+use core_foundation_sys::number::{CFNumberGetTypeID, CFNumberRef};
 use core_foundation_sys::string::{
-    kCFStringEncodingUTF8, CFStringCreateWithBytesNoCopy, CFStringGetCString, CFStringRef,
+    kCFStringEncodingUTF8, CFStringCreateWithBytesNoCopy, CFStringGetCString, CFStringGetLength,
+    CFStringGetTypeID, CFStringRef,
 };
 
-use crate::power::{PowerInfo, PowerProvider};
-use crate::types::PowerMode;
+use crate::channels::{joules_per_unit, ChannelDomain, ChannelListing};
+use crate::command::{self, CommandLimits};
+use crate::cpu_freq::{average_frequency_mhz, parse_voltage_states};
+use crate::gpu::{GpuEngine, GpuResidency, GpuWorkload};
+use crate::overlap::OverlapDetector;
+use crate::power::{ChannelPower, NotificationHandle, PowerInfo, PowerProvider, PowerReadings};
+use crate::power_mode;
+use crate::types::{PowerMode, ThermalPressure};
+
+/// `pmset -g` answers in milliseconds; a run this long is stuck.
+const PMSET_LIMITS: CommandLimits = CommandLimits::DEFAULT
+    .with_timeout(Duration::from_secs(2))
+    .with_attempts(2);
 
 const SMOOTHING_SAMPLE_COUNT: usize = 5;
 const MIN_WARMUP_SAMPLES: usize = 3;
+const ENERGY_MODEL_GROUP: &str = "Energy Model";
+const GPU_STATS_GROUP: &str = "GPU Stats";
+const GPU_PERF_STATES_SUBGROUP: &str = "GPU Performance States";
+/// Channel in [`GPU_PERF_STATES_SUBGROUP`] with the hardware performance
+/// state residency; its siblings hold software-requested states.
+const GPU_PERF_STATES_CHANNEL: &str = "GPUPH";
+const CPU_STATS_GROUP: &str = "CPU Stats";
+/// Subgroup of [`CPU_STATS_GROUP`] with one state channel per cluster; the
+/// per-core subgroup beside it is many times larger.
+const CPU_CLUSTER_STATES_SUBGROUP: &str = "CPU Complex Performance States";
+/// `pmgr` properties holding the E- and P-cluster DVFS frequency tables.
+const E_CLUSTER_VOLTAGE_STATES: &str = "voltage-states1-sram";
+const P_CLUSTER_VOLTAGE_STATES: &str = "voltage-states5-sram";
+/// `kIOReportFormatState`: the channel holds per-state residencies.
+const IOREPORT_FORMAT_STATE: u8 = 2;
+
+/// Darwin notification posted when Low Power Mode is switched on or off.
+const LOW_POWER_MODE_NOTIFICATION: &[u8] = b"com.apple.system.lowpowermode\0";
+/// Darwin notification whose state is the current thermal pressure level.
+const THERMAL_PRESSURE_NOTIFICATION: &[u8] = b"com.apple.system.thermalpressurelevel\0";
+/// High Power Mode posts no notification, so the mode is also re-read this
+/// often.
+const MODE_RECHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Longest a watcher waits before checking whether it was stopped.
+const WATCH_STOP_INTERVAL: Duration = Duration::from_millis(250);
+const NOTIFY_STATUS_OK: u32 = 0;
+/// Upper bound on the UTF-8 bytes per UTF-16 code unit of a CFString.
+const UTF8_MAX_BYTES_PER_CHAR: usize = 4;
+
+extern "C" {
+    fn notify_register_file_descriptor(
+        name: *const c_char,
+        notify_fd: *mut c_int,
+        flags: c_int,
+        out_token: *mut c_int,
+    ) -> u32;
+    fn notify_cancel(token: c_int) -> u32;
+    fn notify_register_check(name: *const c_char, out_token: *mut c_int) -> u32;
+    fn notify_get_state(token: c_int, state: *mut u64) -> u32;
+}
 
 #[derive(Debug, Clone, Copy)]
 struct PowerSample {
     cpu_power: f32,
     gpu_power: f32,
+    ane_power: f32,
     system_power: f32,
 }
 
@@ -61,10 +121,17 @@ extern "C" {
         c: *const c_void,
     ) -> CFDictionaryRef;
 
+    fn IOReportMergeChannels(a: CFMutableDictionaryRef, b: CFDictionaryRef, c: *const c_void);
+
     fn IOReportChannelGetGroup(a: CFDictionaryRef) -> CFStringRef;
+    fn IOReportChannelGetSubGroup(a: CFDictionaryRef) -> CFStringRef;
     fn IOReportChannelGetChannelName(a: CFDictionaryRef) -> CFStringRef;
     fn IOReportChannelGetUnitLabel(a: CFDictionaryRef) -> CFStringRef;
     fn IOReportSimpleGetIntegerValue(a: CFDictionaryRef, b: i32) -> i64;
+    fn IOReportChannelGetFormat(a: CFDictionaryRef) -> u8;
+    fn IOReportStateGetCount(a: CFDictionaryRef) -> i32;
+    fn IOReportStateGetNameForIndex(a: CFDictionaryRef, b: i32) -> CFStringRef;
+    fn IOReportStateGetResidency(a: CFDictionaryRef, b: i32) -> i64;
 }
 
 extern "C" {
@@ -82,6 +149,12 @@ extern "C" {
     ) -> i32;
     fn IOIteratorNext(iterator: u32) -> u32;
     fn IORegistryEntryGetName(entry: u32, name: *mut i8) -> i32;
+    fn IORegistryEntryCreateCFProperties(
+        entry: u32,
+        properties: *mut CFMutableDictionaryRef,
+        allocator: *const c_void,
+        options: u32,
+    ) -> i32;
     fn IOServiceOpen(device: u32, a: u32, b: u32, c: *mut u32) -> i32;
     fn IOServiceClose(conn: u32) -> i32;
     fn IOObjectRelease(obj: u32) -> u32;
@@ -94,6 +167,12 @@ extern "C" {
         osize: *mut usize,
     ) -> i32;
     fn mach_task_self() -> u32;
+    /// Power management settings keyed by power source name ("AC Power",
+    /// "Battery Power", ...).
+    fn IOPMCopyActivePMPreferences() -> CFDictionaryRef;
+    fn IOPSCopyPowerSourcesInfo() -> CFTypeRef;
+    /// The source in use, as a key of `IOPMCopyActivePMPreferences`.
+    fn IOPSGetProvidingPowerSourceType(snapshot: CFTypeRef) -> CFStringRef;
 }
 
 #[repr(C)]
@@ -138,13 +217,13 @@ struct KeyData {
     bytes: [u8; 32],
 }
 
-struct Smc {
+pub(super) struct Smc {
     conn: u32,
     keys: HashMap<u32, KeyInfo>,
 }
 
 impl Smc {
-    fn new() -> Option<Self> {
+    pub(super) fn new() -> Option<Self> {
         let service_name = std::ffi::CString::new("AppleSMC").ok()?;
 
         unsafe {
@@ -242,6 +321,24 @@ impl Smc {
         Some(oval.bytes[0..key_info.data_size as usize].to_vec())
     }
 
+    /// Reads the active charge limit in percent, if one is set.
+    ///
+    /// Intel Macs (and limiter tools such as AlDente) use `BCLM`, the maximum
+    /// charge level. Apple Silicon exposes the built-in 80% limit as the `CHWA`
+    /// flag instead.
+    pub(super) fn read_charge_limit(&mut self) -> Option<u8> {
+        if let Some(&[level, ..]) = self.read_val("BCLM").as_deref() {
+            if (1..100).contains(&level) {
+                return Some(level);
+            }
+        }
+
+        match self.read_val("CHWA").as_deref() {
+            Some(&[1, ..]) => Some(80),
+            _ => None,
+        }
+    }
+
     fn read_system_power(&mut self) -> Option<f32> {
         let data = self.read_val("PSTR")?;
         if data.len() >= 4 {
@@ -278,11 +375,19 @@ fn from_cfstr(val: CFStringRef) -> String {
         return String::new();
     }
     unsafe {
-        let mut buf = [0i8; 128];
-        if CFStringGetCString(val, buf.as_mut_ptr(), 128, kCFStringEncodingUTF8) == 0 {
+        // Sized to the string, so long channel names are not cut off
+        let len = CFStringGetLength(val).max(0) as usize;
+        let mut buf = vec![0u8; len * UTF8_MAX_BYTES_PER_CHAR + 1];
+        if CFStringGetCString(
+            val,
+            buf.as_mut_ptr() as *mut c_char,
+            buf.len() as CFIndex,
+            kCFStringEncodingUTF8,
+        ) == 0
+        {
             return String::new();
         }
-        std::ffi::CStr::from_ptr(buf.as_ptr())
+        std::ffi::CStr::from_ptr(buf.as_ptr() as *const c_char)
             .to_string_lossy()
             .to_string()
     }
@@ -301,7 +406,8 @@ fn cfdict_get_val(dict: CFDictionaryRef, key: &str) -> Option<CFTypeRef> {
     }
 }
 
-struct IOReportIterator {
+/// Walks the channels of an IOReport sample, releasing the sample on drop.
+pub struct IOReportIterator {
     sample: CFDictionaryRef,
     items: CFArrayRef,
     index: isize,
@@ -319,6 +425,43 @@ impl IOReportIterator {
             count,
         })
     }
+
+    /// Applies `f` to every channel as it is read, without collecting the
+    /// sample into a `Vec` first.
+    pub fn map_channels<F, T>(self, f: F) -> impl Iterator<Item = T>
+    where
+        F: Fn(ChannelData) -> T,
+    {
+        self.map(f)
+    }
+
+    /// Channel count and total joules per power domain ("cpu", "gpu", "ane"
+    /// or "other"), in a single pass over the sample.
+    ///
+    /// Channels not reported in an energy unit are skipped, and energy
+    /// channels no domain claims count as "other". Aggregate channels are
+    /// not told apart from their components, so totals can count the same
+    /// joules twice on chips that report both.
+    pub fn count_by_category(self) -> HashMap<String, (usize, f64)> {
+        tally_categories(self)
+    }
+}
+
+fn tally_categories<I>(channels: I) -> HashMap<String, (usize, f64)>
+where
+    I: IntoIterator<Item = ChannelData>,
+{
+    let mut categories: HashMap<String, (usize, f64)> = HashMap::new();
+    for ch in channels {
+        let Some(joules) = energy_to_joules(ch.value, &ch.unit) else {
+            continue;
+        };
+        let domain = ChannelDomain::of(&ch.channel).unwrap_or(ChannelDomain::Other);
+        let (count, total) = categories.entry(domain.label().to_string()).or_default();
+        *count += 1;
+        *total += joules;
+    }
+    categories
 }
 
 impl Drop for IOReportIterator {
@@ -329,11 +472,22 @@ impl Drop for IOReportIterator {
     }
 }
 
-struct ChannelData {
-    group: String,
-    channel: String,
-    unit: String,
-    value: i64,
+/// One channel of an IOReport sample.
+#[derive(Debug, Clone)]
+pub struct ChannelData {
+    /// IOReport group, e.g. "Energy Model".
+    pub group: String,
+    /// IOReport subgroup, e.g. "GPU Performance States"; empty when none.
+    pub subgroup: String,
+    /// Channel name, e.g. "CPU Energy" or "GPU0".
+    pub channel: String,
+    /// Unit label with surrounding whitespace trimmed, e.g. "mJ".
+    pub unit: String,
+    /// Raw integer value in `unit`; zero for state channels.
+    pub value: i64,
+    /// Residency per state, in the order reported, for state channels;
+    /// empty for every other channel.
+    pub states: Vec<(String, i64)>,
 }
 
 impl Iterator for IOReportIterator {
@@ -352,50 +506,92 @@ impl Iterator for IOReportIterator {
         }
 
         let group = from_cfstr(unsafe { IOReportChannelGetGroup(item) });
+        let subgroup = from_cfstr(unsafe { IOReportChannelGetSubGroup(item) });
         let channel = from_cfstr(unsafe { IOReportChannelGetChannelName(item) });
         let unit = from_cfstr(unsafe { IOReportChannelGetUnitLabel(item) })
             .trim()
             .to_string();
-        let value = unsafe { IOReportSimpleGetIntegerValue(item, 0) };
+        let is_state = unsafe { IOReportChannelGetFormat(item) } == IOREPORT_FORMAT_STATE;
+        let (value, states) = if is_state {
+            (0, read_states(item))
+        } else {
+            let value = unsafe { IOReportSimpleGetIntegerValue(item, 0) };
+            (value, Vec::new())
+        };
 
         Some(ChannelData {
             group,
+            subgroup,
             channel,
             unit,
             value,
+            states,
         })
     }
 }
 
-struct IOReportSubscription {
+/// Name and residency of every state of a state-format channel.
+fn read_states(item: CFDictionaryRef) -> Vec<(String, i64)> {
+    let count = unsafe { IOReportStateGetCount(item) }.max(0);
+    (0..count)
+        .map(|index| unsafe {
+            (
+                from_cfstr(IOReportStateGetNameForIndex(item, index)),
+                IOReportStateGetResidency(item, index),
+            )
+        })
+        .collect()
+}
+
+pub(super) struct IOReportSubscription {
     subscription: IOReportSubscriptionRef,
     channels: CFMutableDictionaryRef,
+    /// Subgroups subscribed to, or `None` for the whole group.
+    subgroups: Option<Vec<String>>,
 }
 
 impl IOReportSubscription {
-    fn new() -> Option<Self> {
-        unsafe {
-            let group = cfstr("Energy Model");
-            let chan = IOReportCopyChannelsInGroup(group, null(), 0, 0, 0);
-            CFRelease(group as _);
-
-            if chan.is_null() {
-                return None;
+    /// Subscribe to the "Energy Model" group, which carries the CPU/GPU/ANE
+    /// energy counters used for power metrics.
+    ///
+    /// Only the subgroups holding channels that feed a power domain are
+    /// subscribed to when they can be told apart; every channel in the
+    /// subscription is copied on each sample, and larger chips report
+    /// hundreds that jolt never reads. Falls back to the whole group.
+    pub(super) fn new_energy_model() -> Option<Self> {
+        if let Some(subgroups) = probe_energy_model_subgroups() {
+            let narrowed = copy_channels(ENERGY_MODEL_GROUP, Some(subgroups.as_slice()))
+                .and_then(Self::from_channels);
+            if let Some(mut subscription) = narrowed {
+                subscription.subgroups = Some(subgroups);
+                return Some(subscription);
             }
+        }
+        Self::new(ENERGY_MODEL_GROUP)
+    }
 
-            if cfdict_get_val(chan, "IOReportChannels").is_none() {
-                CFRelease(chan as _);
-                return None;
-            }
+    /// Subscribe to the GPU performance state channels only; the rest of
+    /// the "GPU Stats" group is large and unused.
+    fn new_gpu_states() -> Option<Self> {
+        let mut subgroups = vec![GPU_PERF_STATES_SUBGROUP.to_string()];
+        subgroups.extend(probe_gpu_engine_subgroups());
+        copy_channels(GPU_STATS_GROUP, Some(&subgroups[..])).and_then(Self::from_channels)
+    }
 
-            let count = CFDictionaryGetCount(chan);
-            let channels = CFDictionaryCreateMutableCopy(kCFAllocatorDefault, count, chan);
-            CFRelease(chan as _);
+    /// Subscribe to the per-cluster CPU performance state channels.
+    fn new_cpu_cluster_states() -> Option<Self> {
+        let subgroups = [CPU_CLUSTER_STATES_SUBGROUP.to_string()];
+        copy_channels(CPU_STATS_GROUP, Some(&subgroups[..])).and_then(Self::from_channels)
+    }
 
-            if channels.is_null() {
-                return None;
-            }
+    /// Subscribe to every channel in the given IOReport group.
+    pub(super) fn new(group: &str) -> Option<Self> {
+        copy_channels(group, None).and_then(Self::from_channels)
+    }
 
+    /// Takes ownership of `channels`.
+    fn from_channels(channels: CFMutableDictionaryRef) -> Option<Self> {
+        unsafe {
             let mut sub_dict: CFMutableDictionaryRef = null::<c_void>() as _;
             let subscription =
                 IOReportCreateSubscription(null(), channels, &mut sub_dict, 0, null());
@@ -408,11 +604,12 @@ impl IOReportSubscription {
             Some(Self {
                 subscription,
                 channels,
+                subgroups: None,
             })
         }
     }
 
-    fn sample(&self) -> Option<CFDictionaryRef> {
+    pub(super) fn sample(&self) -> Option<CFDictionaryRef> {
         let sample = unsafe { IOReportCreateSamples(self.subscription, self.channels, null()) };
         if sample.is_null() {
             None
@@ -422,6 +619,62 @@ impl IOReportSubscription {
     }
 }
 
+/// Walks the change between two samples of the same subscription. Neither
+/// sample is released.
+pub(super) fn sample_delta(
+    prev: CFDictionaryRef,
+    current: CFDictionaryRef,
+) -> Option<IOReportIterator> {
+    let delta = unsafe { IOReportCreateSamplesDelta(prev, current, null()) };
+    if delta.is_null() {
+        return None;
+    }
+    let iter = IOReportIterator::new(delta);
+    if iter.is_none() {
+        unsafe { CFRelease(delta as _) };
+    }
+    iter
+}
+
+/// Names of every channel in the IOReport `group` (e.g. "Energy Model" or
+/// "GPU Stats"), in the order IOReport lists them. Only copies the channel
+/// descriptions; nothing is subscribed to or sampled. `None` when the group
+/// does not exist.
+pub fn list_channel_names(group: &str) -> Option<Vec<String>> {
+    let names = channel_names(group)?;
+    Some(names.into_iter().map(|(_, channel)| channel).collect())
+}
+
+/// Every channel of the "Energy Model" group with its average power over
+/// `interval`. Subscribes to the whole group, so channels jolt does not
+/// track are listed too. `None` when IOReport cannot be sampled.
+pub fn list_energy_model_channels(interval: Duration) -> Option<Vec<ChannelListing>> {
+    let subscription = IOReportSubscription::new(ENERGY_MODEL_GROUP)?;
+    let first = subscription.sample()?;
+    let start = Instant::now();
+    std::thread::sleep(interval);
+    let second = subscription.sample();
+    let elapsed_ms = (start.elapsed().as_millis() as u64).max(1);
+
+    let listing = second.and_then(|second| {
+        let channels = sample_delta(first, second).map(|delta| {
+            delta
+                .map(|ch| ChannelListing {
+                    watts: energy_to_watts(ch.value, &ch.unit, elapsed_ms),
+                    group: ch.group,
+                    subgroup: ch.subgroup,
+                    name: ch.channel,
+                    unit: ch.unit,
+                })
+                .collect()
+        });
+        unsafe { CFRelease(second as _) };
+        channels
+    });
+    unsafe { CFRelease(first as _) };
+    listing
+}
+
 impl Drop for IOReportSubscription {
     fn drop(&mut self) {
         unsafe {
@@ -430,6 +683,128 @@ impl Drop for IOReportSubscription {
     }
 }
 
+/// Copies the channels of `group`, limited to `subgroups` when given, into
+/// one mutable dictionary suitable for a subscription.
+fn copy_channels(group: &str, subgroups: Option<&[String]>) -> Option<CFMutableDictionaryRef> {
+    let parts: Vec<Option<&str>> = match subgroups {
+        Some(subgroups) => subgroups.iter().map(|s| Some(s.as_str())).collect(),
+        None => vec![None],
+    };
+
+    unsafe {
+        let group = cfstr(group);
+        let mut merged: CFMutableDictionaryRef = null::<c_void>() as _;
+
+        for subgroup in parts {
+            let subgroup = subgroup.map(cfstr);
+            let chan = IOReportCopyChannelsInGroup(group, subgroup.unwrap_or(null()), 0, 0, 0);
+            if let Some(subgroup) = subgroup {
+                CFRelease(subgroup as _);
+            }
+
+            if chan.is_null() || cfdict_get_val(chan, "IOReportChannels").is_none() {
+                if !chan.is_null() {
+                    CFRelease(chan as _);
+                }
+                if !merged.is_null() {
+                    CFRelease(merged as _);
+                }
+                CFRelease(group as _);
+                return None;
+            }
+
+            if merged.is_null() {
+                merged = CFDictionaryCreateMutableCopy(kCFAllocatorDefault, 0, chan);
+            } else {
+                IOReportMergeChannels(merged, chan, null());
+            }
+            CFRelease(chan as _);
+
+            if merged.is_null() {
+                CFRelease(group as _);
+                return None;
+            }
+        }
+
+        CFRelease(group as _);
+        Some(merged)
+    }
+}
+
+/// Energy Model subgroups that hold every channel feeding a power domain,
+/// or `None` when the subscription cannot be narrowed.
+fn probe_energy_model_subgroups() -> Option<Vec<String>> {
+    select_subgroups(channel_names(ENERGY_MODEL_GROUP)?)
+}
+
+/// "GPU Stats" subgroups holding compute or render utilization channels;
+/// empty when the chip reports none.
+fn probe_gpu_engine_subgroups() -> Vec<String> {
+    let names = channel_names(GPU_STATS_GROUP).unwrap_or_default();
+    let subgroups: BTreeSet<String> = names
+        .into_iter()
+        .filter(|(subgroup, channel)| {
+            !subgroup.is_empty()
+                && subgroup != GPU_PERF_STATES_SUBGROUP
+                && GpuEngine::of(channel).is_some()
+        })
+        .map(|(subgroup, _)| subgroup)
+        .collect();
+    subgroups.into_iter().collect()
+}
+
+/// `(subgroup, channel)` names of every channel in `group`.
+fn channel_names(group: &str) -> Option<Vec<(String, String)>> {
+    let channels = copy_channels(group, None)?;
+    let mut names = Vec::new();
+
+    unsafe {
+        if let Some(items) = cfdict_get_val(channels, "IOReportChannels") {
+            let items = items as CFArrayRef;
+            for i in 0..CFArrayGetCount(items) {
+                let item = CFArrayGetValueAtIndex(items, i) as CFDictionaryRef;
+                if item.is_null() {
+                    continue;
+                }
+                names.push((
+                    from_cfstr(IOReportChannelGetSubGroup(item)),
+                    from_cfstr(IOReportChannelGetChannelName(item)),
+                ));
+            }
+        }
+        CFRelease(channels as _);
+    }
+
+    Some(names)
+}
+
+/// Picks the subgroups containing a channel that [`ChannelDomain::of`]
+/// recognizes, from `(subgroup, channel)` pairs.
+///
+/// Returns `None` if a recognized channel has no subgroup (it could only be
+/// reached through the whole group), if nothing is recognized, or if every
+/// subgroup would be kept anyway.
+fn select_subgroups<I>(channels: I) -> Option<Vec<String>>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let mut all = BTreeSet::new();
+    let mut wanted = BTreeSet::new();
+
+    for (subgroup, channel) in channels {
+        let tracked = ChannelDomain::of(&channel).is_some();
+        if tracked && subgroup.is_empty() {
+            return None;
+        }
+        if tracked {
+            wanted.insert(subgroup.clone());
+        }
+        all.insert(subgroup);
+    }
+
+    (!wanted.is_empty() && wanted.len() < all.len()).then(|| wanted.into_iter().collect())
+}
+
 pub struct MacOSPower {
     info: PowerInfo,
     subscription: Option<IOReportSubscription>,
@@ -441,16 +816,55 @@ pub struct MacOSPower {
     ane_power: f32,
     package_power: f32,
     system_power: f32,
+    channels: Vec<ChannelPower>,
+    overlap: OverlapDetector,
     samples: VecDeque<PowerSample>,
+    /// Whether the last power refresh fell back to the CPU-usage estimate.
+    fell_back: bool,
+    gpu_states: Option<IOReportSubscription>,
+    last_gpu_sample: Option<CFDictionaryRef>,
+    /// Set by [`PowerProvider::subscribe_cpu_stats`].
+    cpu_stats: Option<CpuStats>,
+}
+
+/// The "CPU Stats" subscription and the frequency tables to read it with.
+struct CpuStats {
+    subscription: IOReportSubscription,
+    last_sample: Option<CFDictionaryRef>,
+    e_cluster_mhz: Vec<f32>,
+    p_cluster_mhz: Vec<f32>,
+}
+
+impl CpuStats {
+    /// The frequency table for a cluster channel such as "ECPU" or "PCPU1".
+    fn table_for(&self, cluster: &str) -> &[f32] {
+        match cluster.as_bytes().first() {
+            Some(b'E') => &self.e_cluster_mhz,
+            Some(b'P') => &self.p_cluster_mhz,
+            _ => &[],
+        }
+    }
+}
+
+impl Drop for CpuStats {
+    fn drop(&mut self) {
+        if let Some(sample) = self.last_sample {
+            unsafe { CFRelease(sample as _) };
+        }
+    }
 }
 
 impl PowerProvider for MacOSPower {
     fn new() -> Result<Self> {
-        let subscription = IOReportSubscription::new();
+        let subscription = IOReportSubscription::new_energy_model();
         let smc = Smc::new();
+        let info = PowerInfo {
+            energy_model_subgroups: subscription.as_ref().and_then(|s| s.subgroups.clone()),
+            ..PowerInfo::default()
+        };
 
         let mut provider = Self {
-            info: PowerInfo::default(),
+            info,
             subscription,
             smc,
             last_sample: None,
@@ -460,7 +874,13 @@ impl PowerProvider for MacOSPower {
             ane_power: 0.0,
             package_power: 0.0,
             system_power: 0.0,
+            channels: Vec::new(),
+            overlap: OverlapDetector::default(),
             samples: VecDeque::with_capacity(SMOOTHING_SAMPLE_COUNT),
+            fell_back: false,
+            gpu_states: IOReportSubscription::new_gpu_states(),
+            last_gpu_sample: None,
+            cpu_stats: None,
         };
 
         if let Some(ref sub) = provider.subscription {
@@ -468,7 +888,9 @@ impl PowerProvider for MacOSPower {
                 std::thread::sleep(Duration::from_millis(100));
                 if let Some(sample2) = sub.sample() {
                     let elapsed = Duration::from_millis(100);
-                    provider.calculate_power_from_delta(sample1, sample2, elapsed);
+                    if provider.calculate_power_from_delta(sample1, sample2, elapsed) {
+                        provider.info.raw_readings = Some(provider.raw_readings());
+                    }
                     provider.last_sample = Some(sample2);
                     provider.last_sample_time = Some(Instant::now());
                 } else {
@@ -478,7 +900,9 @@ impl PowerProvider for MacOSPower {
         }
 
         provider.refresh_system_power();
+        provider.refresh_gpu_residency();
         provider.refresh_power_mode();
+        provider.refresh_thermal_pressure();
         provider.record_sample();
         provider.update_info();
         Ok(provider)
@@ -487,7 +911,10 @@ impl PowerProvider for MacOSPower {
     fn refresh(&mut self) -> Result<()> {
         self.refresh_power_metrics();
         self.refresh_system_power();
+        self.refresh_gpu_residency();
+        self.refresh_cpu_frequencies();
         self.refresh_power_mode();
+        self.refresh_thermal_pressure();
         self.record_sample();
         self.update_info();
         Ok(())
@@ -496,20 +923,193 @@ impl PowerProvider for MacOSPower {
     fn info(&self) -> &PowerInfo {
         &self.info
     }
+
+    fn subscribe_cpu_stats(&mut self) -> Result<()> {
+        if self.cpu_stats.is_some() {
+            return Ok(());
+        }
+        let subscription = IOReportSubscription::new_cpu_cluster_states()
+            .ok_or_else(|| eyre!("IOReport has no \"{CPU_STATS_GROUP}\" group"))?;
+        let (e_cluster_mhz, p_cluster_mhz) = read_cluster_frequency_tables()
+            .ok_or_else(|| eyre!("CPU frequency tables not found in the IORegistry"))?;
+        let last_sample = subscription.sample();
+        self.cpu_stats = Some(CpuStats {
+            subscription,
+            last_sample,
+            e_cluster_mhz,
+            p_cluster_mhz,
+        });
+        Ok(())
+    }
+
+    fn subscribe_notification<F>(on_change: F) -> NotificationHandle
+    where
+        F: Fn(PowerMode) + Send + 'static,
+    {
+        NotificationHandle::spawn(move |stop| {
+            let mut fd: c_int = -1;
+            let mut token: c_int = 0;
+            let registered = unsafe {
+                notify_register_file_descriptor(
+                    LOW_POWER_MODE_NOTIFICATION.as_ptr() as *const c_char,
+                    &mut fd,
+                    0,
+                    &mut token,
+                )
+            } == NOTIFY_STATUS_OK;
+
+            let mut mode = read_power_mode();
+            let mut last_check = Instant::now();
+            while !stop.load(Ordering::Relaxed) {
+                let notified = if registered {
+                    wait_for_notification(fd, WATCH_STOP_INTERVAL)
+                } else {
+                    std::thread::sleep(WATCH_STOP_INTERVAL);
+                    false
+                };
+                if !notified && last_check.elapsed() < MODE_RECHECK_INTERVAL {
+                    continue;
+                }
+
+                last_check = Instant::now();
+                if let Some(current) = read_power_mode() {
+                    if mode != Some(current) {
+                        mode = Some(current);
+                        on_change(current);
+                    }
+                }
+            }
+
+            if registered {
+                // Also closes the descriptor
+                unsafe { notify_cancel(token) };
+            }
+        })
+    }
+}
+
+/// Waits up to `timeout` for a notification on `fd`, consuming it.
+fn wait_for_notification(fd: c_int, timeout: Duration) -> bool {
+    let mut poll_fd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let ready = unsafe { libc::poll(&mut poll_fd, 1, timeout.as_millis() as c_int) };
+    if ready <= 0 {
+        return false;
+    }
+    // Each notification delivers the registration token as one int
+    let mut token: c_int = 0;
+    unsafe {
+        libc::read(
+            fd,
+            &mut token as *mut c_int as *mut c_void,
+            size_of::<c_int>(),
+        )
+    };
+    true
+}
+
+/// The level last posted with the thermal pressure notification.
+fn read_thermal_pressure() -> ThermalPressure {
+    let mut token: c_int = 0;
+    let name = THERMAL_PRESSURE_NOTIFICATION.as_ptr() as *const c_char;
+    if unsafe { notify_register_check(name, &mut token) } != NOTIFY_STATUS_OK {
+        return ThermalPressure::Unknown;
+    }
+
+    let mut level: u64 = 0;
+    let status = unsafe { notify_get_state(token, &mut level) };
+    unsafe { notify_cancel(token) };
+    if status == NOTIFY_STATUS_OK {
+        ThermalPressure::from_level(level)
+    } else {
+        ThermalPressure::Unknown
+    }
+}
+
+/// The power mode from the active power management preferences, falling
+/// back to `pmset -g` where IOKit does not provide them. `None` if neither
+/// can be read.
+fn read_power_mode() -> Option<PowerMode> {
+    active_pm_settings()
+        .map(power_mode::from_settings)
+        .or_else(read_pmset_power_mode)
+}
+
+/// The power mode as reported by `pmset`, or `None` if it cannot be run
+/// or hangs, as it does while powerd is wedged.
+fn read_pmset_power_mode() -> Option<PowerMode> {
+    let output = command::run(Command::new("pmset").args(["-g"]), PMSET_LIMITS).ok()?;
+    Some(power_mode::from_pmset(&output.stdout_lossy()))
+}
+
+/// Numeric power management settings of the power source in use, without
+/// spawning a process. `None` where IOKit does not report them.
+fn active_pm_settings() -> Option<Vec<(String, i64)>> {
+    unsafe {
+        let snapshot = IOPSCopyPowerSourcesInfo();
+        if snapshot.is_null() {
+            return None;
+        }
+        let prefs = IOPMCopyActivePMPreferences();
+        let source = IOPSGetProvidingPowerSourceType(snapshot);
+
+        let mut settings = None;
+        if !prefs.is_null() && !source.is_null() {
+            let active = CFDictionaryGetValue(prefs, source as _) as CFDictionaryRef;
+            if !active.is_null() && CFGetTypeID(active as _) == CFDictionaryGetTypeID() {
+                settings = Some(numeric_entries(active));
+            }
+        }
+
+        if !prefs.is_null() {
+            CFRelease(prefs as _);
+        }
+        CFRelease(snapshot);
+        settings
+    }
+}
+
+/// Entries of `dict` with a string key and a number value.
+fn numeric_entries(dict: CFDictionaryRef) -> Vec<(String, i64)> {
+    unsafe {
+        let count = CFDictionaryGetCount(dict).max(0) as usize;
+        let mut keys = vec![null(); count];
+        let mut values = vec![null(); count];
+        CFDictionaryGetKeysAndValues(dict, keys.as_mut_ptr(), values.as_mut_ptr());
+
+        keys.into_iter()
+            .zip(values)
+            .filter(|&(key, value)| {
+                CFGetTypeID(key) == CFStringGetTypeID() && CFGetTypeID(value) == CFNumberGetTypeID()
+            })
+            .filter_map(|(key, value)| {
+                let number = CFNumber::wrap_under_get_rule(value as CFNumberRef).to_i64()?;
+                Some((from_cfstr(key as CFStringRef), number))
+            })
+            .collect()
+    }
 }
 
 impl MacOSPower {
     fn update_info(&mut self) {
         self.info.cpu_power_watts = self.smoothed_value(|s| s.cpu_power);
         self.info.gpu_power_watts = self.smoothed_value(|s| s.gpu_power);
+        self.info.ane_power_watts = self.smoothed_value(|s| s.ane_power);
         self.info.system_power_watts = self.smoothed_value(|s| s.system_power);
         self.info.is_warmed_up = self.samples.len() >= MIN_WARMUP_SAMPLES;
+        self.info.channels = self.channels.clone();
+        self.info.is_fallback_estimate =
+            self.subscription.is_none() || self.last_sample.is_none() || self.fell_back;
     }
 
     fn record_sample(&mut self) {
         let sample = PowerSample {
             cpu_power: self.cpu_power,
             gpu_power: self.gpu_power,
+            ane_power: self.ane_power,
             system_power: self.system_power,
         };
 
@@ -541,24 +1141,32 @@ impl MacOSPower {
     }
 
     fn refresh_power_metrics(&mut self) {
+        self.fell_back = false;
+        let measured = self.read_power_metrics();
+        self.info.raw_readings = measured.then(|| self.raw_readings());
+    }
+
+    /// Updates the raw readings; `false` when nothing new was measured, as
+    /// on the first sample.
+    fn read_power_metrics(&mut self) -> bool {
         let Some(ref subscription) = self.subscription else {
             self.fallback_power_estimate();
-            return;
+            return true;
         };
 
         let Some(current_sample) = subscription.sample() else {
             self.fallback_power_estimate();
-            return;
+            return true;
         };
 
         let (Some(prev_sample), Some(prev_time)) = (self.last_sample, self.last_sample_time) else {
             self.last_sample = Some(current_sample);
             self.last_sample_time = Some(Instant::now());
-            return;
+            return false;
         };
 
         let elapsed = prev_time.elapsed();
-        self.calculate_power_from_delta(prev_sample, current_sample, elapsed);
+        let measured = self.calculate_power_from_delta(prev_sample, current_sample, elapsed);
 
         unsafe {
             CFRelease(prev_sample as _);
@@ -566,59 +1174,84 @@ impl MacOSPower {
 
         self.last_sample = Some(current_sample);
         self.last_sample_time = Some(Instant::now());
+        measured
+    }
+
+    /// The unsmoothed readings of the last power refresh.
+    fn raw_readings(&self) -> PowerReadings {
+        PowerReadings {
+            cpu_watts: self.cpu_power,
+            gpu_watts: self.gpu_power,
+            total_watts: self.package_power,
+        }
     }
 
+    /// Returns `false` when the delta covered no time, leaving the readings
+    /// as they were.
     fn calculate_power_from_delta(
         &mut self,
         prev: CFDictionaryRef,
         current: CFDictionaryRef,
         elapsed: Duration,
-    ) {
+    ) -> bool {
         let delta = unsafe { IOReportCreateSamplesDelta(prev, current, null()) };
         if delta.is_null() {
             self.fallback_power_estimate();
-            return;
+            return true;
         }
 
         let elapsed_ms = elapsed.as_millis() as u64;
         if elapsed_ms == 0 {
             unsafe { CFRelease(delta as _) };
-            return;
+            return false;
         }
 
         let mut cpu_power: f32 = 0.0;
         let mut gpu_power: f32 = 0.0;
         let mut ane_power: f32 = 0.0;
         let mut other_power: f32 = 0.0;
+        let mut channels = Vec::new();
+        // Joules of each entry in `channels`
+        let mut joules = Vec::new();
 
         if let Some(iter) = IOReportIterator::new(delta) {
             for ch in iter {
-                if ch.group != "Energy Model" {
+                if ch.group != ENERGY_MODEL_GROUP {
                     continue;
                 }
 
-                let watts = match energy_to_watts(ch.value, &ch.unit, elapsed_ms) {
-                    Some(w) => w,
-                    None => continue,
+                let Some(energy) = energy_to_joules(ch.value, &ch.unit) else {
+                    continue;
                 };
 
-                let channel_lower = ch.channel.to_lowercase();
-                if channel_lower.contains("gpu") {
-                    gpu_power += watts;
-                } else if channel_lower.contains("cpu") || channel_lower.starts_with("pacc") {
-                    cpu_power += watts;
-                } else if channel_lower.starts_with("ane") {
-                    ane_power += watts;
-                } else if channel_lower.contains("amcc")
-                    || channel_lower.contains("dcs")
-                    || channel_lower.contains("dram")
-                    || channel_lower.contains("isp")
-                    || channel_lower.contains("pmp")
-                    || channel_lower.contains("nub")
-                    || channel_lower.contains("soc")
-                {
-                    other_power += watts;
+                channels.push(ChannelPower {
+                    name: ch.channel.clone(),
+                    watts: joules_to_watts(energy, elapsed_ms),
+                    aggregate: false,
+                });
+                joules.push(energy);
+            }
+        }
+
+        // Count each joule once when aggregates sit next to their components
+        self.overlap
+            .mark_aggregates(&mut channels, ChannelDomain::of);
+        for (channel, energy) in channels.iter().zip(joules).filter(|(c, _)| !c.aggregate) {
+            match ChannelDomain::of(&channel.name) {
+                Some(ChannelDomain::Gpu) => {
+                    gpu_power += channel.watts;
+                    self.info.gpu_energy_joules += energy;
+                }
+                Some(ChannelDomain::Cpu) => {
+                    cpu_power += channel.watts;
+                    self.info.cpu_energy_joules += energy;
                 }
+                Some(ChannelDomain::Ane) => {
+                    ane_power += channel.watts;
+                    self.info.ane_energy_joules += energy;
+                }
+                Some(ChannelDomain::Other) => other_power += channel.watts,
+                None => {}
             }
         }
 
@@ -626,11 +1259,15 @@ impl MacOSPower {
         self.gpu_power = gpu_power;
         self.ane_power = ane_power;
         self.package_power = cpu_power + gpu_power + ane_power + other_power;
+        self.channels = channels;
+        true
     }
 
     fn fallback_power_estimate(&mut self) {
         use sysinfo::System;
 
+        self.fell_back = true;
+
         let mut sys = System::new_all();
         sys.refresh_all();
         std::thread::sleep(Duration::from_millis(50));
@@ -645,21 +1282,107 @@ impl MacOSPower {
         self.gpu_power = 1.0;
         self.ane_power = 0.0;
         self.package_power = self.cpu_power + self.gpu_power;
+        self.channels.clear();
     }
 
-    fn refresh_power_mode(&mut self) {
-        if let Ok(output) = Command::new("pmset").args(["-g"]).output() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
+    fn refresh_gpu_residency(&mut self) {
+        let Some(current) = self.gpu_states.as_ref().and_then(|sub| sub.sample()) else {
+            self.info.gpu_residency = None;
+            self.info.gpu_workload = None;
+            return;
+        };
 
-            if stdout.contains("lowpowermode 1") {
-                self.info.power_mode = PowerMode::LowPower;
-            } else if stdout.contains("highpowermode 1") {
-                self.info.power_mode = PowerMode::HighPerformance;
-            } else {
-                self.info.power_mode = PowerMode::Automatic;
+        if let Some(prev) = self.last_gpu_sample.replace(current) {
+            let channels: Vec<ChannelData> = sample_delta(prev, current)
+                .map(|delta| delta.collect())
+                .unwrap_or_default();
+            self.info.gpu_residency = channels
+                .iter()
+                .find(|ch| {
+                    ch.subgroup == GPU_PERF_STATES_SUBGROUP && ch.channel == GPU_PERF_STATES_CHANNEL
+                })
+                .and_then(|ch| GpuResidency::from_residencies(ch.states.clone()));
+            self.info.gpu_workload = GpuWorkload::from_busy_times(
+                channels
+                    .iter()
+                    .filter(|ch| ch.subgroup != GPU_PERF_STATES_SUBGROUP)
+                    .filter_map(|ch| Some((GpuEngine::of(&ch.channel)?, busy_time(ch)))),
+            );
+            unsafe { CFRelease(prev as _) };
+        }
+    }
+
+    fn refresh_cpu_frequencies(&mut self) {
+        let Some(stats) = self.cpu_stats.as_mut() else {
+            return;
+        };
+        let Some(current) = stats.subscription.sample() else {
+            self.info.cpu_freq_mhz.clear();
+            return;
+        };
+
+        if let Some(prev) = stats.last_sample.replace(current) {
+            let stats = &*stats;
+            self.info.cpu_freq_mhz = sample_delta(prev, current)
+                .map(|delta| {
+                    delta
+                        .filter(|ch| ch.subgroup == CPU_CLUSTER_STATES_SUBGROUP)
+                        .filter_map(|ch| {
+                            let table = stats.table_for(&ch.channel);
+                            let mhz = average_frequency_mhz(&ch.states, table)?;
+                            Some((ch.channel, mhz))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            unsafe { CFRelease(prev as _) };
+        }
+    }
+
+    /// Switches the power mode with `pmset -a powermode`, then reads it
+    /// back into [`PowerInfo::power_mode`]. pmset needs root, so this fails
+    /// unless jolt runs under sudo; it also fails on Macs without the
+    /// requested mode, as High Performance is missing on most.
+    pub fn set_power_mode(&mut self, mode: PowerMode) -> Result<()> {
+        let Some(value) = mode.pmset_value() else {
+            bail!("Cannot set the power mode to {}", mode);
+        };
+        let value = value.to_string();
+        let output = command::run(
+            Command::new("pmset").args(["-a", "powermode", &value]),
+            PMSET_LIMITS,
+        )?;
+        if !output.success() {
+            let stderr = output.stderr_lossy();
+            if unsafe { libc::geteuid() } != 0 {
+                bail!(
+                    "pmset needs root privileges to change the power mode; run with sudo ({})",
+                    stderr.trim()
+                );
             }
+            bail!("pmset -a powermode {} failed: {}", value, stderr.trim());
+        }
+
+        self.refresh_power_mode();
+        if self.info.power_mode != mode {
+            bail!(
+                "Power mode is {} after requesting {}; this Mac may not support it",
+                self.info.power_mode,
+                mode
+            );
+        }
+        Ok(())
+    }
+
+    fn refresh_power_mode(&mut self) {
+        if let Some(mode) = read_power_mode() {
+            self.info.power_mode = mode;
         }
     }
+
+    fn refresh_thermal_pressure(&mut self) {
+        self.info.thermal_pressure = read_thermal_pressure();
+    }
 }
 
 impl Drop for MacOSPower {
@@ -669,19 +1392,165 @@ impl Drop for MacOSPower {
                 CFRelease(sample as _);
             }
         }
+        if let Some(sample) = self.last_gpu_sample {
+            unsafe {
+                CFRelease(sample as _);
+            }
+        }
     }
 }
 
+/// E- and P-cluster frequency tables in MHz from the `pmgr` device. `None`
+/// when the device or either table is missing, as on Intel Macs.
+fn read_cluster_frequency_tables() -> Option<(Vec<f32>, Vec<f32>)> {
+    let service_name = std::ffi::CString::new("AppleARMIODevice").ok()?;
+    unsafe {
+        let service = IOServiceMatching(service_name.as_ptr());
+        let mut existing = 0u32;
+        if IOServiceGetMatchingServices(0, service, &mut existing) != 0 {
+            return None;
+        }
+
+        let mut tables = None;
+        loop {
+            let device = IOIteratorNext(existing);
+            if device == 0 {
+                break;
+            }
+
+            let mut name = [0i8; 128];
+            let is_pmgr = IORegistryEntryGetName(device, name.as_mut_ptr()) == 0
+                && std::ffi::CStr::from_ptr(name.as_ptr()).to_bytes() == b"pmgr";
+            if is_pmgr {
+                let mut props: CFMutableDictionaryRef = null::<c_void>() as _;
+                if IORegistryEntryCreateCFProperties(
+                    device,
+                    &mut props,
+                    kCFAllocatorDefault as _,
+                    0,
+                ) == 0
+                    && !props.is_null()
+                {
+                    let e_cluster = cfdata_bytes(props, E_CLUSTER_VOLTAGE_STATES)
+                        .map(|bytes| parse_voltage_states(&bytes));
+                    let p_cluster = cfdata_bytes(props, P_CLUSTER_VOLTAGE_STATES)
+                        .map(|bytes| parse_voltage_states(&bytes));
+                    tables = e_cluster.zip(p_cluster);
+                    CFRelease(props as _);
+                }
+            }
+            IOObjectRelease(device);
+            if is_pmgr {
+                break;
+            }
+        }
+
+        IOObjectRelease(existing);
+        tables
+    }
+}
+
+/// Copies the bytes of a `CFData` value out of `dict`.
+fn cfdata_bytes(dict: CFDictionaryRef, key: &str) -> Option<Vec<u8>> {
+    let data = cfdict_get_val(dict, key)? as CFDataRef;
+    unsafe {
+        let len = CFDataGetLength(data).max(0) as usize;
+        let ptr = CFDataGetBytePtr(data);
+        if ptr.is_null() {
+            return None;
+        }
+        Some(std::slice::from_raw_parts(ptr, len).to_vec())
+    }
+}
+
+/// Time a utilization channel spent busy: its value for counters, or the
+/// residency outside idle states for state channels.
+fn busy_time(channel: &ChannelData) -> i64 {
+    if channel.states.is_empty() {
+        return channel.value;
+    }
+    channel
+        .states
+        .iter()
+        .filter(|(state, _)| !matches!(state.as_str(), "IDLE" | "OFF" | "DOWN"))
+        .map(|(_, time)| time)
+        .sum()
+}
+
+fn energy_to_joules(value: i64, unit: &str) -> Option<f64> {
+    Some(value as f64 * joules_per_unit(unit)?)
+}
+
 fn energy_to_watts(value: i64, unit: &str, duration_ms: u64) -> Option<f32> {
-    let val = value as f32;
-    let duration_sec = duration_ms as f32 / 1000.0;
-
-    let watts = match unit {
-        "mJ" => val / 1_000.0 / duration_sec,
-        "uJ" => val / 1_000_000.0 / duration_sec,
-        "nJ" => val / 1_000_000_000.0 / duration_sec,
-        _ => return None,
-    };
+    Some(joules_to_watts(energy_to_joules(value, unit)?, duration_ms))
+}
+
+fn joules_to_watts(joules: f64, duration_ms: u64) -> f32 {
+    let duration_sec = duration_ms as f64 / 1000.0;
+    (joules / duration_sec) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(channels: &[(&str, &str)]) -> Vec<(String, String)> {
+        channels
+            .iter()
+            .map(|(subgroup, channel)| (subgroup.to_string(), channel.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_only_subgroups_with_tracked_channels() {
+        let channels = pairs(&[
+            ("CPU Energy", "PACC0_CPU0"),
+            ("GPU Energy", "GPU0"),
+            ("Display", "DISP0"),
+            ("Display", "DISPEXT"),
+        ]);
+        assert_eq!(
+            select_subgroups(channels),
+            Some(vec!["CPU Energy".to_string(), "GPU Energy".to_string()])
+        );
+    }
 
-    Some(watts)
+    #[test]
+    fn falls_back_when_subgroups_cannot_narrow() {
+        // A tracked channel outside any subgroup
+        assert_eq!(
+            select_subgroups(pairs(&[("", "CPU Energy"), ("Display", "DISP0")])),
+            None
+        );
+        // Every subgroup is needed
+        assert_eq!(
+            select_subgroups(pairs(&[("CPU Energy", "ECPU"), ("GPU Energy", "GPU")])),
+            None
+        );
+        assert_eq!(select_subgroups(Vec::new()), None);
+    }
+
+    #[test]
+    fn tallies_channels_and_joules_per_category() {
+        let channel = |name: &str, unit: &str, value: i64| ChannelData {
+            group: ENERGY_MODEL_GROUP.to_string(),
+            subgroup: String::new(),
+            channel: name.to_string(),
+            unit: unit.to_string(),
+            value,
+            states: Vec::new(),
+        };
+        let categories = tally_categories([
+            channel("ECPU0", "mJ", 1500),
+            channel("PCPU0", "uJ", 500_000),
+            channel("GPU0", "nJ", 2_000_000_000),
+            channel("DISP0", "mJ", 250),
+            channel("CPU Residency", "ticks", 9000),
+        ]);
+
+        assert_eq!(categories["cpu"], (2, 2.0));
+        assert_eq!(categories["gpu"], (1, 2.0));
+        assert_eq!(categories["other"], (1, 0.25));
+        assert!(!categories.contains_key("ane"));
+    }
 }
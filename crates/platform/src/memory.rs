@@ -0,0 +1,99 @@
+//! Memory pressure and bandwidth traits and types.
+
+use std::time::Duration;
+
+use color_eyre::eyre::Result;
+
+use crate::types::MemoryPressure;
+
+/// Memory information snapshot.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryInfo {
+    /// Current memory pressure; `Unknown` until the first refresh.
+    pub pressure: MemoryPressure,
+
+    /// Share of physical memory in use, from 0 to 1.
+    pub used_fraction: Option<f32>,
+
+    /// DRAM read plus write traffic in GB/s. `None` until two samples have
+    /// been taken, or where the memory controller counters are unreadable.
+    pub bandwidth_gbps: Option<f32>,
+}
+
+/// Trait for platform-specific memory providers.
+pub trait MemoryProvider {
+    /// Create a new memory provider instance.
+    fn new() -> Result<Self>
+    where
+        Self: Sized;
+
+    /// Refresh memory information from the system.
+    fn refresh(&mut self) -> Result<()>;
+
+    /// Get the current memory information.
+    fn info(&self) -> &MemoryInfo;
+}
+
+/// Bandwidth in GB/s from memory controller byte counters that moved by
+/// `value` in `unit` over `elapsed`, given as `(channel, unit, value)`.
+///
+/// Only the DRAM controller ("DCS") read and write channels count; the
+/// per-client channels (CPU, GPU, ...) report the same traffic again.
+/// `None` when no such channel was seen.
+pub fn bandwidth_gbps<'a, I>(channels: I, elapsed: Duration) -> Option<f32>
+where
+    I: IntoIterator<Item = (&'a str, &'a str, i64)>,
+{
+    let secs = elapsed.as_secs_f64();
+    if secs <= 0.0 {
+        return None;
+    }
+
+    let mut bytes = None;
+    for (channel, unit, value) in channels {
+        let is_dram =
+            channel.starts_with("DCS") && (channel.ends_with("RD") || channel.ends_with("WR"));
+        if !is_dram {
+            continue;
+        }
+        let Some(scale) = bytes_per_unit(unit) else {
+            continue;
+        };
+        *bytes.get_or_insert(0.0) += value.max(0) as f64 * scale;
+    }
+
+    bytes.map(|bytes| (bytes / secs / 1e9) as f32)
+}
+
+fn bytes_per_unit(unit: &str) -> Option<f64> {
+    match unit {
+        "" | "B" | "bytes" => Some(1.0),
+        "KB" | "KiB" => Some(1024.0),
+        "MB" | "MiB" => Some(1024.0 * 1024.0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bandwidth_counts_dram_controller_channels_only() {
+        let channels = [
+            ("DCS RD", "B", 3_000_000_000),
+            ("DCS WR", "MiB", 1000),
+            ("GPU RD", "B", 5_000_000_000),
+            ("DCS RD", "pJ", 7),
+        ];
+        let gbps = bandwidth_gbps(channels, Duration::from_secs(2)).unwrap();
+        let expected = (3e9 + 1000.0 * 1024.0 * 1024.0) / 2.0 / 1e9;
+        assert!((gbps as f64 - expected).abs() < 1e-4);
+
+        assert_eq!(
+            bandwidth_gbps([("GPU RD", "B", 10)], Duration::from_secs(1)),
+            None
+        );
+        assert_eq!(bandwidth_gbps(channels, Duration::ZERO), None);
+    }
+}
@@ -0,0 +1,242 @@
+//! Detection of power channels whose energy is also reported by others.
+//!
+//! Some Energy Model channel sets report an aggregate, such as "CPU Energy"
+//! or the cluster channel "PCPU", next to the components it is made of
+//! ("PCPU0", "PCPU1", ...). Summing every channel then counts the same
+//! joules twice. Aggregates are recognised by name and left out of the sums
+//! unless their value keeps disagreeing with their components, in which
+//! case they are independent channels after all. A channel with an
+//! unrecognised name that keeps equalling the rest of its domain is left
+//! out as well. Components are always the ones counted, since they carry
+//! the finer breakdown.
+
+use std::collections::HashMap;
+
+use crate::power::ChannelPower;
+
+/// Consecutive comparable samples needed before an aggregate by name is
+/// counted after all, or an unnamed channel is treated as an aggregate.
+pub const OVERLAP_SAMPLES: u32 = 3;
+
+/// Relative difference within which a channel equals the sum of others.
+pub const OVERLAP_TOLERANCE: f32 = 0.05;
+
+/// Below this, idle readings agree with anything and prove nothing.
+const MIN_COMPARABLE_WATTS: f32 = 0.1;
+
+/// Suffix of channels summing a whole domain, e.g. "CPU Energy".
+const DOMAIN_AGGREGATE_SUFFIX: &str = " energy";
+
+/// Remembers how aggregate candidates compared over recent samples.
+#[derive(Debug, Default)]
+pub struct OverlapDetector {
+    /// Consecutive samples in which an aggregate by name did not match the
+    /// sum of its components.
+    diverging: HashMap<String, u32>,
+    /// Consecutive samples in which a channel matched the sum of the rest of
+    /// its domain.
+    matching: HashMap<String, u32>,
+}
+
+impl OverlapDetector {
+    /// Sets [`ChannelPower::aggregate`] on every channel of one sample whose
+    /// energy the other channels already report.
+    ///
+    /// `domain` groups channels; ones it returns `None` for are never
+    /// compared.
+    pub fn mark_aggregates<D, F>(&mut self, channels: &mut [ChannelPower], domain: F)
+    where
+        D: PartialEq,
+        F: Fn(&str) -> Option<D>,
+    {
+        let domains: Vec<Option<D>> = channels.iter().map(|c| domain(&c.name)).collect();
+        for channel in channels.iter_mut() {
+            channel.aggregate = false;
+        }
+
+        // Cluster channels first, so a domain aggregate is compared with the
+        // channels that remain counted.
+        for pass in [NamedAggregate::Prefix, NamedAggregate::Domain] {
+            for i in 0..channels.len() {
+                if domains[i].is_none() || NamedAggregate::of(&channels[i].name) != Some(pass) {
+                    continue;
+                }
+                let components: Vec<usize> = (0..channels.len())
+                    .filter(|&j| j != i && !channels[j].aggregate)
+                    .filter(|&j| match pass {
+                        NamedAggregate::Prefix => is_numbered(&channels[i].name, &channels[j].name),
+                        NamedAggregate::Domain => {
+                            domains[j] == domains[i]
+                                && NamedAggregate::of(&channels[j].name)
+                                    != Some(NamedAggregate::Domain)
+                        }
+                    })
+                    .collect();
+                if components.is_empty() {
+                    continue;
+                }
+
+                let streak = self.diverging.entry(channels[i].name.clone()).or_default();
+                match matches_sum(channels, i, &components) {
+                    Some(true) => *streak = 0,
+                    Some(false) => *streak = (*streak + 1).min(OVERLAP_SAMPLES),
+                    None => {}
+                }
+                channels[i].aggregate = *streak < OVERLAP_SAMPLES;
+            }
+        }
+
+        for i in 0..channels.len() {
+            if domains[i].is_none() || channels[i].aggregate {
+                continue;
+            }
+            let others: Vec<usize> = (0..channels.len())
+                .filter(|&j| j != i && !channels[j].aggregate && domains[j] == domains[i])
+                .collect();
+            // With one other channel, equal values are as likely a duplicate
+            // as an aggregate; keep counting both.
+            if others.len() < 2 {
+                continue;
+            }
+
+            let streak = self.matching.entry(channels[i].name.clone()).or_default();
+            match matches_sum(channels, i, &others) {
+                Some(true) => *streak = (*streak + 1).min(OVERLAP_SAMPLES),
+                Some(false) => *streak = 0,
+                None => {}
+            }
+            channels[i].aggregate = *streak >= OVERLAP_SAMPLES;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NamedAggregate {
+    /// Sums the channels named like it plus a number, "ECPU" of "ECPU0".
+    Prefix,
+    /// Sums the rest of its domain, "CPU Energy".
+    Domain,
+}
+
+impl NamedAggregate {
+    fn of(name: &str) -> Option<Self> {
+        if name.to_lowercase().ends_with(DOMAIN_AGGREGATE_SUFFIX) {
+            Some(NamedAggregate::Domain)
+        } else if !name.ends_with(|c: char| c.is_ascii_digit()) {
+            Some(NamedAggregate::Prefix)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether `component` is `aggregate` followed by a number.
+fn is_numbered(aggregate: &str, component: &str) -> bool {
+    component
+        .strip_prefix(aggregate)
+        .is_some_and(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Whether channel `i` equals the sum of `others`, or `None` when both are
+/// too small to tell.
+fn matches_sum(channels: &[ChannelPower], i: usize, others: &[usize]) -> Option<bool> {
+    let value = channels[i].watts;
+    let sum: f32 = others.iter().map(|&j| channels[j].watts).sum();
+    if value.max(sum) < MIN_COMPARABLE_WATTS {
+        return None;
+    }
+    Some((value - sum).abs() <= value.max(sum) * OVERLAP_TOLERANCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(readings: &[(&str, f32)]) -> Vec<ChannelPower> {
+        readings
+            .iter()
+            .map(|&(name, watts)| ChannelPower {
+                name: name.to_string(),
+                watts,
+                aggregate: false,
+            })
+            .collect()
+    }
+
+    fn domain(name: &str) -> Option<&'static str> {
+        let name = name.to_lowercase();
+        if name.contains("cpu") {
+            Some("cpu")
+        } else if name.contains("gpu") {
+            Some("gpu")
+        } else {
+            None
+        }
+    }
+
+    fn aggregates(channels: &[ChannelPower]) -> Vec<&str> {
+        channels
+            .iter()
+            .filter(|c| c.aggregate)
+            .map(|c| c.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn named_aggregates_are_skipped_from_the_first_sample() {
+        let mut detector = OverlapDetector::default();
+        let mut channels = sample(&[
+            ("CPU Energy", 3.0),
+            ("ECPU", 1.0),
+            ("ECPU0", 0.6),
+            ("ECPU1", 0.4),
+            ("PCPU", 2.0),
+            ("PCPU0", 2.0),
+            ("GPU Energy", 0.5),
+            ("GPU0", 0.5),
+            ("DRAM", 0.3),
+        ]);
+        detector.mark_aggregates(&mut channels, domain);
+        assert_eq!(
+            aggregates(&channels),
+            vec!["CPU Energy", "ECPU", "PCPU", "GPU Energy"]
+        );
+    }
+
+    #[test]
+    fn diverging_named_aggregate_is_counted_after_several_samples() {
+        let mut detector = OverlapDetector::default();
+        for round in 1..=OVERLAP_SAMPLES {
+            // "PCPU" reports a shared cluster block, not its cores' sum.
+            let mut channels = sample(&[("PCPU", 1.5), ("PCPU0", 0.3), ("PCPU1", 0.2)]);
+            detector.mark_aggregates(&mut channels, domain);
+            let counted = round >= OVERLAP_SAMPLES;
+            assert_eq!(channels[0].aggregate, !counted, "round {}", round);
+        }
+
+        // Idle readings neither confirm nor refute the relationship.
+        let mut idle = sample(&[("PCPU", 0.0), ("PCPU0", 0.0), ("PCPU1", 0.0)]);
+        detector.mark_aggregates(&mut idle, domain);
+        assert!(!idle[0].aggregate);
+    }
+
+    #[test]
+    fn unnamed_channel_equal_to_its_domain_is_skipped_once_confirmed() {
+        let mut detector = OverlapDetector::default();
+        for round in 1..=OVERLAP_SAMPLES {
+            let mut channels = sample(&[
+                ("CPU_TOTAL0", 2.02),
+                ("CPU_A0", 1.2),
+                ("CPU_B0", 0.8),
+                ("GPU1", 0.4),
+            ]);
+            detector.mark_aggregates(&mut channels, domain);
+            let expected: Vec<&str> = if round >= OVERLAP_SAMPLES {
+                vec!["CPU_TOTAL0"]
+            } else {
+                vec![]
+            };
+            assert_eq!(aggregates(&channels), expected, "round {}", round);
+        }
+    }
+}
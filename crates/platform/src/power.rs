@@ -1,8 +1,14 @@
 //! Power monitoring traits and types.
 
-use color_eyre::eyre::Result;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
 
-use crate::types::PowerMode;
+use color_eyre::eyre::{bail, Result};
+
+use crate::gpu::{GpuResidency, GpuWorkload};
+use crate::types::{PowerMode, ThermalPressure};
 
 /// Power information snapshot.
 ///
@@ -15,22 +21,154 @@ pub struct PowerInfo {
     /// GPU power consumption in watts.
     pub gpu_power_watts: f32,
 
-    /// Total system power consumption in watts.
+    /// Apple Neural Engine power in watts; 0 where it is not reported
+    /// (Linux, the macOS fallback estimate).
+    pub ane_power_watts: f32,
+
+    /// Energy used since the provider was created, in joules, summed from
+    /// the hardware energy counters rather than integrated from watts, so
+    /// irregular refresh intervals do not skew it. Stays 0 for blocks
+    /// without a counter (GPU and ANE on Linux) and does not grow while the
+    /// macOS fallback estimate is in use.
+    pub cpu_energy_joules: f64,
+    pub gpu_energy_joules: f64,
+    pub ane_energy_joules: f64,
+
+    /// Total system power consumption in watts, including the CPU, GPU and
+    /// ANE readings above.
     /// This may be measured (SMC/RAPL) or estimated (sum of components).
     pub system_power_watts: f32,
 
     /// Current power mode.
     pub power_mode: PowerMode,
 
+    /// Current thermal pressure; `Unknown` where the platform does not
+    /// report it.
+    pub thermal_pressure: ThermalPressure,
+
     /// Whether enough samples have been collected for reliable readings.
     /// Power readings may be unstable during the first few samples.
     pub is_warmed_up: bool,
+
+    /// Per-channel readings from the most recent sample, unsmoothed.
+    /// Empty when the platform does not expose individual channels.
+    pub channels: Vec<ChannelPower>,
+
+    /// IOReport Energy Model subgroups the provider subscribed to, when it
+    /// could narrow the subscription. `None` means the whole group (or a
+    /// platform without IOReport).
+    pub energy_model_subgroups: Option<Vec<String>>,
+
+    /// Set when the CPU and GPU readings are an estimate from CPU usage
+    /// rather than hardware energy counters (macOS without a usable IOReport
+    /// subscription). The estimate assumes a fixed 2-17 W CPU and 1 W GPU, so
+    /// it ignores the GPU's real load, the chip generation and frequency, and
+    /// can be off by several watts either way.
+    pub is_fallback_estimate: bool,
+
+    /// GPU performance state residency since the previous refresh, from
+    /// the IOReport "GPU Stats" group. `None` where the group is missing
+    /// (Intel Macs, some VMs, Linux) and until two samples were taken.
+    pub gpu_residency: Option<GpuResidency>,
+
+    /// Split of GPU busy time between compute and render work since the
+    /// previous refresh, from the compute and render utilization channels
+    /// of "GPU Stats". `None` on chips or macOS releases without those
+    /// channels, on Linux, and while the GPU was idle.
+    pub gpu_workload: Option<GpuWorkload>,
+
+    /// Average frequency in MHz of each CPU cluster since the previous
+    /// refresh, keyed by IOReport channel name ("ECPU", "PCPU", ...). Empty
+    /// until [`PowerProvider::subscribe_cpu_stats`] succeeded, and on Linux.
+    pub cpu_freq_mhz: Vec<(String, f32)>,
+
+    /// The unsmoothed CPU, GPU and package readings the last refresh
+    /// measured, for peaks and averages the smoothed values above would
+    /// flatten. `None` when it measured nothing new, as on the first macOS
+    /// sample.
+    pub raw_readings: Option<PowerReadings>,
+}
+
+/// Power drawn by one named hardware channel (an IOReport Energy Model
+/// channel on macOS, a RAPL domain on Linux).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelPower {
+    pub name: String,
+    pub watts: f32,
+    /// Set when other channels already report this channel's energy, so
+    /// sums over channels must skip it. See [`crate::OverlapDetector`].
+    pub aggregate: bool,
 }
 
 impl PowerInfo {
-    /// Get combined CPU + GPU power.
+    /// Get combined CPU + GPU + ANE power.
     pub fn package_power_watts(&self) -> f32 {
-        self.cpu_power_watts + self.gpu_power_watts
+        self.cpu_power_watts + self.gpu_power_watts + self.ane_power_watts
+    }
+}
+
+/// CPU, GPU and total watts of one raw, unsmoothed reading.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PowerReadings {
+    pub cpu_watts: f32,
+    pub gpu_watts: f32,
+    pub total_watts: f32,
+}
+
+/// Peaks and a rolling average of raw readings, which catch short spikes
+/// that the smoothed [`PowerInfo`] values flatten.
+#[derive(Debug, Clone)]
+pub struct PowerStats {
+    peaks: PowerReadings,
+    window: VecDeque<PowerReadings>,
+    window_len: usize,
+}
+
+impl PowerStats {
+    /// Averages over the last `window_len` readings, at least one.
+    pub fn new(window_len: usize) -> Self {
+        let window_len = window_len.max(1);
+        Self {
+            peaks: PowerReadings::default(),
+            window: VecDeque::with_capacity(window_len),
+            window_len,
+        }
+    }
+
+    pub fn record(&mut self, readings: PowerReadings) {
+        self.peaks.cpu_watts = self.peaks.cpu_watts.max(readings.cpu_watts);
+        self.peaks.gpu_watts = self.peaks.gpu_watts.max(readings.gpu_watts);
+        self.peaks.total_watts = self.peaks.total_watts.max(readings.total_watts);
+
+        if self.window.len() >= self.window_len {
+            self.window.pop_front();
+        }
+        self.window.push_back(readings);
+    }
+
+    /// Highest reading of each kind since creation or [`Self::reset_peaks`];
+    /// each peak may come from a different reading.
+    pub fn peaks(&self) -> PowerReadings {
+        self.peaks
+    }
+
+    /// Mean of the readings in the window; zeros before the first one.
+    pub fn average(&self) -> PowerReadings {
+        if self.window.is_empty() {
+            return PowerReadings::default();
+        }
+        let n = self.window.len() as f32;
+        let sum = |f: fn(&PowerReadings) -> f32| self.window.iter().map(f).sum::<f32>() / n;
+        PowerReadings {
+            cpu_watts: sum(|r| r.cpu_watts),
+            gpu_watts: sum(|r| r.gpu_watts),
+            total_watts: sum(|r| r.total_watts),
+        }
+    }
+
+    /// Starts the peaks over from zero; the average window is kept.
+    pub fn reset_peaks(&mut self) {
+        self.peaks = PowerReadings::default();
     }
 }
 
@@ -47,6 +185,19 @@ pub trait PowerProvider {
     /// Get the current power information.
     fn info(&self) -> &PowerInfo;
 
+    /// Starts sampling CPU frequencies into [`PowerInfo::cpu_freq_mhz`] on
+    /// every later refresh. Fails where the platform does not report them.
+    fn subscribe_cpu_stats(&mut self) -> Result<()> {
+        bail!("CPU frequencies are not reported on this platform")
+    }
+
+    /// Calls `on_change` from a background thread whenever the power mode
+    /// changes, until the returned handle is dropped.
+    fn subscribe_notification<F>(on_change: F) -> NotificationHandle
+    where
+        Self: Sized,
+        F: Fn(PowerMode) + Send + 'static;
+
     /// Check if power monitoring is supported on this system.
     ///
     /// Returns false if the required hardware/permissions are not available.
@@ -57,3 +208,112 @@ pub trait PowerProvider {
         true
     }
 }
+
+/// A power mode subscription; dropping it stops the watcher thread.
+#[derive(Debug)]
+pub struct NotificationHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl NotificationHandle {
+    /// Runs `watch` on a new thread. It must return soon after the flag it
+    /// is given is set, since dropping the handle waits for it.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub(crate) fn spawn<W>(watch: W) -> Self
+    where
+        W: FnOnce(Arc<AtomicBool>) + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        Self {
+            stop,
+            thread: Some(thread::spawn(move || watch(flag))),
+        }
+    }
+
+    /// A handle with nothing to stop, for platforms that cannot report
+    /// power mode changes.
+    #[cfg_attr(target_os = "macos", allow(dead_code))]
+    pub(crate) fn inactive() -> Self {
+        Self {
+            stop: Arc::new(AtomicBool::new(true)),
+            thread: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.thread.is_some()
+    }
+}
+
+impl Drop for NotificationHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn package_power_includes_the_neural_engine() {
+        let info = PowerInfo {
+            cpu_power_watts: 4.0,
+            gpu_power_watts: 2.0,
+            ane_power_watts: 1.5,
+            ..PowerInfo::default()
+        };
+        assert_eq!(info.package_power_watts(), 7.5);
+    }
+
+    fn readings(cpu_watts: f32, gpu_watts: f32) -> PowerReadings {
+        PowerReadings {
+            cpu_watts,
+            gpu_watts,
+            total_watts: cpu_watts + gpu_watts,
+        }
+    }
+
+    #[test]
+    fn stats_keep_peaks_and_average_the_window() {
+        let mut stats = PowerStats::new(2);
+        assert_eq!(stats.average(), PowerReadings::default());
+
+        stats.record(readings(9.0, 1.0));
+        stats.record(readings(2.0, 4.0));
+        stats.record(readings(4.0, 2.0));
+
+        let peaks = stats.peaks();
+        assert_eq!((peaks.cpu_watts, peaks.gpu_watts), (9.0, 4.0));
+        assert_eq!(peaks.total_watts, 10.0);
+        assert_eq!(stats.average(), readings(3.0, 3.0));
+
+        stats.reset_peaks();
+        assert_eq!(stats.peaks(), PowerReadings::default());
+        stats.record(readings(1.0, 1.0));
+        assert_eq!(stats.peaks(), readings(1.0, 1.0));
+    }
+
+    #[test]
+    fn dropping_the_handle_stops_the_watcher() {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let seen = Arc::clone(&stopped);
+        let handle = NotificationHandle::spawn(move |stop| {
+            while !stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(5));
+            }
+            seen.store(true, Ordering::Relaxed);
+        });
+        assert!(handle.is_active());
+
+        drop(handle);
+        assert!(stopped.load(Ordering::Relaxed));
+        assert!(!NotificationHandle::inactive().is_active());
+    }
+}
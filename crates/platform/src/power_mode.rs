@@ -0,0 +1,90 @@
+//! The macOS power mode from power management settings.
+//!
+//! The settings come from IOKit's active power management preferences for
+//! the current power source, or from `pmset -g` where those cannot be read.
+//! Both name the same settings: IOKit as `LowPowerMode`, pmset as
+//! `lowpowermode`. Macs with High Power Mode report a single `powermode`
+//! instead, holding the value `pmset powermode` takes.
+
+use crate::types::PowerMode;
+
+/// Mode from numeric settings of the active power source. Keys are matched
+/// regardless of case; without any mode key the Mac has only the automatic
+/// mode.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn from_settings<K: AsRef<str>>(
+    settings: impl IntoIterator<Item = (K, i64)>,
+) -> PowerMode {
+    let mut low_power = false;
+    let mut high_power = false;
+    for (key, value) in settings {
+        let key = key.as_ref();
+        if key.eq_ignore_ascii_case("powermode") {
+            return PowerMode::from_pmset_value(value).unwrap_or(PowerMode::Unknown);
+        }
+        low_power |= key.eq_ignore_ascii_case("lowpowermode") && value == 1;
+        high_power |= key.eq_ignore_ascii_case("highpowermode") && value == 1;
+    }
+
+    if low_power {
+        PowerMode::LowPower
+    } else if high_power {
+        PowerMode::HighPerformance
+    } else {
+        PowerMode::Automatic
+    }
+}
+
+/// Mode from `pmset -g` output: one `key value` setting per line, padded
+/// with spaces. Lines whose value is not a number are skipped.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn from_pmset(stdout: &str) -> PowerMode {
+    from_settings(stdout.lines().filter_map(|line| {
+        let mut fields = line.split_whitespace();
+        let key = fields.next()?;
+        let value = fields.next()?.parse().ok()?;
+        Some((key, value))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PMSET_MACOS_12: &str = include_str!("../../../fixtures/pmset/settings-macos-12.txt");
+    const PMSET_MACOS_14: &str = include_str!("../../../fixtures/pmset/settings-macos-14.txt");
+    const ACTIVE_PREFS_MACOS_13: &str =
+        include_str!("../../../fixtures/pmset/active-prefs-battery-macos-13.tsv");
+
+    #[test]
+    fn pmset_output_is_read_whatever_the_padding() {
+        assert_eq!(from_pmset(PMSET_MACOS_12), PowerMode::LowPower);
+        assert_eq!(from_pmset(PMSET_MACOS_14), PowerMode::HighPerformance);
+
+        let automatic = PMSET_MACOS_12.replace("lowpowermode         1", "lowpowermode 0");
+        assert_eq!(from_pmset(&automatic), PowerMode::Automatic);
+        assert_eq!(
+            from_pmset("Currently in use:\n sleep 1\n"),
+            PowerMode::Automatic
+        );
+    }
+
+    #[test]
+    fn active_preferences_are_read_like_pmset_settings() {
+        let settings = ACTIVE_PREFS_MACOS_13.lines().filter_map(|line| {
+            let (key, value) = line.split_once('\t')?;
+            Some((key, value.parse().ok()?))
+        });
+        assert_eq!(from_settings(settings), PowerMode::LowPower);
+
+        assert_eq!(
+            from_settings([("HighPowerMode", 1)]),
+            PowerMode::HighPerformance
+        );
+        assert_eq!(
+            from_settings([("PowerMode", 0), ("LowPowerMode", 1)]),
+            PowerMode::Automatic
+        );
+        assert_eq!(from_settings([("PowerMode", 7)]), PowerMode::Unknown);
+    }
+}
@@ -86,6 +86,28 @@ impl PowerMode {
             PowerMode::Unknown => "Unknown",
         }
     }
+
+    /// The value `pmset powermode` takes for this mode; `None` for
+    /// [`PowerMode::Unknown`], which cannot be set.
+    pub fn pmset_value(self) -> Option<u8> {
+        match self {
+            PowerMode::Automatic => Some(0),
+            PowerMode::LowPower => Some(1),
+            PowerMode::HighPerformance => Some(2),
+            PowerMode::Unknown => None,
+        }
+    }
+
+    /// The mode a `pmset powermode` value stands for; `None` for values
+    /// pmset does not define.
+    pub fn from_pmset_value(value: i64) -> Option<Self> {
+        match value {
+            0 => Some(PowerMode::Automatic),
+            1 => Some(PowerMode::LowPower),
+            2 => Some(PowerMode::HighPerformance),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for PowerMode {
@@ -94,6 +116,112 @@ impl fmt::Display for PowerMode {
     }
 }
 
+/// How hard the system is throttling to stay within its thermal limits.
+///
+/// Levels follow macOS's thermal pressure notification, from no throttling
+/// to about to sleep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum ThermalPressure {
+    Nominal,
+    Moderate,
+    Heavy,
+    /// Performance is cut sharply to shed heat.
+    Trapping,
+    /// The system is about to sleep to cool down.
+    Sleeping,
+    /// Pressure cannot be determined
+    #[default]
+    Unknown,
+}
+
+impl ThermalPressure {
+    /// Maps the state of the `com.apple.system.thermalpressurelevel`
+    /// notification.
+    pub fn from_level(level: u64) -> Self {
+        match level {
+            0 => ThermalPressure::Nominal,
+            1 => ThermalPressure::Moderate,
+            2 => ThermalPressure::Heavy,
+            3 => ThermalPressure::Trapping,
+            4 => ThermalPressure::Sleeping,
+            _ => ThermalPressure::Unknown,
+        }
+    }
+
+    /// Returns a human-readable label for the thermal pressure.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThermalPressure::Nominal => "Nominal",
+            ThermalPressure::Moderate => "Moderate",
+            ThermalPressure::Heavy => "Heavy",
+            ThermalPressure::Trapping => "Trapping",
+            ThermalPressure::Sleeping => "Sleeping",
+            ThermalPressure::Unknown => "Unknown",
+        }
+    }
+}
+
+impl fmt::Display for ThermalPressure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// How close the system is to running short of memory.
+///
+/// Levels follow macOS's `kern.memorystatus_vm_pressure_level`; elsewhere
+/// they are estimated from the share of memory in use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum MemoryPressure {
+    Normal,
+    /// The system is compressing or swapping to keep up.
+    Warning,
+    /// Apps are being asked to free memory or terminated.
+    Critical,
+    /// Pressure cannot be determined
+    #[default]
+    Unknown,
+}
+
+impl MemoryPressure {
+    /// Maps the value of the `kern.memorystatus_vm_pressure_level` sysctl.
+    pub fn from_level(level: i32) -> Self {
+        match level {
+            1 => MemoryPressure::Normal,
+            2 => MemoryPressure::Warning,
+            4 => MemoryPressure::Critical,
+            _ => MemoryPressure::Unknown,
+        }
+    }
+
+    /// Estimates the level from the share of memory in use, from 0 to 1.
+    pub fn from_used_fraction(used: f32) -> Self {
+        if used >= 0.95 {
+            MemoryPressure::Critical
+        } else if used >= 0.85 {
+            MemoryPressure::Warning
+        } else {
+            MemoryPressure::Normal
+        }
+    }
+
+    /// Returns a human-readable label for the memory pressure.
+    pub fn label(&self) -> &'static str {
+        match self {
+            MemoryPressure::Normal => "Normal",
+            MemoryPressure::Warning => "Warning",
+            MemoryPressure::Critical => "Critical",
+            MemoryPressure::Unknown => "Unknown",
+        }
+    }
+}
+
+impl fmt::Display for MemoryPressure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
 /// Battery technology/chemistry type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum BatteryTechnology {
@@ -160,6 +288,58 @@ impl From<starship_battery::Technology> for BatteryTechnology {
     }
 }
 
+/// Cell chemistry as reported by the battery's fuel gauge.
+///
+/// Narrower than [`BatteryTechnology`]: it only distinguishes the lithium
+/// chemistries laptops actually ship with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatteryChemistry {
+    /// Lithium-polymer (`LiP`)
+    LithiumPolymer,
+    /// Lithium-ion (`LiI`, `LION`)
+    LithiumIon,
+    /// Not reported or not recognised
+    #[default]
+    Unknown,
+}
+
+impl BatteryChemistry {
+    /// Parses the chemistry string from the `AppleSmartBattery` IOService,
+    /// e.g. `LiP` or `LiI`. Case and surrounding quotes are ignored.
+    pub fn from_ioreg(value: &str) -> Self {
+        match value.trim().trim_matches('"').to_ascii_lowercase().as_str() {
+            "lip" | "lipo" | "li-poly" => BatteryChemistry::LithiumPolymer,
+            "lii" | "lion" | "li-ion" => BatteryChemistry::LithiumIon,
+            _ => BatteryChemistry::Unknown,
+        }
+    }
+
+    /// Returns a human-readable label for the chemistry.
+    pub fn label(&self) -> &'static str {
+        match self {
+            BatteryChemistry::LithiumPolymer => "Li-poly",
+            BatteryChemistry::LithiumIon => "Li-ion",
+            BatteryChemistry::Unknown => "Unknown",
+        }
+    }
+}
+
+impl fmt::Display for BatteryChemistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+impl From<BatteryTechnology> for BatteryChemistry {
+    fn from(technology: BatteryTechnology) -> Self {
+        match technology {
+            BatteryTechnology::LithiumPolymer => BatteryChemistry::LithiumPolymer,
+            BatteryTechnology::LithiumIon => BatteryChemistry::LithiumIon,
+            _ => BatteryChemistry::Unknown,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,6 +353,23 @@ mod tests {
         assert_eq!(ChargeState::Unknown.label(), "Unknown");
     }
 
+    #[test]
+    fn test_power_mode_pmset_values() {
+        assert_eq!(PowerMode::Automatic.pmset_value(), Some(0));
+        assert_eq!(PowerMode::LowPower.pmset_value(), Some(1));
+        assert_eq!(PowerMode::HighPerformance.pmset_value(), Some(2));
+        assert_eq!(PowerMode::Unknown.pmset_value(), None);
+        for mode in [
+            PowerMode::Automatic,
+            PowerMode::LowPower,
+            PowerMode::HighPerformance,
+        ] {
+            let value = mode.pmset_value().unwrap();
+            assert_eq!(PowerMode::from_pmset_value(value.into()), Some(mode));
+        }
+        assert_eq!(PowerMode::from_pmset_value(3), None);
+    }
+
     #[test]
     fn test_charge_state_is_plugged_in() {
         assert!(ChargeState::Charging.is_plugged_in());
@@ -213,4 +410,57 @@ mod tests {
         assert_eq!(PowerMode::HighPerformance.label(), "High Performance");
         assert_eq!(PowerMode::Unknown.label(), "Unknown");
     }
+
+    #[test]
+    fn test_thermal_pressure_levels() {
+        assert_eq!(ThermalPressure::from_level(0), ThermalPressure::Nominal);
+        assert_eq!(ThermalPressure::from_level(2), ThermalPressure::Heavy);
+        assert_eq!(ThermalPressure::from_level(4), ThermalPressure::Sleeping);
+        assert_eq!(ThermalPressure::from_level(9), ThermalPressure::Unknown);
+        assert!(ThermalPressure::Moderate > ThermalPressure::Nominal);
+    }
+
+    #[test]
+    fn test_memory_pressure_levels() {
+        assert_eq!(MemoryPressure::from_level(1), MemoryPressure::Normal);
+        assert_eq!(MemoryPressure::from_level(2), MemoryPressure::Warning);
+        assert_eq!(MemoryPressure::from_level(4), MemoryPressure::Critical);
+        assert_eq!(MemoryPressure::from_level(3), MemoryPressure::Unknown);
+        assert_eq!(
+            MemoryPressure::from_used_fraction(0.6),
+            MemoryPressure::Normal
+        );
+        assert_eq!(
+            MemoryPressure::from_used_fraction(0.9),
+            MemoryPressure::Warning
+        );
+        assert_eq!(
+            MemoryPressure::from_used_fraction(0.97),
+            MemoryPressure::Critical
+        );
+    }
+
+    #[test]
+    fn test_battery_chemistry_from_ioreg() {
+        assert_eq!(
+            BatteryChemistry::from_ioreg("\"LiP\""),
+            BatteryChemistry::LithiumPolymer
+        );
+        assert_eq!(
+            BatteryChemistry::from_ioreg("LiI"),
+            BatteryChemistry::LithiumIon
+        );
+        assert_eq!(
+            BatteryChemistry::from_ioreg("LION"),
+            BatteryChemistry::LithiumIon
+        );
+        assert_eq!(
+            BatteryChemistry::from_ioreg("NiMH"),
+            BatteryChemistry::Unknown
+        );
+        assert_eq!(
+            BatteryChemistry::from(BatteryTechnology::LeadAcid),
+            BatteryChemistry::Unknown
+        );
+    }
 }
@@ -6,9 +6,10 @@ mod version;
 pub use request::DaemonRequest;
 pub use response::DaemonResponse;
 pub use types::{
-    BatterySnapshot, BatteryState, ChargeSession, ChargingState, CycleSummary, DaemonStatus,
-    DailyCycle, DailyStat, DailyTopProcess, DataSnapshot, ForecastSnapshot, ForecastSource,
-    HourlyStat, KillProcessResult, KillSignal, PowerMode, PowerSnapshot, ProcessSnapshot,
-    ProcessState, Sample, SessionType, SystemSnapshot, SystemStatsSnapshot, MAX_SUBSCRIBERS,
+    AppEnergy, BatteryHealthEntry, BatterySnapshot, BatteryState, BinaryArch, ChargeSession,
+    ChargingState, CycleSummary, DaemonStatus, DailyCycle, DailyStat, DailyTopProcess,
+    DataSnapshot, ForecastSnapshot, ForecastSource, HourlyStat, KillProcessResult, KillSignal,
+    NetPowerBalance, PowerMode, PowerSnapshot, ProcessSnapshot, ProcessState, Sample, SessionType,
+    SystemSnapshot, SystemStatsSnapshot, MAX_SUBSCRIBERS,
 };
 pub use version::{MIN_SUPPORTED_VERSION, PROTOCOL_VERSION};
@@ -43,6 +43,20 @@ pub enum DaemonRequest {
         from: String,
         to: String,
     },
+    GetBatteryHealth {
+        from: String,
+        to: String,
+    },
+    GetSamples {
+        from: i64,
+        to: i64,
+    },
+    GetTopApps {
+        from: i64,
+        to: i64,
+        include_estimated: bool,
+        limit: usize,
+    },
 }
 
 impl DaemonRequest {
@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 use crate::types::{
-    ChargeSession, CycleSummary, DaemonStatus, DailyCycle, DailyStat, DailyTopProcess,
-    DataSnapshot, HourlyStat, KillProcessResult, Sample,
+    AppEnergy, BatteryHealthEntry, ChargeSession, CycleSummary, DaemonStatus, DailyCycle,
+    DailyStat, DailyTopProcess, DataSnapshot, HourlyStat, KillProcessResult, Sample,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,10 +19,20 @@ pub enum DaemonResponse {
     DataUpdate(DataSnapshot),
     CurrentData(DataSnapshot),
     KillResult(KillProcessResult),
-    SubscriptionRejected { reason: String },
+    SubscriptionRejected {
+        reason: String,
+    },
     CycleSummary(CycleSummary),
     ChargeSessions(Vec<ChargeSession>),
     DailyCycles(Vec<DailyCycle>),
+    BatteryHealth(Vec<BatteryHealthEntry>),
+    /// Samples in the requested range, with the timestamp of the oldest
+    /// stored sample so clients can tell how far back they can go.
+    Samples {
+        samples: Vec<Sample>,
+        oldest: Option<i64>,
+    },
+    TopApps(Vec<AppEnergy>),
 }
 
 impl DaemonResponse {
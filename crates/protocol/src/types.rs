@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
 use crate::version::{MIN_SUPPORTED_VERSION, PROTOCOL_VERSION};
@@ -35,6 +37,18 @@ pub enum ProcessState {
     Unknown,
 }
 
+/// CPU architecture a process's executable was built for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryArch {
+    Arm64,
+    X86_64,
+    /// A fat Mach-O binary with both arm64 and x86_64 slices.
+    Universal,
+    #[default]
+    Unknown,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum KillSignal {
@@ -90,6 +104,36 @@ pub struct BatterySnapshot {
     pub temperature_c: Option<f32>,
     pub daily_min_soc: Option<f32>,
     pub daily_max_soc: Option<f32>,
+    #[serde(default)]
+    pub charge_limit_percent: Option<u8>,
+    /// Set while external power is connected.
+    #[serde(default)]
+    pub net_power: Option<NetPowerBalance>,
+    /// Display-only charge interpolated between the whole percents the
+    /// battery reports, from its measured power. `None` when the power is
+    /// not measured; `charge_percent` stays the authoritative value.
+    #[serde(default)]
+    pub interpolated_percent: Option<f32>,
+}
+
+/// Whether the adapter keeps up with the load while plugged in.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub struct NetPowerBalance {
+    /// Power flowing into the battery: positive is a surplus that charges
+    /// it, negative a deficit that drains it despite external power.
+    pub net_watts: f32,
+    /// Wattage negotiated with the adapter.
+    pub adapter_rated_watts: Option<u32>,
+    /// Power the system draws from the adapter, where reported.
+    pub adapter_input_watts: Option<f32>,
+    /// Minutes until empty if the current deficit continues.
+    pub minutes_to_empty: Option<f32>,
+}
+
+impl NetPowerBalance {
+    pub fn is_deficit(&self) -> bool {
+        self.net_watts < 0.0
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -190,6 +234,24 @@ pub struct ProcessSnapshot {
     pub status: ProcessState,
     pub run_time_secs: u64,
     pub total_cpu_time_secs: u64,
+    #[serde(default)]
+    pub network_bytes_per_sec: Option<f64>,
+    /// App Sandbox container id (the app's bundle id) of a sandboxed macOS
+    /// app. Clients resolve it to a path in their own home directory.
+    #[serde(default)]
+    pub sandbox_container_id: Option<String>,
+    #[serde(default)]
+    pub executable_path: PathBuf,
+    /// Seconds since the Unix epoch. With `executable_path` it tells a
+    /// process apart from an earlier one that had the same PID.
+    #[serde(default)]
+    pub start_time: u64,
+    #[serde(default)]
+    pub team_id: Option<String>,
+    #[serde(default)]
+    pub binary_arch: BinaryArch,
+    #[serde(default)]
+    pub power_assertion_count: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -312,6 +374,16 @@ pub struct DailyTopProcess {
     pub total_energy_wh: f32,
 }
 
+/// Energy an app used over a time range. `estimated_wh` is the part
+/// recorded while power was estimated rather than measured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppEnergy {
+    pub name: String,
+    pub bundle_id: Option<String>,
+    pub energy_wh: f32,
+    pub estimated_wh: f32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChargeSession {
     pub start_time: i64,
@@ -323,6 +395,9 @@ pub struct ChargeSession {
     pub avg_power_watts: Option<f32>,
     pub session_type: SessionType,
     pub is_complete: bool,
+    /// Seconds spent draining while plugged in.
+    #[serde(default)]
+    pub deficit_secs: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -341,4 +416,16 @@ pub struct DailyCycle {
     pub time_at_high_soc_mins: i32,
 }
 
+/// One day of the battery wear log.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BatteryHealthEntry {
+    pub date: String,
+    pub health_percent: f32,
+    pub cycle_count: Option<i32>,
+    pub max_capacity_wh: f32,
+    pub design_capacity_wh: f32,
+    pub full_charge_capacity_mah: Option<i32>,
+    pub design_capacity_mah: Option<i32>,
+}
+
 pub const MAX_SUBSCRIBERS: usize = 10;
@@ -59,6 +59,9 @@ fn sample_battery_snapshot() -> BatterySnapshot {
         temperature_c: Some(32.5),
         daily_min_soc: Some(25.0),
         daily_max_soc: Some(95.0),
+        charge_limit_percent: Some(80),
+        net_power: None,
+        interpolated_percent: Some(85.2),
     }
 }
 
@@ -101,6 +104,13 @@ fn sample_process_snapshot() -> ProcessSnapshot {
             status: ProcessState::Running,
             run_time_secs: 3600,
             total_cpu_time_secs: 120,
+            network_bytes_per_sec: None,
+            sandbox_container_id: None,
+            executable_path: PathBuf::new(),
+            start_time: 1_700_000_000,
+            team_id: None,
+            binary_arch: BinaryArch::Universal,
+            power_assertion_count: None,
         }]),
         is_killable: true,
         disk_read_bytes: 10000,
@@ -108,6 +118,14 @@ fn sample_process_snapshot() -> ProcessSnapshot {
         status: ProcessState::Running,
         run_time_secs: 7200,
         total_cpu_time_secs: 600,
+        network_bytes_per_sec: Some(2048.0),
+        sandbox_container_id: Some("com.apple.Safari".to_string()),
+        executable_path: PathBuf::from("/Applications/Safari.app/Contents/MacOS/Safari"),
+        start_time: 1_700_000_000,
+        // Apple platform binaries carry no team identifier.
+        team_id: None,
+        binary_arch: BinaryArch::Universal,
+        power_assertion_count: None,
     }
 }
 
@@ -206,6 +224,15 @@ fn sample_daily_top_process() -> DailyTopProcess {
     }
 }
 
+fn sample_app_energy() -> AppEnergy {
+    AppEnergy {
+        name: "Safari".to_string(),
+        bundle_id: Some("com.apple.Safari".to_string()),
+        energy_wh: 3.2,
+        estimated_wh: 0.4,
+    }
+}
+
 fn sample_sample() -> Sample {
     Sample {
         timestamp: 1704067200,
@@ -228,6 +255,7 @@ fn sample_charge_session() -> ChargeSession {
         avg_power_watts: Some(17.5),
         session_type: SessionType::Charge,
         is_complete: true,
+        deficit_secs: 0,
     }
 }
 
@@ -248,6 +276,18 @@ fn sample_daily_cycle() -> DailyCycle {
     }
 }
 
+fn sample_battery_health_entry() -> BatteryHealthEntry {
+    BatteryHealthEntry {
+        date: "2024-01-01".to_string(),
+        health_percent: 92.0,
+        cycle_count: Some(245),
+        max_capacity_wh: 52.6,
+        design_capacity_wh: 58.0,
+        full_charge_capacity_mah: Some(4382),
+        design_capacity_mah: Some(4563),
+    }
+}
+
 fn sample_cycle_summary() -> CycleSummary {
     CycleSummary {
         total_cycles_macos: 245,
@@ -342,6 +382,29 @@ fn generate_request_fixtures() {
                 to: "2024-01-07".to_string(),
             },
         ),
+        (
+            "get_battery_health",
+            DaemonRequest::GetBatteryHealth {
+                from: "2024-01-01".to_string(),
+                to: "2024-01-07".to_string(),
+            },
+        ),
+        (
+            "get_samples",
+            DaemonRequest::GetSamples {
+                from: 1704060000,
+                to: 1704067200,
+            },
+        ),
+        (
+            "get_top_apps",
+            DaemonRequest::GetTopApps {
+                from: 1704060000,
+                to: 1704146399,
+                include_estimated: true,
+                limit: 10,
+            },
+        ),
     ];
 
     for (name, request) in requests {
@@ -410,6 +473,21 @@ fn generate_response_fixtures() {
             "daily_cycles",
             DaemonResponse::DailyCycles(vec![sample_daily_cycle()]),
         ),
+        (
+            "battery_health",
+            DaemonResponse::BatteryHealth(vec![sample_battery_health_entry()]),
+        ),
+        (
+            "samples",
+            DaemonResponse::Samples {
+                samples: vec![sample_sample()],
+                oldest: Some(1703980800),
+            },
+        ),
+        (
+            "top_apps",
+            DaemonResponse::TopApps(vec![sample_app_energy()]),
+        ),
     ];
 
     for (name, response) in responses {
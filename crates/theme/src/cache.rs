@@ -139,7 +139,7 @@ pub fn group_schemes(schemes: &[String]) -> Vec<ThemeGroup> {
     }
 
     let mut groups: Vec<ThemeGroup> = grouped.into_values().collect();
-    groups.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    groups.sort_by_key(|g| g.name.to_lowercase());
     groups
 }
 